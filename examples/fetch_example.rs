@@ -39,7 +39,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let display_value = if value.len() > 50 {
             format!("{}...", &value[..50])
         } else {
-            value.clone()
+            value.to_string()
         };
         println!("  {}: {}", key, display_value);
     }
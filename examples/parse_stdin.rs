@@ -0,0 +1,19 @@
+//! Example: Parse HTML from standard input and print the result as JSON
+//!
+//! ```sh
+//! curl -s https://example.com | cargo run --example parse_stdin -- https://example.com
+//! ```
+
+use std::io;
+
+use webpage_info::{HtmlInfo, ParseOptions};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Optional base URL for resolving relative links
+    let base_url = std::env::args().nth(1);
+
+    let info = HtmlInfo::from_reader(io::stdin().lock(), base_url.as_deref(), ParseOptions::new())?;
+    serde_json::to_writer_pretty(io::stdout().lock(), &info)?;
+    println!();
+    Ok(())
+}
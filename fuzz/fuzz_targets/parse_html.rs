@@ -0,0 +1,20 @@
+//! Fuzz the HTML extraction pipeline with arbitrary bytes.
+//!
+//! Calls the non-catching entry point so any panic is reported by the fuzzer
+//! rather than swallowed by `HtmlInfo::try_parse`.
+//!
+//! Run with: `cargo +nightly fuzz run parse_html`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use webpage_info::{HtmlInfo, ParseOptions};
+
+fuzz_target!(|data: &[u8]| {
+    let html = String::from_utf8_lossy(data);
+    let _ = HtmlInfo::from_string_with_options(
+        &html,
+        Some("https://example.com/"),
+        ParseOptions::default(),
+    );
+});
@@ -0,0 +1,56 @@
+/*
+ * C interface to webpage-info, built with the `ffi` feature:
+ *
+ *     cargo build --release --features ffi
+ *
+ * Returned `char *` strings are owned by the caller; release them with
+ * webpage_info_string_free(). A NULL return signals failure, and
+ * webpage_info_last_error() describes it until the next call on the same
+ * thread.
+ */
+
+#ifndef WEBPAGE_INFO_H
+#define WEBPAGE_INFO_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque fetch result. */
+typedef struct WebpageInfo WebpageInfo;
+
+/* Fetch `url` and return the full result as JSON. */
+char *webpage_info_fetch_json(const char *url);
+
+/* Parse `html` without fetching and return it as JSON. `base_url` may be NULL. */
+char *webpage_info_parse_json(const char *html, const char *base_url);
+
+/* Fetch `url` into a handle; release it with webpage_info_free(). */
+WebpageInfo *webpage_info_fetch(const char *url);
+void webpage_info_free(WebpageInfo *info);
+
+void webpage_info_string_free(char *s);
+
+/* Failure of the previous call on this thread, or NULL; owned by the
+ * library, do not free. */
+const char *webpage_info_last_error(void);
+
+char *webpage_info_to_json(const WebpageInfo *info);
+char *webpage_info_url(const WebpageInfo *info);
+uint16_t webpage_info_status_code(const WebpageInfo *info);
+char *webpage_info_title(const WebpageInfo *info);
+char *webpage_info_description(const WebpageInfo *info);
+char *webpage_info_canonical_url(const WebpageInfo *info);
+char *webpage_info_language(const WebpageInfo *info);
+char *webpage_info_text_content(const WebpageInfo *info);
+size_t webpage_info_link_count(const WebpageInfo *info);
+char *webpage_info_link_url(const WebpageInfo *info, size_t index);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* WEBPAGE_INFO_H */
@@ -0,0 +1,153 @@
+//! Basic accessibility statistics
+//!
+//! A coarse audit of common problems, enabled with [`ParseOptions::a11y`]. It
+//! is not a substitute for a full accessibility checker, but is cheap enough to
+//! run on every page of a crawl.
+//!
+//! [`ParseOptions::a11y`]: crate::ParseOptions::a11y
+
+#[cfg(feature = "dom")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "dom")]
+use scraper::{ElementRef, Html, Selector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Accessibility statistics for a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct A11yStats {
+    /// Number of `<img>` elements
+    pub images: usize,
+
+    /// Images without an `alt` attribute (`alt=""` marks decorative images and is not counted)
+    pub images_missing_alt: usize,
+
+    /// Links with no text, `aria-label`, `aria-labelledby`, `title`, or image `alt`
+    pub links_without_name: usize,
+
+    /// Whether `<html>` has no non-empty `lang` attribute
+    pub missing_lang: bool,
+
+    /// Headings that go more than one level deeper than the previous one (e.g. `h2` to `h4`)
+    pub heading_skips: usize,
+}
+
+#[cfg(feature = "dom")]
+fn image_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("img").unwrap())
+}
+
+#[cfg(feature = "dom")]
+fn anchor_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("a[href]").unwrap())
+}
+
+#[cfg(feature = "dom")]
+fn heading_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("h1, h2, h3, h4, h5, h6").unwrap())
+}
+
+#[cfg(feature = "dom")]
+fn html_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("html").unwrap())
+}
+
+#[cfg(feature = "dom")]
+impl A11yStats {
+    /// Compute statistics for a parsed document.
+    pub(crate) fn collect(document: &Html) -> Self {
+        let images: Vec<ElementRef> = document.select(image_selector()).collect();
+        let images_missing_alt = images
+            .iter()
+            .filter(|img| img.value().attr("alt").is_none())
+            .count();
+
+        let links_without_name = document
+            .select(anchor_selector())
+            .filter(|link| !has_accessible_name(link))
+            .count();
+
+        let missing_lang = document
+            .select(html_selector())
+            .next()
+            .and_then(|html| html.value().attr("lang"))
+            .is_none_or(|lang| lang.trim().is_empty());
+
+        let mut heading_skips = 0;
+        let mut previous = None;
+        for heading in document.select(heading_selector()) {
+            let level = heading.value().name()[1..].parse::<u8>().unwrap_or(1);
+            if previous.is_some_and(|previous| level > previous + 1) {
+                heading_skips += 1;
+            }
+            previous = Some(level);
+        }
+
+        Self {
+            images: images.len(),
+            images_missing_alt,
+            links_without_name,
+            missing_lang,
+            heading_skips,
+        }
+    }
+}
+
+/// Whether a link has text or an attribute that screen readers can announce.
+#[cfg(feature = "dom")]
+fn has_accessible_name(link: &ElementRef) -> bool {
+    let non_empty = |value: Option<&str>| value.is_some_and(|v| !v.trim().is_empty());
+
+    link.text().any(|text| !text.trim().is_empty())
+        || non_empty(link.value().attr("aria-label"))
+        || non_empty(link.value().attr("aria-labelledby"))
+        || non_empty(link.value().attr("title"))
+        || link
+            .select(image_selector())
+            .any(|img| non_empty(img.value().attr("alt")))
+}
+
+#[cfg(all(test, feature = "dom"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collects_common_problems() {
+        let html = r#"<html><body>
+            <h1>Title</h1><h3>Skipped</h3><h2>Back</h2><h4>Skipped again</h4>
+            <img src="a.png"><img src="b.png" alt=""><img src="c.png" alt="Chart">
+            <a href="/empty"> </a>
+            <a href="/icon"><img src="i.png" alt="Home"></a>
+            <a href="/labelled" aria-label="Close"></a>
+            <a href="/bare-icon"><img src="x.png"></a>
+            <a href="/text">Read more</a>
+        </body></html>"#;
+        let stats = A11yStats::collect(&Html::parse_document(html));
+
+        assert_eq!(
+            stats,
+            A11yStats {
+                images: 5,
+                images_missing_alt: 2,
+                links_without_name: 2,
+                missing_lang: true,
+                heading_skips: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_lang_present() {
+        let stats = A11yStats::collect(&Html::parse_document(r#"<html lang="en"></html>"#));
+        assert!(!stats.missing_lang);
+        assert_eq!(stats.heading_skips, 0);
+    }
+}
@@ -0,0 +1,204 @@
+//! Cross-page metadata analysis
+//!
+//! [`BatchAnalysis`] looks at the results of a crawl together and reports the
+//! problems a site audit looks for: pages sharing a title or meta description,
+//! and groups of pages declaring the same canonical URL.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::WebpageInfo;
+use crate::consistency::collapse_whitespace;
+use crate::url_util::{self, NormalizeOptions};
+
+/// Pages sharing one value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct DuplicateGroup {
+    /// The shared value (whitespace collapsed; canonical URLs normalized)
+    pub value: String,
+
+    /// Final URLs of the pages sharing it, in input order
+    pub urls: Vec<String>,
+}
+
+/// Duplicate metadata across many pages.
+///
+/// Only values shared by at least two pages are reported. Groups are in order of
+/// first occurrence.
+///
+/// # Example
+/// ```
+/// use webpage_info::{BatchAnalysis, WebpageInfo};
+///
+/// let pages: Vec<WebpageInfo> = ["/a", "/b", "/c"]
+///     .iter()
+///     .map(|path| {
+///         let mut info = WebpageInfo::default();
+///         info.http.url = format!("https://example.com{path}");
+///         info.html.title = Some(if *path == "/c" { "Contact" } else { "Home" }.to_string());
+///         info
+///     })
+///     .collect();
+///
+/// let analysis = BatchAnalysis::analyze(&pages);
+/// assert_eq!(analysis.duplicate_titles[0].value, "Home");
+/// assert_eq!(analysis.duplicate_titles[0].urls.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct BatchAnalysis {
+    /// Titles used by more than one page
+    pub duplicate_titles: Vec<DuplicateGroup>,
+
+    /// Meta descriptions used by more than one page
+    pub duplicate_descriptions: Vec<DuplicateGroup>,
+
+    /// Pages grouped by the canonical URL they declare
+    pub canonical_clusters: Vec<DuplicateGroup>,
+}
+
+impl BatchAnalysis {
+    /// Analyze a set of fetched pages.
+    pub fn analyze<'a>(pages: impl IntoIterator<Item = &'a WebpageInfo>) -> Self {
+        let mut titles = Grouper::default();
+        let mut descriptions = Grouper::default();
+        let mut canonicals = Grouper::default();
+
+        for page in pages {
+            let url = page.http.url.as_str();
+            if let Some(title) = page.html.title.as_deref() {
+                titles.add(collapse_whitespace(title), url);
+            }
+            if let Some(description) = page.html.description.as_deref() {
+                descriptions.add(collapse_whitespace(description), url);
+            }
+            if let Some(canonical) = page.html.canonical_url.as_deref() {
+                let canonical = url::Url::parse(url)
+                    .and_then(|base| base.join(canonical))
+                    .map_or_else(|_| canonical.to_string(), String::from);
+                let canonical = url_util::normalize(&canonical, &NormalizeOptions::default())
+                    .unwrap_or(canonical);
+                canonicals.add(canonical, url);
+            }
+        }
+
+        Self {
+            duplicate_titles: titles.duplicates(),
+            duplicate_descriptions: descriptions.duplicates(),
+            canonical_clusters: canonicals.duplicates(),
+        }
+    }
+
+    /// Whether no duplicates were found.
+    pub fn is_clean(&self) -> bool {
+        self.duplicate_titles.is_empty()
+            && self.duplicate_descriptions.is_empty()
+            && self.canonical_clusters.is_empty()
+    }
+}
+
+/// Groups URLs by value, remembering first-occurrence order.
+#[derive(Default)]
+struct Grouper {
+    groups: Vec<DuplicateGroup>,
+    index: HashMap<String, usize>,
+}
+
+impl Grouper {
+    fn add(&mut self, value: String, url: &str) {
+        if value.is_empty() {
+            return;
+        }
+        let position = *self.index.entry(value.clone()).or_insert_with(|| {
+            self.groups.push(DuplicateGroup {
+                value,
+                urls: Vec::new(),
+            });
+            self.groups.len() - 1
+        });
+        self.groups[position].urls.push(url.to_string());
+    }
+
+    fn duplicates(self) -> Vec<DuplicateGroup> {
+        self.groups
+            .into_iter()
+            .filter(|group| group.urls.len() > 1)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(url: &str, title: &str, description: &str, canonical: Option<&str>) -> WebpageInfo {
+        let mut info = WebpageInfo::default();
+        info.http.url = url.to_string();
+        info.html.title = Some(title.to_string());
+        info.html.description = Some(description.to_string());
+        info.html.canonical_url = canonical.map(str::to_string);
+        info
+    }
+
+    #[test]
+    fn test_reports_duplicates_and_clusters() {
+        let pages = [
+            page(
+                "https://example.com/a",
+                "Shoes",
+                "Buy shoes",
+                Some("/shoes"),
+            ),
+            page(
+                "https://example.com/b",
+                "Shoes ",
+                "Buy  shoes",
+                Some("https://example.com/shoes/"),
+            ),
+            page("https://example.com/c", "Hats", "Buy hats", None),
+            page("https://example.com/d", "Socks", "Buy hats", Some("/socks")),
+        ];
+        let analysis = BatchAnalysis::analyze(&pages);
+
+        let urls = |paths: &[&str]| -> Vec<String> {
+            paths
+                .iter()
+                .map(|p| format!("https://example.com{p}"))
+                .collect()
+        };
+        assert_eq!(
+            analysis.duplicate_titles,
+            [DuplicateGroup {
+                value: "Shoes".to_string(),
+                urls: urls(&["/a", "/b"]),
+            }]
+        );
+        let descriptions: Vec<_> = analysis
+            .duplicate_descriptions
+            .iter()
+            .map(|g| g.value.as_str())
+            .collect();
+        assert_eq!(descriptions, ["Buy shoes", "Buy hats"]);
+        assert_eq!(
+            analysis.canonical_clusters,
+            [DuplicateGroup {
+                value: "https://example.com/shoes".to_string(),
+                urls: urls(&["/a", "/b"]),
+            }]
+        );
+        assert!(!analysis.is_clean());
+    }
+
+    #[test]
+    fn test_unique_pages_are_clean() {
+        let pages = [
+            page("https://example.com/a", "A", "First", None),
+            page("https://example.com/b", "B", "Second", None),
+        ];
+        assert!(BatchAnalysis::analyze(&pages).is_clean());
+    }
+}
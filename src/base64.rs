@@ -0,0 +1,83 @@
+//! Standard base64, for snapshot bodies and MIME parts
+
+#[cfg(feature = "http")]
+use serde::de::Error as _;
+#[cfg(feature = "http")]
+use serde::{Deserialize, Deserializer, Serializer};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Serde adapter: serialize bytes as a base64 string.
+#[cfg(feature = "http")]
+pub(crate) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode(bytes))
+}
+
+/// Serde adapter: deserialize bytes from a base64 string.
+#[cfg(feature = "http")]
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    decode(&encoded).ok_or_else(|| D::Error::custom("invalid base64 body"))
+}
+
+/// Encode bytes with padding.
+#[cfg(feature = "http")]
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decode padded or unpadded base64, skipping line breaks and other whitespace.
+pub(crate) fn decode(encoded: &str) -> Option<Vec<u8>> {
+    let encoded: Vec<u8> = encoded
+        .trim_end_matches(|c: char| c == '=' || c.is_ascii_whitespace())
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    if encoded.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(encoded.len() * 3 / 4);
+    for chunk in encoded.chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = ALPHABET.iter().position(|&a| a == c)? as u32;
+            n |= value << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_round_trip() {
+        for input in [&b""[..], b"f", b"fo", b"foo", b"foob", b"\xff\x00\xfe"] {
+            let encoded = encode(input);
+            assert_eq!(encoded.len() % 4, 0);
+            assert_eq!(decode(&encoded).as_deref(), Some(input));
+        }
+        assert_eq!(encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(decode("Zm9vYg=="), Some(b"foob".to_vec()));
+        assert_eq!(decode("Zm9v\r\nYmFy\n"), Some(b"foobar".to_vec()));
+        assert_eq!(decode("Zm9v!"), None);
+    }
+}
@@ -0,0 +1,140 @@
+//! One-shot fetch configuration
+//!
+//! [`WebpageInfo::builder`] combines [`HttpOptions`] and [`ParseOptions`] in a
+//! single fluent chain for fetching one page.
+
+use std::time::Duration;
+
+use crate::WebpageInfo;
+use crate::client::WebpageClient;
+use crate::error::Result;
+use crate::html::ParseOptions;
+use crate::http::HttpOptions;
+
+/// Builder for fetching a single page, created by [`WebpageInfo::builder`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use webpage_info::{ParseOptions, WebpageInfo};
+///
+/// #[tokio::main]
+/// async fn main() -> webpage_info::Result<()> {
+///     let info = WebpageInfo::builder("https://example.org")
+///         .user_agent("PreviewBot/1.0")
+///         .timeout(Duration::from_secs(5))
+///         .extract(ParseOptions::metadata_only())
+///         .fetch()
+///         .await?;
+///     println!("Title: {:?}", info.html.title);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[must_use = "a FetchBuilder does nothing until `fetch` is awaited"]
+pub struct FetchBuilder {
+    url: String,
+    options: HttpOptions,
+    parse_options: ParseOptions,
+}
+
+impl FetchBuilder {
+    pub(crate) fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            options: HttpOptions::default(),
+            parse_options: ParseOptions::default(),
+        }
+    }
+
+    /// Replace all HTTP options.
+    pub fn http_options(mut self, options: HttpOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the User-Agent header, from a string or a [`UserAgent`](crate::UserAgent) preset.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.options = self.options.user_agent(user_agent);
+        self
+    }
+
+    /// Set the request timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.options = self.options.timeout(timeout);
+        self
+    }
+
+    /// Add a custom header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options = self.options.header(name, value);
+        self
+    }
+
+    /// Set the maximum response body size in bytes.
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.options = self.options.max_body_size(size);
+        self
+    }
+
+    /// Set whether to block requests to private/internal IP addresses.
+    pub fn block_private_ips(mut self, block: bool) -> Self {
+        self.options = self.options.block_private_ips(block);
+        self
+    }
+
+    /// Parse the page with `options`.
+    pub fn extract(mut self, options: ParseOptions) -> Self {
+        self.parse_options = options;
+        self
+    }
+
+    /// Fetch and parse the page.
+    pub async fn fetch(self) -> Result<WebpageInfo> {
+        WebpageClient::new(self.options)?
+            .with_parse_options(self.parse_options)
+            .fetch(&self.url)
+            .await
+    }
+}
+
+impl WebpageInfo {
+    /// Start configuring a fetch of `url`.
+    pub fn builder(url: impl Into<String>) -> FetchBuilder {
+        FetchBuilder::new(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{header, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_builder_combines_http_and_parse_options() {
+        let server = MockServer::start().await;
+        Mock::given(path("/article"))
+            .and(header("user-agent", "PreviewBot/1.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                "<title>Article</title><body><p>Body text</p><a href=\"/x\">x</a></body>",
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+
+        let info = WebpageInfo::builder(format!("{}/article", server.uri()))
+            .block_private_ips(false)
+            .user_agent("PreviewBot/1.0")
+            .timeout(Duration::from_secs(5))
+            .extract(ParseOptions::metadata_only())
+            .fetch()
+            .await
+            .unwrap();
+        assert_eq!(info.html.title.as_deref(), Some("Article"));
+        assert!(info.html.text_content.is_empty());
+        assert!(info.html.links.is_empty());
+    }
+}
@@ -0,0 +1,491 @@
+//! HTTP caching with conditional requests
+//!
+//! With [`HttpOptions::cache`] set, a [`WebpageClient`] stores each response
+//! that carries an `ETag` or `Last-Modified` validator in a [`Cache`]. The
+//! next fetch of the same URL sends `If-None-Match` and `If-Modified-Since`,
+//! and a `304 Not Modified` answer is served from the stored response, so
+//! polling unchanged pages costs no body download.
+//!
+//! A response with a `Vary` header is only reused for requests that send the
+//! same values of the headers it names, such as a rotated `User-Agent`;
+//! `Vary: *` responses are not stored.
+//!
+//! [`WebpageClient`]: crate::WebpageClient
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use url::Url;
+
+use crate::client::ClientState;
+use crate::http::{HttpInfo, HttpOptions};
+
+/// Default [`MemoryCache`] size limit.
+const DEFAULT_MAX_BYTES: usize = 64 * 1024 * 1024; // 64 MB
+
+/// Storage for cached responses, keyed by the requested URL.
+///
+/// A response with a `Vary` header is stored under a key that extends the URL
+/// with the request's values of the headers it varies by, and a placeholder
+/// holding only the `Vary` header is stored under the URL itself.
+///
+/// Implementations must be cheap to call, since lookups happen on the fetch
+/// path; [`MemoryCache`] keeps responses in memory.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Mutex;
+/// use webpage_info::{Cache, CachePolicy, HttpInfo, HttpOptions};
+///
+/// /// Keeps only the most recently stored response.
+/// #[derive(Default)]
+/// struct LastOnly(Mutex<Option<(String, HttpInfo)>>);
+///
+/// impl Cache for LastOnly {
+///     fn get(&self, url: &str) -> Option<HttpInfo> {
+///         let last = self.0.lock().unwrap();
+///         last.as_ref().filter(|(key, _)| key == url).map(|(_, info)| info.clone())
+///     }
+///
+///     fn put(&self, url: &str, info: HttpInfo) {
+///         *self.0.lock().unwrap() = Some((url.to_string(), info));
+///     }
+/// }
+///
+/// let options = HttpOptions::new().cache(CachePolicy::new(LastOnly::default()));
+/// ```
+pub trait Cache: Send + Sync {
+    /// The stored response for `url`, if any.
+    fn get(&self, url: &str) -> Option<HttpInfo>;
+
+    /// Store the response for `url`, replacing any previous one.
+    fn put(&self, url: &str, info: HttpInfo);
+}
+
+/// Lets callers keep a handle on a cache (e.g. to clear it).
+impl<T: Cache + ?Sized> Cache for Arc<T> {
+    fn get(&self, url: &str) -> Option<HttpInfo> {
+        (**self).get(url)
+    }
+
+    fn put(&self, url: &str, info: HttpInfo) {
+        (**self).put(url, info)
+    }
+}
+
+/// In-memory [`Cache`] holding one response per key, evicting the least
+/// recently used responses beyond a size limit.
+#[derive(Debug)]
+pub struct MemoryCache {
+    max_bytes: usize,
+    entries: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    map: HashMap<String, Entry>,
+    /// Keys by last use, oldest first
+    recency: BTreeMap<u64, String>,
+    bytes: usize,
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    info: HttpInfo,
+    size: usize,
+    last_used: u64,
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::with_max_bytes(DEFAULT_MAX_BYTES)
+    }
+}
+
+impl MemoryCache {
+    /// Create an empty cache holding up to 64 MB of responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty cache holding up to `max_bytes` of responses, measured
+    /// by their bodies and headers. Responses larger than that are not stored.
+    pub fn with_max_bytes(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Number of cached entries.
+    pub fn len(&self) -> usize {
+        self.lock().map.len()
+    }
+
+    /// Whether nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.lock().map.is_empty()
+    }
+
+    /// Approximate size of the cached responses in bytes.
+    pub fn size(&self) -> usize {
+        self.lock().bytes
+    }
+
+    /// Drop every cached response.
+    pub fn clear(&self) {
+        *self.lock() = Entries::default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Entries {
+    fn touch(&mut self, key: &str) -> Option<&Entry> {
+        self.clock += 1;
+        let entry = self.map.get_mut(key)?;
+        let key = self.recency.remove(&entry.last_used)?;
+        entry.last_used = self.clock;
+        self.recency.insert(self.clock, key);
+        Some(entry)
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.map.remove(key) {
+            self.recency.remove(&entry.last_used);
+            self.bytes -= entry.size;
+        }
+    }
+}
+
+impl Cache for MemoryCache {
+    fn get(&self, url: &str) -> Option<HttpInfo> {
+        self.lock().touch(url).map(|entry| entry.info.clone())
+    }
+
+    fn put(&self, url: &str, info: HttpInfo) {
+        let size = url.len()
+            + info.body.len()
+            + (info.headers.iter())
+                .map(|(name, value)| name.len() + value.len())
+                .sum::<usize>()
+            + info.snapshot.as_ref().map_or(0, |s| s.body.len());
+        let mut entries = self.lock();
+        entries.remove(url);
+        if size > self.max_bytes {
+            return;
+        }
+        while entries.bytes + size > self.max_bytes {
+            let Some((_, oldest)) = entries.recency.pop_first() else {
+                break;
+            };
+            if let Some(entry) = entries.map.remove(&oldest) {
+                entries.bytes -= entry.size;
+            }
+        }
+        entries.clock += 1;
+        let last_used = entries.clock;
+        entries.recency.insert(last_used, url.to_string());
+        entries.bytes += size;
+        entries.map.insert(
+            url.to_string(),
+            Entry {
+                info,
+                size,
+                last_used,
+            },
+        );
+    }
+}
+
+/// Where and how a client caches responses, set with [`HttpOptions::cache`].
+///
+/// Clones share the same storage, so every clone of a client (and every client
+/// given a clone of the policy) revalidates against one cache.
+#[derive(Clone)]
+pub struct CachePolicy {
+    cache: Arc<dyn Cache>,
+}
+
+impl CachePolicy {
+    /// Cache responses in `cache`.
+    pub fn new(cache: impl Cache + 'static) -> Self {
+        Self {
+            cache: Arc::new(cache),
+        }
+    }
+
+    /// Cache responses in a new [`MemoryCache`].
+    pub fn memory() -> Self {
+        Self::new(MemoryCache::new())
+    }
+
+    /// The storage responses are cached in.
+    pub fn cache(&self) -> &dyn Cache {
+        self.cache.as_ref()
+    }
+
+    /// The stored response to revalidate a request with, if the request can
+    /// use the cache.
+    ///
+    /// Range requests and requests that already carry their own validators,
+    /// such as [`WebpageInfo::refetch`](crate::WebpageInfo::refetch), bypass it.
+    /// `request` holds the headers the request will be sent with.
+    pub(crate) fn lookup(
+        &self,
+        url: &str,
+        options: &HttpOptions,
+        request: &HeaderMap,
+    ) -> Option<HttpInfo> {
+        let conditional = options.headers.iter().any(|(name, _)| {
+            name.eq_ignore_ascii_case("if-none-match")
+                || name.eq_ignore_ascii_case("if-modified-since")
+        });
+        if conditional || options.range.is_some() {
+            return None;
+        }
+        let cached = self.cache.get(url)?;
+        let vary = cached.vary();
+        let cached = if vary.is_empty() {
+            cached
+        } else {
+            self.cache.get(&variant_key(url, &vary, request))?
+        };
+        Some(cached).filter(|cached| validators(cached).next().is_some())
+    }
+
+    /// Store a fresh response to a request sent with `request` headers if it
+    /// can be revalidated later.
+    pub(crate) fn store(&self, url: &str, info: &HttpInfo, request: &HeaderMap) {
+        let no_store = info.header("cache-control").is_some_and(|value| {
+            value
+                .split(',')
+                .any(|directive| directive.trim().eq_ignore_ascii_case("no-store"))
+        });
+        if info.status_code != 200 || no_store || validators(info).next().is_none() {
+            return;
+        }
+        let vary = info.vary();
+        if vary.is_empty() {
+            self.cache.put(url, info.clone());
+        } else if !vary.iter().any(|name| name == "*") {
+            self.cache
+                .put(&variant_key(url, &vary, request), info.clone());
+            // Tells lookups which request headers select the variant
+            let placeholder = HttpInfo {
+                url: info.url.clone(),
+                status_code: info.status_code,
+                headers: (info.headers.iter())
+                    .filter(|(name, _)| name.eq_ignore_ascii_case("vary"))
+                    .cloned()
+                    .collect(),
+                ..HttpInfo::default()
+            };
+            self.cache.put(url, placeholder);
+        }
+    }
+}
+
+impl fmt::Debug for CachePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachePolicy").finish_non_exhaustive()
+    }
+}
+
+/// The cache key of the variant of `url` selected by the request's values of
+/// the `vary` headers.
+fn variant_key(url: &str, vary: &[String], request: &HeaderMap) -> String {
+    let mut key = url.to_string();
+    for name in vary {
+        let value = request.get(name).and_then(|v| v.to_str().ok());
+        key.push_str(&format!("\n{name}: {}", value.unwrap_or_default()));
+    }
+    key
+}
+
+/// The headers a request to `url` starts with, as far as a `Vary` response
+/// header can select on them.
+pub(crate) fn request_headers(url: &str, options: &HttpOptions, state: &ClientState) -> HeaderMap {
+    let Ok(url) = Url::parse(url) else {
+        return HeaderMap::new();
+    };
+    let mut headers = options.headers_for(&url, &url.origin());
+    if let Ok(user_agent) = HeaderValue::from_str(&options.user_agent) {
+        headers.insert(header::USER_AGENT, user_agent);
+    }
+    state.cookies.add_header(&mut headers, &url, options);
+    headers
+}
+
+/// The conditional request headers that revalidate `cached`.
+pub(crate) fn validators(cached: &HttpInfo) -> impl Iterator<Item = (&'static str, &str)> {
+    [
+        ("If-None-Match", cached.header("etag")),
+        ("If-Modified-Since", cached.header("last-modified")),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| Some((name, value?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{header, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::WebpageClient;
+
+    #[tokio::test]
+    async fn test_not_modified_is_served_from_cache() {
+        let server = MockServer::start().await;
+        Mock::given(path("/feed"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/feed"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"v1\"")
+                    .set_body_raw("<title>Feed</title>", "text/html"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/private"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"p\"")
+                    .insert_header("cache-control", "private, no-store")
+                    .set_body_raw("<title>Private</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        let cache = Arc::new(MemoryCache::new());
+        let options = HttpOptions::new()
+            .block_private_ips(false)
+            .cache(CachePolicy::new(cache.clone()));
+        let client = WebpageClient::new(options).unwrap();
+        let url = format!("{}/feed", server.uri());
+
+        let first = client.fetch(&url).await.unwrap();
+        assert_eq!(first.http.status_code, 200);
+        let second = client.fetch(&url).await.unwrap();
+        assert_eq!(second.http.status_code, 200);
+        assert_eq!(second.html.title.as_deref(), Some("Feed"));
+
+        client
+            .fetch(&format!("{}/private", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(cache.len(), 1);
+
+        let stats = client.stats();
+        assert_eq!(stats.cache_misses, 2);
+        assert_eq!(stats.cache_revalidations, 1);
+        assert_eq!(stats.cache_hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_vary_keys_by_request_headers() {
+        use crate::RotationPolicy;
+
+        let server = MockServer::start().await;
+        Mock::given(path("/page"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"v1\"")
+                    .insert_header("vary", "User-Agent")
+                    .set_body_raw("<title>Page</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/any"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"v1\"")
+                    .insert_header("vary", "*")
+                    .set_body_raw("<title>Any</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        let policy = CachePolicy::memory();
+        let options = HttpOptions::new()
+            .block_private_ips(false)
+            .cache(policy.clone());
+        let client = WebpageClient::new(options.clone()).unwrap();
+        let url = format!("{}/page", server.uri());
+        client.fetch(&url).await.unwrap();
+
+        let request = |options: &HttpOptions| request_headers(&url, options, &client.state);
+        assert!(policy.lookup(&url, &options, &request(&options)).is_some());
+        let other = options.clone().user_agent("other-agent/1.0");
+        assert!(policy.lookup(&url, &other, &request(&other)).is_none());
+
+        // A rotated user agent selects its own variant
+        let rotating = WebpageClient::new(options.clone())
+            .unwrap()
+            .with_user_agents(["other-agent/1.0"], RotationPolicy::PerRequest);
+        rotating.fetch(&url).await.unwrap();
+        assert!(policy.lookup(&url, &other, &request(&other)).is_some());
+
+        let any = format!("{}/any", server.uri());
+        client.fetch(&any).await.unwrap();
+        assert!(policy.cache().get(&any).is_none());
+    }
+
+    #[test]
+    fn test_memory_cache_evicts_least_recently_used() {
+        let page = |body: &str| HttpInfo {
+            body: body.to_string(),
+            ..HttpInfo::default()
+        };
+        let cache = MemoryCache::with_max_bytes(30);
+        cache.put("a", page("0123456789"));
+        cache.put("b", page("0123456789"));
+        assert!(cache.get("a").is_some());
+        cache.put("c", page("0123456789"));
+
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.size(), 22);
+
+        cache.put("big", page(&"x".repeat(40)));
+        assert!(cache.get("big").is_none());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_lookup_skips_conditional_and_range_requests() {
+        let policy = CachePolicy::memory();
+        let info = HttpInfo {
+            status_code: 200,
+            headers: vec![(
+                "last-modified".into(),
+                "Tue, 13 Oct 2026 08:00:00 GMT".into(),
+            )],
+            ..HttpInfo::default()
+        };
+        let request = HeaderMap::new();
+        policy.store("https://example.com/", &info, &request);
+
+        let options = HttpOptions::new();
+        let lookup = |url, options| policy.lookup(url, options, &request);
+        assert!(lookup("https://example.com/", &options).is_some());
+        assert!(lookup("https://example.com/other", &options).is_none());
+        let range = HttpOptions::new().range(0..100);
+        assert!(lookup("https://example.com/", &range).is_none());
+        let conditional = HttpOptions::new().header("If-None-Match", "\"x\"");
+        assert!(lookup("https://example.com/", &conditional).is_none());
+    }
+}
@@ -0,0 +1,277 @@
+//! CDN detection from response headers
+//!
+//! CDNs mark the responses they serve with their own headers: a request ID,
+//! the edge location (point of presence) that answered, and whether it came
+//! from cache. [`HttpInfo::cdn`] reads the common ones into a [`CdnInfo`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::http::HttpInfo;
+
+/// A content delivery network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CdnProvider {
+    /// Cloudflare
+    Cloudflare,
+    /// Fastly
+    Fastly,
+    /// Akamai
+    Akamai,
+    /// Amazon CloudFront
+    CloudFront,
+    /// Azure Front Door
+    AzureFrontDoor,
+    /// Vercel's edge network
+    Vercel,
+    /// Netlify's edge network
+    Netlify,
+    /// Bunny CDN
+    Bunny,
+}
+
+/// Whether the CDN served the response from its cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CacheStatus {
+    /// Served from cache (including after revalidation)
+    Hit,
+    /// Fetched from the origin, and possibly cached
+    Miss,
+    /// Served from cache after it expired
+    Stale,
+    /// Not cacheable; the cache was skipped
+    Bypass,
+}
+
+impl CacheStatus {
+    /// Parse a cache status value such as `HIT`, `Miss from cloudfront`, or
+    /// `TCP_MEM_HIT`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.to_ascii_lowercase();
+        let has = |word: &str| {
+            value
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .any(|token| token == word)
+        };
+        if has("stale") || has("expired") || has("updating") {
+            Some(Self::Stale)
+        } else if has("hit") || has("revalidated") {
+            Some(Self::Hit)
+        } else if has("miss") {
+            Some(Self::Miss)
+        } else if has("bypass") || has("dynamic") || has("pass") {
+            Some(Self::Bypass)
+        } else {
+            None
+        }
+    }
+}
+
+/// What the response headers say about the CDN that served it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct CdnInfo {
+    /// The CDN, if recognized
+    pub provider: Option<CdnProvider>,
+
+    /// Cache status at the edge that answered
+    pub cache_status: Option<CacheStatus>,
+
+    /// Edge location that answered, as the CDN names it (e.g. `"LHR"` or
+    /// `"LHR61-P1"`)
+    pub pop: Option<String>,
+
+    /// The CDN's request ID, for support tickets and log correlation
+    pub request_id: Option<String>,
+
+    /// `Server` header
+    pub server: Option<String>,
+}
+
+impl CdnInfo {
+    /// Whether no CDN signal was found.
+    pub fn is_empty(&self) -> bool {
+        self.provider.is_none() && self.cache_status.is_none() && self.pop.is_none()
+    }
+}
+
+impl HttpInfo {
+    /// The CDN that served the response, its cache status, and edge location.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{CacheStatus, CdnProvider, HttpInfo};
+    ///
+    /// let http = HttpInfo {
+    ///     headers: vec![
+    ///         ("cf-ray".to_string(), "8a1b2c3d4e5f6789-LHR".to_string()),
+    ///         ("cf-cache-status".to_string(), "HIT".to_string()),
+    ///     ],
+    ///     ..HttpInfo::default()
+    /// };
+    /// let cdn = http.cdn();
+    /// assert_eq!(cdn.provider, Some(CdnProvider::Cloudflare));
+    /// assert_eq!(cdn.cache_status, Some(CacheStatus::Hit));
+    /// assert_eq!(cdn.pop.as_deref(), Some("LHR"));
+    /// ```
+    pub fn cdn(&self) -> CdnInfo {
+        let header = |name: &str| self.header(name).map(str::trim).filter(|v| !v.is_empty());
+        let server = header("server").map(str::to_string);
+        let server_is = |name: &str| {
+            server
+                .as_deref()
+                .is_some_and(|s| s.to_ascii_lowercase().starts_with(name))
+        };
+        let via = header("via").unwrap_or_default().to_ascii_lowercase();
+        let x_cache = header("x-cache");
+
+        let mut info = CdnInfo {
+            server: server.clone(),
+            ..CdnInfo::default()
+        };
+        if let Some(ray) = header("cf-ray") {
+            info.provider = Some(CdnProvider::Cloudflare);
+            info.request_id = Some(ray.to_string());
+            info.pop = ray.rsplit_once('-').map(|(_, pop)| pop.to_string());
+            info.cache_status = header("cf-cache-status").and_then(CacheStatus::parse);
+        } else if server_is("cloudflare") {
+            info.provider = Some(CdnProvider::Cloudflare);
+            info.cache_status = header("cf-cache-status").and_then(CacheStatus::parse);
+        } else if header("x-amz-cf-id").is_some() || via.contains("cloudfront") {
+            info.provider = Some(CdnProvider::CloudFront);
+            info.request_id = header("x-amz-cf-id").map(str::to_string);
+            info.pop = header("x-amz-cf-pop").map(str::to_string);
+        } else if let Some(id) = header("x-vercel-id") {
+            info.provider = Some(CdnProvider::Vercel);
+            info.request_id = Some(id.to_string());
+            info.pop = id.split("::").next().map(str::to_string);
+            info.cache_status = header("x-vercel-cache").and_then(CacheStatus::parse);
+        } else if let Some(id) = header("x-nf-request-id") {
+            info.provider = Some(CdnProvider::Netlify);
+            info.request_id = Some(id.to_string());
+        } else if let Some(reference) = header("x-azure-ref") {
+            info.provider = Some(CdnProvider::AzureFrontDoor);
+            info.request_id = Some(reference.to_string());
+        } else if server_is("bunnycdn") {
+            info.provider = Some(CdnProvider::Bunny);
+            info.pop = server
+                .as_deref()
+                .and_then(|s| s.split_once('-'))
+                .map(|(_, pop)| pop.to_string());
+            info.cache_status = header("cdn-cache").and_then(CacheStatus::parse);
+        } else if let Some(served_by) = header("x-served-by").filter(|v| v.starts_with("cache-")) {
+            info.provider = Some(CdnProvider::Fastly);
+            info.request_id = header("x-fastly-request-id").map(str::to_string);
+            // `cache-iad-kiad7000046-IAD, cache-lhr7343-LHR`: the last cache answered
+            info.pop = served_by
+                .rsplit(',')
+                .next()
+                .and_then(|node| node.trim().rsplit_once('-'))
+                .map(|(_, pop)| pop.to_string());
+            // `x-cache` lists each cache in the same order
+            info.cache_status = x_cache
+                .and_then(|v| v.rsplit(',').next())
+                .and_then(CacheStatus::parse);
+        } else if server_is("akamai") || header("x-akamai-request-id").is_some() {
+            info.provider = Some(CdnProvider::Akamai);
+            info.request_id = header("x-akamai-request-id").map(str::to_string);
+        }
+
+        if info.cache_status.is_none() {
+            info.cache_status = x_cache
+                .and_then(CacheStatus::parse)
+                .or_else(|| header("cache-status").and_then(rfc9211_status));
+        }
+        info
+    }
+}
+
+/// The status of the cache nearest the client in an RFC 9211 `Cache-Status`
+/// header, e.g. `Origin; fwd=miss, Edge; hit`.
+fn rfc9211_status(value: &str) -> Option<CacheStatus> {
+    let nearest = value.rsplit(',').next()?;
+    let mut params = nearest.split(';').skip(1).map(str::trim);
+    params.find_map(|param| match param.split_once('=') {
+        None if param.eq_ignore_ascii_case("hit") => Some(CacheStatus::Hit),
+        Some((key, value)) if key.eq_ignore_ascii_case("fwd") => {
+            Some(match value.to_ascii_lowercase().as_str() {
+                "bypass" | "uri-miss" => CacheStatus::Bypass,
+                "stale" => CacheStatus::Stale,
+                _ => CacheStatus::Miss,
+            })
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http(headers: &[(&str, &str)]) -> HttpInfo {
+        HttpInfo {
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            ..HttpInfo::default()
+        }
+    }
+
+    #[test]
+    fn test_providers() {
+        let cdn = http(&[
+            ("x-cache", "Miss from cloudfront"),
+            ("x-amz-cf-pop", "LHR61-P1"),
+            ("x-amz-cf-id", "abc=="),
+        ])
+        .cdn();
+        assert_eq!(cdn.provider, Some(CdnProvider::CloudFront));
+        assert_eq!(cdn.cache_status, Some(CacheStatus::Miss));
+        assert_eq!(cdn.pop.as_deref(), Some("LHR61-P1"));
+
+        let cdn = http(&[
+            (
+                "x-served-by",
+                "cache-iad-kiad7000046-IAD, cache-lhr7343-LHR",
+            ),
+            ("x-cache", "MISS, HIT"),
+        ])
+        .cdn();
+        assert_eq!(cdn.provider, Some(CdnProvider::Fastly));
+        assert_eq!(cdn.cache_status, Some(CacheStatus::Hit));
+        assert_eq!(cdn.pop.as_deref(), Some("LHR"));
+
+        let cdn = http(&[
+            ("x-vercel-id", "fra1::iad1::abc-123"),
+            ("x-vercel-cache", "STALE"),
+        ])
+        .cdn();
+        assert_eq!(cdn.provider, Some(CdnProvider::Vercel));
+        assert_eq!(cdn.cache_status, Some(CacheStatus::Stale));
+        assert_eq!(cdn.pop.as_deref(), Some("fra1"));
+
+        let cdn = http(&[
+            ("server", "AkamaiGHost"),
+            ("x-cache", "TCP_MEM_HIT from a23-1-2-3"),
+        ])
+        .cdn();
+        assert_eq!(cdn.provider, Some(CdnProvider::Akamai));
+        assert_eq!(cdn.cache_status, Some(CacheStatus::Hit));
+    }
+
+    #[test]
+    fn test_no_cdn() {
+        let cdn = http(&[("server", "nginx")]).cdn();
+        assert!(cdn.is_empty());
+        assert_eq!(cdn.server.as_deref(), Some("nginx"));
+
+        let cdn = http(&[("cache-status", "Origin; fwd=uri-miss, Edge; hit; ttl=30")]).cdn();
+        assert_eq!(cdn.provider, None);
+        assert_eq!(cdn.cache_status, Some(CacheStatus::Hit));
+    }
+}
@@ -0,0 +1,240 @@
+//! Bot-challenge detection
+//!
+//! Anti-bot services answer suspicious requests with an interstitial (a
+//! JavaScript check or a CAPTCHA) instead of the page. [`WebpageInfo::challenge`]
+//! recognizes them from the status code, headers, and markers in the body, and
+//! [`WebpageInfo::verdict`] folds that together with the other page heuristics
+//! into one [`FetchVerdict`] for routing crawl results.
+
+use serde::{Deserialize, Serialize};
+
+use crate::WebpageInfo;
+use crate::http::HttpInfo;
+
+/// Pages with fewer words than this count as thin content.
+const THIN_CONTENT_WORDS: usize = 150;
+
+/// Body markers of each provider's challenge page. One is enough, at any
+/// status.
+const BODY_MARKERS: &[(ChallengeProvider, &str)] = &[
+    (
+        ChallengeProvider::Cloudflare,
+        "/cdn-cgi/challenge-platform/",
+    ),
+    (ChallengeProvider::Cloudflare, "window._cf_chl_opt"),
+    (ChallengeProvider::PerimeterX, "captcha.px-cdn.net"),
+    (ChallengeProvider::PerimeterX, "_pxCaptcha"),
+    (ChallengeProvider::DataDome, "captcha-delivery.com"),
+    (ChallengeProvider::Imperva, "/_Incapsula_Resource"),
+    (ChallengeProvider::Imperva, "Incapsula incident ID"),
+];
+
+/// Widgets that make a thin page a CAPTCHA.
+const CAPTCHA_MARKERS: &[&str] = &["g-recaptcha", "h-captcha", "cf-turnstile"];
+
+/// Titles of block pages, matched together with a `403`/`429`/`503` status.
+const BLOCK_TITLES: &[&str] = &[
+    "just a moment...",
+    "attention required! | cloudflare",
+    "access denied",
+    "pardon our interruption",
+];
+
+/// The service that served a challenge page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeProvider {
+    /// Cloudflare Bot Management or "Under Attack" mode
+    Cloudflare,
+    /// Akamai Bot Manager
+    Akamai,
+    /// PerimeterX (HUMAN)
+    PerimeterX,
+    /// DataDome
+    DataDome,
+    /// Imperva (Incapsula)
+    Imperva,
+    /// A CAPTCHA widget on an otherwise empty page, from an unknown service
+    Other,
+}
+
+/// What a crawl pipeline should make of a fetched page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FetchVerdict {
+    /// The page looks like the real content
+    Ok,
+    /// An anti-bot interstitial was served instead of the page; retry with a
+    /// different fetch strategy, such as a headless browser
+    Challenged(ChallengeProvider),
+    /// The page is behind a login wall ([`WebpageInfo::requires_login`])
+    LoginRequired,
+    /// The server answered `4xx`/`5xx`
+    ErrorStatus(u16),
+    /// A `2xx` error page ([`WebpageInfo::looks_like_soft_404`])
+    Soft404,
+}
+
+impl HttpInfo {
+    /// The anti-bot service whose headers mark this response as a challenge.
+    ///
+    /// Only header signals are used; [`WebpageInfo::challenge`] also looks at
+    /// the page.
+    pub fn challenge_provider(&self) -> Option<ChallengeProvider> {
+        if self
+            .header("cf-mitigated")
+            .is_some_and(|v| v.eq_ignore_ascii_case("challenge"))
+        {
+            return Some(ChallengeProvider::Cloudflare);
+        }
+        if !matches!(self.status_code, 403 | 429 | 503) {
+            return None;
+        }
+        if self.header("x-datadome").is_some() {
+            return Some(ChallengeProvider::DataDome);
+        }
+        let server = self.header("server").unwrap_or_default();
+        if server.eq_ignore_ascii_case("AkamaiGHost") {
+            return Some(ChallengeProvider::Akamai);
+        }
+        None
+    }
+}
+
+impl WebpageInfo {
+    /// The anti-bot service that served a challenge instead of the page, if
+    /// the response looks like one.
+    ///
+    /// Any of these signals is enough:
+    ///
+    /// - a `cf-mitigated: challenge` header
+    /// - a `403`/`429`/`503` from a bot manager's edge (`x-datadome`,
+    ///   `Server: AkamaiGHost`)
+    /// - a provider's challenge script or resource in the body
+    /// - a `403`/`429`/`503` titled like a block page ("Just a moment...",
+    ///   "Access Denied")
+    /// - a reCAPTCHA, hCaptcha, or Turnstile widget on a page with thin content
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{ChallengeProvider, FetchVerdict, WebpageInfo};
+    ///
+    /// let mut info = WebpageInfo::default();
+    /// info.http.status_code = 403;
+    /// info.http.headers = vec![("cf-mitigated".to_string(), "challenge".to_string())];
+    /// assert_eq!(info.challenge(), Some(ChallengeProvider::Cloudflare));
+    /// assert_eq!(info.verdict(), FetchVerdict::Challenged(ChallengeProvider::Cloudflare));
+    /// ```
+    pub fn challenge(&self) -> Option<ChallengeProvider> {
+        if let Some(provider) = self.http.challenge_provider() {
+            return Some(provider);
+        }
+
+        let body = &self.http.body;
+        if let Some((provider, _)) = BODY_MARKERS
+            .iter()
+            .find(|(_, marker)| body.contains(marker))
+        {
+            return Some(*provider);
+        }
+
+        let blocked = matches!(self.http.status_code, 403 | 429 | 503);
+        let block_title = self.html.title.as_deref().is_some_and(|title| {
+            let title = title.to_lowercase();
+            BLOCK_TITLES.contains(&title.trim())
+        });
+        if blocked && block_title {
+            let server = self.http.header("server").unwrap_or_default();
+            return Some(if server.eq_ignore_ascii_case("cloudflare") {
+                ChallengeProvider::Cloudflare
+            } else {
+                ChallengeProvider::Other
+            });
+        }
+
+        let captcha = CAPTCHA_MARKERS.iter().any(|marker| body.contains(marker));
+        (captcha && self.html.word_count() < THIN_CONTENT_WORDS).then_some(ChallengeProvider::Other)
+    }
+
+    /// Classify the fetch for routing: challenged, behind a login, an error
+    /// status, a soft 404, or fine, checked in that order.
+    pub fn verdict(&self) -> FetchVerdict {
+        if let Some(provider) = self.challenge() {
+            FetchVerdict::Challenged(provider)
+        } else if self.requires_login() {
+            FetchVerdict::LoginRequired
+        } else if self.http.status_code >= 400 {
+            FetchVerdict::ErrorStatus(self.http.status_code)
+        } else if self.looks_like_soft_404() {
+            FetchVerdict::Soft404
+        } else {
+            FetchVerdict::Ok
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(status_code: u16, title: &str, body: &str) -> WebpageInfo {
+        let mut info = WebpageInfo::default();
+        info.http.status_code = status_code;
+        info.http.url = "https://example.com/products/1".to_string();
+        info.http.body = body.to_string();
+        info.html.title = Some(title.to_string());
+        info.html.text_content = "Product description ".repeat(100);
+        info
+    }
+
+    #[test]
+    fn test_challenge_signals() {
+        let mut info = page(403, "Access Denied", "");
+        info.http.headers = vec![("Server".to_string(), "AkamaiGHost".to_string())];
+        assert_eq!(info.challenge(), Some(ChallengeProvider::Akamai));
+
+        let info = page(
+            200,
+            "Shop",
+            r#"<script src="https://captcha-delivery.com/c.js"></script>"#,
+        );
+        assert_eq!(info.challenge(), Some(ChallengeProvider::DataDome));
+
+        let mut info = page(503, "Just a moment...", "");
+        assert_eq!(info.challenge(), Some(ChallengeProvider::Other));
+        info.http.headers = vec![("server".to_string(), "cloudflare".to_string())];
+        assert_eq!(info.challenge(), Some(ChallengeProvider::Cloudflare));
+
+        let info = page(200, "Access Denied", "");
+        assert_eq!(info.challenge(), None, "a block title needs a block status");
+    }
+
+    #[test]
+    fn test_captcha_widget_needs_thin_content() {
+        let mut info = page(200, "Contact us", r#"<div class="g-recaptcha"></div>"#);
+        assert_eq!(info.challenge(), None);
+
+        info.html.text_content = "Please verify you are human".to_string();
+        assert_eq!(info.challenge(), Some(ChallengeProvider::Other));
+    }
+
+    #[test]
+    fn test_verdict_order() {
+        assert_eq!(page(200, "Shop", "").verdict(), FetchVerdict::Ok);
+        assert_eq!(
+            page(500, "Server Error", "").verdict(),
+            FetchVerdict::ErrorStatus(500)
+        );
+        assert_eq!(page(401, "", "").verdict(), FetchVerdict::LoginRequired);
+        assert_eq!(
+            page(200, "Page not found", "").verdict(),
+            FetchVerdict::Soft404
+        );
+        assert_eq!(
+            page(403, "Pardon Our Interruption", "").verdict(),
+            FetchVerdict::Challenged(ChallengeProvider::Other)
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Document character encoding detection
+//!
+//! Follows the precedence of the HTML encoding sniffing algorithm: a byte order
+//! mark wins over the `Content-Type` charset, which wins over a `<meta>`
+//! declaration in the first 1024 bytes. Every declaration found is recorded so
+//! disagreements (a common cause of mojibake) can be reported.
+
+use std::fmt;
+
+use encoding_rs::{Encoding, UTF_8};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How many bytes to scan for a `<meta>` charset declaration.
+const META_PRESCAN_LEN: usize = 1024;
+
+/// Where a charset declaration came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CharsetSource {
+    /// A byte order mark at the start of the body
+    Bom,
+    /// The `charset` parameter of the `Content-Type` header
+    ContentType,
+    /// A `<meta charset>` or `<meta http-equiv="Content-Type">` tag
+    Meta,
+    /// Nothing was declared; UTF-8 is assumed
+    #[default]
+    Default,
+}
+
+impl fmt::Display for CharsetSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CharsetSource::Bom => "BOM",
+            CharsetSource::ContentType => "Content-Type",
+            CharsetSource::Meta => "<meta>",
+            CharsetSource::Default => "default",
+        })
+    }
+}
+
+/// The effective charset of a document and every declaration found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct CharsetInfo {
+    /// Effective charset (WHATWG encoding name, lowercase)
+    pub charset: String,
+
+    /// Source the effective charset was taken from
+    pub source: CharsetSource,
+
+    /// Every declaration found, in precedence order
+    pub declared: Vec<(CharsetSource, String)>,
+}
+
+impl CharsetInfo {
+    /// Detect the charset of a response body.
+    ///
+    /// `content_type` is the full `Content-Type` header value, including parameters.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{CharsetInfo, CharsetSource};
+    ///
+    /// let body = b"<meta charset=\"latin1\"><title>Caf\xe9</title>";
+    /// let info = CharsetInfo::detect(body, Some("text/html; charset=utf-8"));
+    /// assert_eq!(info.charset, "utf-8");
+    /// assert_eq!(info.source, CharsetSource::ContentType);
+    /// assert!(info.has_conflict());
+    /// ```
+    pub fn detect(body: &[u8], content_type: Option<&str>) -> Self {
+        let declared: Vec<(CharsetSource, String)> = [
+            (CharsetSource::Bom, bom_charset(body).map(str::to_string)),
+            (
+                CharsetSource::ContentType,
+                content_type.and_then(content_type_charset),
+            ),
+            (CharsetSource::Meta, meta_charset(body)),
+        ]
+        .into_iter()
+        .filter_map(|(source, label)| Some((source, normalize(&label?))))
+        .collect();
+
+        let (source, charset) = declared
+            .first()
+            .cloned()
+            .unwrap_or((CharsetSource::Default, "utf-8".to_string()));
+
+        Self {
+            charset,
+            source,
+            declared,
+        }
+    }
+
+    /// Decode `body` with the effective charset.
+    ///
+    /// A byte order mark is stripped, unknown charsets fall back to UTF-8, and
+    /// bytes invalid in the charset are replaced with U+FFFD.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::CharsetInfo;
+    ///
+    /// let body = b"<title>\xcf\xf0\xe8\xe2\xe5\xf2</title>";
+    /// let info = CharsetInfo::detect(body, Some("text/html; charset=windows-1251"));
+    /// assert_eq!(info.decode(body), "<title>Привет</title>");
+    /// ```
+    pub fn decode(&self, body: &[u8]) -> String {
+        let encoding = Encoding::for_label(self.charset.as_bytes()).unwrap_or(UTF_8);
+        // A <meta> can only be read if the document is ASCII-compatible, so a
+        // UTF-16 declaration there means UTF-8
+        let encoding = match self.source {
+            CharsetSource::Meta => encoding.output_encoding(),
+            _ => encoding,
+        };
+        encoding.decode(body).0.into_owned()
+    }
+
+    /// Whether two sources declared different charsets.
+    pub fn has_conflict(&self) -> bool {
+        self.declared
+            .iter()
+            .any(|(_, charset)| *charset != self.charset)
+    }
+
+    /// A warning describing the conflict, if there is one.
+    pub fn conflict_warning(&self) -> Option<String> {
+        if !self.has_conflict() {
+            return None;
+        }
+        let declarations: Vec<String> = self
+            .declared
+            .iter()
+            .map(|(source, charset)| format!("{source} says {charset}"))
+            .collect();
+        Some(format!(
+            "charset conflict: {}; using {} from {}",
+            declarations.join(", "),
+            self.charset,
+            self.source
+        ))
+    }
+}
+
+/// Canonical lowercase name for a charset label, or the trimmed label if unknown.
+fn normalize(label: &str) -> String {
+    let label = label.trim();
+    Encoding::for_label(label.as_bytes())
+        .map_or(label, |encoding| encoding.name())
+        .to_ascii_lowercase()
+}
+
+fn bom_charset(body: &[u8]) -> Option<&'static str> {
+    Encoding::for_bom(body).map(|(encoding, _)| encoding.name())
+}
+
+/// The `charset` parameter of a `Content-Type` value.
+fn content_type_charset(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches(['"', '\'']).to_string())
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// A charset declared by a `<meta>` tag near the start of the document.
+fn meta_charset(body: &[u8]) -> Option<String> {
+    let head = &body[..body.len().min(META_PRESCAN_LEN)];
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+
+    let mut rest = head.as_str();
+    while let Some(start) = rest.find("<meta") {
+        rest = &rest[start + "<meta".len()..];
+        let tag = &rest[..rest.find('>').unwrap_or(rest.len())];
+        if let Some(pos) = tag.find("charset=") {
+            let value = tag[pos + "charset=".len()..].trim_start_matches(['"', '\'']);
+            let end = value
+                .find(|c: char| c == '"' || c == '\'' || c == ';' || c == '/' || c.is_whitespace())
+                .unwrap_or(value.len());
+            if end > 0 {
+                return Some(value[..end].to_string());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence_and_conflicts() {
+        let body = b"\xef\xbb\xbf<meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1251\">";
+        let info = CharsetInfo::detect(body, Some("text/html; charset=\"ISO-8859-1\""));
+
+        assert_eq!(info.charset, "utf-8");
+        assert_eq!(info.source, CharsetSource::Bom);
+        assert_eq!(
+            info.declared,
+            [
+                (CharsetSource::Bom, "utf-8".to_string()),
+                (CharsetSource::ContentType, "windows-1252".to_string()),
+                (CharsetSource::Meta, "windows-1251".to_string()),
+            ]
+        );
+        assert_eq!(
+            info.conflict_warning().unwrap(),
+            "charset conflict: BOM says utf-8, Content-Type says windows-1252, <meta> says windows-1251; using utf-8 from BOM"
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let body = b"<title>\x82\xb1\x82\xf1\x82\xc9\x82\xbf\x82\xcd</title>";
+        let info = CharsetInfo::detect(body, Some("text/html; charset=Shift_JIS"));
+        assert_eq!(info.decode(body), "<title>こんにちは</title>");
+
+        let body = b"<meta charset=gbk><title>\xc4\xe3\xba\xc3</title>";
+        assert_eq!(
+            CharsetInfo::detect(body, None).decode(body),
+            "<meta charset=gbk><title>你好</title>"
+        );
+
+        let body = b"\xef\xbb\xbf<title>caf\xc3\xa9</title>";
+        let info = CharsetInfo::detect(body, Some("text/html; charset=iso-8859-1"));
+        assert_eq!(info.decode(body), "<title>café</title>");
+
+        let body = b"<meta charset=utf-16><title>caf\xc3\xa9</title>";
+        assert_eq!(
+            CharsetInfo::detect(body, None).decode(body),
+            "<meta charset=utf-16><title>café</title>"
+        );
+    }
+
+    #[test]
+    fn test_aliases_do_not_conflict() {
+        let info = CharsetInfo::detect(
+            b"<meta charset=latin1>",
+            Some("text/html; charset=iso-8859-1"),
+        );
+        assert_eq!(info.charset, "windows-1252");
+        assert!(!info.has_conflict());
+    }
+
+    #[test]
+    fn test_defaults_to_utf8() {
+        let info = CharsetInfo::detect(b"<p>no declaration</p>", Some("text/html"));
+        assert_eq!(info.charset, "utf-8");
+        assert_eq!(info.source, CharsetSource::Default);
+        assert!(info.declared.is_empty());
+        assert!(info.conflict_warning().is_none());
+    }
+}
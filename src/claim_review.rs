@@ -0,0 +1,193 @@
+//! Fact-check markup
+//!
+//! Fact-checking sites publish their verdicts as Schema.org
+//! [`ClaimReview`](https://schema.org/ClaimReview) items. [`HtmlInfo::claim_reviews`]
+//! reads them into typed [`ClaimReview`] values for misinformation research and
+//! news tooling.
+
+use crate::html::HtmlInfo;
+use crate::schema_org::{SchemaOrg, name, number, one_or_many, text, url};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A fact-check of a claim.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ClaimReview {
+    /// The claim being checked (`claimReviewed`)
+    pub claim: Option<String>,
+
+    /// The verdict (`reviewRating`)
+    pub rating: Option<ClaimRating>,
+
+    /// Name of the fact-checker (`author`)
+    pub author: Option<String>,
+
+    /// Where and by whom the claim was made (`itemReviewed`)
+    pub item_reviewed: Option<ReviewedClaim>,
+
+    /// URL of the fact-check article
+    pub url: Option<String>,
+
+    /// When the fact-check was published
+    pub date_published: Option<String>,
+}
+
+/// The verdict of a [`ClaimReview`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ClaimRating {
+    /// The verdict in words, e.g. `"False"` or `"Mostly true"` (`alternateName`)
+    pub verdict: Option<String>,
+
+    /// Numeric rating (`ratingValue`)
+    pub value: Option<f64>,
+
+    /// Best possible rating (`bestRating`)
+    pub best: Option<f64>,
+
+    /// Worst possible rating (`worstRating`)
+    pub worst: Option<f64>,
+}
+
+/// The claim a [`ClaimReview`] checks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ReviewedClaim {
+    /// Who made the claim
+    pub author: Option<String>,
+
+    /// When the claim was made
+    pub date_published: Option<String>,
+
+    /// URLs where the claim appeared (`appearance` and `firstAppearance`)
+    pub appearances: Vec<String>,
+}
+
+impl ClaimReview {
+    /// Read a Schema.org item, or `None` if it is not a `ClaimReview`.
+    pub fn from_schema(item: &SchemaOrg) -> Option<Self> {
+        if item.schema_type != "ClaimReview" {
+            return None;
+        }
+        let value = &item.value;
+        Some(Self {
+            claim: text(value.get("claimReviewed")),
+            rating: value.get("reviewRating").map(|rating| ClaimRating {
+                verdict: text(rating.get("alternateName")).or_else(|| text(rating.get("name"))),
+                value: number(rating.get("ratingValue")),
+                best: number(rating.get("bestRating")),
+                worst: number(rating.get("worstRating")),
+            }),
+            author: name(value.get("author")),
+            item_reviewed: value.get("itemReviewed").map(|claim| ReviewedClaim {
+                author: name(claim.get("author")),
+                date_published: text(claim.get("datePublished")),
+                appearances: ["firstAppearance", "appearance"]
+                    .iter()
+                    .flat_map(|key| one_or_many(claim.get(key)))
+                    .filter_map(|appearance| url(Some(appearance)))
+                    .collect(),
+            }),
+            url: text(value.get("url")),
+            date_published: text(value.get("datePublished")),
+        })
+    }
+}
+
+impl HtmlInfo {
+    /// Fact-checks declared as Schema.org `ClaimReview` items.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<script type="application/ld+json">{
+    ///     "@type": "ClaimReview",
+    ///     "claimReviewed": "The moon is made of cheese",
+    ///     "reviewRating": {"@type": "Rating", "ratingValue": 1, "alternateName": "False"}
+    /// }</script>"#;
+    /// let reviews = HtmlInfo::from_string(html, None).unwrap().claim_reviews();
+    /// assert_eq!(reviews[0].claim.as_deref(), Some("The moon is made of cheese"));
+    /// assert_eq!(reviews[0].rating.as_ref().unwrap().verdict.as_deref(), Some("False"));
+    /// ```
+    pub fn claim_reviews(&self) -> Vec<ClaimReview> {
+        self.schema_org
+            .iter()
+            .filter_map(ClaimReview::from_schema)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_claim_review() {
+        let items = SchemaOrg::parse(
+            r#"{
+                "@type": "ClaimReview",
+                "url": "https://factcheck.example/moon",
+                "datePublished": "2026-10-01",
+                "claimReviewed": " The moon is made of cheese ",
+                "author": {"@type": "Organization", "name": "Fact Desk"},
+                "reviewRating": {
+                    "@type": "Rating",
+                    "ratingValue": "1",
+                    "bestRating": 5,
+                    "worstRating": 1,
+                    "alternateName": "False"
+                },
+                "itemReviewed": {
+                    "@type": "Claim",
+                    "author": [{"@type": "Person", "name": "A. Poster"}],
+                    "datePublished": "2026-09-30",
+                    "firstAppearance": "https://social.example/post/1",
+                    "appearance": [{"@type": "CreativeWork", "url": "https://blog.example/cheese"}]
+                }
+            }"#,
+        );
+
+        let review = ClaimReview::from_schema(&items[0]).unwrap();
+        assert_eq!(
+            review,
+            ClaimReview {
+                claim: Some("The moon is made of cheese".to_string()),
+                rating: Some(ClaimRating {
+                    verdict: Some("False".to_string()),
+                    value: Some(1.0),
+                    best: Some(5.0),
+                    worst: Some(1.0),
+                }),
+                author: Some("Fact Desk".to_string()),
+                item_reviewed: Some(ReviewedClaim {
+                    author: Some("A. Poster".to_string()),
+                    date_published: Some("2026-09-30".to_string()),
+                    appearances: vec![
+                        "https://social.example/post/1".to_string(),
+                        "https://blog.example/cheese".to_string(),
+                    ],
+                }),
+                url: Some("https://factcheck.example/moon".to_string()),
+                date_published: Some("2026-10-01".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_other_types_are_skipped() {
+        let items = SchemaOrg::parse(r#"[{"@type": "Article"}, {"@type": "ClaimReview"}]"#);
+        assert!(ClaimReview::from_schema(&items[0]).is_none());
+        assert_eq!(
+            ClaimReview::from_schema(&items[1]).unwrap(),
+            ClaimReview::default()
+        );
+    }
+}
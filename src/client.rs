@@ -0,0 +1,1238 @@
+//! Reusable client for fetching many web pages
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::stream::{self, StreamExt};
+use reqwest::Client;
+use tokio::time::{self, Instant};
+
+use crate::cache;
+use crate::charset::CharsetInfo;
+use crate::cookies::{Cookie, CookieJar};
+use crate::error::{Error, Result};
+use crate::html::{HtmlInfo, ParseOptions};
+use crate::http::{self, HttpInfo, HttpOptions, Overrides, RawResponse};
+use crate::link_header;
+#[cfg(feature = "log")]
+use crate::logging;
+use crate::observer::{FetchObserver, Observers};
+use crate::policy::Policies;
+use crate::scheduler::{Limits, Scheduler};
+use crate::security_policy;
+use crate::sink::ResultSink;
+use crate::snapshot::Snapshot;
+use crate::stats::{ClientStats, StatsCollector};
+use crate::transport::Transport;
+use crate::user_agent::{RotationPolicy, UserAgentRotation};
+use crate::{SCHEMA_VERSION, WebpageInfo};
+
+/// A client that fetches and parses web pages with shared configuration.
+///
+/// The underlying connection pool is reused across requests, so constructing one
+/// client and fetching many URLs with it is much cheaper than calling
+/// [`WebpageInfo::fetch`] repeatedly.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use webpage_info::{HttpOptions, WebpageClient};
+///
+/// #[tokio::main]
+/// async fn main() -> webpage_info::Result<()> {
+///     let client = WebpageClient::new(HttpOptions::new())?;
+///     let results = client
+///         .fetch_many(["https://example.org", "https://example.com"])
+///         .await;
+///     for result in results {
+///         println!("{:?}", result.map(|info| info.html.title));
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct WebpageClient {
+    pub(crate) client: Client,
+    pub(crate) options: HttpOptions,
+    pub(crate) parse_options: ParseOptions,
+    pub(crate) state: ClientState,
+}
+
+/// Per-client state shared by every request the client makes.
+#[derive(Clone, Default)]
+pub(crate) struct ClientState {
+    pub(crate) observers: Observers,
+    pub(crate) stats: Arc<StatsCollector>,
+    pub(crate) scheduler: Arc<Scheduler>,
+    pub(crate) transport: Option<Arc<dyn Transport>>,
+    pub(crate) user_agents: Option<Arc<UserAgentRotation>>,
+    pub(crate) policies: Policies,
+    pub(crate) cookies: Arc<CookieJar>,
+    #[cfg(feature = "log")]
+    pub(crate) log_level: Option<log::Level>,
+}
+
+impl fmt::Debug for WebpageClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebpageClient")
+            .field("options", &self.options)
+            .field("parse_options", &self.parse_options)
+            .field("observers", &self.state.observers.len())
+            .field("custom_transport", &self.state.transport.is_some())
+            .field("user_agents", &self.state.user_agents)
+            .field("policies", &self.state.policies)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WebpageClient {
+    /// Create a client from HTTP options.
+    pub fn new(options: HttpOptions) -> Result<Self> {
+        Ok(Self {
+            client: options.build_client()?,
+            options,
+            parse_options: ParseOptions::default(),
+            state: ClientState::default(),
+        })
+    }
+
+    /// Parse every fetched page with `options` instead of the defaults.
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.parse_options = options;
+        self
+    }
+
+    /// Register an observer for fetch lifecycle events.
+    ///
+    /// Observers are notified in registration order.
+    pub fn with_observer(mut self, observer: impl FetchObserver + 'static) -> Self {
+        self.state.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// Rotate through several user agents instead of always sending
+    /// [`HttpOptions::user_agent`].
+    ///
+    /// Agents are assigned round-robin, per request or per host depending on
+    /// `policy`, and the one used is recorded in [`HttpInfo::user_agent`]. An
+    /// empty list turns rotation off.
+    pub fn with_user_agents<I, S>(mut self, agents: I, policy: RotationPolicy) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let agents = agents.into_iter().map(Into::into).collect();
+        self.state.user_agents = UserAgentRotation::new(agents, policy).map(Arc::new);
+        self
+    }
+
+    /// The options this client was created with.
+    pub fn options(&self) -> &HttpOptions {
+        &self.options
+    }
+
+    /// The options fetched pages are parsed with.
+    pub fn parse_options(&self) -> &ParseOptions {
+        &self.parse_options
+    }
+
+    /// Request statistics accumulated since the client was created.
+    ///
+    /// Clones of a client share the same statistics, like they share the
+    /// connection pool.
+    pub fn stats(&self) -> ClientStats {
+        self.state.stats.snapshot()
+    }
+
+    /// The cookies of this client's session, with
+    /// [`HttpOptions::cookies`] enabled.
+    ///
+    /// Clones of a client share the same session.
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.state.cookies.cookies()
+    }
+
+    /// Forget every cookie of the session, as after logging out.
+    pub fn clear_cookies(&self) {
+        self.state.cookies.clear();
+    }
+
+    /// Fetch and parse a single webpage.
+    pub async fn fetch(&self, url: &str) -> Result<WebpageInfo> {
+        self.fetch_with(url, &Overrides::default()).await
+    }
+
+    /// Fetch and parse a single webpage, replacing some of the client's options
+    /// for this request only.
+    ///
+    /// The request still uses the client's connection pool, observers, and
+    /// policies.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use webpage_info::{HttpOptions, Overrides, WebpageClient};
+    ///
+    /// # async fn run() -> webpage_info::Result<()> {
+    /// let client = WebpageClient::new(HttpOptions::new())?;
+    /// let overrides = Overrides::new()
+    ///     .timeout(Duration::from_secs(5))
+    ///     .header("Accept-Language", "de");
+    /// let info = client.fetch_with("https://example.com", &overrides).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_with(&self, url: &str, overrides: &Overrides) -> Result<WebpageInfo> {
+        #[cfg(feature = "log")]
+        if let Some(level) = self.state.log_level {
+            let started = std::time::Instant::now();
+            let result = self.fetch_page(url, overrides).await;
+            logging::log_fetch(level, url, &result, started.elapsed());
+            return result;
+        }
+        self.fetch_page(url, overrides).await
+    }
+
+    /// Fetch a webpage without parsing it, keeping the response details that
+    /// [`HttpInfo`] does not model.
+    ///
+    /// SSRF protection, the body size limit, observers, and policies apply as
+    /// for [`fetch`](Self::fetch).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use webpage_info::{HttpOptions, WebpageClient};
+    ///
+    /// # async fn run() -> webpage_info::Result<()> {
+    /// let client = WebpageClient::new(HttpOptions::new())?;
+    /// let raw = client.fetch_raw("https://example.com").await?;
+    /// println!("{:?} from {:?}", raw.version, raw.remote_addr);
+    /// for cookie in raw.headers.get_all("set-cookie") {
+    ///     println!("{cookie:?}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_raw(&self, url: &str) -> Result<RawResponse> {
+        self.fetch_response(url, &Overrides::default()).await
+    }
+
+    async fn fetch_page(&self, url: &str, overrides: &Overrides) -> Result<WebpageInfo> {
+        let response = self.fetch_response(url, overrides).await?;
+        let info = parse_response(response.info, &self.parse_options)?;
+        self.state.observers.on_parse_complete(&info);
+        Ok(info)
+    }
+
+    pub(crate) async fn fetch_response(
+        &self,
+        url: &str,
+        overrides: &Overrides,
+    ) -> Result<RawResponse> {
+        let url = &*self.state.policies.check_request(url)?;
+        let mut options = self.options_for(url);
+        if !overrides.is_empty() {
+            overrides.apply(options.to_mut());
+        }
+        // Captured before validators are added, for responses with `Vary`
+        let request = (options.cache.as_ref()).map(|cache| {
+            (
+                cache.clone(),
+                cache::request_headers(url, &options, &self.state),
+            )
+        });
+        let cached = request.as_ref().and_then(|(cache, request)| {
+            let cached = cache.lookup(url, &options, request);
+            self.state.stats.record_cache_lookup(cached.is_some());
+            cached
+        });
+        if let Some(cached) = &cached {
+            let headers = &mut options.to_mut().headers;
+            for (name, value) in cache::validators(cached) {
+                headers.push((name.to_string(), value.to_string()));
+            }
+        }
+        let response = self.fetch_retrying(url, &options).await?;
+        self.state.policies.check_response(&response.info)?;
+        if let Some((cache, request)) = request {
+            match cached {
+                Some(cached) if response.info.status_code == 304 => {
+                    self.state.stats.record_cache_hit();
+                    return Ok(RawResponse::from_info(cached));
+                }
+                _ => cache.store(url, &response.info, &request),
+            }
+        }
+        Ok(response)
+    }
+
+    /// Fetch `url`, retrying transient failures per [`HttpOptions::retries`].
+    async fn fetch_retrying(&self, url: &str, options: &HttpOptions) -> Result<RawResponse> {
+        let Some(policy) = &options.retries else {
+            return self.fetch_once(url, options).await;
+        };
+        let mut attempt = 1;
+        loop {
+            let result = self.fetch_once(url, options).await;
+            let status_error;
+            let error = match &result {
+                Ok(response) => match policy.status_error(&response.info) {
+                    Some(error) => {
+                        status_error = error;
+                        &status_error
+                    }
+                    None => return result,
+                },
+                Err(error) if policy.retries(error) => error,
+                Err(_) => return result,
+            };
+            let Some(delay) = policy.delay(attempt, error) else {
+                return result;
+            };
+            attempt += 1;
+            self.state.observers.on_retry(url, attempt, error);
+            time::sleep(delay).await;
+        }
+    }
+
+    async fn fetch_once(&self, url: &str, options: &HttpOptions) -> Result<RawResponse> {
+        match &self.state.transport {
+            Some(transport) => Ok(RawResponse::from_info(
+                self.fetch_with_transport(transport.as_ref(), url, options)
+                    .await?,
+            )),
+            None => http::fetch(&self.client, url, options, &self.state).await,
+        }
+    }
+
+    /// The options for a request to `url`, with the rotated user agent if any.
+    pub(crate) fn options_for(&self, url: &str) -> Cow<'_, HttpOptions> {
+        let Some(rotation) = &self.state.user_agents else {
+            return Cow::Borrowed(&self.options);
+        };
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let mut options = self.options.clone();
+        options.user_agent = rotation.pick(&host).to_string();
+        Cow::Owned(options)
+    }
+
+    async fn fetch_with_transport(
+        &self,
+        transport: &dyn Transport,
+        url: &str,
+        options: &HttpOptions,
+    ) -> Result<HttpInfo> {
+        if options.block_private_ips {
+            http::validate_request_for_ssrf(url, options).await?;
+        }
+        self.state.observers.on_request(url)?;
+
+        let parsed = url::Url::parse(url).ok();
+        let _in_flight = (self.state.stats).start(
+            parsed
+                .as_ref()
+                .and_then(|u| u.host_str())
+                .unwrap_or_default(),
+        );
+        let mut info = transport
+            .fetch_with_policy(url, options, self.state.policies.request())
+            .await
+            .inspect_err(|_| self.state.stats.record_failure())?;
+        if info.user_agent.is_empty() {
+            info.user_agent = options.user_agent.clone();
+        }
+        let max = options.max_body_size;
+        if info.body.len() > max {
+            let end = info.body.floor_char_boundary(max);
+            info.body.truncate(end);
+        }
+        if options.snapshot && info.snapshot.is_none() {
+            let body = info.body.as_bytes().to_vec();
+            info.snapshot = Some(Snapshot::capture(
+                &info.url,
+                info.status_code,
+                &info.headers,
+                body,
+            ));
+        }
+        self.state.stats.record_bytes(info.body.len() as u64);
+        self.state.observers.on_response(&info);
+        Ok(info)
+    }
+
+    /// Fetch many webpages concurrently.
+    ///
+    /// At most [`HttpOptions::max_concurrency`] requests run at once, and at most
+    /// [`HttpOptions::max_concurrent_per_host`] to any one host, counting the
+    /// other batch fetches of this client and its clones. Fetches still
+    /// outstanding at the [`HttpOptions::batch_deadline`] are cancelled and fail
+    /// with [`Error::Timeout`]. Results are returned in the same order as the
+    /// input URLs.
+    pub async fn fetch_many<I, S>(&self, urls: I) -> Vec<Result<WebpageInfo>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let batch = Batch::new(self);
+        let batch = &batch;
+        stream::iter(urls)
+            .map(|url| async move {
+                let url = url.as_ref();
+                batch.run(url, 0, self.fetch(url)).await
+            })
+            .buffered(batch.window(false))
+            .collect()
+            .await
+    }
+
+    /// Fetch many webpages like [`fetch_many`](Self::fetch_many), returning
+    /// each result with the data it was paired with.
+    ///
+    /// The data can be anything identifying the URL to the caller, such as a
+    /// database ID, so results can be matched up even when redirects change
+    /// the URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use webpage_info::{HttpOptions, WebpageClient};
+    ///
+    /// # async fn run() -> webpage_info::Result<()> {
+    /// let client = WebpageClient::new(HttpOptions::new())?;
+    /// let rows = [(17, "https://example.com"), (42, "https://example.org")];
+    /// let pages = rows.iter().map(|&(id, url)| (url, id));
+    /// for (id, result) in client.fetch_many_tagged(pages).await {
+    ///     println!("{id}: {:?}", result.map(|info| info.html.title));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_many_tagged<I, S, T>(&self, urls: I) -> Vec<(T, Result<WebpageInfo>)>
+    where
+        I: IntoIterator<Item = (S, T)>,
+        S: AsRef<str>,
+    {
+        let batch = Batch::new(self);
+        let batch = &batch;
+        stream::iter(urls)
+            .map(|(url, data)| async move {
+                let url = url.as_ref();
+                (data, batch.run(url, 0, self.fetch(url)).await)
+            })
+            .buffered(batch.window(false))
+            .collect()
+            .await
+    }
+
+    /// Fetch many webpages like [`fetch_many`](Self::fetch_many), starting
+    /// higher-priority URLs first.
+    ///
+    /// Priorities also order this batch against the client's other batch
+    /// fetches, which use priority 0, so interactive requests can jump ahead of
+    /// a background backfill sharing the client. Equal priorities keep input
+    /// order, and per-host limits still apply. Results are returned in the same
+    /// order as the input URLs.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use webpage_info::WebpageClient;
+    ///
+    /// # async fn run(client: WebpageClient, backfill: Vec<String>) {
+    /// let background = client.clone();
+    /// tokio::spawn(async move { background.fetch_many(backfill).await });
+    ///
+    /// // Starts as soon as a slot frees up
+    /// let results = client
+    ///     .fetch_many_prioritized([("https://example.com/now", 10)])
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn fetch_many_prioritized<I, S>(&self, urls: I) -> Vec<Result<WebpageInfo>>
+    where
+        I: IntoIterator<Item = (S, i32)>,
+        S: AsRef<str>,
+    {
+        let urls = by_priority(urls);
+        let mut results: Vec<Option<Result<WebpageInfo>>> = urls.iter().map(|_| None).collect();
+        let batch = Batch::new(self);
+        let batch = &batch;
+        let mut done = stream::iter(urls)
+            .map(|(index, url, priority)| async move {
+                let url = url.as_ref();
+                (index, batch.run(url, priority, self.fetch(url)).await)
+            })
+            .buffer_unordered(batch.window(true));
+        while let Some((index, result)) = done.next().await {
+            results[index] = Some(result);
+        }
+        results
+            .into_iter()
+            .map(|result| result.expect("every URL is fetched"))
+            .collect()
+    }
+
+    /// Fetch many webpages concurrently, handing each result to a sink as it completes.
+    ///
+    /// Fetch failures are passed to [`ResultSink::store_error`] and do not stop the
+    /// batch; an error returned by the sink itself aborts it. Returns the number of
+    /// pages stored.
+    pub async fn fetch_many_into<I, S, K>(&self, urls: I, sink: &mut K) -> Result<usize>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        K: ResultSink + ?Sized,
+    {
+        self.store_many(urls.into_iter().map(|url| (url, 0)), false, sink)
+            .await
+    }
+
+    /// Fetch many webpages into a sink like
+    /// [`fetch_many_into`](Self::fetch_many_into), starting higher-priority URLs
+    /// first as in [`fetch_many_prioritized`](Self::fetch_many_prioritized).
+    ///
+    /// The URLs are collected up front to order them.
+    pub async fn fetch_many_into_prioritized<I, S, K>(&self, urls: I, sink: &mut K) -> Result<usize>
+    where
+        I: IntoIterator<Item = (S, i32)>,
+        S: AsRef<str>,
+        K: ResultSink + ?Sized,
+    {
+        let urls = by_priority(urls)
+            .into_iter()
+            .map(|(_, url, priority)| (url, priority));
+        self.store_many(urls, true, sink).await
+    }
+
+    async fn store_many<I, S, K>(&self, urls: I, prioritized: bool, sink: &mut K) -> Result<usize>
+    where
+        I: Iterator<Item = (S, i32)>,
+        S: AsRef<str>,
+        K: ResultSink + ?Sized,
+    {
+        let batch = Batch::new(self);
+        let batch = &batch;
+        let mut results = stream::iter(urls)
+            .map(|(url, priority)| async move {
+                let url = url.as_ref().to_string();
+                let result = batch.run(&url, priority, self.fetch(&url)).await;
+                (url, result)
+            })
+            .buffer_unordered(batch.window(prioritized));
+
+        let mut stored = 0;
+        while let Some((url, result)) = results.next().await {
+            match result {
+                Ok(info) => {
+                    sink.store(info)?;
+                    stored += 1;
+                }
+                Err(error) => sink.store_error(&url, &error)?,
+            }
+        }
+        sink.flush()?;
+
+        Ok(stored)
+    }
+}
+
+/// Number of URLs per concurrent request a batch queues ahead when a per-host
+/// limit applies, so requests to other hosts can start while one host is busy,
+/// or when it has priorities, so they compete with other batches' URLs.
+const QUEUE_LOOKAHEAD: usize = 16;
+
+/// The URLs with their input index, highest priority first, then in input order.
+fn by_priority<I, S>(urls: I) -> Vec<(usize, S, i32)>
+where
+    I: IntoIterator<Item = (S, i32)>,
+{
+    let mut urls: Vec<_> = (urls.into_iter().enumerate())
+        .map(|(index, (url, priority))| (index, url, priority))
+        .collect();
+    urls.sort_by_key(|&(_, _, priority)| Reverse(priority));
+    urls
+}
+
+/// One batch fetch: the client's scheduler and this batch's deadline.
+struct Batch<'a> {
+    scheduler: &'a Arc<Scheduler>,
+    limits: Limits,
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl<'a> Batch<'a> {
+    fn new(client: &'a WebpageClient) -> Self {
+        let options = &client.options;
+        Self {
+            scheduler: &client.state.scheduler,
+            limits: Limits::new(options),
+            deadline: (options.batch_deadline).map(|limit| (Instant::now() + limit, limit)),
+        }
+    }
+
+    /// Number of fetches the batch stream keeps pending.
+    fn window(&self, prioritized: bool) -> usize {
+        if prioritized || self.limits.per_host.is_some() {
+            self.limits.total.saturating_mul(QUEUE_LOOKAHEAD)
+        } else {
+            self.limits.total
+        }
+    }
+
+    /// Run `fetch` for `url` once the scheduler starts it, cancelling it at the
+    /// deadline.
+    async fn run(
+        &self,
+        url: &str,
+        priority: i32,
+        fetch: impl Future<Output = Result<WebpageInfo>>,
+    ) -> Result<WebpageInfo> {
+        let scheduled = async {
+            let _permit = self.scheduler.acquire(url, priority, self.limits).await;
+            fetch.await
+        };
+        let Some((deadline, limit)) = self.deadline else {
+            return scheduled.await;
+        };
+        let timeout = || Error::Timeout {
+            url: url.to_string(),
+            deadline: limit,
+        };
+        // Fetches queued past the deadline are not started at all
+        if Instant::now() >= deadline {
+            return Err(timeout());
+        }
+        time::timeout_at(deadline, scheduled)
+            .await
+            .unwrap_or_else(|_| Err(timeout()))
+    }
+}
+
+/// Parse a fetched response into a [`WebpageInfo`].
+///
+/// The lightweight backend has no parse options.
+#[cfg_attr(not(feature = "dom"), allow(unused_variables))]
+pub(crate) fn parse_response(http_info: HttpInfo, options: &ParseOptions) -> Result<WebpageInfo> {
+    // Validate content type is HTML-ish
+    if let Some(ref ct) = http_info.content_type
+        && !ct.contains("html")
+        && !ct.contains("xml")
+    {
+        if let Some(page) = http_info.error_page() {
+            return Err(Error::ErrorStatus {
+                url: http_info.url.clone(),
+                page: Box::new(page),
+            });
+        }
+        return Err(Error::InvalidContentType {
+            url: http_info.url.clone(),
+            content_type: ct.clone(),
+        });
+    }
+
+    #[cfg(feature = "dom")]
+    let mut html =
+        HtmlInfo::from_string_with_options(&http_info.body, Some(&http_info.url), options.clone())?;
+    #[cfg(not(feature = "dom"))]
+    let mut html = HtmlInfo::from_string_lightweight(&http_info.body)?;
+
+    if let Some(warning) = http_info
+        .charset
+        .as_ref()
+        .and_then(CharsetInfo::conflict_warning)
+    {
+        html.warnings.push(warning);
+    }
+    link_header::merge(&http_info, &mut html);
+    html.warnings
+        .extend(security_policy::conflict_warnings(&http_info, &html));
+
+    Ok(WebpageInfo {
+        schema_version: SCHEMA_VERSION,
+        http: http_info,
+        html,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn server() -> MockServer {
+        let server = MockServer::start().await;
+        for (route, title) in [("/a", "Page A"), ("/b", "Page B")] {
+            Mock::given(path(route))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(
+                    format!("<html><head><title>{title}</title></head></html>"),
+                    "text/html",
+                ))
+                .mount(&server)
+                .await;
+        }
+        server
+    }
+
+    fn client() -> WebpageClient {
+        WebpageClient::new(HttpOptions::new().block_private_ips(false)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_many_preserves_order() {
+        let server = server().await;
+        let urls = [format!("{}/b", server.uri()), format!("{}/a", server.uri())];
+
+        let results = client().fetch_many(&urls).await;
+        let titles: Vec<_> = results
+            .into_iter()
+            .map(|r| r.unwrap().html.title.unwrap())
+            .collect();
+        assert_eq!(titles, ["Page B", "Page A"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_many_limits_per_host() {
+        use std::time::{Duration, Instant};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/slow"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html></html>", "text/html")
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&server)
+            .await;
+        let urls = vec![format!("{}/slow", server.uri()); 3];
+
+        let limited = WebpageClient::new(
+            HttpOptions::new()
+                .block_private_ips(false)
+                .max_concurrent_per_host(1),
+        )
+        .unwrap();
+        let start = Instant::now();
+        let results = limited.fetch_many(&urls).await;
+        assert!(results.iter().all(Result::is_ok));
+        assert!(start.elapsed() >= Duration::from_millis(600));
+
+        let start = Instant::now();
+        client().fetch_many(&urls).await;
+        assert!(start.elapsed() < Duration::from_millis(600));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_many_prioritized() {
+        use std::sync::Mutex;
+        use std::time::Duration;
+
+        #[derive(Default)]
+        struct Requests(Mutex<Vec<String>>);
+        impl FetchObserver for Requests {
+            fn on_request(&self, url: &str) -> Result<()> {
+                let path = url.rsplit('/').next().unwrap_or_default();
+                self.0.lock().unwrap().push(path.to_string());
+                Ok(())
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html></html>", "text/html")
+                    .set_delay(Duration::from_millis(100)),
+            )
+            .mount(&server)
+            .await;
+        let url = |name: &str| format!("{}/{name}", server.uri());
+
+        let requests = Arc::new(Requests::default());
+        let client = WebpageClient::new(
+            HttpOptions::new()
+                .block_private_ips(false)
+                .max_concurrency(1),
+        )
+        .unwrap()
+        .with_observer(requests.clone());
+
+        let backfill = [url("bg1"), url("bg2"), url("bg3")];
+        let interactive = async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            client
+                .fetch_many_prioritized([(url("soon"), 1), (url("now"), 10)])
+                .await
+        };
+        let (background, results) = tokio::join!(client.fetch_many(&backfill), interactive);
+        assert!(background.iter().chain(&results).all(Result::is_ok));
+        assert_eq!(
+            *requests.0.lock().unwrap(),
+            ["bg1", "now", "soon", "bg2", "bg3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_many_deadline() {
+        use std::time::Instant;
+
+        let server = server().await;
+        Mock::given(path("/stuck"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html></html>", "text/html")
+                    .set_delay(Duration::from_secs(10)),
+            )
+            .mount(&server)
+            .await;
+        let urls = [
+            format!("{}/a", server.uri()),
+            format!("{}/stuck", server.uri()),
+            format!("{}/b", server.uri()),
+        ];
+
+        let client = WebpageClient::new(
+            HttpOptions::new()
+                .block_private_ips(false)
+                .batch_deadline(Duration::from_millis(300)),
+        )
+        .unwrap();
+        let start = Instant::now();
+        let results = client.fetch_many(&urls).await;
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(results[0].is_ok() && results[2].is_ok());
+        let err = results[1].as_ref().unwrap_err();
+        assert!(matches!(err, Error::Timeout { .. }));
+        assert_eq!(err.url(), Some(urls[1].as_str()));
+        assert!(err.is_retryable());
+        assert_eq!(client.stats().requests_in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_many_tagged() {
+        let server = server().await;
+        let urls = [
+            (format!("{}/b", server.uri()), "row-2"),
+            ("http://127.0.0.1:1/".to_string(), "row-3"),
+            (format!("{}/a", server.uri()), "row-1"),
+        ];
+
+        let results = client().fetch_many_tagged(urls).await;
+        let ids: Vec<_> = results.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, ["row-2", "row-3", "row-1"]);
+        assert_eq!(
+            results[0].1.as_ref().unwrap().html.title.as_deref(),
+            Some("Page B")
+        );
+        assert!(results[1].1.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_many_into_sink() {
+        let server = server().await;
+        let urls = [
+            format!("{}/a", server.uri()),
+            "http://127.0.0.1:1/".to_string(),
+            format!("{}/b", server.uri()),
+        ];
+
+        let mut sink: Vec<WebpageInfo> = Vec::new();
+        let stored = client().fetch_many_into(&urls, &mut sink).await.unwrap();
+        assert_eq!(stored, 2);
+        assert_eq!(sink.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_raw_keeps_protocol_details() {
+        let server = MockServer::start().await;
+        Mock::given(path("/raw"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .append_header("set-cookie", "a=1")
+                    .append_header("set-cookie", "b=2")
+                    .append_header(
+                        "x-latin1",
+                        reqwest::header::HeaderValue::from_bytes(b"caf\xe9").unwrap(),
+                    )
+                    .set_body_raw(vec![0xff, 0xd8, 0xff], "image/jpeg"),
+            )
+            .mount(&server)
+            .await;
+
+        let raw = client()
+            .fetch_raw(&format!("{}/raw", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(raw.info.status_code, 200);
+        assert_eq!(raw.version, Some(reqwest::Version::HTTP_11));
+        assert!(raw.remote_addr.is_some_and(|addr| addr.ip().is_loopback()));
+        assert_eq!(raw.headers.get_all("set-cookie").iter().count(), 2);
+        assert_eq!(raw.headers["x-latin1"].as_bytes(), b"caf\xe9");
+        assert!(raw.info.header("x-latin1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_with_overrides() {
+        use std::time::Duration;
+        use wiremock::matchers::header;
+
+        let server = MockServer::start().await;
+        Mock::given(path("/de"))
+            .and(header("user-agent", "Special/1.0"))
+            .and(header("accept-language", "de"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw("<title>Hallo</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/slow"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_delay(Duration::from_millis(500))
+                    .set_body_raw("<title>Slow</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = WebpageClient::new(
+            HttpOptions::new()
+                .block_private_ips(false)
+                .header("Accept-Language", "en"),
+        )
+        .unwrap();
+        let overrides = Overrides::new()
+            .user_agent("Special/1.0")
+            .header("Accept-Language", "de");
+        let info = client
+            .fetch_with(&format!("{}/de", server.uri()), &overrides)
+            .await
+            .unwrap();
+        assert_eq!(info.html.title.as_deref(), Some("Hallo"));
+        assert_eq!(info.http.user_agent, "Special/1.0");
+
+        let slow = format!("{}/slow", server.uri());
+        let overrides = Overrides::new().timeout(Duration::from_millis(50));
+        let err = client.fetch_with(&slow, &overrides).await.unwrap_err();
+        assert!(err.is_retryable());
+        // The client's own options are unchanged
+        assert!(client.fetch(&slow).await.is_ok());
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl FetchObserver for Recorder {
+        fn on_request(&self, url: &str) -> Result<()> {
+            self.events.lock().unwrap().push(format!("request {url}"));
+            if url.contains("forbidden") {
+                return Err(Error::Aborted("forbidden".to_string()));
+            }
+            Ok(())
+        }
+
+        fn on_redirect(&self, from: &str, to: &str, status: u16) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("redirect {status} {from} -> {to}"));
+        }
+
+        fn on_response(&self, info: &crate::HttpInfo) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("response {}", info.status_code));
+        }
+
+        fn on_parse_complete(&self, info: &WebpageInfo) {
+            self.events.lock().unwrap().push(format!(
+                "parsed {}",
+                info.html.title.as_deref().unwrap_or("")
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_observer_sees_lifecycle() {
+        let server = server().await;
+        Mock::given(path("/old"))
+            .respond_with(ResponseTemplate::new(301).insert_header("location", "/a"))
+            .mount(&server)
+            .await;
+
+        let recorder = Arc::new(Recorder::default());
+        let client = client().with_observer(recorder.clone());
+        let info = client
+            .fetch(&format!("{}/old", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(info.http.redirect_count, 1);
+
+        let uri = server.uri();
+        assert_eq!(
+            *recorder.events.lock().unwrap(),
+            [
+                format!("request {uri}/old"),
+                format!("redirect 301 {uri}/old -> {uri}/a"),
+                format!("request {uri}/a"),
+                "response 200".to_string(),
+                "parsed Page A".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_observer_can_abort_request() {
+        let server = server().await;
+        let client = client().with_observer(Recorder::default());
+
+        let err = client
+            .fetch(&format!("{}/forbidden", server.uri()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Aborted(_)));
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_progress_reports_and_aborts() {
+        struct AbortAfter(u64, std::sync::Mutex<Vec<(u64, Option<u64>)>>);
+
+        impl FetchObserver for AbortAfter {
+            fn on_progress(&self, _url: &str, downloaded: u64, total: Option<u64>) -> Result<()> {
+                self.1.lock().unwrap().push((downloaded, total));
+                if downloaded > self.0 {
+                    return Err(Error::Aborted("too large".to_string()));
+                }
+                Ok(())
+            }
+        }
+
+        let server = server().await;
+        let url = format!("{}/a", server.uri());
+        let len = "<html><head><title>Page A</title></head></html>".len() as u64;
+
+        let progress = Arc::new(AbortAfter(u64::MAX, Default::default()));
+        client()
+            .with_observer(progress.clone())
+            .fetch(&url)
+            .await
+            .unwrap();
+        let events = progress.1.lock().unwrap().clone();
+        assert_eq!(events.last(), Some(&(len, Some(len))));
+
+        let err = client()
+            .with_observer(AbortAfter(0, Default::default()))
+            .fetch(&url)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Aborted(_)));
+    }
+
+    #[tokio::test]
+    async fn test_stats_count_requests() {
+        let server = server().await;
+        let client = client();
+        let urls = [format!("{}/a", server.uri()), format!("{}/b", server.uri())];
+        client.fetch_many(&urls).await;
+        let _ = client.fetch("http://127.0.0.1:1/").await;
+
+        let stats = client.stats();
+        assert_eq!(stats.requests_total, 3);
+        assert_eq!(stats.requests_in_flight, 0);
+        assert_eq!(stats.requests_failed, 1);
+        assert!(stats.bytes_downloaded > 0);
+        assert_eq!(stats.hosts["127.0.0.1"].requests, 3);
+    }
+
+    #[tokio::test]
+    async fn test_body_is_decoded_with_charset() {
+        let server = MockServer::start().await;
+        Mock::given(path("/cp1251"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                b"<html><head><title>\xcf\xf0\xe8\xe2\xe5\xf2</title></head></html>".to_vec(),
+                "text/html; charset=windows-1251",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(path("/sjis"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                b"<meta charset=\"shift_jis\"><title>\x93\xfa\x96\x7b</title>".to_vec(),
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+
+        let info = client()
+            .fetch(&format!("{}/cp1251", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(info.html.title.as_deref(), Some("Привет"));
+        let info = client()
+            .fetch(&format!("{}/sjis", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(info.html.title.as_deref(), Some("日本"));
+        assert!(info.html.warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_charset_conflict_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(path("/latin"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                "<html><head><meta charset=\"windows-1251\"><title>T</title></head></html>",
+                "text/html; charset=iso-8859-1",
+            ))
+            .mount(&server)
+            .await;
+
+        let info = client()
+            .fetch(&format!("{}/latin", server.uri()))
+            .await
+            .unwrap();
+        let charset = info.http.charset.unwrap();
+        assert_eq!(charset.charset, "windows-1252");
+        assert_eq!(charset.source, crate::CharsetSource::ContentType);
+        assert!(info.html.warnings[0].starts_with("charset conflict:"));
+    }
+
+    #[tokio::test]
+    async fn test_referrer_policy_conflict_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(path("/referrer"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("referrer-policy", "no-referrer")
+                    .set_body_raw(
+                        "<meta name=\"referrer\" content=\"unsafe-url\"><title>T</title>",
+                        "text/html",
+                    ),
+            )
+            .mount(&server)
+            .await;
+
+        let info = client()
+            .fetch(&format!("{}/referrer", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(
+            info.http.referrer_policy(),
+            Some(crate::ReferrerPolicy::NoReferrer)
+        );
+        assert_eq!(
+            info.html.referrer_policy(),
+            Some(crate::ReferrerPolicy::UnsafeUrl)
+        );
+        assert!(info.html.warnings[0].starts_with("referrer policy conflict:"));
+    }
+
+    #[tokio::test]
+    async fn test_link_header_fills_canonical() {
+        let server = MockServer::start().await;
+        Mock::given(path("/linked"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "link",
+                        "</canonical>; rel=\"canonical\", </style.css>; rel=preload; as=style",
+                    )
+                    .set_body_raw("<title>T</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        let info = client()
+            .fetch(&format!("{}/linked", server.uri()))
+            .await
+            .unwrap();
+        let links = info.http.link_header();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[1].as_type.as_deref(), Some("style"));
+        assert_eq!(
+            info.html.canonical_url,
+            Some(format!("{}/canonical", server.uri()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_rotation() {
+        let server = MockServer::start().await;
+        for agent in ["bot-a", "bot-b"] {
+            Mock::given(path("/ua"))
+                .and(wiremock::matchers::header("user-agent", agent))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_raw(format!("<title>{agent}</title>"), "text/html"),
+                )
+                .mount(&server)
+                .await;
+        }
+        let url = format!("{}/ua", server.uri());
+
+        let rotating = client().with_user_agents(["bot-a", "bot-b"], RotationPolicy::PerRequest);
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            let info = rotating.fetch(&url).await.unwrap();
+            assert_eq!(
+                info.html.title.as_deref(),
+                Some(info.http.user_agent.as_str())
+            );
+            seen.push(info.http.user_agent);
+        }
+        assert_eq!(seen, ["bot-a", "bot-b", "bot-a"]);
+
+        let info = client().fetch(&url).await.unwrap();
+        assert_eq!(info.http.status_code, 404);
+        assert_eq!(info.http.user_agent, HttpOptions::default().user_agent);
+    }
+
+    #[tokio::test]
+    async fn test_error_status_keeps_error_page() {
+        let server = MockServer::start().await;
+        Mock::given(path("/blocked"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .insert_header("cf-ray", "1234")
+                    .set_body_raw(r#"{"error":"blocked"}"#, "application/json"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/captcha"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .set_body_raw("<html><title>Just a moment...</title></html>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        let err = client()
+            .fetch(&format!("{}/blocked", server.uri()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(403));
+        assert!(!err.is_retryable());
+        let Error::ErrorStatus { page, .. } = err else {
+            panic!("expected ErrorStatus, got {err:?}");
+        };
+        assert_eq!(page.body_preview, r#"{"error":"blocked"}"#);
+        assert!(page.headers.iter().any(|(name, _)| name == "cf-ray"));
+
+        let info = client()
+            .fetch(&format!("{}/captcha", server.uri()))
+            .await
+            .unwrap();
+        let page = info.http.error_page().unwrap();
+        assert_eq!(page.title.as_deref(), Some("Just a moment..."));
+    }
+}
@@ -0,0 +1,50 @@
+//! zstd compression of stored bodies
+//!
+//! Archived HTML dominates the storage of large crawls and compresses well.
+//! With the `zstd` feature, [`Snapshot::save_compressed`](crate::Snapshot::save_compressed)
+//! and `SqliteSink::compression` store zstd frames, and everything that reads
+//! them back recognizes the frames by their magic number, so compressed and
+//! uncompressed data can be mixed.
+
+use std::borrow::Cow;
+use std::io;
+
+/// Magic number at the start of every zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compress `bytes` at a zstd `level` (1 to 22; 0 selects the default, 3).
+#[cfg(feature = "zstd")]
+pub(crate) fn compress(bytes: &[u8], level: i32) -> io::Result<Vec<u8>> {
+    zstd::encode_all(bytes, level)
+}
+
+/// `bytes`, decompressed if they are a zstd frame.
+pub(crate) fn decompress(bytes: &[u8]) -> io::Result<Cow<'_, [u8]>> {
+    if !bytes.starts_with(&ZSTD_MAGIC) {
+        return Ok(Cow::Borrowed(bytes));
+    }
+    #[cfg(feature = "zstd")]
+    return zstd::decode_all(bytes).map(Cow::Owned);
+    #[cfg(not(feature = "zstd"))]
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reading zstd-compressed data requires the `zstd` feature",
+    ))
+}
+
+#[cfg(all(test, feature = "zstd"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let html = "<p>compressible</p>".repeat(1000);
+        let compressed = compress(html.as_bytes(), 19).unwrap();
+        assert!(compressed.len() < html.len() / 10);
+        assert_eq!(decompress(&compressed).unwrap(), html.as_bytes());
+        assert!(matches!(
+            decompress(html.as_bytes()).unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -0,0 +1,246 @@
+//! Cookie consent banner detection
+//!
+//! Crawlers in the EU often receive a consent interstitial instead of the page
+//! itself. Enabled with [`ParseOptions::detect_consent`], this recognizes the
+//! common consent-management platforms by their script hosts and container
+//! elements, and flags documents whose visible text is mostly the banner.
+//!
+//! [`ParseOptions::detect_consent`]: crate::ParseOptions::detect_consent
+
+#[cfg(feature = "dom")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "dom")]
+use scraper::{ElementRef, Html, Selector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Bodies with less visible text than this are checked for consent wording.
+#[cfg(feature = "dom")]
+const SHORT_BODY_LEN: usize = 1_000;
+
+/// A consent-management platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ConsentPlatform {
+    /// OneTrust / CookiePro
+    OneTrust,
+    /// Cookiebot
+    Cookiebot,
+    /// Didomi
+    Didomi,
+    /// TrustArc
+    TrustArc,
+    /// Quantcast Choice
+    Quantcast,
+    /// Usercentrics
+    Usercentrics,
+}
+
+/// Script hosts and container element ids that identify each platform.
+#[cfg(feature = "dom")]
+const PLATFORM_MARKERS: &[(ConsentPlatform, &[&str], &[&str])] = &[
+    (
+        ConsentPlatform::OneTrust,
+        &[
+            "cdn.cookielaw.org",
+            "optanon.blob.core.windows.net",
+            "cookiepro.com",
+        ],
+        &["onetrust-consent-sdk", "onetrust-banner-sdk"],
+    ),
+    (
+        ConsentPlatform::Cookiebot,
+        &["consent.cookiebot.com", "consent.cookiebot.eu"],
+        &["CybotCookiebotDialog"],
+    ),
+    (
+        ConsentPlatform::Didomi,
+        &["sdk.privacy-center.org"],
+        &["didomi-host", "didomi-notice"],
+    ),
+    (
+        ConsentPlatform::TrustArc,
+        &["consent.trustarc.com"],
+        &["truste-consent-track", "consent_blackbar"],
+    ),
+    (
+        ConsentPlatform::Quantcast,
+        &["quantcast.mgr.consensu.org", "cmp.quantcast.com"],
+        &["qc-cmp2-container"],
+    ),
+    (
+        ConsentPlatform::Usercentrics,
+        &["app.usercentrics.eu", "web.cmp.usercentrics.eu"],
+        &["usercentrics-root"],
+    ),
+];
+
+/// Consent banner detection result.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ConsentInfo {
+    /// Consent-management platforms found on the page
+    pub platforms: Vec<ConsentPlatform>,
+
+    /// Whether the visible text likely comes from a consent interstitial rather
+    /// than the page content
+    pub interstitial: bool,
+}
+
+#[cfg(feature = "dom")]
+fn script_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("script[src]").unwrap())
+}
+
+#[cfg(feature = "dom")]
+fn id_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("[id]").unwrap())
+}
+
+#[cfg(feature = "dom")]
+fn body_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("body").unwrap())
+}
+
+#[cfg(feature = "dom")]
+impl ConsentInfo {
+    /// Detect consent platforms and interstitials in a parsed document.
+    pub(crate) fn detect(document: &Html) -> Self {
+        let sources: Vec<&str> = document
+            .select(script_selector())
+            .filter_map(|script| script.value().attr("src"))
+            .collect();
+        let containers: Vec<(ConsentPlatform, ElementRef)> = document
+            .select(id_selector())
+            .filter_map(|element| {
+                let id = element.value().id()?;
+                PLATFORM_MARKERS
+                    .iter()
+                    .find(|(_, _, ids)| ids.contains(&id))
+                    .map(|(platform, _, _)| (*platform, element))
+            })
+            .collect();
+
+        let platforms: Vec<ConsentPlatform> = PLATFORM_MARKERS
+            .iter()
+            .filter(|(platform, hosts, _)| {
+                sources
+                    .iter()
+                    .any(|src| hosts.iter().any(|host| src.contains(host)))
+                    || containers.iter().any(|(found, _)| found == platform)
+            })
+            .map(|(platform, _, _)| *platform)
+            .collect();
+
+        let body_text = document
+            .select(body_selector())
+            .next()
+            .map(visible_text)
+            .unwrap_or_default();
+        // Nested containers (e.g. OneTrust's SDK wrapper and banner) would be
+        // counted twice; the outermost one covers the inner ones.
+        let banner_len: usize = containers
+            .iter()
+            .filter(|(_, element)| {
+                !containers.iter().any(|(_, outer)| {
+                    outer.id() != element.id() && element.ancestors().any(|a| a.id() == outer.id())
+                })
+            })
+            .map(|(_, element)| visible_text(*element).len())
+            .sum();
+
+        let interstitial = if banner_len > 0 {
+            banner_len * 2 >= body_text.len()
+        } else {
+            body_text.len() < SHORT_BODY_LEN && mentions_consent(&body_text)
+        };
+
+        Self {
+            platforms,
+            interstitial,
+        }
+    }
+}
+
+/// Visible text under an element, excluding scripts and styles, space-separated.
+#[cfg(feature = "dom")]
+fn visible_text(element: ElementRef) -> String {
+    let mut text = String::new();
+    for descendant in element.descendants() {
+        let Some(fragment) = descendant.value().as_text() else {
+            continue;
+        };
+        let hidden = descendant.ancestors().any(|ancestor| {
+            ancestor
+                .value()
+                .as_element()
+                .is_some_and(|el| matches!(el.name(), "script" | "style" | "noscript"))
+        });
+        let fragment = fragment.trim();
+        if !hidden && !fragment.is_empty() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(fragment);
+        }
+    }
+    text
+}
+
+/// Whether text reads like a cookie consent prompt.
+#[cfg(feature = "dom")]
+fn mentions_consent(text: &str) -> bool {
+    let text = text.to_lowercase();
+    text.contains("cookie") && (text.contains("consent") || text.contains("accept"))
+}
+
+#[cfg(all(test, feature = "dom"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_platform_and_interstitial() {
+        let html = r#"<html><head>
+            <script src="https://cdn.cookielaw.org/scripttemplates/otSDKStub.js"></script>
+        </head><body>
+            <div id="onetrust-consent-sdk"><div id="onetrust-banner-sdk">
+                We use cookies to personalise content and ads. Accept all cookies?
+            </div></div>
+            <p>Hi</p>
+        </body></html>"#;
+        let info = ConsentInfo::detect(&Html::parse_document(html));
+        assert_eq!(info.platforms, [ConsentPlatform::OneTrust]);
+        assert!(info.interstitial);
+    }
+
+    #[test]
+    fn test_banner_over_real_content_is_not_interstitial() {
+        let article = "Real article text. ".repeat(100);
+        let html = format!(
+            r#"<body><div id="CybotCookiebotDialog">Accept cookies</div><article>{article}</article></body>"#
+        );
+        let info = ConsentInfo::detect(&Html::parse_document(&html));
+        assert_eq!(info.platforms, [ConsentPlatform::Cookiebot]);
+        assert!(!info.interstitial);
+    }
+
+    #[test]
+    fn test_generic_consent_wall() {
+        let html =
+            "<body><h1>Before you continue</h1><p>We use cookies. Accept or reject.</p></body>";
+        let info = ConsentInfo::detect(&Html::parse_document(html));
+        assert!(info.platforms.is_empty());
+        assert!(info.interstitial);
+
+        let info = ConsentInfo::detect(&Html::parse_document("<body><p>Hello</p></body>"));
+        assert_eq!(info, ConsentInfo::default());
+    }
+}
@@ -0,0 +1,188 @@
+//! Cross-checks between metadata sources
+//!
+//! Pages usually state their title, description, and URL several times (HTML,
+//! OpenGraph, Twitter cards, Schema.org). [`HtmlInfo::consistency_report`]
+//! compares these so share debuggers can point out which preview will differ.
+
+use crate::html::HtmlInfo;
+use crate::url_util::{self, NormalizeOptions};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The piece of metadata that disagrees between sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ConsistencyField {
+    /// `<title>`, `og:title`, `twitter:title`, Schema.org `headline`
+    Title,
+    /// Meta description, `og:description`, `twitter:description`, Schema.org `description`
+    Description,
+    /// `<link rel="canonical">`, `og:url`
+    Url,
+}
+
+/// One field whose sources disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct ConsistencyIssue {
+    /// Which field disagrees
+    pub field: ConsistencyField,
+
+    /// `(source, value)` for every source that provided a value
+    pub values: Vec<(String, String)>,
+}
+
+/// Result of [`HtmlInfo::consistency_report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ConsistencyReport {
+    /// Fields whose sources disagree
+    pub issues: Vec<ConsistencyIssue>,
+}
+
+impl ConsistencyReport {
+    /// Whether every field agrees across its sources.
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl HtmlInfo {
+    /// Compare title, description, and URL across the metadata sources that provide them.
+    ///
+    /// Text is compared after collapsing whitespace; URLs with [`url_util::same_page`]. Sources
+    /// that are missing are skipped rather than reported.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{ConsistencyField, HtmlInfo};
+    ///
+    /// let html = r#"<title>Launch day</title>
+    ///     <meta property="og:title" content="Launch day!">
+    ///     <meta name="twitter:title" content="Launch day">"#;
+    /// let report = HtmlInfo::from_string(html, None).unwrap().consistency_report();
+    /// # #[cfg(feature = "opengraph")] {
+    /// assert_eq!(report.issues[0].field, ConsistencyField::Title);
+    /// assert_eq!(report.issues[0].values.len(), 3);
+    /// # }
+    /// ```
+    pub fn consistency_report(&self) -> ConsistencyReport {
+        #[cfg(feature = "schema-org")]
+        let schema = |key: &str| {
+            self.schema_org
+                .iter()
+                .find_map(|item| item.get_str(key))
+                .map(str::to_string)
+        };
+        #[cfg(not(feature = "schema-org"))]
+        let schema = |_: &str| None;
+        let meta = |name: &str| self.meta(name).map(str::to_string);
+
+        let checks = [
+            (
+                ConsistencyField::Title,
+                vec![
+                    ("title", self.title.clone()),
+                    ("og:title", self.opengraph.title.clone()),
+                    ("twitter:title", meta("twitter:title")),
+                    ("schema:headline", schema("headline")),
+                ],
+            ),
+            (
+                ConsistencyField::Description,
+                vec![
+                    ("description", self.description.clone()),
+                    ("og:description", self.opengraph.description.clone()),
+                    ("twitter:description", meta("twitter:description")),
+                    ("schema:description", schema("description")),
+                ],
+            ),
+            (
+                ConsistencyField::Url,
+                vec![
+                    ("canonical", self.canonical_url.clone()),
+                    ("og:url", self.opengraph.url.clone()),
+                ],
+            ),
+        ];
+
+        let issues = checks
+            .into_iter()
+            .filter_map(|(field, sources)| {
+                let values: Vec<(String, String)> = sources
+                    .into_iter()
+                    .filter_map(|(source, value)| Some((source.to_string(), value?)))
+                    .collect();
+                let normalize = |value: &str| match field {
+                    ConsistencyField::Url => normalize_url(value),
+                    _ => collapse_whitespace(value),
+                };
+                let first = normalize(&values.first()?.1);
+                values
+                    .iter()
+                    .any(|(_, value)| normalize(value) != first)
+                    .then_some(ConsistencyIssue { field, values })
+            })
+            .collect();
+
+        ConsistencyReport { issues }
+    }
+}
+
+pub(crate) fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn normalize_url(value: &str) -> String {
+    url_util::normalize(value, &NormalizeOptions::default())
+        .unwrap_or_else(|| value.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "schema-org")]
+    use crate::SchemaOrg;
+
+    #[cfg(feature = "schema-org")]
+    #[test]
+    fn test_reports_each_mismatched_field() {
+        let mut info = HtmlInfo {
+            title: Some("Post  title".to_string()),
+            description: Some("Short".to_string()),
+            canonical_url: Some("https://example.com".to_string()),
+            ..HtmlInfo::default()
+        };
+        info.opengraph.title = Some("Post title".to_string());
+        info.opengraph.description = Some("Longer description".to_string());
+        info.opengraph.url = Some("https://example.com/".to_string());
+        info.schema_org.extend(SchemaOrg::parse(
+            r#"{"@type": "Article", "headline": "Other"}"#,
+        ));
+
+        let report = info.consistency_report();
+        let fields: Vec<_> = report.issues.iter().map(|issue| issue.field).collect();
+        assert_eq!(
+            fields,
+            [ConsistencyField::Title, ConsistencyField::Description]
+        );
+        assert_eq!(
+            report.issues[0].values[2],
+            ("schema:headline".to_string(), "Other".to_string())
+        );
+    }
+
+    #[test]
+    fn test_missing_sources_are_not_issues() {
+        let info = HtmlInfo {
+            title: Some("Only title".to_string()),
+            ..HtmlInfo::default()
+        };
+        assert!(info.consistency_report().is_consistent());
+    }
+}
@@ -0,0 +1,609 @@
+//! Conversion of page content to other formats
+//!
+//! [`to_markdown`] renders the main content of a page (its `<article>` or
+//! `<main>`, falling back to the body) as Markdown, keeping headings, links,
+//! lists, emphasis, and images, for read-later and archiving tools.
+//! [`to_plain_text`] renders the same content as readable plain text with
+//! links as numbered footnotes, for email digests and terminals.
+
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+use scraper::{ElementRef, Html, Node, Selector};
+use url::Url;
+
+use crate::html::BLOCK_ELEMENTS;
+
+/// Elements nested deeper than this are flattened to their text, so hostile
+/// markup cannot exhaust the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Elements whose content is never rendered.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "head", "svg", "iframe", "object", "button",
+];
+
+/// Options controlling [`to_markdown_with_options`] and [`to_plain_text_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct ContentOptions {
+    /// Convert the whole body instead of the main content.
+    ///
+    /// Default: false.
+    pub whole_body: bool,
+}
+
+impl ContentOptions {
+    /// Create a new ContentOptions with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the whole body is converted instead of the main content.
+    pub fn whole_body(mut self, whole_body: bool) -> Self {
+        self.whole_body = whole_body;
+        self
+    }
+}
+
+fn main_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse(r#"article, main, [role="main"]"#).unwrap())
+}
+
+fn body_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("body").unwrap())
+}
+
+/// Convert the main content of an HTML document to Markdown.
+///
+/// Relative link and image URLs are resolved against `base_url` when given.
+///
+/// # Example
+/// ```
+/// use webpage_info::content;
+///
+/// let html = r#"<body><nav>Menu</nav><article>
+///     <h1>Hello</h1><p>Some <em>new</em> <a href="/docs">docs</a>.</p>
+/// </article></body>"#;
+/// let markdown = content::to_markdown(html, Some("https://example.com/"));
+/// assert_eq!(markdown, "# Hello\n\nSome *new* [docs](https://example.com/docs).");
+/// ```
+pub fn to_markdown(html: &str, base_url: Option<&str>) -> String {
+    to_markdown_with_options(html, base_url, &ContentOptions::default())
+}
+
+/// Convert an HTML document to Markdown with custom options.
+pub fn to_markdown_with_options(
+    html: &str,
+    base_url: Option<&str>,
+    options: &ContentOptions,
+) -> String {
+    render(html, base_url, options, Format::Markdown)
+}
+
+/// Convert the main content of an HTML document to plain text.
+///
+/// Links become numbered references such as `docs [1]`, listed with their URLs
+/// at the end; a URL linked several times keeps its first number.
+///
+/// # Example
+/// ```
+/// use webpage_info::content;
+///
+/// let html = r#"<article><h1>Hello</h1><p>Read the <a href="/docs">docs</a>.</p></article>"#;
+/// let text = content::to_plain_text(html, Some("https://example.com/"));
+/// assert_eq!(text, "Hello\n=====\n\nRead the docs [1].\n\n[1] https://example.com/docs");
+/// ```
+pub fn to_plain_text(html: &str, base_url: Option<&str>) -> String {
+    to_plain_text_with_options(html, base_url, &ContentOptions::default())
+}
+
+/// Convert an HTML document to plain text with custom options.
+pub fn to_plain_text_with_options(
+    html: &str,
+    base_url: Option<&str>,
+    options: &ContentOptions,
+) -> String {
+    render(html, base_url, options, Format::PlainText)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Markdown,
+    PlainText,
+}
+
+fn render(html: &str, base_url: Option<&str>, options: &ContentOptions, format: Format) -> String {
+    let document = Html::parse_document(html);
+    let base = base_url.and_then(|u| Url::parse(u).ok());
+    let root = (!options.whole_body)
+        .then(|| document.select(main_selector()).next())
+        .flatten()
+        .or_else(|| document.select(body_selector()).next())
+        .unwrap_or_else(|| document.root_element());
+
+    let footnotes = RefCell::new(Vec::new());
+    let mut renderer = Renderer::new(base.as_ref(), format, &footnotes);
+    renderer.blocks(root, 0);
+    let mut out = renderer.out;
+
+    let footnotes = footnotes.into_inner();
+    if !footnotes.is_empty() {
+        let references: Vec<String> = footnotes
+            .iter()
+            .enumerate()
+            .map(|(index, url)| format!("[{}] {url}", index + 1))
+            .collect();
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        out.push_str(&references.join("\n"));
+    }
+    out
+}
+
+/// Accumulates rendered blocks separated by blank lines.
+struct Renderer<'a> {
+    base: Option<&'a Url>,
+    format: Format,
+    /// Link URLs referenced from plain text, numbered from 1
+    footnotes: &'a RefCell<Vec<String>>,
+    out: String,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(base: Option<&'a Url>, format: Format, footnotes: &'a RefCell<Vec<String>>) -> Self {
+        Self {
+            base,
+            format,
+            footnotes,
+            out: String::new(),
+        }
+    }
+
+    fn markdown(&self) -> bool {
+        self.format == Format::Markdown
+    }
+
+    /// Append a text node, escaping Markdown syntax when rendering Markdown.
+    fn text(&self, out: &mut String, text: &str) {
+        push_text(out, text, self.markdown());
+    }
+
+    fn push_block(&mut self, block: &str) {
+        let block = block.trim_matches('\n');
+        if block.trim().is_empty() {
+            return;
+        }
+        if !self.out.is_empty() {
+            self.out.push_str("\n\n");
+        }
+        self.out.push_str(block);
+    }
+
+    /// Render the children of `parent`, grouping runs of inline content into paragraphs.
+    fn blocks(&mut self, parent: ElementRef, depth: usize) {
+        let mut paragraph = String::new();
+        for child in parent.children() {
+            match child.value() {
+                Node::Text(text) => self.text(&mut paragraph, text),
+                Node::Element(element) => {
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+                    let name = element.name();
+                    if SKIPPED_ELEMENTS.contains(&name) {
+                        continue;
+                    }
+                    if BLOCK_ELEMENTS.contains(&name) {
+                        self.push_block(paragraph.trim());
+                        paragraph.clear();
+                        self.block(child, depth + 1);
+                    } else {
+                        self.inline(child, &mut paragraph, depth + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.push_block(paragraph.trim());
+    }
+
+    fn block(&mut self, element: ElementRef, depth: usize) {
+        if depth > MAX_DEPTH {
+            let mut text = String::new();
+            self.text(&mut text, &element.text().collect::<String>());
+            self.push_block(text.trim());
+            return;
+        }
+
+        let name = element.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                let text = self.inline_text(element, depth);
+                if text.is_empty() {
+                    return;
+                }
+                let heading = match (self.format, level) {
+                    (Format::Markdown, _) => format!("{} {text}", "#".repeat(level)),
+                    (Format::PlainText, 1 | 2) => {
+                        let underline = if level == 1 { "=" } else { "-" };
+                        format!("{text}\n{}", underline.repeat(text.chars().count()))
+                    }
+                    (Format::PlainText, _) => text,
+                };
+                self.push_block(&heading);
+            }
+            "ul" | "ol" => self.list(element, name == "ol", depth),
+            "blockquote" => {
+                let inner = self.nested(element, depth);
+                let quoted: Vec<String> = inner
+                    .lines()
+                    .map(|line| format!("> {line}").trim_end().to_string())
+                    .collect();
+                self.push_block(&quoted.join("\n"));
+            }
+            "pre" => {
+                let code: String = element.text().collect();
+                let code = code.trim_matches('\n');
+                if self.markdown() {
+                    let fence = if code.contains("```") { "~~~" } else { "```" };
+                    self.push_block(&format!("{fence}\n{code}\n{fence}"));
+                } else {
+                    let indented: Vec<String> =
+                        code.lines().map(|line| format!("    {line}")).collect();
+                    self.push_block(&indented.join("\n"));
+                }
+            }
+            "hr" => self.push_block("---"),
+            "table" => self.table(element, depth),
+            _ => self.blocks(element, depth),
+        }
+    }
+
+    fn list(&mut self, list: ElementRef, ordered: bool, depth: usize) {
+        let items: Vec<String> = list
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|item| item.value().name() == "li")
+            .enumerate()
+            .filter_map(|(index, item)| {
+                let content = self.nested(item, depth);
+                if content.is_empty() {
+                    return None;
+                }
+                let marker = if ordered {
+                    format!("{}. ", index + 1)
+                } else {
+                    "- ".to_string()
+                };
+                let indent = " ".repeat(marker.len());
+                let mut lines = content.lines();
+                let mut rendered = format!("{marker}{}", lines.next().unwrap_or_default());
+                for line in lines {
+                    rendered.push('\n');
+                    if !line.is_empty() {
+                        rendered.push_str(&indent);
+                        rendered.push_str(line);
+                    }
+                }
+                Some(rendered)
+            })
+            .collect();
+        self.push_block(&items.join("\n"));
+    }
+
+    fn table(&mut self, table: ElementRef, depth: usize) {
+        let rows: Vec<Vec<String>> = table
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|row| row.value().name() == "tr")
+            .map(|row| {
+                row.children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|cell| matches!(cell.value().name(), "th" | "td"))
+                    .map(|cell| {
+                        let text = self.inline_text(cell, depth);
+                        if self.markdown() {
+                            text.replace('|', "\\|")
+                        } else {
+                            text
+                        }
+                    })
+                    .collect()
+            })
+            .filter(|cells: &Vec<String>| !cells.is_empty())
+            .collect();
+        let Some(columns) = rows.iter().map(Vec::len).max() else {
+            return;
+        };
+        if !self.markdown() {
+            let lines: Vec<String> = rows.iter().map(|row| row.join(" | ")).collect();
+            self.push_block(&lines.join("\n"));
+            return;
+        }
+
+        let line = |cells: &[String]| {
+            let mut line = String::from("|");
+            for column in 0..columns {
+                line.push(' ');
+                line.push_str(cells.get(column).map_or("", String::as_str));
+                line.push_str(" |");
+            }
+            line
+        };
+        let mut lines = vec![line(&rows[0]), line(&vec!["---".to_string(); columns])];
+        lines.extend(rows[1..].iter().map(|row| line(row)));
+        self.push_block(&lines.join("\n"));
+    }
+
+    /// Render an element's children as a standalone fragment.
+    fn nested(&self, element: ElementRef, depth: usize) -> String {
+        let mut inner = Renderer::new(self.base, self.format, self.footnotes);
+        inner.blocks(element, depth);
+        inner.out
+    }
+
+    /// Render an element's content as a single line of inline markup.
+    fn inline_text(&self, element: ElementRef, depth: usize) -> String {
+        let mut text = String::new();
+        for child in element.children() {
+            match child.value() {
+                Node::Text(fragment) => self.text(&mut text, fragment),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.inline(child, &mut text, depth + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+        text.trim().to_string()
+    }
+
+    fn inline(&self, element: ElementRef, out: &mut String, depth: usize) {
+        let name = element.value().name();
+        if SKIPPED_ELEMENTS.contains(&name) {
+            return;
+        }
+        if depth > MAX_DEPTH {
+            self.text(out, &element.text().collect::<String>());
+            return;
+        }
+
+        match name {
+            "br" => {
+                if !out.trim().is_empty() {
+                    out.truncate(out.trim_end().len());
+                    out.push_str(if self.markdown() { "  \n" } else { "\n" });
+                }
+            }
+            "img" => {
+                let src = element
+                    .value()
+                    .attr("src")
+                    .and_then(|src| self.resolve(src))
+                    .filter(|src| !src.starts_with("data:"));
+                let alt = element.value().attr("alt").unwrap_or_default().trim();
+                match src {
+                    Some(src) if self.markdown() => {
+                        out.push_str(&format!("![{}]({src})", escape(alt)));
+                    }
+                    Some(_) if !alt.is_empty() => self.text(out, &format!("[image: {alt}]")),
+                    _ => {}
+                }
+            }
+            "a" => {
+                let text = self.inline_text(element, depth);
+                let raw_href = element.value().attr("href").unwrap_or_default();
+                let href = self
+                    .resolve(raw_href)
+                    .filter(|href| !href.starts_with("javascript:"));
+                match href {
+                    Some(href) if self.markdown() && text.is_empty() => {
+                        out.push_str(&format!("<{href}>"));
+                    }
+                    Some(href) if self.markdown() => {
+                        wrap(out, element, &format!("[{text}]({href})"));
+                    }
+                    // In-page anchors are meaningless outside the page
+                    Some(href) if !raw_href.trim_start().starts_with('#') => {
+                        let number = self.footnote(href);
+                        if text.is_empty() {
+                            wrap(out, element, &format!("[{number}]"));
+                        } else {
+                            wrap(out, element, &format!("{text} [{number}]"));
+                        }
+                    }
+                    _ => wrap(out, element, &text),
+                }
+            }
+            "strong" | "b" => self.emphasis(element, out, "**", depth),
+            "em" | "i" => self.emphasis(element, out, "*", depth),
+            "code" | "kbd" | "samp" => {
+                let code: String = element.text().collect();
+                let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+                if code.is_empty() {
+                    return;
+                }
+                if self.markdown() {
+                    let tick = if code.contains('`') { "``" } else { "`" };
+                    wrap(out, element, &format!("{tick}{code}{tick}"));
+                } else {
+                    wrap(out, element, &code);
+                }
+            }
+            _ => {
+                for child in element.children() {
+                    match child.value() {
+                        Node::Text(text) => self.text(out, text),
+                        Node::Element(_) => {
+                            if let Some(child) = ElementRef::wrap(child) {
+                                self.inline(child, out, depth + 1);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn emphasis(&self, element: ElementRef, out: &mut String, marker: &str, depth: usize) {
+        let text = self.inline_text(element, depth);
+        if text.is_empty() {
+            return;
+        }
+        if self.markdown() {
+            wrap(out, element, &format!("{marker}{text}{marker}"));
+        } else {
+            wrap(out, element, &text);
+        }
+    }
+
+    /// The footnote number of a link URL, adding it if new.
+    fn footnote(&self, url: String) -> usize {
+        let mut footnotes = self.footnotes.borrow_mut();
+        let index = match footnotes.iter().position(|known| *known == url) {
+            Some(index) => index,
+            None => {
+                footnotes.push(url);
+                footnotes.len() - 1
+            }
+        };
+        index + 1
+    }
+
+    fn resolve(&self, url: &str) -> Option<String> {
+        let url = url.trim();
+        if url.is_empty() {
+            return None;
+        }
+        Some(match self.base.map(|base| base.join(url)) {
+            Some(Ok(resolved)) => resolved.into(),
+            _ => url.to_string(),
+        })
+    }
+}
+
+/// Append rendered inline markup, keeping the whitespace that surrounded the element.
+fn wrap(out: &mut String, element: ElementRef, rendered: &str) {
+    let text: String = element.text().collect();
+    if text.starts_with(char::is_whitespace) {
+        push_text(out, " ", false);
+    }
+    out.push_str(rendered);
+    if text.ends_with(char::is_whitespace) {
+        out.push(' ');
+    }
+}
+
+/// Append a text node with whitespace collapsed and, if `escape_markdown`,
+/// Markdown syntax escaped.
+fn push_text(out: &mut String, text: &str, escape_markdown: bool) {
+    let leading = text.starts_with(char::is_whitespace);
+    let trailing = text.ends_with(char::is_whitespace);
+    let words: Vec<_> = text.split_whitespace().collect();
+
+    if (leading || words.is_empty()) && !out.is_empty() && !out.ends_with([' ', '\n']) {
+        out.push(' ');
+    }
+    if words.is_empty() {
+        return;
+    }
+    let text = words.join(" ");
+    if escape_markdown {
+        out.push_str(&escape(&text));
+    } else {
+        out.push_str(&text);
+    }
+    if trailing {
+        out.push(' ');
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structure_is_preserved() {
+        let html = r#"<html><body>
+            <header>Site menu</header>
+            <main>
+                <h2>Install</h2>
+                <p>Run <code>cargo add</code> and read the <strong>guide</strong>:</p>
+                <ul>
+                    <li>First</li>
+                    <li>Second<ol><li>Nested</li></ol></li>
+                </ul>
+                <blockquote><p>Quoted line</p></blockquote>
+                <img src="/shot.png" alt="Screenshot">
+                <pre>fn main() {
+    println!("hi");
+}</pre>
+                <table><tr><th>Name</th><th>Value</th></tr><tr><td>a|b</td><td>1</td></tr></table>
+            </main>
+        </body></html>"#;
+        let markdown = to_markdown(html, Some("https://example.com/docs/"));
+
+        assert_eq!(
+            markdown,
+            "## Install\n\n\
+             Run `cargo add` and read the **guide**:\n\n\
+             - First\n\
+             - Second\n\n  1. Nested\n\n\
+             > Quoted line\n\n\
+             ![Screenshot](https://example.com/shot.png)\n\n\
+             ```\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+             | Name | Value |\n| --- | --- |\n| a\\|b | 1 |"
+        );
+    }
+
+    #[test]
+    fn test_whole_body_and_escaping() {
+        let html = "<body><nav>Menu</nav><article><p>2 * 3 = [six]<br>done</p></article></body>";
+
+        assert_eq!(to_markdown(html, None), "2 \\* 3 = \\[six\\]  \ndone");
+        assert_eq!(
+            to_markdown_with_options(html, None, &ContentOptions::new().whole_body(true)),
+            "Menu\n\n2 \\* 3 = \\[six\\]  \ndone"
+        );
+    }
+
+    #[test]
+    fn test_plain_text_with_footnotes() {
+        let html = r##"<body><main>
+            <h2>Links</h2>
+            <p>See <a href="/a">the <b>first</b></a> and <a href="https://b.example/">second</a>,
+               then <a href="/a">first again</a> or <a href="#top">top</a>.</p>
+            <ul><li>Item *one*</li></ul>
+            <img src="/x.png" alt="Chart"><br>
+            <pre>let x = 1;</pre>
+        </main></body>"##;
+        let text = to_plain_text(html, Some("https://example.com/"));
+
+        assert_eq!(
+            text,
+            "Links\n-----\n\n\
+             See the first [1] and second [2], then first again [1] or top.\n\n\
+             - Item *one*\n\n\
+             [image: Chart]\n\n    let x = 1;\n\n\
+             [1] https://example.com/a\n[2] https://b.example/"
+        );
+        assert_eq!(to_plain_text("<p>No links</p>", None), "No links");
+    }
+}
@@ -0,0 +1,436 @@
+//! Cookies and sessions
+//!
+//! Some sites only serve their real metadata once a consent or session cookie
+//! is set, often by a redirect to a consent page and back. With
+//! [`HttpOptions::cookies`] enabled, a [`WebpageClient`] keeps the cookies its
+//! responses set and sends them with later requests, including the next hops
+//! of a redirect chain; clones of the client share one session.
+//! [`HttpOptions::cookie`] sends a cookie to a domain before any were set, and
+//! [`HttpInfo::cookies`] lists the cookies set while fetching a page.
+//!
+//! [`WebpageClient`]: crate::WebpageClient
+//! [`HttpInfo::cookies`]: crate::HttpInfo::cookies
+
+use std::sync::Mutex;
+
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::http::HttpOptions;
+use crate::http_date::{now, parse_http_date};
+
+/// A cookie set by a `Set-Cookie` response header.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct Cookie {
+    /// Cookie name
+    pub name: String,
+
+    /// Cookie value
+    pub value: String,
+
+    /// Host or domain the cookie is sent to
+    pub domain: String,
+
+    /// Whether the cookie is only sent to [`domain`](Self::domain) itself,
+    /// because the response set no `Domain` attribute
+    pub host_only: bool,
+
+    /// Path prefix the cookie is sent to
+    pub path: String,
+
+    /// Only sent over `https`
+    pub secure: bool,
+
+    /// Hidden from scripts (`HttpOnly`)
+    pub http_only: bool,
+
+    /// Expiry in seconds since the Unix epoch; `None` for a session cookie
+    pub expires: Option<u64>,
+}
+
+impl Cookie {
+    /// Parse a `Set-Cookie` header value received from `url`.
+    ///
+    /// Returns `None` for malformed values and for a `Domain` that does not
+    /// cover the host of `url` or is a public suffix of it.
+    pub(crate) fn parse(set_cookie: &str, url: &Url) -> Option<Self> {
+        let host = url.host_str()?.trim_end_matches('.').to_ascii_lowercase();
+        let mut parts = set_cookie.split(';');
+        let (name, value) = parts.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let mut cookie = Self {
+            name: name.to_string(),
+            value: value.trim().trim_matches('"').to_string(),
+            domain: host.clone(),
+            host_only: true,
+            path: default_path(url),
+            ..Self::default()
+        };
+        let mut max_age = None;
+        for attribute in parts {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    let domain = value
+                        .trim_start_matches('.')
+                        .trim_end_matches('.')
+                        .to_ascii_lowercase();
+                    if !domain_matches(&host, &domain) {
+                        return None;
+                    }
+                    // A public suffix such as `co.uk` would leak the cookie to
+                    // every site under it; only its own host may set it, and
+                    // then as a host-only cookie
+                    if is_public_suffix(&domain) {
+                        if domain != host {
+                            return None;
+                        }
+                        continue;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "expires" => {
+                    cookie.expires = cookie
+                        .expires
+                        .or_else(|| parse_http_date(&value.replace('-', " ")));
+                }
+                _ => {}
+            }
+        }
+        // Max-Age takes precedence over Expires
+        if let Some(max_age) = max_age {
+            cookie.expires = Some(now().saturating_add_signed(max_age.max(-1)));
+        }
+        Some(cookie)
+    }
+
+    /// Whether the cookie has expired, which for a `Set-Cookie` means it
+    /// deletes any stored cookie of the same name.
+    pub fn is_expired(&self) -> bool {
+        self.expires.is_some_and(|expires| expires <= now())
+    }
+
+    /// Whether the cookie is sent with a request to `url`.
+    pub fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let host_matches = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(&host, &self.domain)
+        };
+        let path = url.path();
+        let path_matches = path == self.path
+            || path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/'));
+        host_matches
+            && path_matches
+            && (!self.secure || url.scheme() == "https")
+            && !self.is_expired()
+    }
+
+    /// Whether `other` replaces this cookie when stored.
+    fn same_slot(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.domain == other.domain
+            && self.host_only == other.host_only
+            && self.path == other.path
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|sub| sub.ends_with('.'))
+}
+
+/// Whether `domain` is a public suffix, under which unrelated sites register
+/// (`com`, `co.uk`, `github.io`); unlisted single labels count as one.
+fn is_public_suffix(domain: &str) -> bool {
+    psl::suffix(domain.as_bytes()).is_some_and(|suffix| suffix.as_bytes() == domain.as_bytes())
+}
+
+/// The directory of the request path, the default `Path` of a cookie.
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(end) => url.path()[..end].to_string(),
+    }
+}
+
+/// The cookies of a client's session.
+#[derive(Debug, Default)]
+pub(crate) struct CookieJar {
+    cookies: Mutex<Vec<Cookie>>,
+}
+
+impl CookieJar {
+    /// Store `cookie`, replacing one with the same name, domain, and path; an
+    /// expired cookie only removes the stored one.
+    pub(crate) fn store(&self, cookie: Cookie) {
+        let mut cookies = self.lock();
+        cookies.retain(|stored| !stored.same_slot(&cookie) && !stored.is_expired());
+        if !cookie.is_expired() {
+            cookies.push(cookie);
+        }
+    }
+
+    /// Every unexpired cookie, in the order they were set.
+    pub(crate) fn cookies(&self) -> Vec<Cookie> {
+        let mut cookies = self.lock();
+        cookies.retain(|cookie| !cookie.is_expired());
+        cookies.clone()
+    }
+
+    pub(crate) fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// The cookies to send to `url`: the session's, then any cookie seeded
+    /// with [`HttpOptions::cookie`] whose name the session has not set.
+    pub(crate) fn request_cookies(&self, url: &Url, options: &HttpOptions) -> Vec<Cookie> {
+        let mut cookies: Vec<Cookie> = if options.cookies {
+            let stored = self.lock();
+            stored
+                .iter()
+                .filter(|cookie| cookie.matches(url))
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        for (domain, value) in &options.seed_cookies {
+            let Some(seeded) = seed(domain, value) else {
+                continue;
+            };
+            if seeded.matches(url) && !cookies.iter().any(|c| c.name == seeded.name) {
+                cookies.push(seeded);
+            }
+        }
+        // More specific paths first, as browsers send them
+        cookies.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+        cookies
+    }
+
+    /// Add the cookies for `url` to a request's `headers`, after any custom
+    /// `Cookie` header.
+    pub(crate) fn add_header(&self, headers: &mut HeaderMap, url: &Url, options: &HttpOptions) {
+        let Some(value) = header_value(&self.request_cookies(url, options)) else {
+            return;
+        };
+        let value = match headers.get(header::COOKIE).and_then(|v| v.to_str().ok()) {
+            Some(custom) => format!("{custom}; {value}"),
+            None => value,
+        };
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(header::COOKIE, value);
+        }
+    }
+
+    /// Parse the `Set-Cookie` headers of a response from `url`, storing them
+    /// if [`HttpOptions::cookies`] is enabled.
+    pub(crate) fn receive(
+        &self,
+        headers: &HeaderMap,
+        url: &Url,
+        options: &HttpOptions,
+    ) -> Vec<Cookie> {
+        let received: Vec<Cookie> = headers
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|value| Cookie::parse(value.to_str().ok()?, url))
+            .collect();
+        if options.cookies {
+            for cookie in &received {
+                self.store(cookie.clone());
+            }
+        }
+        received
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Cookie>> {
+        self.cookies.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A cookie seeded for `domain` and its subdomains.
+fn seed(domain: &str, value: &str) -> Option<Cookie> {
+    let domain = domain.trim().trim_start_matches('.').to_ascii_lowercase();
+    let url = Url::parse(&format!("https://{domain}/")).ok()?;
+    let mut cookie = Cookie::parse(value, &url)?;
+    cookie.host_only = false;
+    Some(cookie)
+}
+
+/// The `Cookie` request header for `cookies`.
+fn header_value(cookies: &[Cookie]) -> Option<String> {
+    (!cookies.is_empty()).then(|| {
+        cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{header, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::WebpageClient;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let page = url("https://www.example.com/news/today");
+        let cookie = Cookie::parse(
+            "sid=\"abc\"; Domain=.Example.com; Path=/news; Secure; HttpOnly; Expires=Wed, 21-Oct-2037 07:28:00 GMT",
+            &page,
+        )
+        .unwrap();
+        assert_eq!(cookie.value, "abc");
+        assert_eq!(cookie.domain, "example.com");
+        assert!(!cookie.host_only && cookie.secure && cookie.http_only);
+        assert_eq!(cookie.expires, Some(2_139_722_880));
+        assert!(cookie.matches(&url("https://example.com/news/")));
+        assert!(!cookie.matches(&url("http://example.com/news")));
+        assert!(!cookie.matches(&url("https://example.com/newsletter")));
+
+        let cookie = Cookie::parse("lang=de", &page).unwrap();
+        assert!(cookie.host_only);
+        assert_eq!(cookie.path, "/news");
+        assert!(!cookie.matches(&url("https://cdn.www.example.com/news")));
+
+        assert!(Cookie::parse("a=1; Domain=other.example", &page).is_none());
+        assert!(Cookie::parse("a=1; Domain=com", &page).is_none());
+
+        let evil = url("https://evil.co.uk/");
+        assert!(Cookie::parse("a=1; Domain=co.uk", &evil).is_none());
+        let cookie = Cookie::parse("a=1; Domain=evil.co.uk", &evil).unwrap();
+        assert!(cookie.matches(&url("https://www.evil.co.uk/")));
+        assert!(!cookie.matches(&url("https://other.co.uk/")));
+        // A suffix's own host may still set a cookie for itself only
+        let cookie = Cookie::parse("a=1; Domain=github.io", &url("https://github.io/")).unwrap();
+        assert!(cookie.host_only);
+        assert!(!cookie.matches(&url("https://someone.github.io/")));
+        assert!(Cookie::parse("novalue", &page).is_none());
+        assert!(
+            Cookie::parse(
+                "a=1; Max-Age=0; Expires=Wed, 21 Oct 2037 07:28:00 GMT",
+                &page
+            )
+            .unwrap()
+            .is_expired()
+        );
+    }
+
+    #[test]
+    fn test_jar_replaces_deletes_and_seeds() {
+        let jar = CookieJar::default();
+        let page = url("https://example.com/");
+        jar.store(Cookie::parse("sid=1", &page).unwrap());
+        jar.store(Cookie::parse("sid=2", &page).unwrap());
+        jar.store(Cookie::parse("theme=dark; Path=/app", &page).unwrap());
+        assert_eq!(jar.cookies().len(), 2);
+
+        let options = HttpOptions::new()
+            .cookies(true)
+            .cookie("example.com", "sid=seeded")
+            .cookie("example.com", "consent=yes");
+        let sent = jar.request_cookies(&url("https://shop.example.com/app/cart"), &options);
+        assert_eq!(
+            header_value(&sent).as_deref(),
+            Some("sid=seeded; consent=yes")
+        );
+        let sent = jar.request_cookies(&url("https://example.com/app/cart"), &options);
+        assert_eq!(
+            header_value(&sent).as_deref(),
+            Some("theme=dark; sid=2; consent=yes")
+        );
+
+        jar.store(Cookie::parse("sid=; Max-Age=0", &page).unwrap());
+        assert_eq!(jar.cookies()[0].name, "theme");
+        let without_jar = HttpOptions::new().cookie("example.com", "consent=yes");
+        let sent = jar.request_cookies(&url("https://example.com/app/"), &without_jar);
+        assert_eq!(header_value(&sent).as_deref(), Some("consent=yes"));
+    }
+
+    #[test]
+    fn test_public_suffix_cookie_is_not_shared() {
+        let jar = CookieJar::default();
+        let options = HttpOptions::new().cookies(true);
+        let mut headers = HeaderMap::new();
+        for value in ["track=1; Domain=co.uk", "sid=2; Domain=evil.co.uk"] {
+            headers.append(header::SET_COOKIE, HeaderValue::from_static(value));
+        }
+        let received = jar.receive(&headers, &url("https://evil.co.uk/"), &options);
+        assert_eq!(received.len(), 1);
+        assert!(
+            jar.request_cookies(&url("https://other.co.uk/"), &options)
+                .is_empty()
+        );
+        let sent = jar.request_cookies(&url("https://www.evil.co.uk/"), &options);
+        assert_eq!(header_value(&sent).as_deref(), Some("sid=2"));
+    }
+
+    #[tokio::test]
+    async fn test_cookies_follow_redirects() {
+        let server = MockServer::start().await;
+        Mock::given(path("/article"))
+            .and(header("cookie", "visit=1; consent=yes"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw("<title>Article</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/article"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("location", "/consent")
+                    .insert_header("set-cookie", "visit=1; HttpOnly"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/consent"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("location", "/article")
+                    .insert_header("set-cookie", "consent=yes; Path=/"),
+            )
+            .mount(&server)
+            .await;
+
+        let options = HttpOptions::new().block_private_ips(false).cookies(true);
+        let client = WebpageClient::new(options).unwrap();
+        let url = format!("{}/article", server.uri());
+        let info = client.fetch(&url).await.unwrap();
+        assert_eq!(info.html.title.as_deref(), Some("Article"));
+        let names: Vec<_> = info.http.cookies.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["visit", "consent"]);
+        assert_eq!(client.cookies(), info.http.cookies);
+
+        client.clear_cookies();
+        assert!(client.cookies().is_empty());
+    }
+}
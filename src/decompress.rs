@@ -0,0 +1,169 @@
+//! Transparent decompression of local files
+//!
+//! Crawl corpora are usually stored compressed. [`read_file`] recognizes
+//! gzip, Brotli, and zstd files by extension (and gzip and zstd by their magic
+//! bytes when sniffing) and returns the decompressed contents. Output is capped
+//! at [`MAX_DECOMPRESSED_SIZE`] so a small file cannot expand without bound.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Largest decompressed file accepted.
+#[cfg(any(feature = "decompress", feature = "zstd"))]
+const MAX_DECOMPRESSED_SIZE: u64 = 100 * 1024 * 1024; // 100 MB
+
+/// Compression formats recognized in local files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+impl Compression {
+    fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "gz" | "gzip" => Some(Self::Gzip),
+            "br" => Some(Self::Brotli),
+            "zst" | "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Brotli streams have no magic number, so only gzip and zstd are sniffed.
+    fn from_magic(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+}
+
+/// Read a file, decompressing it if its extension (or, with `sniff`, its
+/// leading bytes) marks it as compressed.
+pub(crate) fn read_file(path: &Path, sniff: bool) -> io::Result<Vec<u8>> {
+    let bytes = fs::read(path)?;
+    let compression = Compression::from_extension(path)
+        .or_else(|| sniff.then(|| Compression::from_magic(&bytes)).flatten());
+    match compression {
+        Some(compression) => decompress(&bytes, compression),
+        None => Ok(bytes),
+    }
+}
+
+#[cfg_attr(
+    not(any(feature = "decompress", feature = "zstd")),
+    allow(unused_variables)
+)]
+fn decompress(bytes: &[u8], compression: Compression) -> io::Result<Vec<u8>> {
+    match compression {
+        #[cfg(feature = "decompress")]
+        Compression::Gzip => read_capped(flate2::read::MultiGzDecoder::new(bytes)),
+        #[cfg(feature = "decompress")]
+        Compression::Brotli => read_capped(brotli_decompressor::Decompressor::new(bytes, 4096)),
+        #[cfg(not(feature = "decompress"))]
+        Compression::Gzip | Compression::Brotli => Err(unsupported(
+            "reading compressed files requires the `decompress` feature",
+        )),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => read_capped(zstd::stream::read::Decoder::new(bytes)?),
+        #[cfg(not(feature = "zstd"))]
+        Compression::Zstd => Err(unsupported(
+            "reading zstd-compressed files requires the `zstd` feature",
+        )),
+    }
+}
+
+/// Read a decoder to the end, failing once it exceeds [`MAX_DECOMPRESSED_SIZE`].
+#[cfg(any(feature = "decompress", feature = "zstd"))]
+fn read_capped(decoder: impl io::Read) -> io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut out = Vec::new();
+    decoder
+        .take(MAX_DECOMPRESSED_SIZE + 1)
+        .read_to_end(&mut out)?;
+    if out.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("decompressed file exceeds {MAX_DECOMPRESSED_SIZE} bytes"),
+        ));
+    }
+    Ok(out)
+}
+
+#[cfg(not(all(feature = "decompress", feature = "zstd")))]
+fn unsupported(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, reason)
+}
+
+#[cfg(all(test, feature = "decompress"))]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    const HTML: &[u8] = b"<html><head><title>Compressed</title></head></html>";
+
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("webpage-info-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_reads_gzip_by_extension_or_magic() {
+        let path = temp_file("page.html.gz", &gzip(HTML));
+        assert_eq!(read_file(&path, false).unwrap(), HTML);
+        fs::remove_file(path).unwrap();
+
+        let path = temp_file("page.html", &gzip(HTML));
+        assert_ne!(read_file(&path, false).unwrap(), HTML);
+        assert_eq!(read_file(&path, true).unwrap(), HTML);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_reads_brotli_by_extension() {
+        let mut compressed = Vec::new();
+        brotli::CompressorWriter::new(&mut compressed, 4096, 5, 22)
+            .write_all(HTML)
+            .unwrap();
+        let path = temp_file("page.html.br", &compressed);
+        assert_eq!(read_file(&path, false).unwrap(), HTML);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_reads_zstd_by_extension_or_magic() {
+        let compressed = zstd::encode_all(HTML, 3).unwrap();
+        let path = temp_file("page.html.zst", &compressed);
+        assert_eq!(read_file(&path, false).unwrap(), HTML);
+        fs::remove_file(path).unwrap();
+
+        let path = temp_file("page.zstd.html", &compressed);
+        assert_eq!(read_file(&path, true).unwrap(), HTML);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_decompression_bombs_are_rejected() {
+        let bomb = gzip(&vec![0; MAX_DECOMPRESSED_SIZE as usize + 1]);
+        let path = temp_file("bomb.html.gz", &bomb);
+        let err = read_file(&path, false).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,5 +1,7 @@
 //! Error types for webpage-info
 
+use std::fmt;
+
 use thiserror::Error;
 
 /// Errors that can occur when fetching or parsing webpage information.
@@ -15,25 +17,337 @@ pub enum Error {
 
     /// HTTP request failed
     #[cfg(feature = "http")]
-    #[error("HTTP request failed: {0}")]
-    Http(#[from] reqwest::Error),
+    #[error("HTTP request to {url} failed during {phase}: {source}")]
+    Http {
+        /// The URL being fetched when the failure occurred
+        url: String,
+        /// The stage of the fetch that failed
+        phase: FetchPhase,
+        /// The HTTP status code, if a response was received
+        status: Option<u16>,
+        /// The underlying reqwest error
+        #[source]
+        source: reqwest::Error,
+    },
+
+    /// More redirects than allowed by `HttpOptions::max_redirects`
+    #[cfg(feature = "http")]
+    #[error("too many redirects fetching {url} (limit {max_redirects})")]
+    TooManyRedirects {
+        /// The originally requested URL
+        url: String,
+        /// The configured redirect limit
+        max_redirects: usize,
+    },
+
+    /// A redirect pointed back to a URL already visited in the same fetch
+    #[cfg(feature = "http")]
+    #[error("redirect loop fetching {url}: {}", chain.join(" -> "))]
+    RedirectLoop {
+        /// The originally requested URL
+        url: String,
+        /// Every URL visited, ending with the repeated one
+        chain: Vec<String>,
+    },
+
+    /// A redirect went from `https` to `http`
+    #[cfg(feature = "http")]
+    #[error("insecure redirect fetching {url}: {}", chain.join(" -> "))]
+    InsecureRedirect {
+        /// The originally requested URL
+        url: String,
+        /// Every URL visited, ending with the `http` target
+        chain: Vec<String>,
+    },
+
+    /// A batch fetch was cancelled at `HttpOptions::batch_deadline`
+    #[cfg(feature = "http")]
+    #[error("fetching {url} cancelled at the batch deadline of {deadline:?}")]
+    Timeout {
+        /// The URL that was being fetched
+        url: String,
+        /// The configured batch deadline
+        deadline: std::time::Duration,
+    },
+
+    /// The fetch was aborted by an observer hook
+    #[cfg(feature = "http")]
+    #[error("fetch aborted: {0}")]
+    Aborted(String),
+
+    /// A request policy denied the URL
+    #[cfg(feature = "http")]
+    #[error("request to {url} denied by policy: {reason}")]
+    RequestDenied {
+        /// The URL that was denied
+        url: String,
+        /// The reason given by the policy
+        reason: String,
+    },
+
+    /// A response policy rejected the response before parsing
+    #[cfg(feature = "http")]
+    #[error("response from {url} rejected by policy: {reason}")]
+    ResponseRejected {
+        /// The URL of the rejected response
+        url: String,
+        /// The reason given by the policy
+        reason: String,
+    },
+
+    /// The server answered with a `4xx`/`5xx` status and a non-HTML body
+    #[cfg(feature = "http")]
+    #[error("{url} returned HTTP {}", page.status_code)]
+    ErrorStatus {
+        /// The URL that returned the response
+        url: String,
+        /// The response, with a preview of its body
+        page: Box<crate::ErrorPage>,
+    },
+
+    /// A custom [`Transport`](crate::Transport) failed to fetch the page
+    #[cfg(feature = "http")]
+    #[error("transport failed to fetch {url}: {reason}")]
+    Transport {
+        /// The URL being fetched
+        url: String,
+        /// Description of the failure
+        reason: String,
+    },
+
+    /// Failed to build the underlying HTTP client
+    #[cfg(feature = "http")]
+    #[error("failed to build HTTP client: {0}")]
+    ClientBuild(#[source] reqwest::Error),
+
+    /// Failed to launch or configure the headless browser
+    #[cfg(feature = "render")]
+    #[error("failed to launch browser: {0}")]
+    Browser(String),
+
+    /// A null or non-UTF-8 argument was passed to a C function
+    #[cfg(feature = "ffi")]
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
 
     /// Failed to read file
     #[error("failed to read file: {0}")]
     Io(#[from] std::io::Error),
 
+    /// JSON serialization error
+    #[cfg(feature = "http")]
+    #[error("JSON serialization error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Malformed MHTML input
+    #[error("invalid MHTML: {0}")]
+    Mhtml(String),
+
+    /// Malformed WARC input
+    #[cfg(feature = "warc")]
+    #[error("invalid WARC record: {0}")]
+    Warc(String),
+
+    /// SQLite storage error
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
     /// HTML parsing error
     #[error("failed to parse HTML")]
     ParseError,
 
     /// Invalid response (non-HTML content type)
-    #[error("invalid content type: expected HTML, got {0}")]
-    InvalidContentType(String),
+    #[error("invalid content type for {url}: expected HTML, got {content_type}")]
+    InvalidContentType {
+        /// The URL that returned the response
+        url: String,
+        /// The content type returned by the server
+        content_type: String,
+    },
 
     /// Request blocked due to SSRF protection
     #[cfg(feature = "http")]
-    #[error("SSRF protection: {0}")]
-    SsrfBlocked(String),
+    #[error("SSRF protection: {reason}")]
+    SsrfBlocked {
+        /// The URL that was blocked
+        url: String,
+        /// Why the request was blocked
+        reason: String,
+    },
+}
+
+/// The stage of a fetch at which an error occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FetchPhase {
+    /// Resolving the host name
+    Dns,
+    /// Establishing the TCP connection
+    Connect,
+    /// Negotiating TLS
+    Tls,
+    /// Sending the request or waiting for response headers
+    Request,
+    /// Following a redirect
+    Redirect,
+    /// Downloading or decoding the response body
+    Body,
+    /// Interpreting the downloaded document
+    Parse,
+}
+
+impl fmt::Display for FetchPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Dns => "dns",
+            Self::Connect => "connect",
+            Self::Tls => "tls",
+            Self::Request => "request",
+            Self::Redirect => "redirect",
+            Self::Body => "body",
+            Self::Parse => "parse",
+        })
+    }
+}
+
+impl Error {
+    /// Wrap a reqwest error, classifying the phase from the error itself.
+    #[cfg(feature = "http")]
+    pub(crate) fn from_reqwest(url: &str, source: reqwest::Error) -> Self {
+        let phase = classify_reqwest_error(&source);
+        Self::from_reqwest_in(url, phase, source)
+    }
+
+    /// Wrap a reqwest error that occurred in a known phase.
+    #[cfg(feature = "http")]
+    pub(crate) fn from_reqwest_in(url: &str, phase: FetchPhase, source: reqwest::Error) -> Self {
+        Self::Http {
+            url: url.to_string(),
+            phase,
+            status: source.status().map(|s| s.as_u16()),
+            source,
+        }
+    }
+
+    /// The redirect chain of a [`RedirectLoop`](Self::RedirectLoop) or
+    /// [`InsecureRedirect`](Self::InsecureRedirect) error.
+    pub fn redirect_chain(&self) -> Option<&[String]> {
+        match self {
+            #[cfg(feature = "http")]
+            Self::RedirectLoop { chain, .. } | Self::InsecureRedirect { chain, .. } => Some(chain),
+            _ => None,
+        }
+    }
+
+    /// The URL the error relates to, if known.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            #[cfg(feature = "http")]
+            Self::Http { url, .. }
+            | Self::SsrfBlocked { url, .. }
+            | Self::Transport { url, .. }
+            | Self::RequestDenied { url, .. }
+            | Self::ResponseRejected { url, .. }
+            | Self::ErrorStatus { url, .. }
+            | Self::TooManyRedirects { url, .. }
+            | Self::RedirectLoop { url, .. }
+            | Self::InsecureRedirect { url, .. }
+            | Self::Timeout { url, .. } => Some(url),
+            Self::InvalidContentType { url, .. } => Some(url),
+            _ => None,
+        }
+    }
+
+    /// The fetch phase the error occurred in, if applicable.
+    pub fn phase(&self) -> Option<FetchPhase> {
+        match self {
+            #[cfg(feature = "http")]
+            Self::Http { phase, .. } => Some(*phase),
+            #[cfg(feature = "http")]
+            Self::TooManyRedirects { .. }
+            | Self::RedirectLoop { .. }
+            | Self::InsecureRedirect { .. } => Some(FetchPhase::Redirect),
+            Self::InvalidContentType { .. } | Self::ParseError => Some(FetchPhase::Parse),
+            _ => None,
+        }
+    }
+
+    /// The HTTP status code associated with the error, if any.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            #[cfg(feature = "http")]
+            Self::Http { status, .. } => *status,
+            #[cfg(feature = "http")]
+            Self::ErrorStatus { page, .. } => Some(page.status_code),
+            _ => None,
+        }
+    }
+
+    /// Whether retrying the same request might succeed.
+    ///
+    /// True for timeouts (including batch deadlines), connection failures,
+    /// interrupted bodies, and `429`/`5xx` statuses. DNS, TLS, SSRF, and content errors are permanent.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "http")]
+            Self::Http {
+                phase,
+                status,
+                source,
+                ..
+            } => {
+                if let Some(status) = status {
+                    return *status == 429 || (500..600).contains(status);
+                }
+                source.is_timeout()
+                    || matches!(
+                        phase,
+                        FetchPhase::Connect | FetchPhase::Request | FetchPhase::Body
+                    )
+            }
+            #[cfg(feature = "http")]
+            Self::ErrorStatus { page, .. } => {
+                page.status_code == 429 || (500..600).contains(&page.status_code)
+            }
+            #[cfg(feature = "http")]
+            Self::Timeout { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// Determine the fetch phase from a reqwest error and its source chain.
+///
+/// reqwest only distinguishes connect errors as a whole, so DNS and TLS
+/// failures are recognized from the messages of the underlying errors.
+#[cfg(feature = "http")]
+fn classify_reqwest_error(error: &reqwest::Error) -> FetchPhase {
+    if error.is_redirect() {
+        return FetchPhase::Redirect;
+    }
+    if error.is_body() || error.is_decode() {
+        return FetchPhase::Body;
+    }
+    if !error.is_connect() {
+        return FetchPhase::Request;
+    }
+
+    let mut source = std::error::Error::source(error);
+    while let Some(err) = source {
+        let message = err.to_string().to_lowercase();
+        if message.contains("dns error") || message.contains("failed to lookup address") {
+            return FetchPhase::Dns;
+        }
+        if message.contains("certificate")
+            || message.contains("tls")
+            || message.contains("handshake")
+        {
+            return FetchPhase::Tls;
+        }
+        source = err.source();
+    }
+
+    FetchPhase::Connect
 }
 
 /// Result type alias for webpage-info operations.
@@ -0,0 +1,120 @@
+//! Summaries of `4xx`/`5xx` responses
+//!
+//! Blocks, captchas, and rate limits usually come back as an error status
+//! with a short page explaining why. [`HttpInfo::error_page`] summarizes such
+//! a response for diagnosis. Error responses that are not HTML fail the fetch
+//! with [`Error::ErrorStatus`](crate::Error::ErrorStatus), which carries the
+//! same summary.
+
+use serde::{Deserialize, Serialize};
+
+use crate::http::HttpInfo;
+use crate::text::decode_entities;
+
+/// Maximum length of [`ErrorPage::body_preview`], in bytes.
+const ERROR_PREVIEW_LEN: usize = 2048;
+
+/// An error response, for diagnosing why a fetch was refused.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ErrorPage {
+    /// HTTP status code, `400` or above
+    pub status_code: u16,
+
+    /// Content-Type of the error body, without parameters
+    pub content_type: Option<String>,
+
+    /// Response headers
+    pub headers: Vec<(String, String)>,
+
+    /// `<title>` of the error page, if it has one
+    pub title: Option<String>,
+
+    /// The start of the body, at most 2 KiB
+    pub body_preview: String,
+
+    /// Whether the body was longer than the preview
+    pub truncated: bool,
+}
+
+impl HttpInfo {
+    /// A summary of the response if its status is `400` or above.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HttpInfo;
+    ///
+    /// let http = HttpInfo {
+    ///     status_code: 403,
+    ///     body: "<html><title>Access denied</title><p>Ray ID: 1234</p></html>".to_string(),
+    ///     ..HttpInfo::default()
+    /// };
+    /// let page = http.error_page().unwrap();
+    /// assert_eq!(page.title.as_deref(), Some("Access denied"));
+    /// assert!(page.body_preview.contains("Ray ID"));
+    /// ```
+    pub fn error_page(&self) -> Option<ErrorPage> {
+        if self.status_code < 400 {
+            return None;
+        }
+        let end = self.body.floor_char_boundary(ERROR_PREVIEW_LEN);
+        Some(ErrorPage {
+            status_code: self.status_code,
+            content_type: self.content_type.clone(),
+            headers: self.headers.clone(),
+            title: title(&self.body),
+            body_preview: self.body[..end].to_string(),
+            truncated: end < self.body.len(),
+        })
+    }
+}
+
+/// The text of the first `<title>` element, found without parsing the page.
+fn title(body: &str) -> Option<String> {
+    let lower = body.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = decode_entities(&body[start..end]);
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_page_preview() {
+        assert!(HttpInfo::default().error_page().is_none());
+
+        let body = format!(
+            "<HTML><Title data-x>\n  Too Many &amp; More </Title>{}</HTML>",
+            "é".repeat(ERROR_PREVIEW_LEN)
+        );
+        let http = HttpInfo {
+            status_code: 429,
+            content_type: Some("text/html".to_string()),
+            headers: vec![("retry-after".to_string(), "120".to_string())],
+            body,
+            ..HttpInfo::default()
+        };
+        let page = http.error_page().unwrap();
+        assert_eq!(page.status_code, 429);
+        assert_eq!(page.title.as_deref(), Some("Too Many & More"));
+        assert!(page.truncated);
+        assert!(page.body_preview.len() <= ERROR_PREVIEW_LEN);
+        assert_eq!(page.headers[0].1, "120");
+
+        let http = HttpInfo {
+            status_code: 500,
+            body: "Internal Server Error".to_string(),
+            ..HttpInfo::default()
+        };
+        let page = http.error_page().unwrap();
+        assert_eq!(page.title, None);
+        assert_eq!(page.body_preview, "Internal Server Error");
+        assert!(!page.truncated);
+    }
+}
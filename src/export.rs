@@ -0,0 +1,247 @@
+//! Export helpers for batch pipelines
+//!
+//! Converts [`WebpageInfo`] results into JSON Lines and CSV for crawl pipelines
+//! feeding spreadsheets and data warehouses.
+
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::WebpageInfo;
+use crate::error::Result;
+
+/// Serialize a result as a single JSON Lines record (terminated by `\n`).
+///
+/// # Example
+/// ```
+/// use webpage_info::{WebpageInfo, export};
+///
+/// let line = export::to_json_line(&WebpageInfo::default()).unwrap();
+/// assert!(line.ends_with('\n'));
+/// assert_eq!(line.matches('\n').count(), 1);
+/// ```
+pub fn to_json_line(info: &WebpageInfo) -> Result<String> {
+    let mut line = serde_json::to_string(info)?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// A flattened subset of [`WebpageInfo`] with one column per field.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ExportRecord {
+    /// Final URL after redirects
+    pub url: String,
+
+    /// HTTP status code
+    pub status_code: u16,
+
+    /// Document title
+    pub title: Option<String>,
+
+    /// Meta description
+    pub description: Option<String>,
+
+    /// Canonical URL
+    pub canonical_url: Option<String>,
+
+    /// OpenGraph title
+    pub og_title: Option<String>,
+
+    /// OpenGraph description
+    pub og_description: Option<String>,
+
+    /// First OpenGraph image URL
+    pub og_image: Option<String>,
+
+    /// OpenGraph type
+    pub og_type: Option<String>,
+
+    /// OpenGraph site name
+    pub og_site_name: Option<String>,
+}
+
+impl ExportRecord {
+    /// Column names, in the order written by [`CsvWriter`].
+    pub const COLUMNS: &'static [&'static str] = &[
+        "url",
+        "status_code",
+        "title",
+        "description",
+        "canonical_url",
+        "og_title",
+        "og_description",
+        "og_image",
+        "og_type",
+        "og_site_name",
+    ];
+
+    /// Field values in [`COLUMNS`](Self::COLUMNS) order; missing values are empty.
+    pub fn values(&self) -> Vec<String> {
+        let opt = |v: &Option<String>| v.clone().unwrap_or_default();
+        vec![
+            self.url.clone(),
+            self.status_code.to_string(),
+            opt(&self.title),
+            opt(&self.description),
+            opt(&self.canonical_url),
+            opt(&self.og_title),
+            opt(&self.og_description),
+            opt(&self.og_image),
+            opt(&self.og_type),
+            opt(&self.og_site_name),
+        ]
+    }
+}
+
+impl From<&WebpageInfo> for ExportRecord {
+    fn from(info: &WebpageInfo) -> Self {
+        let og = &info.html.opengraph;
+        Self {
+            url: info.http.url.clone(),
+            status_code: info.http.status_code,
+            title: info.html.title.clone(),
+            description: info.html.description.clone(),
+            canonical_url: info.html.canonical_url.clone(),
+            og_title: og.title.clone(),
+            og_description: og.description.clone(),
+            og_image: og.images.first().map(|img| img.url.clone()),
+            og_type: og.og_type.clone(),
+            og_site_name: og.site_name.clone(),
+        }
+    }
+}
+
+/// Writes [`ExportRecord`]s as RFC 4180 CSV, emitting the header row first.
+///
+/// # Example
+/// ```
+/// use webpage_info::{WebpageInfo, export::CsvWriter};
+///
+/// let mut csv = CsvWriter::new(Vec::new());
+/// csv.write(&WebpageInfo::default()).unwrap();
+/// let output = String::from_utf8(csv.into_inner()).unwrap();
+/// assert!(output.starts_with("url,status_code,title"));
+/// ```
+#[derive(Debug)]
+pub struct CsvWriter<W: Write> {
+    writer: W,
+    header_written: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+    /// Create a CSV writer around any `Write` implementation.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            header_written: false,
+        }
+    }
+
+    /// Write one result as a CSV row.
+    pub fn write(&mut self, info: &WebpageInfo) -> Result<()> {
+        self.write_record(&ExportRecord::from(info))
+    }
+
+    /// Write an already flattened record as a CSV row.
+    pub fn write_record(&mut self, record: &ExportRecord) -> Result<()> {
+        if !self.header_written {
+            self.write_row(ExportRecord::COLUMNS.iter().copied())?;
+            self.header_written = true;
+        }
+        let values = record.values();
+        self.write_row(values.iter().map(String::as_str))
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Consume the CSV writer and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_row<'a>(&mut self, fields: impl Iterator<Item = &'a str>) -> Result<()> {
+        let mut line = String::new();
+        for (index, field) in fields.enumerate() {
+            if index > 0 {
+                line.push(',');
+            }
+            push_csv_field(&mut line, field);
+        }
+        line.push('\n');
+        Ok(self.writer.write_all(line.as_bytes())?)
+    }
+}
+
+/// Append a field, quoting it if it contains separators, quotes, or line breaks.
+fn push_csv_field(line: &mut String, field: &str) {
+    if field.contains([',', '"', '\n', '\r']) {
+        line.push('"');
+        line.push_str(&field.replace('"', "\"\""));
+        line.push('"');
+    } else {
+        line.push_str(field);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OpengraphMedia;
+
+    fn sample() -> WebpageInfo {
+        let mut info = WebpageInfo::default();
+        info.http.url = "https://example.com/".to_string();
+        info.http.status_code = 200;
+        info.html.title = Some("Hello, \"World\"".to_string());
+        info.html.opengraph.og_type = Some("article".to_string());
+        info.html
+            .opengraph
+            .images
+            .push(OpengraphMedia::new("https://example.com/a.png"));
+        info
+    }
+
+    #[test]
+    fn test_record_flattening() {
+        let record = ExportRecord::from(&sample());
+        assert_eq!(record.url, "https://example.com/");
+        assert_eq!(record.status_code, 200);
+        assert_eq!(
+            record.og_image.as_deref(),
+            Some("https://example.com/a.png")
+        );
+        assert_eq!(record.values().len(), ExportRecord::COLUMNS.len());
+    }
+
+    #[test]
+    fn test_csv_escaping_and_single_header() {
+        let mut csv = CsvWriter::new(Vec::new());
+        csv.write(&sample()).unwrap();
+        csv.write(&sample()).unwrap();
+        let output = String::from_utf8(csv.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], ExportRecord::COLUMNS.join(","));
+        assert_eq!(
+            lines[1],
+            r#"https://example.com/,200,"Hello, ""World""",,,,,https://example.com/a.png,article,"#
+        );
+    }
+
+    #[test]
+    fn test_json_line_is_single_line() {
+        let mut info = sample();
+        info.html.text_content = "line one\nline two".to_string();
+
+        let line = to_json_line(&info).unwrap();
+        assert_eq!(line.matches('\n').count(), 1);
+        let back: WebpageInfo = serde_json::from_str(&line).unwrap();
+        assert_eq!(back.html.text_content, info.html.text_content);
+    }
+}
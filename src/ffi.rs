@@ -0,0 +1,430 @@
+//! C ABI for embedding the extractor in other languages
+//!
+//! Build the shared and static libraries with the `ffi` feature:
+//!
+//! ```sh
+//! cargo build --release --features ffi
+//! ```
+//!
+//! and include `include/webpage_info.h`. Fetches run on a runtime owned by the
+//! library and block the calling thread, so the functions can be called from
+//! any thread of a Python, Ruby, or Go process.
+//!
+//! Strings returned as `char *` are owned by the caller and must be released
+//! with [`webpage_info_string_free`]; handles from [`webpage_info_fetch`] with
+//! [`webpage_info_free`]. A null return means failure; the message is available
+//! from [`webpage_info_last_error`] on the same thread until the next call.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+use std::sync::OnceLock;
+
+use tokio::runtime::Runtime;
+
+use crate::WebpageInfo;
+use crate::client::WebpageClient;
+use crate::error::{Error, Result};
+use crate::html::HtmlInfo;
+use crate::http::HttpOptions;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// The runtime fetches are driven on, created on first use.
+fn runtime() -> Result<&'static Runtime> {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = Runtime::new()?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// The client shared by all fetches, so connections are pooled across calls.
+fn client() -> Result<&'static WebpageClient> {
+    static CLIENT: OnceLock<WebpageClient> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = WebpageClient::new(HttpOptions::default())?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// # Safety
+///
+/// `url` must be null or a valid NUL-terminated string.
+unsafe fn fetch(url: *const c_char) -> Result<WebpageInfo> {
+    // SAFETY: guaranteed by the caller.
+    let url = unsafe { str_arg(url, "url") }?;
+    runtime()?.block_on(client()?.fetch(url))
+}
+
+/// Borrow a required string argument.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string that outlives the
+/// returned reference.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str> {
+    if ptr.is_null() {
+        return Err(Error::InvalidArgument(format!("`{name}` is null")));
+    }
+    // SAFETY: checked for null above; validity is guaranteed by the caller.
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| Error::InvalidArgument(format!("`{name}` is not valid UTF-8")))
+}
+
+/// Hand a string to the caller, dropping interior NUL bytes.
+fn into_c_string(s: impl Into<Vec<u8>>) -> *mut c_char {
+    let mut bytes = s.into();
+    bytes.retain(|&b| b != 0);
+    CString::new(bytes).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn optional_string(s: Option<&str>) -> *mut c_char {
+    s.map_or(ptr::null_mut(), into_c_string)
+}
+
+/// Forget the previous call's error; every exported function starts with this.
+fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Unwrap `result`, recording the error for [`webpage_info_last_error`].
+fn record<T>(result: Result<T>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(err) => {
+            let message = CString::new(err.to_string().replace('\0', "")).ok();
+            LAST_ERROR.with(|last| *last.borrow_mut() = message);
+            None
+        }
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> *mut c_char {
+    record(serde_json::to_string(value).map_err(Error::from)).map_or(ptr::null_mut(), into_c_string)
+}
+
+/// Borrow the handle behind `info`, if it is not null.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+unsafe fn handle<'a>(info: *const WebpageInfo) -> Option<&'a WebpageInfo> {
+    // SAFETY: guaranteed by the caller.
+    unsafe { info.as_ref() }
+}
+
+/// Fetch `url` and return the full result as JSON, or null on failure.
+///
+/// # Safety
+///
+/// `url` must be null or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_fetch_json(url: *const c_char) -> *mut c_char {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    record(unsafe { fetch(url) }).map_or(ptr::null_mut(), |info| to_json(&info))
+}
+
+/// Parse `html` without fetching and return the [`HtmlInfo`] as JSON, or null
+/// on failure. `base_url` may be null.
+///
+/// # Safety
+///
+/// `html` and `base_url` must each be null or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_parse_json(
+    html: *const c_char,
+    base_url: *const c_char,
+) -> *mut c_char {
+    clear_last_error();
+    let parsed = (|| {
+        // SAFETY: guaranteed by the caller.
+        let html = unsafe { str_arg(html, "html") }?;
+        let base_url = if base_url.is_null() {
+            None
+        } else {
+            // SAFETY: guaranteed by the caller.
+            Some(unsafe { str_arg(base_url, "base_url") }?)
+        };
+        HtmlInfo::from_string(html, base_url)
+    })();
+    record(parsed).map_or(ptr::null_mut(), |info| to_json(&info))
+}
+
+/// Fetch `url` and return a handle for the accessors below, or null on failure.
+///
+/// # Safety
+///
+/// `url` must be null or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_fetch(url: *const c_char) -> *mut WebpageInfo {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    record(unsafe { fetch(url) }).map_or(ptr::null_mut(), |info| Box::into_raw(Box::new(info)))
+}
+
+/// Release a handle from [`webpage_info_fetch`]. Null is ignored.
+///
+/// # Safety
+///
+/// `info` must be null or a handle that has not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_free(info: *mut WebpageInfo) {
+    clear_last_error();
+    if !info.is_null() {
+        // SAFETY: the handle came from `Box::into_raw` in `webpage_info_fetch`.
+        drop(unsafe { Box::from_raw(info) });
+    }
+}
+
+/// Release a string returned by this library. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library that has not been
+/// freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_string_free(s: *mut c_char) {
+    clear_last_error();
+    if !s.is_null() {
+        // SAFETY: the string came from `CString::into_raw`.
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// The message of the failure of the previous call on this thread, or null if
+/// it succeeded.
+///
+/// The string is owned by the library and valid until the next call on the
+/// same thread; do not free it.
+#[unsafe(no_mangle)]
+pub extern "C" fn webpage_info_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// The full result as JSON.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_to_json(info: *const WebpageInfo) -> *mut c_char {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    unsafe { handle(info) }.map_or(ptr::null_mut(), to_json)
+}
+
+/// The final URL after redirects.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_url(info: *const WebpageInfo) -> *mut c_char {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    optional_string(unsafe { handle(info) }.map(|info| info.http.url.as_str()))
+}
+
+/// The HTTP status code, or 0 for a null handle.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_status_code(info: *const WebpageInfo) -> u16 {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    unsafe { handle(info) }.map_or(0, |info| info.http.status_code)
+}
+
+/// The document title, or null if there is none.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_title(info: *const WebpageInfo) -> *mut c_char {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    optional_string(unsafe { handle(info) }.and_then(|info| info.html.title.as_deref()))
+}
+
+/// The meta description, or null if there is none.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_description(info: *const WebpageInfo) -> *mut c_char {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    optional_string(unsafe { handle(info) }.and_then(|info| info.html.description.as_deref()))
+}
+
+/// The canonical URL, or null if there is none.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_canonical_url(info: *const WebpageInfo) -> *mut c_char {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    optional_string(unsafe { handle(info) }.and_then(|info| info.html.canonical_url.as_deref()))
+}
+
+/// The document language, or null if there is none.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_language(info: *const WebpageInfo) -> *mut c_char {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    optional_string(unsafe { handle(info) }.and_then(|info| info.html.language.as_deref()))
+}
+
+/// The extracted body text (empty without the `text` feature).
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_text_content(info: *const WebpageInfo) -> *mut c_char {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    optional_string(unsafe { handle(info) }.map(|info| info.html.text_content.as_str()))
+}
+
+/// The number of extracted links.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_link_count(info: *const WebpageInfo) -> usize {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    unsafe { handle(info) }.map_or(0, |info| info.html.links.len())
+}
+
+/// The resolved URL of the link at `index`, or null if out of range.
+///
+/// # Safety
+///
+/// `info` must be null or a live handle from [`webpage_info_fetch`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn webpage_info_link_url(
+    info: *const WebpageInfo,
+    index: usize,
+) -> *mut c_char {
+    clear_last_error();
+    // SAFETY: guaranteed by the caller.
+    optional_string(
+        unsafe { handle(info) }
+            .and_then(|info| info.html.links.get(index))
+            .map(|link| &*link.url),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take(s: *mut c_char) -> Option<String> {
+        if s.is_null() {
+            return None;
+        }
+        let owned = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { webpage_info_string_free(s) };
+        Some(owned)
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let html = CString::new("<title>Hi</title><a href=\"/a\">A</a>").unwrap();
+        let base = CString::new("https://example.com/").unwrap();
+        let json = take(unsafe { webpage_info_parse_json(html.as_ptr(), base.as_ptr()) }).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["title"], "Hi");
+
+        assert!(unsafe { webpage_info_parse_json(ptr::null(), ptr::null()) }.is_null());
+        let error = unsafe { CStr::from_ptr(webpage_info_last_error()) };
+        assert!(error.to_str().unwrap().contains("`html` is null"));
+
+        take(unsafe { webpage_info_parse_json(html.as_ptr(), ptr::null()) }).unwrap();
+        assert!(webpage_info_last_error().is_null());
+    }
+
+    #[test]
+    fn test_header_declares_every_function() {
+        let header = include_str!("../include/webpage_info.h");
+        let source = include_str!("ffi.rs");
+        let exported = source
+            .lines()
+            .filter_map(|line| line.split("extern \"C\" fn ").nth(1))
+            .filter_map(|rest| rest.split('(').next());
+        for name in exported {
+            let declared = [' ', '*'].map(|before| format!("{before}{name}("));
+            assert!(
+                declared.iter().any(|d| header.contains(d)),
+                "{name} missing"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fetch_failure_sets_last_error() {
+        let url = CString::new("http://127.0.0.1:9/").unwrap();
+        let info = unsafe { webpage_info_fetch(url.as_ptr()) };
+        assert!(info.is_null());
+        let error = unsafe { CStr::from_ptr(webpage_info_last_error()) };
+        assert!(error.to_str().unwrap().contains("SSRF"));
+
+        assert_eq!(unsafe { webpage_info_status_code(info) }, 0);
+        assert!(take(unsafe { webpage_info_title(info) }).is_none());
+        unsafe { webpage_info_free(info) };
+    }
+
+    #[test]
+    fn test_accessors() {
+        let mut page = WebpageInfo::default();
+        page.http.status_code = 200;
+        page.html = HtmlInfo::from_string(
+            "<title>Page</title><a href=\"/next\">Next</a>",
+            Some("https://example.com/"),
+        )
+        .unwrap();
+        let info = Box::into_raw(Box::new(page));
+
+        assert_eq!(unsafe { webpage_info_status_code(info) }, 200);
+        assert_eq!(
+            take(unsafe { webpage_info_title(info) }).as_deref(),
+            Some("Page")
+        );
+        assert!(take(unsafe { webpage_info_description(info) }).is_none());
+        #[cfg(feature = "links")]
+        {
+            assert_eq!(unsafe { webpage_info_link_count(info) }, 1);
+            assert_eq!(
+                take(unsafe { webpage_info_link_url(info, 0) }).as_deref(),
+                Some("https://example.com/next")
+            );
+        }
+        assert!(take(unsafe { webpage_info_link_url(info, 5) }).is_none());
+        assert!(
+            take(unsafe { webpage_info_to_json(info) })
+                .unwrap()
+                .contains("\"Page\"")
+        );
+        unsafe { webpage_info_free(info) };
+    }
+}
@@ -0,0 +1,462 @@
+//! Geographic metadata
+//!
+//! Pages declare where they are about in several vocabularies: the
+//! `geo.position` and `ICBM` meta tags, OpenGraph `place:location:*`
+//! properties, and Schema.org `geo` values (`GeoCoordinates` or `GeoShape`) on
+//! places, businesses, and event locations. [`HtmlInfo::geo`] normalizes them
+//! into [`GeoPoint`]s and [`GeoShape`]s; with the `geojson` feature,
+//! [`Geo::to_geojson`] converts the result for mapping libraries.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "schema-org", feature = "geojson"))]
+use serde_json::Value;
+#[cfg(feature = "geojson")]
+use serde_json::json;
+
+use crate::html::HtmlInfo;
+#[cfg(feature = "schema-org")]
+use crate::schema_org::{number, one_or_many, text};
+
+/// A WGS 84 coordinate.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GeoPoint {
+    /// Latitude in degrees, -90 to 90
+    pub latitude: f64,
+
+    /// Longitude in degrees, -180 to 180
+    pub longitude: f64,
+
+    /// Elevation in meters
+    pub elevation: Option<f64>,
+}
+
+/// An area from a Schema.org `GeoShape`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum GeoShape {
+    /// The rectangle between two corners (`box`)
+    Box {
+        /// Lower corner
+        south_west: GeoPoint,
+        /// Upper corner
+        north_east: GeoPoint,
+    },
+    /// A circle (`circle`)
+    Circle {
+        /// Center of the circle
+        center: GeoPoint,
+        /// Radius in meters
+        radius: f64,
+    },
+    /// A path through the points (`line`)
+    Line(Vec<GeoPoint>),
+    /// A closed polygon (`polygon`)
+    Polygon(Vec<GeoPoint>),
+}
+
+/// Geographic metadata of a page.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Geo {
+    /// Distinct coordinates: meta tags first, then Schema.org
+    pub points: Vec<GeoPoint>,
+
+    /// Schema.org shapes
+    pub shapes: Vec<GeoShape>,
+
+    /// Place name from `geo.placename`, or the name of the first Schema.org
+    /// item with a `geo` value
+    pub place_name: Option<String>,
+}
+
+impl GeoPoint {
+    /// A point, or `None` if the coordinates are out of range.
+    pub fn new(latitude: f64, longitude: f64) -> Option<Self> {
+        ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)).then_some(
+            Self {
+                latitude,
+                longitude,
+                elevation: None,
+            },
+        )
+    }
+
+    /// Parse `"lat;lon"` or `"lat, lon"`, as in `geo.position` and `ICBM`.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::GeoPoint;
+    ///
+    /// let point = GeoPoint::parse("48.8584;2.2945").unwrap();
+    /// assert_eq!((point.latitude, point.longitude), (48.8584, 2.2945));
+    /// assert!(GeoPoint::parse("91, 0").is_none());
+    /// ```
+    pub fn parse(value: &str) -> Option<Self> {
+        match numbers(value).as_slice() {
+            [latitude, longitude] => Self::new(*latitude, *longitude),
+            _ => None,
+        }
+    }
+
+    /// The point as a GeoJSON `Point` geometry.
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson(&self) -> Value {
+        json!({"type": "Point", "coordinates": self.position()})
+    }
+
+    /// A GeoJSON position: longitude first.
+    #[cfg(feature = "geojson")]
+    fn position(&self) -> Vec<f64> {
+        let mut position = vec![self.longitude, self.latitude];
+        position.extend(self.elevation);
+        position
+    }
+}
+
+impl GeoShape {
+    /// Parse a Schema.org `GeoShape` property: space-separated `lat lon` pairs,
+    /// plus a radius in meters for `circle`.
+    ///
+    /// `kind` is the property name: `box`, `circle`, `line`, or `polygon`.
+    pub fn parse(kind: &str, value: &str) -> Option<Self> {
+        let numbers = numbers(value);
+        if kind == "circle" {
+            let [latitude, longitude, radius] = numbers[..] else {
+                return None;
+            };
+            return Some(Self::Circle {
+                center: GeoPoint::new(latitude, longitude)?,
+                radius,
+            });
+        }
+        if !numbers.len().is_multiple_of(2) {
+            return None;
+        }
+        let points: Vec<GeoPoint> = numbers
+            .chunks(2)
+            .map(|pair| GeoPoint::new(pair[0], pair[1]))
+            .collect::<Option<_>>()?;
+        match (kind, points.as_slice()) {
+            ("box", [south_west, north_east]) => Some(Self::Box {
+                south_west: *south_west,
+                north_east: *north_east,
+            }),
+            ("line", [_, _, ..]) => Some(Self::Line(points)),
+            ("polygon", [_, _, _, ..]) => Some(Self::Polygon(points)),
+            _ => None,
+        }
+    }
+
+    /// The shape as a GeoJSON geometry.
+    ///
+    /// GeoJSON has no circles; a circle becomes its center `Point`, with the
+    /// radius left to [`Geo::to_geojson`]'s feature properties.
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson(&self) -> Value {
+        match self {
+            Self::Box {
+                south_west: sw,
+                north_east: ne,
+            } => {
+                let ring = [
+                    [sw.longitude, sw.latitude],
+                    [ne.longitude, sw.latitude],
+                    [ne.longitude, ne.latitude],
+                    [sw.longitude, ne.latitude],
+                    [sw.longitude, sw.latitude],
+                ];
+                json!({"type": "Polygon", "coordinates": [ring]})
+            }
+            Self::Circle { center, .. } => center.to_geojson(),
+            Self::Line(points) => json!({
+                "type": "LineString",
+                "coordinates": points.iter().map(GeoPoint::position).collect::<Vec<_>>(),
+            }),
+            Self::Polygon(points) => {
+                let mut ring: Vec<_> = points.iter().map(GeoPoint::position).collect();
+                if points.first() != points.last() {
+                    ring.push(points[0].position());
+                }
+                json!({"type": "Polygon", "coordinates": [ring]})
+            }
+        }
+    }
+}
+
+impl Geo {
+    /// Whether no coordinates or shapes were found.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty() && self.shapes.is_empty()
+    }
+
+    /// The points and shapes as a GeoJSON `FeatureCollection`.
+    ///
+    /// Every feature has the `place_name` as its `name` property; circles also
+    /// get a `radius` in meters.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<meta name="geo.position" content="48.8584;2.2945">"#;
+    /// let geojson = HtmlInfo::from_string(html, None).unwrap().geo().to_geojson();
+    /// assert_eq!(geojson["features"][0]["geometry"]["coordinates"][0], 2.2945);
+    /// ```
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson(&self) -> Value {
+        let feature = |geometry: Value, radius: Option<f64>| {
+            let mut properties = json!({"name": self.place_name});
+            if let Some(radius) = radius {
+                properties["radius"] = json!(radius);
+            }
+            json!({"type": "Feature", "geometry": geometry, "properties": properties})
+        };
+        let points = self
+            .points
+            .iter()
+            .map(|point| feature(point.to_geojson(), None));
+        let shapes = self.shapes.iter().map(|shape| {
+            let radius = match shape {
+                GeoShape::Circle { radius, .. } => Some(*radius),
+                _ => None,
+            };
+            feature(shape.to_geojson(), radius)
+        });
+        json!({"type": "FeatureCollection", "features": points.chain(shapes).collect::<Vec<_>>()})
+    }
+}
+
+impl HtmlInfo {
+    /// Coordinates and areas declared by meta tags and Schema.org data.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<meta name="ICBM" content="51.5007, -0.1246">
+    ///     <meta name="geo.placename" content="London">"#;
+    /// let geo = HtmlInfo::from_string(html, None).unwrap().geo();
+    /// assert_eq!(geo.points[0].latitude, 51.5007);
+    /// assert_eq!(geo.place_name.as_deref(), Some("London"));
+    /// ```
+    pub fn geo(&self) -> Geo {
+        let mut geo = Geo {
+            place_name: self
+                .meta("geo.placename")
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string),
+            ..Geo::default()
+        };
+        let opengraph = match (
+            self.meta("place:location:latitude"),
+            self.meta("place:location:longitude"),
+        ) {
+            (Some(latitude), Some(longitude)) => {
+                GeoPoint::parse(&format!("{latitude};{longitude}"))
+            }
+            _ => None,
+        };
+        let meta_points = [self.meta("geo.position"), self.meta("ICBM")]
+            .into_iter()
+            .flatten()
+            .filter_map(GeoPoint::parse)
+            .chain(opengraph);
+        for point in meta_points {
+            push_point(&mut geo.points, point);
+        }
+        self.add_schema_geo(&mut geo);
+        geo
+    }
+
+    /// Add Schema.org `geo` values of items and their `location`s.
+    #[cfg(feature = "schema-org")]
+    fn add_schema_geo(&self, geo: &mut Geo) {
+        let places = self.schema_org.iter().flat_map(|item| {
+            std::iter::once(&item.value).chain(one_or_many(item.value.get("location")))
+        });
+        for place in places {
+            let Some(value) = place.get("geo") else {
+                continue;
+            };
+            let mut found = false;
+            for value in one_or_many(Some(value)) {
+                if let Some(point) = schema_point(value) {
+                    push_point(&mut geo.points, point);
+                    found = true;
+                }
+                for kind in ["box", "circle", "line", "polygon"] {
+                    let shape =
+                        text(value.get(kind)).and_then(|shape| GeoShape::parse(kind, &shape));
+                    if let Some(shape) = shape {
+                        geo.shapes.push(shape);
+                        found = true;
+                    }
+                }
+            }
+            if found && geo.place_name.is_none() {
+                geo.place_name = text(place.get("name"));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "schema-org"))]
+    fn add_schema_geo(&self, _: &mut Geo) {}
+}
+
+/// A point from `GeoCoordinates`, whose values may be numbers or strings.
+#[cfg(feature = "schema-org")]
+fn schema_point(value: &Value) -> Option<GeoPoint> {
+    let mut point = GeoPoint::new(
+        number(value.get("latitude"))?,
+        number(value.get("longitude"))?,
+    )?;
+    point.elevation = number(value.get("elevation"));
+    Some(point)
+}
+
+/// Add a point unless one with the same coordinates is already present.
+fn push_point(points: &mut Vec<GeoPoint>, point: GeoPoint) {
+    let duplicate = points
+        .iter()
+        .any(|p| p.latitude == point.latitude && p.longitude == point.longitude);
+    if !duplicate {
+        points.push(point);
+    }
+}
+
+/// The numbers in a list separated by whitespace, commas, or semicolons.
+fn numbers(value: &str) -> Vec<f64> {
+    value
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse::<f64>().ok().filter(|n| n.is_finite()))
+        .collect::<Option<_>>()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shapes() {
+        let point = |latitude, longitude| GeoPoint::new(latitude, longitude).unwrap();
+        assert_eq!(
+            GeoShape::parse("box", "40.0 -75.0 41.0 -74.0"),
+            Some(GeoShape::Box {
+                south_west: point(40.0, -75.0),
+                north_east: point(41.0, -74.0),
+            })
+        );
+        assert_eq!(
+            GeoShape::parse("circle", "40.7 -74.0 500"),
+            Some(GeoShape::Circle {
+                center: point(40.7, -74.0),
+                radius: 500.0,
+            })
+        );
+        assert_eq!(
+            GeoShape::parse("line", "1 2, 3 4"),
+            Some(GeoShape::Line(vec![point(1.0, 2.0), point(3.0, 4.0)]))
+        );
+        assert!(GeoShape::parse("polygon", "1 2 3 4").is_none());
+        assert!(GeoShape::parse("box", "1 2 3").is_none());
+        assert!(GeoShape::parse("line", "1 2 x 4").is_none());
+        assert!(GeoShape::parse("sphere", "1 2 3 4").is_none());
+    }
+
+    #[cfg(feature = "dom")]
+    #[test]
+    fn test_meta_points_are_deduplicated() {
+        let html = r#"<meta name="geo.position" content="48.8584;2.2945">
+            <meta name="ICBM" content="48.8584, 2.2945">
+            <meta property="place:location:latitude" content="40.7">
+            <meta property="place:location:longitude" content="-74.0">"#;
+
+        let geo = HtmlInfo::from_string(html, None).unwrap().geo();
+        assert_eq!(
+            geo.points,
+            [
+                GeoPoint::new(48.8584, 2.2945).unwrap(),
+                GeoPoint::new(40.7, -74.0).unwrap(),
+            ]
+        );
+        assert!(HtmlInfo::default().geo().is_empty());
+    }
+
+    #[cfg(all(feature = "dom", feature = "schema-org"))]
+    #[test]
+    fn test_schema_org_geo() {
+        let html = r#"<script type="application/ld+json">[{
+            "@type": "Event",
+            "location": {
+                "@type": "Place",
+                "name": "Central Park",
+                "geo": {"@type": "GeoCoordinates", "latitude": "40.7829", "longitude": -73.9654, "elevation": 40}
+            }
+        }, {
+            "@type": "Place",
+            "geo": {"@type": "GeoShape", "circle": "40.7 -74.0 500"}
+        }]</script>"#;
+
+        let geo = HtmlInfo::from_string(html, None).unwrap().geo();
+        assert_eq!(geo.place_name.as_deref(), Some("Central Park"));
+        assert_eq!(
+            geo.points,
+            [GeoPoint {
+                latitude: 40.7829,
+                longitude: -73.9654,
+                elevation: Some(40.0),
+            }]
+        );
+        assert_eq!(geo.shapes.len(), 1);
+    }
+
+    #[cfg(feature = "geojson")]
+    #[test]
+    fn test_to_geojson() {
+        let geo = Geo {
+            points: vec![GeoPoint::new(1.0, 2.0).unwrap()],
+            shapes: vec![
+                GeoShape::parse("box", "0 0 1 1").unwrap(),
+                GeoShape::parse("circle", "1 2 50").unwrap(),
+            ],
+            place_name: Some("Somewhere".to_string()),
+        };
+        assert_eq!(
+            geo.to_geojson(),
+            json!({
+                "type": "FeatureCollection",
+                "features": [
+                    {
+                        "type": "Feature",
+                        "geometry": {"type": "Point", "coordinates": [2.0, 1.0]},
+                        "properties": {"name": "Somewhere"}
+                    },
+                    {
+                        "type": "Feature",
+                        "geometry": {
+                            "type": "Polygon",
+                            "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]]
+                        },
+                        "properties": {"name": "Somewhere"}
+                    },
+                    {
+                        "type": "Feature",
+                        "geometry": {"type": "Point", "coordinates": [2.0, 1.0]},
+                        "properties": {"name": "Somewhere", "radius": 50.0}
+                    }
+                ]
+            })
+        );
+    }
+}
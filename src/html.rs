@@ -1,18 +1,60 @@
 //! HTML document parsing and metadata extraction
 
-use std::collections::{HashMap, HashSet};
-use std::fs;
+#[cfg(any(
+    all(feature = "dom", feature = "text"),
+    all(any(feature = "dom", feature = "streaming"), feature = "links")
+))]
+use std::collections::HashSet;
+#[cfg(feature = "dom")]
+use std::io::{self, Read};
+#[cfg(feature = "dom")]
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "dom")]
 use std::path::Path;
+use std::sync::Arc;
+#[cfg(feature = "dom")]
 use std::sync::OnceLock;
+use std::time::Duration;
+#[cfg(feature = "dom")]
+use std::time::Instant;
 
+#[cfg(all(feature = "dom", feature = "text"))]
+use scraper::ElementRef;
+#[cfg(feature = "dom")]
 use scraper::{Html, Selector};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "dom", all(feature = "streaming", feature = "links")))]
 use url::Url;
 
-use crate::error::Result;
+use crate::a11y::A11yStats;
+#[cfg(feature = "dom")]
+use crate::charset::CharsetInfo;
+use crate::consent::ConsentInfo;
+#[cfg(feature = "dom")]
+use crate::decompress;
+#[cfg(feature = "dom")]
+use crate::error::{Error, Result};
+use crate::icons::Icon;
+use crate::images::ImageCandidate;
+#[cfg(feature = "dom")]
+use crate::images::rank_images;
+use crate::link_safety::{LinkSafetyList, LinkVerdict};
+use crate::meta::MetaMap;
 use crate::opengraph::Opengraph;
+use crate::prices::Price;
+#[cfg(feature = "dom")]
+use crate::prices::extract_microdata_prices;
+use crate::rating::ContentRating;
+use crate::resources::Resource;
+#[cfg(feature = "dom")]
+use crate::resources::extract_resources;
+#[cfg(feature = "schema-org")]
 use crate::schema_org::SchemaOrg;
+use crate::security_policy::ReferrerPolicy;
+use crate::spa::SpaSignals;
 
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
 const FEED_MIME_TYPES: &[&str] = &[
     "application/atom+xml",
     "application/rss+xml",
@@ -22,57 +64,160 @@ const FEED_MIME_TYPES: &[&str] = &[
 ];
 
 // Security limits to prevent DoS via resource exhaustion
-const MAX_LINKS: usize = 10_000;
-const MAX_SCHEMA_ORG_ITEMS: usize = 100;
+#[cfg(all(any(feature = "dom", feature = "streaming"), feature = "links"))]
+pub(crate) const MAX_LINKS: usize = 10_000;
+#[cfg(all(any(feature = "dom", feature = "streaming"), feature = "schema-org"))]
+pub(crate) const MAX_SCHEMA_ORG_ITEMS: usize = 100;
+#[cfg(all(feature = "dom", feature = "text"))]
 const MAX_TEXT_CONTENT_LEN: usize = 1_000_000; // 1 MB of text
 
+#[cfg(all(feature = "dom", feature = "text"))]
+const MAX_LEAD_TEXT_LEN: usize = 1_000;
+/// Paragraphs shorter than this are not considered substantive lead text.
+#[cfg(all(feature = "dom", feature = "text"))]
+const MIN_LEAD_TEXT_LEN: usize = 80;
+/// Elements that start a new line in [`TextLayout::Lines`] and [`TextLayout::Paragraphs`].
+#[cfg(feature = "dom")]
+pub(crate) const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+/// Maximum number of [`HtmlInfo::text_blocks`].
+#[cfg(all(feature = "dom", feature = "text"))]
+const MAX_TEXT_BLOCKS: usize = 10_000;
+/// Containers whose paragraphs are boilerplate rather than content.
+#[cfg(all(feature = "dom", feature = "text"))]
+const BOILERPLATE_ELEMENTS: &[&str] = &["nav", "header", "footer", "aside", "form", "figcaption"];
+/// ARIA landmark roles of page chrome.
+#[cfg(all(feature = "dom", feature = "text"))]
+const BOILERPLATE_ROLES: &[&str] = &[
+    "banner",
+    "navigation",
+    "contentinfo",
+    "complementary",
+    "search",
+];
+/// `id`/`class` fragments that mark cookie banners, bylines, and similar boilerplate.
+#[cfg(all(feature = "dom", feature = "text"))]
+const BOILERPLATE_MARKERS: &[&str] = &[
+    "cookie",
+    "consent",
+    "banner",
+    "breadcrumb",
+    "byline",
+    "meta",
+    "share",
+    "newsletter",
+    "caption",
+];
+
+// How many loop iterations to run between time budget checks
+#[cfg(feature = "dom")]
+const BUDGET_CHECK_INTERVAL: usize = 256;
+
+#[cfg(feature = "dom")]
 fn title_selector() -> &'static Selector {
     static SELECTOR: OnceLock<Selector> = OnceLock::new();
     SELECTOR.get_or_init(|| Selector::parse("title").unwrap())
 }
 
+#[cfg(feature = "dom")]
 fn html_selector() -> &'static Selector {
     static SELECTOR: OnceLock<Selector> = OnceLock::new();
     SELECTOR.get_or_init(|| Selector::parse("html").unwrap())
 }
 
+#[cfg(feature = "dom")]
 fn meta_selector() -> &'static Selector {
     static SELECTOR: OnceLock<Selector> = OnceLock::new();
     SELECTOR.get_or_init(|| Selector::parse("meta").unwrap())
 }
 
+#[cfg(feature = "dom")]
 fn canonical_selector() -> &'static Selector {
     static SELECTOR: OnceLock<Selector> = OnceLock::new();
     SELECTOR.get_or_init(|| Selector::parse(r#"link[rel="canonical"]"#).unwrap())
 }
 
+#[cfg(feature = "dom")]
 fn feed_selector() -> &'static Selector {
     static SELECTOR: OnceLock<Selector> = OnceLock::new();
     SELECTOR.get_or_init(|| Selector::parse(r#"link[rel="alternate"]"#).unwrap())
 }
 
+#[cfg(all(feature = "dom", feature = "text"))]
 fn body_selector() -> &'static Selector {
     static SELECTOR: OnceLock<Selector> = OnceLock::new();
     SELECTOR.get_or_init(|| Selector::parse("body").unwrap())
 }
 
+#[cfg(all(feature = "dom", feature = "text"))]
 fn exclude_selector() -> &'static Selector {
     static SELECTOR: OnceLock<Selector> = OnceLock::new();
     SELECTOR.get_or_init(|| Selector::parse("script, style, noscript").unwrap())
 }
 
+#[cfg(all(feature = "dom", feature = "links"))]
 fn link_selector() -> &'static Selector {
     static SELECTOR: OnceLock<Selector> = OnceLock::new();
     SELECTOR.get_or_init(|| Selector::parse("a[href]").unwrap())
 }
 
+#[cfg(all(feature = "dom", feature = "text"))]
+fn paragraph_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("body p").unwrap())
+}
+
+#[cfg(feature = "dom")]
+fn password_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse(r#"input[type="password" i]"#).unwrap())
+}
+
+#[cfg(all(feature = "dom", feature = "schema-org"))]
 fn schema_org_selector() -> &'static Selector {
     static SELECTOR: OnceLock<Selector> = OnceLock::new();
     SELECTOR.get_or_init(|| Selector::parse(r#"script[type="application/ld+json"]"#).unwrap())
 }
 
 /// Parsed HTML document information.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct HtmlInfo {
     /// Document title from `<title>` tag
     pub title: Option<String>,
@@ -86,38 +231,662 @@ pub struct HtmlInfo {
     /// RSS/Atom feed URL from `<link rel="alternate" type="application/rss+xml">`
     pub feed_url: Option<String>,
 
+    /// Next page URL from `<link rel="next">` (resolved if base URL provided)
+    pub next_url: Option<String>,
+
+    /// Previous page URL from `<link rel="prev">` (resolved if base URL provided)
+    pub prev_url: Option<String>,
+
+    /// Whether the page has infinite-scroll or "Load more" markers
+    pub infinite_scroll: bool,
+
+    /// JSON oEmbed endpoint from `<link rel="alternate"
+    /// type="application/json+oembed">` (resolved if base URL provided)
+    pub oembed_url: Option<String>,
+
+    /// Favicons and touch icons from `<link rel="icon">` and its variants, in
+    /// document order, then `/favicon.ico` if no favicon is declared (resolved
+    /// if base URL provided); see [`HtmlInfo::best_icon`]
+    pub icons: Vec<Icon>,
+
     /// Document language from `<html lang="...">`
     pub language: Option<String>,
 
+    /// License URL from `rel="license"` or the Schema.org `license` property
+    pub license: Option<String>,
+
+    /// Copyright statement from a `copyright` or `dcterms.rights` meta tag, or
+    /// the Schema.org `copyrightNotice` property
+    pub copyright: Option<String>,
+
+    /// Ratings from `<meta name="rating">`, Schema.org `contentRating`, and
+    /// `og:restrictions:*`, if the page declares any
+    pub content_rating: Option<ContentRating>,
+
     /// Text content extracted from the body (tags stripped)
     pub text_content: String,
 
-    /// All meta tags as key-value pairs
-    pub meta: HashMap<String, String>,
+    /// Body text segmented into paragraphs, headings, list items, and quotes,
+    /// in document order
+    pub text_blocks: Vec<TextBlock>,
+
+    /// First substantive paragraph, skipping navigation, cookie banners, and bylines
+    pub lead_text: Option<String>,
+
+    /// All meta tags, in document order, including repeated names
+    pub meta: MetaMap,
 
     /// OpenGraph metadata
     pub opengraph: Opengraph,
 
     /// Schema.org structured data (JSON-LD)
+    #[cfg(feature = "schema-org")]
     pub schema_org: Vec<SchemaOrg>,
 
+    /// Schema.org items embedded as HTML microdata, in JSON-LD form;
+    /// [`HtmlInfo::structured_data`] iterates all three sources
+    #[cfg(feature = "schema-org")]
+    pub microdata: Vec<SchemaOrg>,
+
+    /// Schema.org items described with RDFa Lite attributes, in JSON-LD form
+    #[cfg(feature = "schema-org")]
+    pub rdfa: Vec<SchemaOrg>,
+
+    /// Text segments referenced by the Schema.org `speakable` property
+    #[cfg(feature = "schema-org")]
+    pub speakable: Vec<String>,
+
+    /// Prices from microdata `itemprop="price"` elements; [`HtmlInfo::prices`]
+    /// merges them with the other sources
+    pub microdata_prices: Vec<Price>,
+
     /// All links found in the document
     pub links: Vec<Link>,
+
+    /// Body images ranked as preview fallbacks, best first; only filled when the
+    /// metadata declares no OpenGraph, Twitter, or Schema.org image
+    pub image_candidates: Vec<ImageCandidate>,
+
+    /// Whether the document contains a password input
+    pub has_password_field: bool,
+
+    /// Scripts and stylesheets referenced by the document, for SRI auditing
+    pub resources: Vec<Resource>,
+
+    /// Raw `<head>…</head>` markup, if requested with [`ParseOptions::raw_head`]
+    pub raw_head: Option<String>,
+
+    /// Consent banner detection, if requested with [`ParseOptions::detect_consent`]
+    pub consent: Option<ConsentInfo>,
+
+    /// Accessibility statistics, if requested with [`ParseOptions::a11y`]
+    pub a11y_stats: Option<A11yStats>,
+
+    /// Framework markers and body structure, for [`HtmlInfo::is_client_rendered`]
+    pub spa_signals: SpaSignals,
+
+    /// Non-fatal problems encountered during extraction (e.g. an exceeded time budget)
+    pub warnings: Vec<String>,
 }
 
 /// A link found in the HTML document.
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+///
+/// Fields are shared strings: links repeating the same URL, anchor text, or
+/// `rel` value within a document point at a single allocation.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Link {
     /// The URL of the link (resolved if base URL provided)
-    pub url: String,
+    pub url: Arc<str>,
 
     /// The anchor text of the link
-    pub text: String,
+    pub text: Arc<str>,
 
     /// The rel attribute if present
-    pub rel: Option<String>,
+    pub rel: Option<Arc<str>>,
+
+    /// The target attribute if present (e.g. `_blank`)
+    pub target: Option<Arc<str>>,
+
+    /// The download attribute if present; empty when it names no file
+    pub download: Option<Arc<str>>,
+
+    /// The referrerpolicy attribute, if present and valid
+    pub referrer_policy: Option<ReferrerPolicy>,
+
+    /// Safety verdict of an external link, set by
+    /// [`HtmlInfo::annotate_links`] or [`ParseOptions::link_safety`]
+    pub verdict: Option<LinkVerdict>,
+}
+
+impl Link {
+    /// Whether the `rel` attribute contains `value` (case-insensitive).
+    pub fn has_rel(&self, value: &str) -> bool {
+        self.rel.as_deref().is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|token| token.eq_ignore_ascii_case(value))
+        })
+    }
+
+    /// Whether the link opens a new browsing context with access to
+    /// `window.opener`: `target="_blank"` without `rel="noopener"` or
+    /// `rel="noreferrer"`.
+    ///
+    /// Current browsers imply `noopener` for `_blank`, but older ones do not,
+    /// so tab-nabbing audits still flag these links.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<a href="/a" target="_blank">A</a>
+    ///     <a href="/b" target="_blank" rel="noopener">B</a>"#;
+    /// let info = HtmlInfo::from_string(html, None).unwrap();
+    /// # #[cfg(feature = "links")]
+    /// assert_eq!(info.unsafe_blank_links().len(), 1);
+    /// ```
+    pub fn is_unsafe_blank(&self) -> bool {
+        self.target
+            .as_deref()
+            .is_some_and(|target| target.trim().eq_ignore_ascii_case("_blank"))
+            && !self.has_rel("noopener")
+            && !self.has_rel("noreferrer")
+    }
+}
+
+/// The kind of a [`TextBlock`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TextBlockKind {
+    /// A `<p>` or other block of running text
+    #[default]
+    Paragraph,
+    /// An `<h1>`–`<h6>` heading
+    Heading,
+    /// An `<li>` list item
+    ListItem,
+    /// Text inside a `<blockquote>`
+    Quote,
+}
+
+/// A block-level segment of the body text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TextBlock {
+    /// What kind of element the text came from
+    pub kind: TextBlockKind,
+
+    /// The block's text, with its text nodes joined by spaces
+    pub text: String,
+}
+
+impl HtmlInfo {
+    /// Content of the meta tag with the given name; the last one if it repeats.
+    pub fn meta(&self, name: &str) -> Option<&str> {
+        self.meta.get(name)
+    }
+
+    /// Contents of every meta tag with the given name, in document order.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<meta property="article:tag" content="rust">
+    ///     <meta property="article:tag" content="html">"#;
+    /// let info = HtmlInfo::from_string(html, None).unwrap();
+    /// assert_eq!(info.meta_all("article:tag"), ["rust", "html"]);
+    /// ```
+    pub fn meta_all(&self, name: &str) -> &[String] {
+        self.meta.get_all(name)
+    }
+
+    /// Meta tags in one namespace, with the prefix stripped from their names.
+    ///
+    /// Useful for namespaces without a typed struct, such as `twitter:`, `dc:`,
+    /// `al:`, or `fb:`.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<meta name="twitter:card" content="summary">
+    ///     <meta name="twitter:site" content="@example">"#;
+    /// let info = HtmlInfo::from_string(html, None).unwrap();
+    /// let twitter = info.meta_with_prefix("twitter:");
+    /// assert_eq!(twitter.get("card"), Some("summary"));
+    /// assert_eq!(twitter.get("site"), Some("@example"));
+    /// ```
+    pub fn meta_with_prefix(&self, prefix: &str) -> MetaMap {
+        self.meta.with_prefix(prefix)
+    }
+
+    /// Links that open a new tab with access to `window.opener`; see
+    /// [`Link::is_unsafe_blank`].
+    pub fn unsafe_blank_links(&self) -> Vec<&Link> {
+        self.links
+            .iter()
+            .filter(|link| link.is_unsafe_blank())
+            .collect()
+    }
+}
+
+/// Deduplicates strings extracted from one document.
+#[cfg(all(any(feature = "dom", feature = "streaming"), feature = "links"))]
+#[derive(Default)]
+pub(crate) struct Interner(HashSet<Arc<str>>);
+
+#[cfg(all(any(feature = "dom", feature = "streaming"), feature = "links"))]
+impl Interner {
+    pub(crate) fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(existing) = self.0.get(value) {
+            return existing.clone();
+        }
+        let value: Arc<str> = Arc::from(value);
+        self.0.insert(value.clone());
+        value
+    }
+}
+
+/// How [`HtmlInfo::text_content`] separates the text of different elements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextLayout {
+    /// Join all text with single spaces
+    #[default]
+    Flat,
+    /// Start a new line at block boundaries (`<p>`, `<div>`, `<li>`, headings, `<br>`, ...)
+    Lines,
+    /// Like [`TextLayout::Lines`], but separate blocks with a blank line to keep
+    /// paragraph breaks
+    Paragraphs,
+}
+
+/// Options controlling HTML extraction.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Maximum wall-clock time to spend extracting from the parsed document.
+    ///
+    /// The clock starts before the document is tokenized and built into a
+    /// tree, but that step cannot be interrupted: the budget is only checked
+    /// between and during extraction passes. Limit the input size to bound
+    /// the parse itself. When the budget is exceeded, extraction stops and the
+    /// partially populated result is returned with a warning. Default:
+    /// unlimited.
+    pub time_budget: Option<Duration>,
+
+    /// Skip text extraction for documents larger than this many bytes.
+    ///
+    /// Metadata is still extracted; `text_content` is left empty and a warning is
+    /// recorded. Default: unlimited.
+    pub skip_text_above: Option<usize>,
+
+    /// Keep the raw `<head>` markup, capped at this many bytes.
+    ///
+    /// Default: not kept.
+    pub raw_head: Option<usize>,
+
+    /// Detect cookie consent banners into [`HtmlInfo::consent`].
+    ///
+    /// Default: false.
+    pub detect_consent: bool,
+
+    /// Decode leftover character references (e.g. `&amp;` in double-escaped
+    /// markup) and NFC-normalize extracted strings.
+    ///
+    /// Default: true.
+    pub decode_entities: bool,
+
+    /// Compute [`HtmlInfo::a11y_stats`].
+    ///
+    /// Default: false.
+    pub a11y: bool,
+
+    /// Separators inserted between text at block boundaries in `text_content`.
+    ///
+    /// Default: [`TextLayout::Flat`].
+    pub text_layout: TextLayout,
+
+    /// Collapse runs of whitespace inside text nodes to a single space.
+    ///
+    /// Default: false.
+    pub collapse_whitespace: bool,
+
+    /// Leave hidden elements out of `text_content` and `text_blocks`: `<template>`
+    /// content and elements with `hidden`, `aria-hidden="true"`, or an inline
+    /// `display: none` / `visibility: hidden` style.
+    ///
+    /// Default: true.
+    pub skip_hidden: bool,
+
+    /// Leave navigation, headers, footers, sidebars, and other page chrome out
+    /// of `text_content` and `text_blocks`.
+    ///
+    /// Default: false.
+    pub exclude_boilerplate: bool,
+
+    /// Run the passes that walk the body: links, text, lead text, and image
+    /// candidates.
+    ///
+    /// Default: true.
+    pub extract_body: bool,
+
+    /// Set [`Link::verdict`] on external links from these lists.
+    ///
+    /// Default: `None`.
+    pub link_safety: Option<Arc<LinkSafetyList>>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            time_budget: None,
+            skip_text_above: None,
+            raw_head: None,
+            detect_consent: false,
+            decode_entities: true,
+            a11y: false,
+            text_layout: TextLayout::Flat,
+            collapse_whitespace: false,
+            skip_hidden: true,
+            exclude_boilerplate: false,
+            extract_body: true,
+            link_safety: None,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Create a new ParseOptions with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Options that only extract head metadata: title, meta tags, OpenGraph,
+    /// Schema.org, and the other `<head>` fields.
+    ///
+    /// Skipping the body passes makes link previews of long articles much
+    /// cheaper.
+    pub fn metadata_only() -> Self {
+        Self::default().extract_body(false)
+    }
+
+    /// Set the maximum time to spend extracting from the parsed document.
+    ///
+    /// Protects workers against documents that are slow to extract from (e.g.
+    /// huge link or text counts). Building the tree is not interrupted, so cap
+    /// the input size as well to bound the parse.
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Skip text extraction when the document exceeds `size` bytes.
+    ///
+    /// Keeps latency predictable for preview services that only need metadata
+    /// from very large pages.
+    pub fn skip_text_above(mut self, size: usize) -> Self {
+        self.skip_text_above = Some(size);
+        self
+    }
+
+    /// Keep the raw `<head>…</head>` markup on [`HtmlInfo::raw_head`], up to `max_len` bytes.
+    ///
+    /// Lets debugging tools and share-preview validators show the exact markup the
+    /// extracted values came from.
+    pub fn raw_head(mut self, max_len: usize) -> Self {
+        self.raw_head = Some(max_len);
+        self
+    }
+
+    /// Set whether to detect cookie consent banners into [`HtmlInfo::consent`].
+    ///
+    /// Recognizes OneTrust, Cookiebot, Didomi, and other consent-management
+    /// platforms, and flags pages whose visible text is mostly the banner.
+    pub fn detect_consent(mut self, detect: bool) -> Self {
+        self.detect_consent = detect;
+        self
+    }
+
+    /// Set whether to decode character references and NFC-normalize extracted strings.
+    ///
+    /// Disable to get strings exactly as the parser produced them.
+    pub fn decode_entities(mut self, decode: bool) -> Self {
+        self.decode_entities = decode;
+        self
+    }
+
+    /// Set whether to compute accessibility statistics on [`HtmlInfo::a11y_stats`].
+    ///
+    /// Counts images without alt text, unnamed links, a missing `lang`, and skipped
+    /// heading levels, for audit dashboards.
+    pub fn a11y(mut self, a11y: bool) -> Self {
+        self.a11y = a11y;
+        self
+    }
+
+    /// Set how text from different block elements is separated in `text_content`.
+    ///
+    /// [`TextLayout::Flat`] joins everything with spaces, which loses the
+    /// document's structure; the other layouts keep lines and paragraphs.
+    pub fn text_layout(mut self, layout: TextLayout) -> Self {
+        self.text_layout = layout;
+        self
+    }
+
+    /// Set whether runs of whitespace inside text are collapsed to a single space.
+    ///
+    /// Text inside `<pre>` is left as is.
+    pub fn collapse_whitespace(mut self, collapse: bool) -> Self {
+        self.collapse_whitespace = collapse;
+        self
+    }
+
+    /// Set whether hidden elements are left out of the extracted text.
+    ///
+    /// Hidden SEO copy and collapsed cookie dialogs otherwise end up in
+    /// `text_content`. Disable to extract all text regardless of visibility.
+    pub fn skip_hidden(mut self, skip: bool) -> Self {
+        self.skip_hidden = skip;
+        self
+    }
+
+    /// Set whether page chrome is left out of the extracted text.
+    ///
+    /// Skips `nav`, `header`, `footer`, and `aside` elements, elements with a
+    /// `banner`, `navigation`, `contentinfo`, `complementary`, or `search` role,
+    /// and cookie banners, share bars, and similar boilerplate recognized by
+    /// their `id` or `class`, so text statistics reflect the article.
+    pub fn exclude_boilerplate(mut self, exclude: bool) -> Self {
+        self.exclude_boilerplate = exclude;
+        self
+    }
+
+    /// Set whether to run the body passes (links, text, lead text, and image
+    /// candidates).
+    pub fn extract_body(mut self, extract: bool) -> Self {
+        self.extract_body = extract;
+        self
+    }
+
+    /// Annotate external links with their verdict from `list` while
+    /// extracting them.
+    pub fn link_safety(mut self, list: LinkSafetyList) -> Self {
+        self.link_safety = Some(Arc::new(list));
+        self
+    }
+}
+
+/// Point in time after which extraction should stop.
+#[cfg(feature = "dom")]
+#[derive(Debug, Clone, Copy)]
+struct Deadline(Option<Instant>);
+
+#[cfg(feature = "dom")]
+impl Deadline {
+    fn start(budget: Option<Duration>) -> Self {
+        Self(budget.and_then(|b| Instant::now().checked_add(b)))
+    }
+
+    fn expired(&self) -> bool {
+        self.0.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Inputs shared by all extraction steps.
+#[cfg(feature = "dom")]
+struct Context<'a> {
+    base_url: Option<&'a Url>,
+    source: &'a str,
+    options: &'a ParseOptions,
+    deadline: Deadline,
 }
 
+/// An extraction step; returns `false` if it was interrupted by the deadline.
+#[cfg(feature = "dom")]
+type Phase = fn(&mut HtmlInfo, &Html, &Context) -> bool;
+
+/// Extraction steps in execution order, cheap head metadata first.
+#[cfg(feature = "dom")]
+const PHASES: &[(&str, Phase)] = &[
+    ("raw_head", |info, _, ctx| {
+        info.extract_raw_head(ctx);
+        true
+    }),
+    ("title", |info, document, _| {
+        info.title = HtmlInfo::extract_title(document);
+        true
+    }),
+    ("language", |info, document, _| {
+        info.language = HtmlInfo::extract_language(document);
+        true
+    }),
+    ("canonical", |info, document, _| {
+        info.canonical_url = HtmlInfo::extract_canonical(document);
+        true
+    }),
+    ("feed", |info, document, _| {
+        info.feed_url = HtmlInfo::extract_feed(document);
+        true
+    }),
+    ("pagination", |info, document, ctx| {
+        info.extract_pagination(document, ctx.base_url);
+        true
+    }),
+    ("icons", |info, document, ctx| {
+        info.extract_icons(document, ctx.base_url);
+        true
+    }),
+    ("oembed", |info, document, ctx| {
+        info.extract_oembed(document, ctx.base_url);
+        true
+    }),
+    // Sets description, meta, and opengraph
+    ("meta", |info, document, ctx| {
+        info.extract_meta_tags(document, ctx)
+    }),
+    #[cfg(feature = "schema-org")]
+    ("schema_org", |info, document, _| {
+        info.schema_org = HtmlInfo::extract_schema_org(document);
+        true
+    }),
+    #[cfg(feature = "schema-org")]
+    ("microdata", |info, document, ctx| {
+        info.microdata = HtmlInfo::extract_microdata(document, ctx.base_url);
+        true
+    }),
+    #[cfg(feature = "schema-org")]
+    ("rdfa", |info, document, ctx| {
+        info.rdfa = HtmlInfo::extract_rdfa(document, ctx.base_url);
+        true
+    }),
+    // Runs after schema_org, which declares the references
+    #[cfg(feature = "schema-org")]
+    ("speakable", |info, document, _| {
+        info.extract_speakable(document);
+        true
+    }),
+    // Runs after meta and schema_org, which provide the fallbacks
+    ("license", |info, document, _| {
+        info.extract_license(document);
+        true
+    }),
+    ("rating", |info, _, _| {
+        info.content_rating = ContentRating::detect(info);
+        true
+    }),
+    // Runs after meta and schema_org, which declare preview images
+    ("images", |info, document, ctx| {
+        if !info.has_preview_image() {
+            info.image_candidates = rank_images(document, ctx.base_url);
+        }
+        true
+    }),
+    #[cfg(feature = "text")]
+    ("lead", |info, document, _| {
+        info.lead_text = HtmlInfo::extract_lead_text(document);
+        true
+    }),
+    ("prices", |info, document, _| {
+        info.microdata_prices = extract_microdata_prices(document);
+        true
+    }),
+    ("forms", |info, document, _| {
+        info.has_password_field = document.select(password_selector()).next().is_some();
+        true
+    }),
+    ("resources", |info, document, ctx| {
+        info.resources = extract_resources(document, ctx.base_url);
+        true
+    }),
+    ("consent", |info, document, ctx| {
+        if ctx.options.detect_consent {
+            info.consent = Some(ConsentInfo::detect(document));
+        }
+        true
+    }),
+    ("a11y", |info, document, ctx| {
+        if ctx.options.a11y {
+            info.a11y_stats = Some(A11yStats::collect(document));
+        }
+        true
+    }),
+    ("spa", |info, document, _| {
+        info.spa_signals = SpaSignals::detect(document);
+        true
+    }),
+    #[cfg(feature = "links")]
+    ("links", |info, document, ctx| {
+        let complete = info.extract_links(document, ctx);
+        if let Some(list) = &ctx.options.link_safety {
+            info.annotate_links(ctx.base_url.map(Url::as_str), list);
+        }
+        complete
+    }),
+    #[cfg(feature = "text")]
+    ("text", |info, document, ctx| {
+        info.extract_text_content(document, ctx)
+    }),
+];
+
+/// Phases skipped when [`ParseOptions::extract_body`] is off.
+#[cfg(feature = "dom")]
+const BODY_PHASES: &[&str] = &[
+    "images",
+    "lead",
+    "links",
+    "microdata",
+    "rdfa",
+    "speakable",
+    "text",
+];
+
+#[cfg(feature = "dom")]
 impl HtmlInfo {
     /// Parse HTML from a string.
     ///
@@ -134,40 +903,181 @@ impl HtmlInfo {
     /// assert_eq!(info.title, Some("Hello".to_string()));
     /// ```
     pub fn from_string(html: &str, base_url: Option<&str>) -> Result<Self> {
+        Self::from_string_with_options(html, base_url, ParseOptions::default())
+    }
+
+    /// Parse HTML from a string with custom parse options.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use webpage_info::{HtmlInfo, ParseOptions};
+    ///
+    /// let options = ParseOptions::new().time_budget(Duration::from_millis(50));
+    /// let html = "<html><head><title>Hello</title></head><body>World</body></html>";
+    /// let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+    /// assert_eq!(info.title, Some("Hello".to_string()));
+    /// ```
+    pub fn from_string_with_options(
+        html: &str,
+        base_url: Option<&str>,
+        options: ParseOptions,
+    ) -> Result<Self> {
+        let deadline = Deadline::start(options.time_budget);
         let base = base_url.and_then(|u| Url::parse(u).ok());
-        let document = Html::parse_document(html);
-        Ok(Self::extract(&document, base.as_ref()))
+        let ctx = Context {
+            base_url: base.as_ref(),
+            source: html,
+            options: &options,
+            deadline,
+        };
+
+        let mut info = Self::extract(&Html::parse_document(html), &ctx);
+
+        if options.decode_entities {
+            info.normalize_strings();
+        }
+        Ok(info)
+    }
+
+    /// Parse untrusted bytes without ever panicking.
+    ///
+    /// Invalid UTF-8 is replaced with U+FFFD. Any panic raised while parsing is
+    /// caught and reported as [`Error::ParseError`], so a malicious document cannot
+    /// take down the calling thread. Combine with [`ParseOptions::time_budget`] and
+    /// a cap on the input size to also bound the time spent on hostile input.
+    ///
+    /// Note: panics can only be caught when the crate is built with `panic = "unwind"`
+    /// (the default).
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{HtmlInfo, ParseOptions};
+    ///
+    /// let bytes = b"<title>Hi</title>\xff\xfe<a href=\"/x\">";
+    /// let info = HtmlInfo::try_parse(bytes, None, ParseOptions::default()).unwrap();
+    /// assert_eq!(info.title, Some("Hi".to_string()));
+    /// ```
+    pub fn try_parse(input: &[u8], base_url: Option<&str>, options: ParseOptions) -> Result<Self> {
+        let html = String::from_utf8_lossy(input);
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            Self::from_string_with_options(&html, base_url, options)
+        }))
+        .unwrap_or(Err(Error::ParseError))
     }
 
     /// Parse HTML from a file.
     ///
+    /// Files ending in `.gz` or `.br` are decompressed first (with the default
+    /// `decompress` feature), as are `.zst` files with the `zstd` feature.
+    ///
     /// # Arguments
     /// * `path` - Path to the HTML file
     /// * `base_url` - Optional base URL for resolving relative links
     pub fn from_file(path: impl AsRef<Path>, base_url: Option<&str>) -> Result<Self> {
-        let content = fs::read_to_string(path)?;
+        let bytes = decompress::read_file(path.as_ref(), false)?;
+        let content =
+            String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
         Self::from_string(&content, base_url)
     }
 
+    /// Parse HTML from a file of unknown compression and charset.
+    ///
+    /// Like [`from_file`](Self::from_file), but gzip and zstd are also
+    /// recognized by their leading bytes regardless of the file name, and the
+    /// contents are decoded from the charset declared by a byte order mark or
+    /// `<meta charset>` instead of requiring UTF-8.
+    pub fn from_path_auto(path: impl AsRef<Path>, base_url: Option<&str>) -> Result<Self> {
+        let bytes = decompress::read_file(path.as_ref(), true)?;
+        Self::from_bytes(&bytes, base_url, ParseOptions::default())
+    }
+
+    /// Parse HTML bytes of any encoding.
+    ///
+    /// The charset is taken from a byte order mark or a `<meta charset>`
+    /// declaration, defaulting to UTF-8; bytes invalid in that encoding are
+    /// replaced with U+FFFD. Conflicting declarations add a warning to
+    /// [`warnings`](Self::warnings).
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{HtmlInfo, ParseOptions};
+    ///
+    /// let bytes = b"<meta charset=\"windows-1252\"><title>Caf\xe9</title>";
+    /// let info = HtmlInfo::from_bytes(bytes, None, ParseOptions::default()).unwrap();
+    /// assert_eq!(info.title.as_deref(), Some("Café"));
+    /// ```
+    pub fn from_bytes(bytes: &[u8], base_url: Option<&str>, options: ParseOptions) -> Result<Self> {
+        let charset = CharsetInfo::detect(bytes, None);
+        let mut info = Self::from_string_with_options(&charset.decode(bytes), base_url, options)?;
+        if let Some(warning) = charset.conflict_warning() {
+            info.warnings.push(warning);
+        }
+        Ok(info)
+    }
+
+    /// Read HTML from a reader, such as standard input, and parse it with
+    /// [`from_bytes`](Self::from_bytes).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use webpage_info::{HtmlInfo, ParseOptions};
+    ///
+    /// let info = HtmlInfo::from_reader(std::io::stdin().lock(), None, ParseOptions::default())?;
+    /// # Ok::<(), webpage_info::Error>(())
+    /// ```
+    pub fn from_reader(
+        mut reader: impl Read,
+        base_url: Option<&str>,
+        options: ParseOptions,
+    ) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes, base_url, options)
+    }
+
     /// Extract all information from a parsed HTML document.
-    fn extract(document: &Html, base_url: Option<&Url>) -> Self {
-        let mut info = Self {
-            title: Self::extract_title(document),
-            language: Self::extract_language(document),
-            canonical_url: Self::extract_canonical(document),
-            feed_url: Self::extract_feed(document),
-            text_content: Self::extract_text_content(document),
-            links: Self::extract_links(document, base_url),
-            schema_org: Self::extract_schema_org(document),
-            ..Default::default()
-        };
+    fn extract(document: &Html, ctx: &Context) -> Self {
+        let mut info = Self::default();
+        let phases: Vec<_> = PHASES
+            .iter()
+            .filter(|(name, _)| ctx.options.extract_body || !BODY_PHASES.contains(name))
+            .collect();
 
-        // Extract meta tags (sets description, meta, and opengraph)
-        info.extract_meta_tags(document);
+        for (index, (_, phase)) in phases.iter().enumerate() {
+            if ctx.deadline.expired() || !phase(&mut info, document, ctx) {
+                let incomplete: Vec<&str> = phases[index..].iter().map(|(name, _)| *name).collect();
+                info.warnings.push(format!(
+                    "parse time budget of {:?} exceeded; incomplete: {}",
+                    ctx.options.time_budget.unwrap_or_default(),
+                    incomplete.join(", ")
+                ));
+                break;
+            }
+        }
 
         info
     }
 
+    fn extract_raw_head(&mut self, ctx: &Context) {
+        let Some(limit) = ctx.options.raw_head else {
+            return;
+        };
+        let Some(head) = raw_head_markup(ctx.source) else {
+            return;
+        };
+
+        let end = floor_char_boundary(head, limit);
+        if end < head.len() {
+            self.warnings.push(format!(
+                "raw head truncated to {} of {} bytes",
+                end,
+                head.len()
+            ));
+        }
+        self.raw_head = Some(head[..end].to_string());
+    }
+
     fn extract_title(document: &Html) -> Option<String> {
         document
             .select(title_selector())
@@ -185,43 +1095,17 @@ impl HtmlInfo {
             .filter(|s| !s.is_empty())
     }
 
-    fn extract_meta_tags(&mut self, document: &Html) {
-        for element in document.select(meta_selector()) {
-            let el = element.value();
-
-            // Get content value
-            let content = match el.attr("content") {
-                Some(c) => c.trim().to_string(),
-                None => {
-                    // Handle charset meta tag
-                    if let Some(charset) = el.attr("charset") {
-                        self.meta.insert("charset".to_string(), charset.to_string());
-                    }
-                    continue;
-                }
-            };
-
-            // Get property/name
-            let property = el
-                .attr("property")
-                .or_else(|| el.attr("name"))
-                .or_else(|| el.attr("http-equiv"));
-
-            if let Some(prop) = property {
-                let prop = prop.trim().to_string();
-                self.meta.insert(prop.clone(), content.clone());
-
-                // Handle OpenGraph
-                if let Some(og_prop) = prop.strip_prefix("og:") {
-                    self.opengraph.extend(og_prop, content.clone());
-                }
-
-                // Handle description
-                if prop == "description" {
-                    self.description = Some(content);
-                }
+    fn extract_meta_tags(&mut self, document: &Html, ctx: &Context) -> bool {
+        for (index, element) in document.select(meta_selector()).enumerate() {
+            if index % BUDGET_CHECK_INTERVAL == 0 && ctx.deadline.expired() {
+                return false;
             }
+
+            let el = element.value();
+            self.add_meta_tag(|name| el.attr(name));
         }
+
+        true
     }
 
     fn extract_canonical(document: &Html) -> Option<String> {
@@ -236,89 +1120,197 @@ impl HtmlInfo {
     fn extract_feed(document: &Html) -> Option<String> {
         for element in document.select(feed_selector()) {
             let el = element.value();
-            if let Some(link_type) = el.attr("type")
-                && FEED_MIME_TYPES.contains(&link_type)
-            {
+            if el.attr("type").is_some_and(is_feed_type) {
                 return el.attr("href").map(|s| s.trim().to_string());
             }
         }
         None
     }
 
-    fn extract_text_content(document: &Html) -> String {
+    #[cfg(feature = "text")]
+    fn extract_text_content(&mut self, document: &Html, ctx: &Context) -> bool {
+        if let Some(limit) = ctx.options.skip_text_above
+            && ctx.source.len() > limit
+        {
+            self.warnings.push(format!(
+                "text extraction skipped: document is {} bytes (limit {})",
+                ctx.source.len(),
+                limit
+            ));
+            return true;
+        }
+
         let Some(body) = document.select(body_selector()).next() else {
-            return String::new();
+            return true;
         };
 
         // Pre-collect excluded node IDs for O(1) lookup instead of O(n) per text node
-        let excluded_ids: HashSet<_> = document
+        let mut excluded_ids: HashSet<_> = document
             .select(exclude_selector())
             .map(|el| el.id())
             .collect();
+        let options = ctx.options;
+        if options.skip_hidden || options.exclude_boilerplate {
+            excluded_ids.extend(
+                body.descendants()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|&el| {
+                        (options.skip_hidden && is_hidden(el))
+                            || (options.exclude_boilerplate && is_page_chrome(el))
+                    })
+                    .map(|el| el.id()),
+            );
+        }
 
-        let mut text = String::with_capacity(4096); // Pre-allocate reasonable size
+        let layout = ctx.options.text_layout;
+        let text = &mut self.text_content;
+        text.reserve(4096); // Pre-allocate reasonable size
 
-        for node in body.descendants() {
+        let mut last_block = None;
+        let mut line_break = false;
+        for (index, node) in body.descendants().enumerate() {
             // Stop if we've reached the size limit
             if text.len() >= MAX_TEXT_CONTENT_LEN {
                 break;
             }
 
-            if let Some(text_node) = node.value().as_text() {
-                // Check if any ancestor is excluded (O(depth) instead of O(depth * n))
-                let is_excluded = node.ancestors().any(|a| excluded_ids.contains(&a.id()));
-
-                if !is_excluded {
-                    let trimmed = text_node.trim();
-                    if !trimmed.is_empty() {
-                        if !text.is_empty() {
-                            text.push(' ');
-                        }
-                        // Limit how much we add to stay within bounds
-                        let remaining = MAX_TEXT_CONTENT_LEN.saturating_sub(text.len());
-                        if trimmed.len() <= remaining {
-                            text.push_str(trimmed);
-                        } else {
-                            text.push_str(&trimmed[..remaining]);
-                            break;
-                        }
-                    }
-                }
+            if index % BUDGET_CHECK_INTERVAL == 0 && ctx.deadline.expired() {
+                return false;
+            }
+
+            if let Some(element) = node.value().as_element() {
+                line_break |= element.name() == "br";
+                continue;
+            }
+            let Some(text_node) = node.value().as_text() else {
+                continue;
+            };
+            // Check if any ancestor is excluded (O(depth) instead of O(depth * n))
+            if node.ancestors().any(|a| excluded_ids.contains(&a.id())) {
+                continue;
+            }
+            let trimmed = text_node.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let preformatted = || {
+                node.ancestors()
+                    .any(|a| a.value().as_element().is_some_and(|el| el.name() == "pre"))
+            };
+            let collapsed;
+            let fragment = if ctx.options.collapse_whitespace && !preformatted() {
+                collapsed = trimmed.split_whitespace().collect::<Vec<_>>().join(" ");
+                collapsed.as_str()
+            } else {
+                trimmed
+            };
+
+            let block = node
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|el| BLOCK_ELEMENTS.contains(&el.value().name()));
+            let new_block = block.map(|a| a.id()) != last_block || self.text_blocks.is_empty();
+            if !text.is_empty() {
+                let separator = match layout {
+                    TextLayout::Flat => " ",
+                    TextLayout::Lines if new_block => "\n",
+                    TextLayout::Paragraphs if new_block => "\n\n",
+                    _ if line_break => "\n",
+                    _ => " ",
+                };
+                text.push_str(separator);
+            }
+            last_block = block.map(|a| a.id());
+            line_break = false;
+
+            // Limit how much we add to stay within bounds
+            let remaining = MAX_TEXT_CONTENT_LEN.saturating_sub(text.len());
+            let truncated = fragment.len() > remaining;
+            let fragment = &fragment[..floor_char_boundary(fragment, remaining)];
+            text.push_str(fragment);
+
+            let blocks = &mut self.text_blocks;
+            if let Some(current) = blocks.last_mut().filter(|_| !new_block) {
+                current.text.push(' ');
+                current.text.push_str(fragment);
+            } else if blocks.len() < MAX_TEXT_BLOCKS {
+                blocks.push(TextBlock {
+                    kind: block.map_or(TextBlockKind::Paragraph, text_block_kind),
+                    text: fragment.to_string(),
+                });
+            }
+
+            if truncated {
+                break;
             }
         }
 
-        text
+        true
     }
 
-    fn extract_links(document: &Html, base_url: Option<&Url>) -> Vec<Link> {
-        document
-            .select(link_selector())
-            .filter_map(|element| {
-                let href = element.value().attr("href")?;
-                let href = href.trim();
-
-                // Skip empty and javascript: links
-                if href.is_empty() || href.starts_with("javascript:") {
-                    return None;
-                }
+    #[cfg(feature = "links")]
+    fn extract_links(&mut self, document: &Html, ctx: &Context) -> bool {
+        let mut strings = Interner::default();
+        for (index, element) in document.select(link_selector()).enumerate() {
+            if self.links.len() >= MAX_LINKS {
+                break;
+            }
 
-                let url = if let Some(base) = base_url {
-                    base.join(href)
-                        .map(|u| u.to_string())
-                        .unwrap_or_else(|_| href.to_string())
-                } else {
-                    href.to_string()
-                };
+            if index % BUDGET_CHECK_INTERVAL == 0 && ctx.deadline.expired() {
+                return false;
+            }
 
-                let text = element.text().collect::<String>().trim().to_string();
-                let rel = element.value().attr("rel").map(|s| s.to_string());
+            let Some(url) = element
+                .value()
+                .attr("href")
+                .and_then(|href| resolve_link(href, ctx.base_url, &mut strings))
+            else {
+                continue;
+            };
 
-                Some(Link { url, text, rel })
+            let text = strings.intern(element.text().collect::<String>().trim());
+            let attr = |name| element.value().attr(name);
+            let rel = attr("rel").map(|s| strings.intern(s));
+            let target = attr("target").map(|s| strings.intern(s));
+            let download = attr("download").map(|s| strings.intern(s));
+            let referrer_policy = attr("referrerpolicy").and_then(ReferrerPolicy::parse);
+
+            self.links.push(Link {
+                url,
+                text,
+                rel,
+                target,
+                download,
+                referrer_policy,
+                verdict: None,
+            });
+        }
+
+        true
+    }
+
+    #[cfg(feature = "text")]
+    fn extract_lead_text(document: &Html) -> Option<String> {
+        document
+            .select(paragraph_selector())
+            .filter(|paragraph| {
+                !std::iter::once(*paragraph)
+                    .chain(paragraph.ancestors().filter_map(ElementRef::wrap))
+                    .any(is_boilerplate)
+            })
+            .map(|paragraph| {
+                let text: String = paragraph.text().collect();
+                text.split_whitespace().collect::<Vec<_>>().join(" ")
+            })
+            .find(|text| text.len() >= MIN_LEAD_TEXT_LEN)
+            .map(|mut text| {
+                text.truncate(floor_char_boundary(&text, MAX_LEAD_TEXT_LEN));
+                text
             })
-            .take(MAX_LINKS)
-            .collect()
     }
 
+    #[cfg(feature = "schema-org")]
     fn extract_schema_org(document: &Html) -> Vec<SchemaOrg> {
         document
             .select(schema_org_selector())
@@ -331,7 +1323,185 @@ impl HtmlInfo {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+impl HtmlInfo {
+    /// Record one `<meta>` element, given a lookup for its attributes.
+    pub(crate) fn add_meta_tag<'a>(&mut self, attr: impl Fn(&str) -> Option<&'a str>) {
+        // Get content value
+        let content = match attr("content") {
+            Some(c) => c.trim().to_string(),
+            None => {
+                // Handle charset meta tag
+                if let Some(charset) = attr("charset") {
+                    self.meta.append("charset", charset);
+                }
+                return;
+            }
+        };
+
+        // Get property/name
+        let property = attr("property")
+            .or_else(|| attr("name"))
+            .or_else(|| attr("http-equiv"));
+
+        if let Some(prop) = property {
+            let prop = prop.trim();
+            self.meta.append(prop, content.as_str());
+
+            // Handle OpenGraph
+            #[cfg(feature = "opengraph")]
+            if let Some(og_prop) = prop.strip_prefix("og:") {
+                self.opengraph.extend(og_prop, content.clone());
+            }
+
+            // Handle description
+            if prop == "description" {
+                self.description = Some(content);
+            }
+        }
+    }
+}
+
+/// Whether a `<link rel="alternate">` type denotes a feed.
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+pub(crate) fn is_feed_type(link_type: &str) -> bool {
+    FEED_MIME_TYPES.contains(&link_type)
+}
+
+/// Resolve an `href` against the base URL, or `None` for empty and `javascript:` links.
+#[cfg(all(any(feature = "dom", feature = "streaming"), feature = "links"))]
+pub(crate) fn resolve_link(
+    href: &str,
+    base_url: Option<&Url>,
+    strings: &mut Interner,
+) -> Option<Arc<str>> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with("javascript:") {
+        return None;
+    }
+
+    Some(match base_url.map(|base| base.join(href)) {
+        Some(Ok(resolved)) => strings.intern(resolved.as_str()),
+        _ => strings.intern(href),
+    })
+}
+
+/// Classify the text of a block element from its name and ancestors.
+#[cfg(all(feature = "dom", feature = "text"))]
+fn text_block_kind(block: ElementRef) -> TextBlockKind {
+    match block.value().name() {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => TextBlockKind::Heading,
+        "li" => TextBlockKind::ListItem,
+        "blockquote" => TextBlockKind::Quote,
+        _ if block
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|a| a.value().name() == "blockquote") =>
+        {
+            TextBlockKind::Quote
+        }
+        _ => TextBlockKind::Paragraph,
+    }
+}
+
+/// Whether an element is navigation, a banner, or similar page furniture.
+#[cfg(all(feature = "dom", feature = "text"))]
+fn is_boilerplate(element: ElementRef) -> bool {
+    let element = element.value();
+    BOILERPLATE_ELEMENTS.contains(&element.name())
+        || element
+            .id()
+            .into_iter()
+            .chain(element.classes())
+            .any(|name| {
+                let name = name.to_ascii_lowercase();
+                BOILERPLATE_MARKERS
+                    .iter()
+                    .any(|marker| name.contains(marker))
+            })
+}
+
+/// Whether an element is hidden from readers, for [`ParseOptions::skip_hidden`].
+#[cfg(all(feature = "dom", feature = "text"))]
+fn is_hidden(element: ElementRef) -> bool {
+    let element = element.value();
+    if element.name() == "template" {
+        return true;
+    }
+    // `hidden="until-found"` content is revealed by find-in-page, so it counts as visible
+    if element
+        .attr("hidden")
+        .is_some_and(|value| !value.trim().eq_ignore_ascii_case("until-found"))
+    {
+        return true;
+    }
+    if element
+        .attr("aria-hidden")
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"))
+    {
+        return true;
+    }
+    element.attr("style").is_some_and(|style| {
+        let style: String = style
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        style.split(';').any(|declaration| {
+            let declaration = declaration.trim_end_matches("!important");
+            declaration == "display:none" || declaration == "visibility:hidden"
+        })
+    })
+}
+
+/// Whether an element is page chrome left out of the text by
+/// [`ParseOptions::exclude_boilerplate`].
+///
+/// Unlike [`is_boilerplate`], forms are kept: some frameworks wrap the whole page in one.
+#[cfg(all(feature = "dom", feature = "text"))]
+fn is_page_chrome(element: ElementRef) -> bool {
+    let value = element.value();
+    if value.name() == "form" {
+        return false;
+    }
+    is_boilerplate(element)
+        || value.attr("role").is_some_and(|role| {
+            BOILERPLATE_ROLES.contains(&role.trim().to_ascii_lowercase().as_str())
+        })
+}
+
+/// The source markup from `<head` through `</head>` (or the end of the document).
+#[cfg(feature = "dom")]
+fn raw_head_markup(source: &str) -> Option<&str> {
+    // ASCII lowercasing keeps byte offsets valid for slicing `source`
+    let lower = source.to_ascii_lowercase();
+    let start = lower
+        .match_indices("<head")
+        .map(|(index, _)| index)
+        .find(|&index| {
+            let next = lower.as_bytes().get(index + "<head".len());
+            next.is_none_or(|c| matches!(c, b'>' | b'/') || c.is_ascii_whitespace())
+        })?;
+    let end = lower[start..]
+        .find("</head>")
+        .map_or(source.len(), |offset| start + offset + "</head>".len());
+    Some(&source[start..end])
+}
+
+/// Largest index `<= index` that lies on a char boundary of `s`.
+#[cfg(feature = "dom")]
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    let mut index = index;
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(all(test, feature = "dom"))]
 mod tests {
     use super::*;
 
@@ -363,12 +1533,67 @@ mod tests {
             info.canonical_url,
             Some("https://example.com/test".to_string())
         );
-        assert_eq!(info.opengraph.title, Some("OG Title".to_string()));
-        assert_eq!(info.opengraph.og_type, Some("article".to_string()));
+        #[cfg(feature = "opengraph")]
+        {
+            assert_eq!(info.opengraph.title, Some("OG Title".to_string()));
+            assert_eq!(info.opengraph.og_type, Some("article".to_string()));
+        }
+        #[cfg(feature = "text")]
         assert!(info.text_content.contains("Hello World"));
-        assert_eq!(info.links.len(), 1);
-        assert_eq!(info.links[0].url, "https://example.com/about");
-        assert_eq!(info.links[0].text, "About Us");
+        #[cfg(feature = "links")]
+        {
+            assert_eq!(info.links.len(), 1);
+            assert_eq!(&*info.links[0].url, "https://example.com/about");
+            assert_eq!(&*info.links[0].text, "About Us");
+        }
+    }
+
+    #[cfg(feature = "links")]
+    #[test]
+    fn test_repeated_link_strings_are_shared() {
+        let html = r#"
+            <a href="/tag" rel="tag">Tag</a>
+            <a href="https://example.com/tag" rel="tag">Tag</a>
+            <a href="/other" rel="nofollow">Other</a>
+        "#;
+
+        let info = HtmlInfo::from_string(html, Some("https://example.com/")).unwrap();
+        let [first, second, third] = &info.links[..] else {
+            panic!("expected three links");
+        };
+        assert!(Arc::ptr_eq(&first.url, &second.url));
+        assert!(Arc::ptr_eq(&first.text, &second.text));
+        assert!(Arc::ptr_eq(
+            first.rel.as_ref().unwrap(),
+            second.rel.as_ref().unwrap()
+        ));
+        assert_eq!(third.rel.as_deref(), Some("nofollow"));
+    }
+
+    #[cfg(feature = "links")]
+    #[test]
+    fn test_link_security_attributes() {
+        let html = r#"
+            <a href="/a" target="_blank">Unsafe</a>
+            <a href="/b" target="_BLANK" rel="external NoOpener">Opener</a>
+            <a href="/c" target="_blank" rel="noreferrer" referrerpolicy="origin">Referrer</a>
+            <a href="/report.pdf" download referrerpolicy="bogus">Report</a>
+            <a href="/d" target="main" download="d.txt">Named</a>
+        "#;
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        let unsafe_links: Vec<&str> = info
+            .unsafe_blank_links()
+            .iter()
+            .map(|link| &*link.url)
+            .collect();
+        assert_eq!(unsafe_links, ["/a"]);
+        assert!(info.links[1].has_rel("noopener"));
+        assert_eq!(info.links[2].referrer_policy, Some(ReferrerPolicy::Origin));
+        assert_eq!(info.links[3].download.as_deref(), Some(""));
+        assert_eq!(info.links[3].referrer_policy, None);
+        assert_eq!(info.links[4].target.as_deref(), Some("main"));
+        assert_eq!(info.links[4].download.as_deref(), Some("d.txt"));
     }
 
     #[test]
@@ -385,6 +1610,7 @@ mod tests {
         assert_eq!(info.feed_url, Some("/feed.xml".to_string()));
     }
 
+    #[cfg(feature = "schema-org")]
     #[test]
     fn test_schema_org_extraction() {
         let html = r#"
@@ -402,6 +1628,7 @@ mod tests {
         assert_eq!(info.schema_org[0].schema_type, "Article");
     }
 
+    #[cfg(feature = "text")]
     #[test]
     fn test_text_excludes_scripts() {
         let html = r#"
@@ -421,4 +1648,384 @@ mod tests {
         assert!(!info.text_content.contains("console.log"));
         assert!(!info.text_content.contains(".hidden"));
     }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_text_layout_and_whitespace() {
+        let html = "<body><h1>Title</h1><p>First   <b>bold</b>\n  line<br>second</p>\
+                    <ul><li>One</li><li>Two</li></ul><pre>a   b</pre></body>";
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        assert_eq!(
+            info.text_content,
+            "Title First bold line second One Two a   b"
+        );
+
+        let options = ParseOptions::new()
+            .text_layout(TextLayout::Lines)
+            .collapse_whitespace(true);
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert_eq!(
+            info.text_content,
+            "Title\nFirst bold line\nsecond\nOne\nTwo\na   b"
+        );
+
+        let options = ParseOptions::new().text_layout(TextLayout::Paragraphs);
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert!(info.text_content.starts_with("Title\n\nFirst"));
+        assert!(info.text_content.contains("second\n\nOne\n\nTwo"));
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_text_blocks() {
+        let html = "<body><h2>News</h2><div>Intro <a href=\"/\">link</a>\
+                    <p>Body <em>text</em></p></div><ul><li>One</li><li>Two</li></ul>\
+                    <blockquote><p>Quoted</p></blockquote><script>x()</script></body>";
+        let info = HtmlInfo::from_string(html, None).unwrap();
+
+        let blocks: Vec<_> = info
+            .text_blocks
+            .iter()
+            .map(|b| (b.kind, b.text.as_str()))
+            .collect();
+        assert_eq!(
+            blocks,
+            [
+                (TextBlockKind::Heading, "News"),
+                (TextBlockKind::Paragraph, "Intro link"),
+                (TextBlockKind::Paragraph, "Body text"),
+                (TextBlockKind::ListItem, "One"),
+                (TextBlockKind::ListItem, "Two"),
+                (TextBlockKind::Quote, "Quoted"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_hidden_elements_are_skipped() {
+        let html = r#"<body>
+            <p>Visible</p>
+            <div hidden>Hidden attribute</div>
+            <div hidden="until-found">Findable</div>
+            <span aria-hidden="true">Icon label</span>
+            <div style="color: red; DISPLAY : none !important">Styled away</div>
+            <div style="visibility:hidden">Invisible</div>
+            <template><p>Template</p></template>
+            <p style="display: block">Shown</p>
+        </body>"#;
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        assert_eq!(info.text_content, "Visible Findable Shown");
+
+        let options = ParseOptions::new().skip_hidden(false);
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert!(info.text_content.contains("Styled away"));
+        assert!(info.text_content.contains("Icon label"));
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_exclude_boilerplate() {
+        let html = r#"<body><form>
+            <header><a href="/">Home</a></header>
+            <nav>Menu</nav>
+            <div role="complementary">Related</div>
+            <div class="share-bar">Share</div>
+            <article><h1>Story</h1><p>The article body.</p></article>
+            <footer>Copyright</footer>
+        </form></body>"#;
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        assert!(info.text_content.starts_with("Home Menu Related"));
+
+        let options = ParseOptions::new().exclude_boilerplate(true);
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert_eq!(info.text_content, "Story The article body.");
+        assert_eq!(info.text_blocks.len(), 2);
+        #[cfg(feature = "links")]
+        assert_eq!(info.links.len(), 1);
+    }
+
+    #[test]
+    fn test_time_budget_exceeded_returns_partial() {
+        let html = "<html><head><title>Slow</title></head><body><p>Text</p></body></html>";
+        let options = ParseOptions::new().time_budget(Duration::ZERO);
+
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert_eq!(info.title, None);
+        assert!(info.text_content.is_empty());
+        assert_eq!(info.warnings.len(), 1);
+        assert!(info.warnings[0].contains("time budget"));
+        assert!(info.warnings[0].contains("title"));
+    }
+
+    #[test]
+    fn test_time_budget_not_exceeded() {
+        let html = "<html><head><title>Fast</title></head><body><p>Text</p></body></html>";
+        let options = ParseOptions::new().time_budget(Duration::from_secs(60));
+
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert_eq!(info.title, Some("Fast".to_string()));
+        #[cfg(feature = "text")]
+        assert!(info.text_content.contains("Text"));
+        assert!(info.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_try_parse_arbitrary_bytes() {
+        // Deterministic xorshift so failures are reproducible
+        let mut state: u32 = 0x9e37_79b9;
+        let fragments: &[&[u8]] = &[
+            b"<",
+            b">",
+            b"</",
+            b"<a href=",
+            b"<meta property=og:image content=",
+            b"<script>",
+            b"<title>",
+            b"\"",
+            b"&#",
+            b"\xff",
+            b"\xe2\x82",
+            b"<![CDATA[",
+            b"<!--",
+            b"<svg><math>",
+        ];
+
+        for _ in 0..200 {
+            let mut input = Vec::new();
+            for _ in 0..64 {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                if state.is_multiple_of(3) {
+                    input.push((state >> 8) as u8);
+                } else {
+                    input.extend_from_slice(fragments[state as usize % fragments.len()]);
+                }
+            }
+            assert!(
+                HtmlInfo::try_parse(
+                    &input,
+                    Some("https://example.com/"),
+                    ParseOptions::default()
+                )
+                .is_ok()
+            );
+        }
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_text_truncation_respects_char_boundaries() {
+        // Multi-byte characters straddling MAX_TEXT_CONTENT_LEN must not panic
+        let html = format!("<body><p>a{}</p></body>", "é".repeat(MAX_TEXT_CONTENT_LEN));
+        let info = HtmlInfo::try_parse(html.as_bytes(), None, ParseOptions::default()).unwrap();
+        assert!(info.text_content.len() <= MAX_TEXT_CONTENT_LEN);
+        assert!(info.text_content.ends_with('é'));
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_skip_text_above_threshold() {
+        let html = "<html><head><title>Big</title></head><body><p>Lots of text</p></body></html>";
+
+        let options = ParseOptions::new().skip_text_above(16);
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert_eq!(info.title, Some("Big".to_string()));
+        assert!(info.text_content.is_empty());
+        assert!(info.warnings[0].contains("text extraction skipped"));
+
+        let options = ParseOptions::new().skip_text_above(html.len());
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert!(info.text_content.contains("Lots of text"));
+        assert!(info.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_metadata_only_skips_body_passes() {
+        let html = r#"<html><head><title>Head</title>
+            <meta property="og:title" content="Graph"></head>
+            <body><p>Some long article text here.</p><a href="/next">Next</a></body></html>"#;
+
+        let info =
+            HtmlInfo::from_string_with_options(html, None, ParseOptions::metadata_only()).unwrap();
+        assert_eq!(info.title.as_deref(), Some("Head"));
+        #[cfg(feature = "opengraph")]
+        assert_eq!(info.opengraph.title.as_deref(), Some("Graph"));
+        assert!(info.text_content.is_empty());
+        assert!(info.links.is_empty());
+        assert!(info.lead_text.is_none());
+        assert!(info.warnings.is_empty());
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_lead_text_skips_boilerplate() {
+        let intro = "The committee approved the new budget on Tuesday after a long debate \
+                     about transit funding and school repairs.";
+        let html = format!(
+            r#"<body>
+                <nav><p>Home / News / Politics / A very long breadcrumb trail that goes on and on</p></nav>
+                <div class="cookie-notice"><p>We use cookies to improve your experience on this site, please accept them.</p></div>
+                <article>
+                    <p class="article-byline">By A. Reporter, Staff writer covering city hall and municipal affairs</p>
+                    <p>Short teaser.</p>
+                    <p>{intro}</p>
+                    <p>Second paragraph that is also long enough to count as substantive content here.</p>
+                </article>
+            </body>"#
+        );
+        let info = HtmlInfo::from_string(&html, None).unwrap();
+        assert_eq!(
+            info.lead_text.as_deref(),
+            Some(
+                intro
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .as_str()
+            )
+        );
+
+        let info = HtmlInfo::from_string("<body><p>Too short.</p></body>", None).unwrap();
+        assert!(info.lead_text.is_none());
+    }
+
+    #[test]
+    fn test_from_bytes_and_reader() {
+        let bytes = b"\xef\xbb\xbf<meta charset=\"iso-8859-1\"><title>Caf\xc3\xa9</title>";
+        let info = HtmlInfo::from_bytes(bytes, None, ParseOptions::default()).unwrap();
+        // The byte order mark wins over the meta declaration
+        assert_eq!(info.title.as_deref(), Some("Caf\u{e9}"));
+        assert!(info.warnings[0].starts_with("charset conflict"));
+
+        let reader = &b"<title>Piped</title><a href=\"/x\">x</a>"[..];
+        let info = HtmlInfo::from_reader(
+            reader,
+            Some("https://example.com/"),
+            ParseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(info.title.as_deref(), Some("Piped"));
+        #[cfg(feature = "links")]
+        assert_eq!(&*info.links[0].url, "https://example.com/x");
+    }
+
+    #[test]
+    fn test_from_path_auto_decodes_declared_charset() {
+        let path = std::env::temp_dir().join(format!("webpage-info-{}-latin1", std::process::id()));
+        std::fs::write(
+            &path,
+            b"<meta charset=\"iso-8859-1\"><title>Caf\xe9</title>",
+        )
+        .unwrap();
+
+        let info = HtmlInfo::from_path_auto(&path, None).unwrap();
+        assert_eq!(info.title.as_deref(), Some("Caf\u{e9}"));
+        assert!(HtmlInfo::from_file(&path, None).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "opengraph")]
+    #[test]
+    fn test_image_candidates_only_without_declared_image() {
+        let body = r#"<body><img src="/photo.jpg" width="800" height="600"></body>"#;
+
+        let info = HtmlInfo::from_string(body, Some("https://example.com/")).unwrap();
+        assert_eq!(
+            info.image_candidates[0].url,
+            "https://example.com/photo.jpg"
+        );
+
+        let html =
+            format!(r#"<meta property="og:image" content="https://example.com/og.jpg">{body}"#);
+        let info = HtmlInfo::from_string(&html, None).unwrap();
+        assert!(info.image_candidates.is_empty());
+    }
+
+    #[test]
+    fn test_double_escaped_entities_are_decoded() {
+        let html = r#"<title>Tom &amp;amp; Jerry&amp;#8217;s</title>
+            <meta property="og:title" content="Caf&amp;eacute; &amp;mdash; Cafe&#x301;">
+            <meta property="og:image" content="https://example.com/a?x=1&amp;amp;y=Cafe&#x301;">
+            <a href="/x">Read &amp;raquo;</a>"#;
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        assert_eq!(info.title.as_deref(), Some("Tom & Jerry\u{2019}s"));
+        #[cfg(feature = "opengraph")]
+        assert_eq!(
+            info.opengraph.title.as_deref(),
+            Some("Caf\u{e9} \u{2014} Caf\u{e9}")
+        );
+        assert_eq!(
+            info.meta.get("og:image"),
+            Some("https://example.com/a?x=1&amp;y=Cafe\u{301}")
+        );
+        #[cfg(feature = "links")]
+        assert_eq!(&*info.links[0].text, "Read \u{bb}");
+
+        let options = ParseOptions::new().decode_entities(false);
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert_eq!(info.title.as_deref(), Some("Tom &amp; Jerry&#8217;s"));
+    }
+
+    #[test]
+    fn test_password_field_detected() {
+        let html = r#"<form><input name="user"><input type="PASSWORD" name="pw"></form>"#;
+        assert!(
+            HtmlInfo::from_string(html, None)
+                .unwrap()
+                .has_password_field
+        );
+        assert!(
+            !HtmlInfo::from_string("<input type=text>", None)
+                .unwrap()
+                .has_password_field
+        );
+    }
+
+    #[test]
+    fn test_a11y_stats_opt_in() {
+        let html = r#"<html><body><img src="a.png"><a href="/x"></a></body></html>"#;
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        assert!(info.a11y_stats.is_none());
+
+        let options = ParseOptions::new().a11y(true);
+        let stats = HtmlInfo::from_string_with_options(html, None, options)
+            .unwrap()
+            .a11y_stats
+            .unwrap();
+        assert_eq!(stats.images_missing_alt, 1);
+        assert_eq!(stats.links_without_name, 1);
+        assert!(stats.missing_lang);
+    }
+
+    #[test]
+    fn test_raw_head_kept_and_capped() {
+        let html =
+            "<html><HEAD lang=x><title>T</title></HEAD><body><header>h</header></body></html>";
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        assert!(info.raw_head.is_none());
+
+        let options = ParseOptions::new().raw_head(1024);
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert_eq!(
+            info.raw_head.as_deref(),
+            Some("<HEAD lang=x><title>T</title></HEAD>")
+        );
+
+        let options = ParseOptions::new().raw_head(13);
+        let info = HtmlInfo::from_string_with_options(html, None, options).unwrap();
+        assert_eq!(info.raw_head.as_deref(), Some("<HEAD lang=x>"));
+        assert!(info.warnings[0].starts_with("raw head truncated"));
+
+        let options = ParseOptions::new().raw_head(1024);
+        let info = HtmlInfo::from_string_with_options("<header>x</header>", None, options).unwrap();
+        assert!(info.raw_head.is_none());
+    }
 }
@@ -1,21 +1,36 @@
 //! HTTP client for fetching web pages
 
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::borrow::Cow;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::ops::Range;
 use std::time::Duration;
 
 use futures_util::StreamExt;
-use reqwest::{Client, Response};
+use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Method, Response, Version};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::error::{Error, Result};
+use crate::cache::CachePolicy;
+use crate::charset::CharsetInfo;
+use crate::client::ClientState;
+use crate::cookies::Cookie;
+use crate::error::{Error, FetchPhase, Result};
+use crate::informational::InformationalResponse;
+use crate::retry::RetryPolicy;
+use crate::snapshot::Snapshot;
+use crate::user_agent;
 
 const DEFAULT_MAX_REDIRECTS: usize = 10;
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024; // 10 MB
+const DEFAULT_MAX_CONCURRENCY: usize = 8;
 
 /// HTTP response information.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
 pub struct HttpInfo {
     /// The final URL after following redirects
     pub url: String,
@@ -26,17 +41,136 @@ pub struct HttpInfo {
     /// Response headers
     pub headers: Vec<(String, String)>,
 
+    /// Interim `1xx` responses received before this one, if the transport
+    /// reports them; see [`HttpInfo::early_hints`]
+    pub informational: Vec<InformationalResponse>,
+
     /// Content-Type header value
     pub content_type: Option<String>,
 
-    /// Number of redirects followed.
-    ///
-    /// Note: This is currently always 0 as reqwest doesn't expose redirect count directly.
-    /// The field is retained for API compatibility and potential future implementation.
+    /// Detected body charset and, if they disagree, every declaration found
+    pub charset: Option<CharsetInfo>,
+
+    /// Number of redirects followed
     pub redirect_count: u32,
 
-    /// Response body as string
+    /// Each redirect followed, in order
+    pub redirects: Vec<RedirectHop>,
+
+    /// Cookies set by this response and the redirects before it, in order
+    pub cookies: Vec<Cookie>,
+
+    /// User-Agent sent with the request
+    pub user_agent: String,
+
+    /// Response body, decoded with the detected [`charset`](Self::charset)
     pub body: String,
+
+    /// The raw response, if [`HttpOptions::snapshot`] was enabled
+    pub snapshot: Option<Snapshot>,
+}
+
+/// A redirect followed while fetching a page.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct RedirectHop {
+    /// The URL that answered with the redirect
+    pub url: String,
+
+    /// The redirect status code, such as `301` or `302`
+    pub status: u16,
+}
+
+impl HttpInfo {
+    /// The first value of a response header, matched case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Header names listed in every `Vary` response header, lowercased and
+    /// deduplicated; `"*"` means the response may vary by anything.
+    pub fn vary(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (_, value) in self
+            .headers
+            .iter()
+            .filter(|(n, _)| n.eq_ignore_ascii_case("vary"))
+        {
+            for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                let name = name.to_ascii_lowercase();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// Whether the response declares it may differ by request header `name`,
+    /// either by listing it in `Vary` or with `Vary: *`.
+    ///
+    /// A cache keyed on the URL should add the request's value of each such
+    /// header to the key.
+    pub fn varies_by(&self, name: &str) -> bool {
+        self.vary()
+            .iter()
+            .any(|vary| vary == "*" || vary.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the response may differ by `User-Agent`, as for sites serving
+    /// separate mobile pages.
+    pub fn varies_by_user_agent(&self) -> bool {
+        self.varies_by("user-agent")
+    }
+
+    /// Whether the response may differ by `Accept-Language`, as for sites
+    /// that pick the language from the request.
+    pub fn varies_by_language(&self) -> bool {
+        self.varies_by("accept-language")
+    }
+}
+
+/// A fetched response with the protocol details [`HttpInfo`] does not model.
+///
+/// Returned by [`WebpageClient::fetch_raw`](crate::WebpageClient::fetch_raw).
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    /// The response as the crate models it; the body is not parsed
+    pub info: HttpInfo,
+
+    /// HTTP version of the final response, if fetched by the built-in client
+    pub version: Option<Version>,
+
+    /// Address of the server that sent the final response, if known
+    pub remote_addr: Option<SocketAddr>,
+
+    /// Every header of the final response, including repeated headers and
+    /// values that are not valid UTF-8
+    pub headers: HeaderMap,
+}
+
+impl RawResponse {
+    /// Wrap a response fetched by a custom [`Transport`](crate::Transport),
+    /// which only reports what [`HttpInfo`] holds.
+    pub(crate) fn from_info(info: HttpInfo) -> Self {
+        let headers = info
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((name.parse::<HeaderName>().ok()?, value.parse().ok()?))
+            })
+            .collect();
+        Self {
+            info,
+            version: None,
+            remote_addr: None,
+            headers,
+        }
+    }
 }
 
 /// Configuration for HTTP requests.
@@ -75,6 +209,153 @@ pub struct HttpOptions {
 
     /// Additional headers to send
     pub headers: Vec<(String, String)>,
+
+    /// Follow redirects from `https` to `http`.
+    ///
+    /// When disabled, such a redirect fails with [`Error::InsecureRedirect`].
+    /// Default: false.
+    pub allow_insecure_redirects: bool,
+
+    /// Maximum number of concurrent requests in batch fetches.
+    ///
+    /// Shared by all batch fetches of a client and its clones. Default: 8.
+    pub max_concurrency: usize,
+
+    /// Maximum number of concurrent requests to one host in batch fetches.
+    ///
+    /// Applies to the host of each input URL, not to redirect targets, and is
+    /// shared by all batch fetches of a client and its clones.
+    /// Default: `None` (only [`max_concurrency`](Self::max_concurrency) applies).
+    pub max_concurrent_per_host: Option<usize>,
+
+    /// Overall time limit of a batch fetch.
+    ///
+    /// Fetches still outstanding when it passes are cancelled and reported as
+    /// [`Error::Timeout`]. Default: `None` (only [`timeout`](Self::timeout)
+    /// bounds each request).
+    pub batch_deadline: Option<Duration>,
+
+    /// Default request headers sent before [`headers`](Self::headers), which
+    /// override them.
+    ///
+    /// Default: [`HeaderProfile::Minimal`].
+    pub header_profile: HeaderProfile,
+
+    /// Send the `Sec-Fetch-*` headers of a top-level navigation with the
+    /// [`HeaderProfile::Browser`] profile, and the `Sec-CH-UA` client hints a
+    /// Chromium browser with the configured user agent would send.
+    ///
+    /// Default: false.
+    pub sec_fetch_headers: bool,
+
+    /// Only fetch these bytes of the response body.
+    ///
+    /// Sent as a `Range` header; servers that ignore it have their response
+    /// cut to the same bytes while streaming. The bytes discarded before the
+    /// range count against [`max_body_size`](Self::max_body_size), so the body
+    /// is empty if such a server's range starts past it. Compressed transfer
+    /// encodings are not requested, since a byte range of compressed data
+    /// cannot be decoded.
+    /// Default: `None` (the whole body, up to [`max_body_size`](Self::max_body_size)).
+    pub range: Option<Range<u64>>,
+
+    /// Keep the raw response in [`HttpInfo::snapshot`].
+    ///
+    /// Default: false.
+    pub snapshot: bool,
+
+    /// Revalidate previously fetched pages with conditional requests and
+    /// serve `304 Not Modified` answers from the cache.
+    ///
+    /// Default: `None` (no caching).
+    pub cache: Option<CachePolicy>,
+
+    /// Retry requests that fail to connect, time out, or are answered with a
+    /// transient error status.
+    ///
+    /// Default: `None` (failures are returned at once).
+    pub retries: Option<RetryPolicy>,
+
+    /// Send requests through this HTTP or HTTPS proxy, e.g.
+    /// `http://proxy.corp.example:3128`.
+    ///
+    /// Default: `None` (the `HTTP_PROXY`/`HTTPS_PROXY` environment variables
+    /// apply).
+    pub proxy: Option<String>,
+
+    /// Hosts reached directly instead of through [`proxy`](Self::proxy):
+    /// domains (matching their subdomains), IP addresses, or CIDR ranges.
+    pub no_proxy: Vec<String>,
+
+    /// Credentials for the [`proxy`](Self::proxy), sent as
+    /// `Proxy-Authorization: Basic`.
+    pub proxy_auth: Option<ProxyAuth>,
+
+    /// Keep the cookies responses set and send them with later requests of
+    /// the same client, including the next hops of a redirect chain.
+    ///
+    /// Default: false.
+    pub cookies: bool,
+
+    /// Cookies sent to a domain and its subdomains whether or not
+    /// [`cookies`](Self::cookies) is enabled, as `(domain, set_cookie)` pairs;
+    /// a cookie of the same name set by a response takes precedence.
+    pub seed_cookies: Vec<(String, String)>,
+}
+
+/// Basic credentials for [`HttpOptions::proxy`]; the password is left out of
+/// `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxyAuth {
+    /// User name
+    pub username: String,
+    /// Password
+    pub password: String,
+}
+
+impl fmt::Debug for ProxyAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProxyAuth")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A set of default request headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HeaderProfile {
+    /// Only the headers reqwest sends itself
+    #[default]
+    Minimal,
+    /// An `Accept` header preferring HTML, as sent by well-behaved crawlers
+    Bot,
+    /// `Accept`, `Accept-Language`, and `Upgrade-Insecure-Requests` as sent by
+    /// desktop browsers, for origins that serve degraded HTML to bare clients
+    Browser,
+}
+
+impl HeaderProfile {
+    /// The headers of this profile.
+    fn headers(self) -> &'static [(HeaderName, &'static str)] {
+        const BOT: &[(HeaderName, &str)] = &[(
+            header::ACCEPT,
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+        )];
+        const BROWSER: &[(HeaderName, &str)] = &[
+            (
+                header::ACCEPT,
+                "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,\
+                 image/webp,*/*;q=0.8",
+            ),
+            (header::ACCEPT_LANGUAGE, "en-US,en;q=0.9"),
+            (header::UPGRADE_INSECURE_REQUESTS, "1"),
+        ];
+        match self {
+            Self::Minimal => &[],
+            Self::Bot => BOT,
+            Self::Browser => BROWSER,
+        }
+    }
 }
 
 impl Default for HttpOptions {
@@ -91,6 +372,21 @@ impl Default for HttpOptions {
                 env!("CARGO_PKG_VERSION")
             ),
             headers: Vec::new(),
+            allow_insecure_redirects: false,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            max_concurrent_per_host: None,
+            batch_deadline: None,
+            header_profile: HeaderProfile::Minimal,
+            sec_fetch_headers: false,
+            range: None,
+            snapshot: false,
+            cache: None,
+            retries: None,
+            proxy: None,
+            no_proxy: Vec::new(),
+            proxy_auth: None,
+            cookies: false,
+            seed_cookies: Vec::new(),
         }
     }
 }
@@ -142,7 +438,7 @@ impl HttpOptions {
         self
     }
 
-    /// Set the User-Agent header.
+    /// Set the User-Agent header, from a string or a [`UserAgent`](crate::UserAgent) preset.
     pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
         self.user_agent = user_agent.into();
         self
@@ -154,36 +450,329 @@ impl HttpOptions {
         self
     }
 
-    /// Build a reqwest Client from these options.
-    fn build_client(&self) -> Result<Client> {
-        let redirect_policy = if self.follow_redirects {
-            reqwest::redirect::Policy::limited(self.max_redirects)
-        } else {
-            reqwest::redirect::Policy::none()
-        };
+    /// Set whether to follow redirects from `https` to `http`.
+    ///
+    /// **Security Note:** A downgrade exposes the rest of the exchange to
+    /// network attackers.
+    pub fn allow_insecure_redirects(mut self, allow: bool) -> Self {
+        self.allow_insecure_redirects = allow;
+        self
+    }
+
+    /// Set the maximum number of concurrent requests in batch fetches.
+    pub fn max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = max;
+        self
+    }
+
+    /// Set the maximum number of concurrent requests to one host in batch
+    /// fetches.
+    pub fn max_concurrent_per_host(mut self, max: usize) -> Self {
+        self.max_concurrent_per_host = Some(max);
+        self
+    }
+
+    /// Set the overall time limit of batch fetches.
+    pub fn batch_deadline(mut self, deadline: Duration) -> Self {
+        self.batch_deadline = Some(deadline);
+        self
+    }
+
+    /// Set the default request headers profile.
+    pub fn header_profile(mut self, profile: HeaderProfile) -> Self {
+        self.header_profile = profile;
+        self
+    }
+
+    /// Set whether to send `Sec-Fetch-*` and client-hint headers with the
+    /// browser profile.
+    pub fn sec_fetch_headers(mut self, send: bool) -> Self {
+        self.sec_fetch_headers = send;
+        self
+    }
+
+    /// Only fetch the given bytes of the response body.
+    ///
+    /// Head metadata is usually within the first 64 KB, so `range(0..65536)`
+    /// avoids downloading the rest of large pages.
+    pub fn range(mut self, range: Range<u64>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Set whether to keep the raw response for archival in [`HttpInfo::snapshot`].
+    pub fn snapshot(mut self, snapshot: bool) -> Self {
+        self.snapshot = snapshot;
+        self
+    }
+
+    /// Cache responses and revalidate them with `If-None-Match` and
+    /// `If-Modified-Since`.
+    pub fn cache(mut self, policy: CachePolicy) -> Self {
+        self.cache = Some(policy);
+        self
+    }
+
+    /// Retry failed requests according to `policy`.
+    pub fn retries(mut self, policy: RetryPolicy) -> Self {
+        self.retries = Some(policy);
+        self
+    }
 
+    /// Send requests through a proxy.
+    ///
+    /// With [`block_private_ips`](Self::block_private_ips), host names of
+    /// proxied requests are not resolved locally, since the proxy resolves
+    /// them; literal private IPs and internal host names are still blocked.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Reach these hosts directly instead of through the proxy.
+    pub fn no_proxy<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.no_proxy = hosts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Authenticate to the proxy with basic credentials.
+    pub fn proxy_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.proxy_auth = Some(ProxyAuth {
+            username: username.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// Set whether to keep cookies between requests.
+    pub fn cookies(mut self, enabled: bool) -> Self {
+        self.cookies = enabled;
+        self
+    }
+
+    /// Send a cookie to `domain` and its subdomains, such as a consent cookie
+    /// the site would otherwise set after a click.
+    ///
+    /// `cookie` is written like a `Set-Cookie` value, e.g. `"consent=yes"` or
+    /// `"sid=abc; Path=/app; Secure"`.
+    pub fn cookie(mut self, domain: impl Into<String>, cookie: impl Into<String>) -> Self {
+        self.seed_cookies.push((domain.into(), cookie.into()));
+        self
+    }
+
+    /// Build a reqwest Client from these options.
+    ///
+    /// Redirects are followed manually by [`fetch`] so every hop is observable.
+    pub(crate) fn build_client(&self) -> Result<Client> {
         let mut builder = Client::builder()
             .danger_accept_invalid_certs(self.allow_insecure)
-            .redirect(redirect_policy)
+            .redirect(reqwest::redirect::Policy::none())
             .timeout(self.timeout)
             .user_agent(&self.user_agent);
+        if let Some(url) = &self.proxy {
+            let mut proxy = reqwest::Proxy::all(url)
+                .map_err(Error::ClientBuild)?
+                .no_proxy(reqwest::NoProxy::from_string(&self.no_proxy.join(",")));
+            if let Some(auth) = &self.proxy_auth {
+                proxy = proxy.basic_auth(&auth.username, &auth.password);
+            }
+            builder = builder.proxy(proxy);
+        }
+        builder.build().map_err(Error::ClientBuild)
+    }
 
-        // Add default headers
-        let mut headers = reqwest::header::HeaderMap::new();
+    /// Whether a request to the host name of `url` goes through
+    /// [`proxy`](Self::proxy).
+    ///
+    /// Only domain entries of `no_proxy` are matched; IP hosts are checked
+    /// the same either way.
+    pub(crate) fn is_proxied(&self, url: &Url) -> bool {
+        let Some(host) = self.proxy.as_ref().and(url.host_str()) else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        !self.no_proxy.iter().any(|entry| {
+            let entry = entry
+                .trim()
+                .trim_start_matches("*.")
+                .trim_start_matches('.');
+            let entry = entry.to_ascii_lowercase();
+            entry == "*"
+                || host == entry
+                || host
+                    .strip_suffix(entry.as_str())
+                    .is_some_and(|sub| sub.ends_with('.'))
+        })
+    }
+
+    /// Custom headers to send to `url`.
+    ///
+    /// Credentials are only sent to the origin of the initially requested URL,
+    /// so a redirect to another host cannot capture them.
+    pub(crate) fn headers_for(&self, url: &Url, origin: &url::Origin) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let same_origin = url.origin() == *origin;
+        for (name, value) in self.header_profile.headers() {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+        if self.sec_fetch_headers && self.header_profile == HeaderProfile::Browser {
+            // A navigation typed into the address bar; redirects to another
+            // origin make it cross-site
+            let site = if same_origin { "none" } else { "cross-site" };
+            headers.insert("sec-fetch-site", HeaderValue::from_static(site));
+            headers.insert("sec-fetch-mode", HeaderValue::from_static("navigate"));
+            headers.insert("sec-fetch-dest", HeaderValue::from_static("document"));
+            headers.insert("sec-fetch-user", HeaderValue::from_static("?1"));
+            for (name, value) in user_agent::client_hints(&self.user_agent) {
+                if let Ok(value) = HeaderValue::from_str(&value) {
+                    headers.insert(name, value);
+                }
+            }
+        }
         for (name, value) in &self.headers {
-            if let (Ok(name), Ok(value)) = (
-                name.parse::<reqwest::header::HeaderName>(),
-                value.parse::<reqwest::header::HeaderValue>(),
-            ) {
+            if let (Ok(name), Ok(value)) =
+                (name.parse::<HeaderName>(), value.parse::<HeaderValue>())
+            {
+                if !same_origin && SENSITIVE_HEADERS.contains(&name) {
+                    continue;
+                }
                 headers.insert(name, value);
             }
         }
-        builder = builder.default_headers(headers);
+        headers
+    }
+}
+
+/// Settings that replace a client's [`HttpOptions`] for a single request.
+///
+/// Passed to [`WebpageClient::fetch_with`](crate::WebpageClient::fetch_with),
+/// so requests with different needs can share one client and its connection
+/// pool. Unset fields keep the client's value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Overrides {
+    /// Request timeout
+    pub timeout: Option<Duration>,
 
-        Ok(builder.build()?)
+    /// User-Agent header, replacing any rotated user agent
+    pub user_agent: Option<String>,
+
+    /// Headers sent after the client's [`HttpOptions::headers`], which they
+    /// override
+    pub headers: Vec<(String, String)>,
+
+    /// Maximum response body size in bytes
+    pub max_body_size: Option<usize>,
+
+    /// Follow HTTP redirects
+    pub follow_redirects: Option<bool>,
+
+    /// Maximum number of redirects to follow
+    pub max_redirects: Option<usize>,
+
+    /// Only fetch these bytes of the response body
+    pub range: Option<Range<u64>>,
+
+    /// Keep the raw response in [`HttpInfo::snapshot`]
+    pub snapshot: Option<bool>,
+}
+
+impl Overrides {
+    /// Create overrides that change nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the request timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the User-Agent header.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Add a custom header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the maximum response body size in bytes.
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.max_body_size = Some(size);
+        self
+    }
+
+    /// Set whether to follow redirects.
+    pub fn follow_redirects(mut self, follow: bool) -> Self {
+        self.follow_redirects = Some(follow);
+        self
+    }
+
+    /// Set the maximum number of redirects to follow.
+    pub fn max_redirects(mut self, max: usize) -> Self {
+        self.max_redirects = Some(max);
+        self
+    }
+
+    /// Only fetch the given bytes of the response body.
+    pub fn range(mut self, range: Range<u64>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Set whether to keep the raw response in [`HttpInfo::snapshot`].
+    pub fn snapshot(mut self, snapshot: bool) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    /// Whether these overrides leave every option unchanged.
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the overrides to a request's options.
+    pub(crate) fn apply(&self, options: &mut HttpOptions) {
+        if let Some(timeout) = self.timeout {
+            options.timeout = timeout;
+        }
+        if let Some(user_agent) = &self.user_agent {
+            options.user_agent.clone_from(user_agent);
+        }
+        options.headers.extend(self.headers.iter().cloned());
+        if let Some(size) = self.max_body_size {
+            options.max_body_size = size;
+        }
+        if let Some(follow) = self.follow_redirects {
+            options.follow_redirects = follow;
+        }
+        if let Some(max) = self.max_redirects {
+            options.max_redirects = max;
+        }
+        if let Some(range) = &self.range {
+            options.range = Some(range.clone());
+        }
+        if let Some(snapshot) = self.snapshot {
+            options.snapshot = snapshot;
+        }
     }
 }
 
+/// Headers dropped when a redirect leaves the original origin.
+const SENSITIVE_HEADERS: &[HeaderName] = &[
+    header::AUTHORIZATION,
+    header::COOKIE,
+    header::PROXY_AUTHORIZATION,
+];
+
 /// Check if an IPv4 address is private/internal.
 fn is_private_ipv4(ip: Ipv4Addr) -> bool {
     ip.is_loopback()                           // 127.0.0.0/8
@@ -218,7 +807,22 @@ fn is_private_ip(ip: IpAddr) -> bool {
 }
 
 /// Validate URL for SSRF protection (async DNS resolution).
-async fn validate_url_for_ssrf(url: &str) -> Result<()> {
+#[cfg(any(feature = "render", test))]
+pub(crate) async fn validate_url_for_ssrf(url: &str) -> Result<()> {
+    validate_target(url, true).await
+}
+
+/// Validate URL for SSRF protection before requesting it with `options`.
+///
+/// A proxy resolves the host names of the requests it forwards, so for those
+/// only literal IP addresses and internal host names are checked here; what
+/// the names resolve to is up to the proxy's egress rules.
+pub(crate) async fn validate_request_for_ssrf(url: &str, options: &HttpOptions) -> Result<()> {
+    let proxied = Url::parse(url).is_ok_and(|url| options.is_proxied(&url));
+    validate_target(url, !proxied).await
+}
+
+async fn validate_target(url: &str, resolve: bool) -> Result<()> {
     let parsed = Url::parse(url).map_err(|e| Error::InvalidUrl(e.to_string()))?;
 
     // Only allow http and https schemes
@@ -243,10 +847,25 @@ async fn validate_url_for_ssrf(url: &str) -> Result<()> {
         || host_lower.ends_with(".internal")
         || host_lower == "metadata.google.internal"
     {
-        return Err(Error::SsrfBlocked(format!(
-            "blocked request to internal host: {}",
-            host
-        )));
+        return Err(Error::SsrfBlocked {
+            url: url.to_string(),
+            reason: format!("blocked request to internal host: {}", host),
+        });
+    }
+
+    let literal = match parsed.host() {
+        Some(url::Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+        Some(url::Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+        _ => None,
+    };
+    if let Some(ip) = literal.filter(|ip| is_private_ip(*ip)) {
+        return Err(Error::SsrfBlocked {
+            url: url.to_string(),
+            reason: format!("blocked request to private IP: {ip}"),
+        });
+    }
+    if !resolve {
+        return Ok(());
     }
 
     // Resolve hostname and check all IP addresses (async to avoid blocking runtime)
@@ -259,11 +878,14 @@ async fn validate_url_for_ssrf(url: &str) -> Result<()> {
     if let Ok(addrs) = tokio::net::lookup_host(&addr_str).await {
         for addr in addrs {
             if is_private_ip(addr.ip()) {
-                return Err(Error::SsrfBlocked(format!(
-                    "blocked request to private IP: {} (resolved from {})",
-                    addr.ip(),
-                    host
-                )));
+                return Err(Error::SsrfBlocked {
+                    url: url.to_string(),
+                    reason: format!(
+                        "blocked request to private IP: {} (resolved from {})",
+                        addr.ip(),
+                        host
+                    ),
+                });
             }
         }
     }
@@ -272,32 +894,297 @@ async fn validate_url_for_ssrf(url: &str) -> Result<()> {
     Ok(())
 }
 
-/// Fetch a URL and return HTTP information.
-pub async fn fetch(url: &str, options: &HttpOptions) -> Result<HttpInfo> {
-    // SSRF protection: validate URL before making request
-    if options.block_private_ips {
-        validate_url_for_ssrf(url).await?;
+/// Fetch a URL with the given client and return the response.
+///
+/// Redirects are followed here rather than by reqwest so observers see every hop.
+pub(crate) async fn fetch(
+    client: &Client,
+    url: &str,
+    options: &HttpOptions,
+    state: &ClientState,
+) -> Result<RawResponse> {
+    let mut chain = RedirectChain::start(url, options, state).await?;
+    let mut range = options.range.clone().filter(|r| !r.is_empty());
+
+    loop {
+        state.observers.on_request(chain.current.as_str())?;
+
+        let mut headers = chain.headers();
+        if let Some(range) = &range {
+            let value = format!("bytes={}-{}", range.start, range.end - 1);
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.insert(header::RANGE, value);
+            }
+            headers.insert(
+                header::ACCEPT_ENCODING,
+                HeaderValue::from_static("identity"),
+            );
+        }
+
+        let _in_flight = state
+            .stats
+            .start(chain.current.host_str().unwrap_or_default());
+        let response = chain.send(client, Method::GET, headers).await?;
+        chain.receive(&response);
+
+        let status = response.status();
+        if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && range.is_some() {
+            // The body is shorter than the range start (or empty); fetch it whole
+            range = None;
+            continue;
+        }
+        if options.follow_redirects && chain.follow(&response).await? {
+            continue;
+        }
+
+        // Servers without range support answer 200 with the whole body
+        let (skip, limit) = match &range {
+            Some(range) if status == reqwest::StatusCode::PARTIAL_CONTENT => {
+                (0, range_len(range, options.max_body_size))
+            }
+            Some(range) => (range.start, range_len(range, options.max_body_size)),
+            None => (0, options.max_body_size),
+        };
+        let body = BodyLimits {
+            skip,
+            keep: limit,
+            download: options.max_body_size,
+        };
+        let version = response.version();
+        let remote_addr = response.remote_addr();
+        let raw_headers = response.headers().clone();
+        let mut info =
+            response_to_info(response, body, chain.hops, options.snapshot, state).await?;
+        info.user_agent = options.user_agent.clone();
+        info.cookies = chain.cookies;
+        state.observers.on_response(&info);
+        return Ok(RawResponse {
+            info,
+            version: Some(version),
+            remote_addr,
+            headers: raw_headers,
+        });
     }
+}
 
-    let client = options.build_client()?;
-    let response = client.get(url).send().await?;
+/// A redirect chain being followed hop by hop, with the checks every hop
+/// must pass.
+pub(crate) struct RedirectChain<'a> {
+    /// The initially requested URL
+    url: &'a str,
+    options: &'a HttpOptions,
+    state: &'a ClientState,
+    origin: url::Origin,
+    /// Every URL requested so far, for loop detection and errors
+    visited: Vec<String>,
+    /// Where loop detection starts; a URL revisited with new cookies is no loop
+    loop_start: usize,
+    /// The URL of the next request
+    pub(crate) current: Url,
+    /// Each redirect followed, in order
+    pub(crate) hops: Vec<RedirectHop>,
+    /// Cookies set by the responses so far
+    pub(crate) cookies: Vec<Cookie>,
+}
 
-    response_to_info(response, options.max_body_size).await
+impl<'a> RedirectChain<'a> {
+    /// Start a chain at `url`, checking it against SSRF protection.
+    pub(crate) async fn start(
+        url: &'a str,
+        options: &'a HttpOptions,
+        state: &'a ClientState,
+    ) -> Result<Self> {
+        if options.block_private_ips {
+            validate_request_for_ssrf(url, options).await?;
+        }
+        let current = Url::parse(url).map_err(|e| Error::InvalidUrl(e.to_string()))?;
+        Ok(Self {
+            url,
+            options,
+            state,
+            origin: current.origin(),
+            visited: vec![current.to_string()],
+            loop_start: 0,
+            current,
+            hops: Vec::new(),
+            cookies: Vec::new(),
+        })
+    }
+
+    /// Custom headers and cookies to send to the current URL.
+    pub(crate) fn headers(&self) -> HeaderMap {
+        let mut headers = self.options.headers_for(&self.current, &self.origin);
+        self.state
+            .cookies
+            .add_header(&mut headers, &self.current, self.options);
+        headers
+    }
+
+    /// Request the current URL.
+    pub(crate) async fn send(
+        &self,
+        client: &Client,
+        method: Method,
+        headers: HeaderMap,
+    ) -> Result<Response> {
+        client
+            .request(method, self.current.clone())
+            .timeout(self.options.timeout)
+            .header(header::USER_AGENT, &self.options.user_agent)
+            .headers(headers)
+            .send()
+            .await
+            .map_err(|e| {
+                self.state.stats.record_failure();
+                Error::from_reqwest(self.current.as_str(), e)
+            })
+    }
+
+    /// Take the cookies a response from the current URL sets.
+    pub(crate) fn receive(&mut self, response: &Response) {
+        let received = self
+            .state
+            .cookies
+            .receive(response.headers(), &self.current, self.options);
+        if self.options.cookies && !received.is_empty() {
+            self.loop_start = self.visited.len();
+        }
+        self.cookies.extend(received);
+    }
+
+    /// Move to the target of `response` if it is a redirect, returning whether
+    /// it was one.
+    ///
+    /// Fails if the target exceeds [`HttpOptions::max_redirects`], is rejected
+    /// by a request policy or SSRF protection, loops, or downgrades to `http`.
+    pub(crate) async fn follow(&mut self, response: &Response) -> Result<bool> {
+        let status = response.status();
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|v| v.to_str().ok());
+        let Some(location) = location.filter(|_| is_redirect(status)) else {
+            return Ok(false);
+        };
+        let options = self.options;
+        if self.hops.len() >= options.max_redirects {
+            return Err(Error::TooManyRedirects {
+                url: self.url.to_string(),
+                max_redirects: options.max_redirects,
+            });
+        }
+
+        let current = &self.current;
+        let next = current
+            .join(location)
+            .map_err(|e| Error::InvalidUrl(format!("redirect to '{}': {}", location, e)))?;
+        let next = match self.state.policies.check_request(next.as_str())? {
+            Cow::Borrowed(_) => next,
+            Cow::Owned(rewritten) => {
+                Url::parse(&rewritten).map_err(|e| Error::InvalidUrl(e.to_string()))?
+            }
+        };
+        // A public host must not be able to redirect into the private network
+        if options.block_private_ips {
+            validate_request_for_ssrf(next.as_str(), options).await?;
+        }
+        check_redirect(
+            self.url,
+            &mut self.visited,
+            self.loop_start,
+            current,
+            &next,
+            options,
+        )?;
+        self.state
+            .observers
+            .on_redirect(current.as_str(), next.as_str(), status.as_u16());
+        self.hops.push(RedirectHop {
+            url: current.to_string(),
+            status: status.as_u16(),
+        });
+        self.current = next;
+        Ok(true)
+    }
+}
+
+/// Record a redirect hop, rejecting loops and `https` to `http` downgrades.
+///
+/// Only URLs from `chain[loop_start..]` count as visited.
+fn check_redirect(
+    url: &str,
+    chain: &mut Vec<String>,
+    loop_start: usize,
+    current: &Url,
+    next: &Url,
+    options: &HttpOptions,
+) -> Result<()> {
+    let repeated = chain[loop_start..]
+        .iter()
+        .any(|visited| *visited == next.as_str());
+    chain.push(next.to_string());
+    if repeated {
+        return Err(Error::RedirectLoop {
+            url: url.to_string(),
+            chain: std::mem::take(chain),
+        });
+    }
+    if current.scheme() == "https" && next.scheme() == "http" && !options.allow_insecure_redirects {
+        return Err(Error::InsecureRedirect {
+            url: url.to_string(),
+            chain: std::mem::take(chain),
+        });
+    }
+    Ok(())
+}
+
+/// Number of bytes of `range` to read, capped at `max_body_size`.
+fn range_len(range: &Range<u64>, max_body_size: usize) -> usize {
+    usize::try_from(range.end - range.start)
+        .unwrap_or(usize::MAX)
+        .min(max_body_size)
+}
+
+/// Whether a status code is a redirect that carries a `Location` to follow.
+fn is_redirect(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 301 | 302 | 303 | 307 | 308)
+}
+
+/// Which bytes of a response body to read.
+#[derive(Debug, Clone, Copy)]
+struct BodyLimits {
+    /// Leading bytes to discard, for servers that ignored a `Range` header
+    skip: u64,
+    /// Bytes to keep after the skipped ones
+    keep: usize,
+    /// Bytes to download in total, skipped ones included
+    download: usize,
 }
 
 /// Convert a reqwest Response to HttpInfo with streaming body size limit.
-async fn response_to_info(response: Response, max_body_size: usize) -> Result<HttpInfo> {
+///
+/// Discarded bytes count against the download limit, so a server that ignores
+/// a `Range` starting past [`HttpOptions::max_body_size`] yields an empty body
+/// instead of being read to the range start.
+async fn response_to_info(
+    response: Response,
+    limits: BodyLimits,
+    redirects: Vec<RedirectHop>,
+    snapshot: bool,
+    state: &ClientState,
+) -> Result<HttpInfo> {
     let url = response.url().to_string();
     let status_code = response.status().as_u16();
 
-    let content_type = response
+    let raw_content_type = response
         .headers()
         .get(reqwest::header::CONTENT_TYPE)
         .and_then(|v| v.to_str().ok())
-        .map(|s| {
-            // Extract just the mime type, not charset
-            s.split(';').next().unwrap_or(s).trim().to_string()
-        });
+        .map(str::to_string);
+    // Extract just the mime type, not charset
+    let content_type = raw_content_type
+        .as_deref()
+        .map(|s| s.split(';').next().unwrap_or(s).trim().to_string());
 
     let headers: Vec<(String, String)> = response
         .headers()
@@ -311,33 +1198,49 @@ async fn response_to_info(response: Response, max_body_size: usize) -> Result<Ht
         .collect();
 
     // Stream body with size limit - stops downloading when limit reached
-    let content_length = response.content_length().unwrap_or(0) as usize;
-    let capacity = content_length.min(max_body_size).min(1024 * 1024); // Cap initial alloc at 1MB
+    let announced_length = response.content_length();
+    let content_length = announced_length.unwrap_or(0) as usize;
+    let capacity = content_length.min(limits.keep).min(1024 * 1024); // Cap initial alloc at 1MB
     let mut bytes = Vec::with_capacity(capacity);
+    let mut skip = limits.skip;
+    let mut downloaded = 0;
     let mut stream = response.bytes_stream();
 
     while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
-        let remaining = max_body_size.saturating_sub(bytes.len());
-        if remaining == 0 {
-            break;
-        }
-        let to_take = chunk.len().min(remaining);
-        bytes.extend_from_slice(&chunk[..to_take]);
-        if to_take < chunk.len() {
+        let chunk = chunk.map_err(|e| Error::from_reqwest_in(&url, FetchPhase::Body, e))?;
+        let chunk = &chunk[..chunk.len().min(limits.download - downloaded)];
+        let skipped = usize::try_from(skip).unwrap_or(usize::MAX).min(chunk.len());
+        skip -= skipped as u64;
+        let to_take = (chunk.len() - skipped).min(limits.keep - bytes.len());
+        bytes.extend_from_slice(&chunk[skipped..skipped + to_take]);
+        downloaded += skipped + to_take;
+        state.stats.record_bytes((skipped + to_take) as u64);
+        state
+            .observers
+            .on_progress(&url, downloaded as u64, announced_length)?;
+        if bytes.len() == limits.keep || downloaded == limits.download {
             break; // Hit the limit
         }
     }
 
-    let body = String::from_utf8_lossy(&bytes).into_owned();
+    let charset = CharsetInfo::detect(&bytes, raw_content_type.as_deref());
+    let body = charset.decode(&bytes);
+    let snapshot = snapshot.then(|| Snapshot::capture(&url, status_code, &headers, bytes));
 
     Ok(HttpInfo {
         url,
         status_code,
         headers,
+        // Not reported by reqwest
+        informational: Vec::new(),
         content_type,
-        redirect_count: 0,
+        charset: Some(charset),
+        redirect_count: redirects.len() as u32,
+        redirects,
+        cookies: Vec::new(),
+        user_agent: String::new(),
         body,
+        snapshot,
     })
 }
 
@@ -355,6 +1258,11 @@ mod tests {
         assert_eq!(options.max_body_size, DEFAULT_MAX_BODY_SIZE);
         assert!(options.block_private_ips);
         assert!(options.user_agent.contains("webpage-info"));
+        assert_eq!(options.max_concurrency, DEFAULT_MAX_CONCURRENCY);
+        assert_eq!(options.max_concurrent_per_host, None);
+        assert_eq!(options.batch_deadline, None);
+        assert_eq!(options.range, None);
+        assert!(!options.snapshot);
     }
 
     #[test]
@@ -367,7 +1275,9 @@ mod tests {
             .max_body_size(1024)
             .block_private_ips(false)
             .user_agent("Custom Agent")
-            .header("X-Custom", "Value");
+            .header("X-Custom", "Value")
+            .max_concurrency(2)
+            .max_concurrent_per_host(1);
 
         assert!(options.allow_insecure);
         assert!(!options.follow_redirects);
@@ -377,6 +1287,69 @@ mod tests {
         assert!(!options.block_private_ips);
         assert_eq!(options.user_agent, "Custom Agent");
         assert_eq!(options.headers.len(), 1);
+        assert_eq!(options.max_concurrency, 2);
+        assert_eq!(options.max_concurrent_per_host, Some(1));
+    }
+
+    #[test]
+    fn test_vary() {
+        let info = HttpInfo {
+            headers: vec![
+                (
+                    "Vary".to_string(),
+                    "Accept-Encoding, User-Agent".to_string(),
+                ),
+                ("vary".to_string(), "user-agent,Cookie".to_string()),
+            ],
+            ..HttpInfo::default()
+        };
+        assert_eq!(info.vary(), ["accept-encoding", "user-agent", "cookie"]);
+        assert!(info.varies_by_user_agent());
+        assert!(info.varies_by("COOKIE"));
+        assert!(!info.varies_by_language());
+
+        let info = HttpInfo {
+            headers: vec![("vary".to_string(), "*".to_string())],
+            ..HttpInfo::default()
+        };
+        assert!(info.varies_by_language());
+        assert!(!HttpInfo::default().varies_by_user_agent());
+    }
+
+    #[test]
+    fn test_header_profiles() {
+        let url = Url::parse("https://example.com/").unwrap();
+        let other = Url::parse("https://cdn.example.net/").unwrap();
+        let origin = url.origin();
+
+        let headers = HttpOptions::new().headers_for(&url, &origin);
+        assert!(headers.is_empty());
+
+        let bot = HttpOptions::new().header_profile(HeaderProfile::Bot);
+        assert!(
+            bot.headers_for(&url, &origin)[header::ACCEPT]
+                .to_str()
+                .unwrap()
+                .starts_with("text/html")
+        );
+
+        let browser = HttpOptions::new()
+            .user_agent(crate::UserAgent::Chrome)
+            .header_profile(HeaderProfile::Browser)
+            .sec_fetch_headers(true)
+            .header("Accept-Language", "de-DE");
+        let headers = browser.headers_for(&url, &origin);
+        assert_eq!(headers[header::ACCEPT_LANGUAGE], "de-DE");
+        assert_eq!(headers["sec-fetch-site"], "none");
+        assert_eq!(headers["sec-fetch-mode"], "navigate");
+        assert_eq!(headers["sec-ch-ua-platform"], r#""Windows""#);
+        assert_eq!(
+            browser.headers_for(&other, &origin)["sec-fetch-site"],
+            "cross-site"
+        );
+
+        let no_sec_fetch = bot.sec_fetch_headers(true).headers_for(&url, &origin);
+        assert!(!no_sec_fetch.contains_key("sec-fetch-site"));
     }
 
     #[tokio::test]
@@ -406,6 +1379,98 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_fetch_through_proxy() {
+        use wiremock::matchers::{header, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let proxy = MockServer::start().await;
+        Mock::given(path("/page"))
+            .and(header("host", "public.example"))
+            .and(header("proxy-authorization", "Basic dXNlcjpwYXNz"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<title>Hi</title>", "text/html"))
+            .mount(&proxy)
+            .await;
+
+        // The proxy resolves public.example; only the literal IP is blocked locally
+        let options = HttpOptions::new()
+            .proxy(proxy.uri())
+            .proxy_auth("user", "pass");
+        assert!(!format!("{options:?}").contains("pass\""));
+        let client = options.build_client().unwrap();
+        let state = ClientState::default();
+        let response = fetch(&client, "http://public.example/page", &options, &state)
+            .await
+            .unwrap();
+        assert!(response.info.body.contains("Hi"));
+        let err = fetch(&client, "http://10.0.0.1/", &options, &state)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::SsrfBlocked { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_into_private_network_is_blocked() {
+        use wiremock::matchers::{header, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // A proxy, so the public host passes the SSRF check without DNS
+        let proxy = MockServer::start().await;
+        for (from, to) in [
+            ("/metadata", "http://169.254.169.254/latest/meta-data/"),
+            ("/loopback", "http://127.0.0.1/admin"),
+        ] {
+            Mock::given(path(from))
+                .and(header("host", "public.example"))
+                .respond_with(ResponseTemplate::new(302).insert_header("location", to))
+                .mount(&proxy)
+                .await;
+        }
+        Mock::given(path("/latest/meta-data/"))
+            .and(header("host", "169.254.169.254"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ami-id"))
+            .mount(&proxy)
+            .await;
+
+        let options = HttpOptions::new().proxy(proxy.uri());
+        let client = options.build_client().unwrap();
+        let state = ClientState::default();
+        for (path, blocked) in [
+            ("/metadata", "http://169.254.169.254/latest/meta-data/"),
+            ("/loopback", "http://127.0.0.1/admin"),
+        ] {
+            let url = format!("http://public.example{path}");
+            let err = fetch(&client, &url, &options, &state).await.unwrap_err();
+            assert!(
+                matches!(&err, Error::SsrfBlocked { url, .. } if url == blocked),
+                "{err:?}"
+            );
+        }
+
+        let options = options.block_private_ips(false);
+        let client = options.build_client().unwrap();
+        let info = fetch(&client, "http://public.example/metadata", &options, &state)
+            .await
+            .unwrap()
+            .info;
+        assert_eq!(info.url, "http://169.254.169.254/latest/meta-data/");
+        assert_eq!(info.redirect_count, 1);
+        assert_eq!(info.body, "ami-id");
+    }
+
+    #[test]
+    fn test_no_proxy_hosts() {
+        let url = |url| Url::parse(url).unwrap();
+        let options = HttpOptions::new()
+            .proxy("http://proxy.example:3128")
+            .no_proxy([".corp.example", "intranet"]);
+        assert!(options.is_proxied(&url("https://example.com/")));
+        assert!(!options.is_proxied(&url("https://wiki.corp.example/")));
+        assert!(!options.is_proxied(&url("http://INTRANET/")));
+        assert!(options.is_proxied(&url("http://notcorp.example/")));
+        assert!(!HttpOptions::new().is_proxied(&url("https://example.com/")));
+    }
+
     #[tokio::test]
     async fn test_ssrf_blocks_internal_domain() {
         let result = validate_url_for_ssrf("http://server.local/").await;
@@ -456,4 +1521,244 @@ mod tests {
             "2607:f8b0:4004:800::200e".parse().unwrap()
         ));
     }
+
+    #[tokio::test]
+    async fn test_connect_error_carries_context() {
+        let options = HttpOptions::new().block_private_ips(false);
+        let client = options.build_client().unwrap();
+        let err = fetch(
+            &client,
+            "http://127.0.0.1:1/",
+            &options,
+            &ClientState::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.url(), Some("http://127.0.0.1:1/"));
+        assert_eq!(err.phase(), Some(FetchPhase::Connect));
+        assert_eq!(err.status(), None);
+        assert!(err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_dns_error_is_not_retryable() {
+        let options = HttpOptions::new().block_private_ips(false);
+        let client = options.build_client().unwrap();
+        let err = fetch(
+            &client,
+            "http://nonexistent.invalid/",
+            &options,
+            &ClientState::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.phase(), Some(FetchPhase::Dns));
+        assert!(!err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_ssrf_error_carries_url() {
+        let err = validate_url_for_ssrf("http://localhost/admin")
+            .await
+            .unwrap_err();
+        assert_eq!(err.url(), Some("http://localhost/admin"));
+        assert!(!err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_redirects_counted_and_credentials_stay_on_origin() {
+        use wiremock::matchers::{header_exists, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let origin = MockServer::start().await;
+        let other = MockServer::start().await;
+        Mock::given(path("/start"))
+            .and(header_exists("authorization"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("location", format!("{}/end", other.uri())),
+            )
+            .mount(&origin)
+            .await;
+        Mock::given(path("/end"))
+            .and(header_exists("authorization"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&other)
+            .await;
+        Mock::given(path("/end"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("done"))
+            .mount(&other)
+            .await;
+
+        let options = HttpOptions::new()
+            .block_private_ips(false)
+            .header("Authorization", "Bearer secret");
+        let client = options.build_client().unwrap();
+        let url = format!("{}/start", origin.uri());
+        let info = fetch(&client, &url, &options, &ClientState::default())
+            .await
+            .unwrap()
+            .info;
+
+        assert_eq!(info.status_code, 200);
+        assert_eq!(info.redirect_count, 1);
+        assert_eq!(
+            info.redirects,
+            [RedirectHop {
+                url: url.clone(),
+                status: 302,
+            }]
+        );
+        assert_eq!(info.url, format!("{}/end", other.uri()));
+    }
+
+    #[tokio::test]
+    async fn test_range_request() {
+        use wiremock::matchers::{header, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/ranged"))
+            .and(header("range", "bytes=0-9"))
+            .and(header("accept-encoding", "identity"))
+            .respond_with(ResponseTemplate::new(206).set_body_string("<html><he"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/whole"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("0123456789abcdef"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/empty"))
+            .and(header("range", "bytes=0-9"))
+            .respond_with(ResponseTemplate::new(416))
+            .mount(&server)
+            .await;
+        Mock::given(path("/empty"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let options = HttpOptions::new().block_private_ips(false).range(0..10);
+        let client = options.build_client().unwrap();
+        let state = ClientState::default();
+
+        let ranged = format!("{}/ranged", server.uri());
+        let info = fetch(&client, &ranged, &options, &state)
+            .await
+            .unwrap()
+            .info;
+        assert_eq!(info.status_code, 206);
+        assert_eq!(info.body, "<html><he");
+
+        let whole = format!("{}/whole", server.uri());
+        let info = fetch(&client, &whole, &options, &state).await.unwrap().info;
+        assert_eq!(info.body, "0123456789");
+        let options = options.range(4..8);
+        let info = fetch(&client, &whole, &options, &state).await.unwrap().info;
+        assert_eq!(info.body, "4567");
+
+        let state = ClientState::default();
+        let options = options.range(10..16).max_body_size(12);
+        let info = fetch(&client, &whole, &options, &state).await.unwrap().info;
+        assert_eq!(info.body, "ab");
+        // The discarded prefix is downloaded too
+        assert_eq!(state.stats.snapshot().bytes_downloaded, 12);
+        let options = options.range(12..16);
+        let info = fetch(&client, &whole, &options, &state).await.unwrap().info;
+        assert!(info.body.is_empty());
+
+        let empty = format!("{}/empty", server.uri());
+        let options = options.range(0..10).max_body_size(DEFAULT_MAX_BODY_SIZE);
+        let info = fetch(&client, &empty, &options, &state).await.unwrap().info;
+        assert_eq!(info.status_code, 200);
+        assert!(info.body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_too_many_redirects() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        for hop in 0..5 {
+            Mock::given(path(format!("/{hop}")))
+                .respond_with(
+                    ResponseTemplate::new(302).insert_header("location", format!("/{}", hop + 1)),
+                )
+                .mount(&server)
+                .await;
+        }
+
+        let options = HttpOptions::new().block_private_ips(false).max_redirects(3);
+        let client = options.build_client().unwrap();
+        let url = format!("{}/0", server.uri());
+        let err = fetch(&client, &url, &options, &ClientState::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::TooManyRedirects {
+                max_redirects: 3,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_loop_reports_chain() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        for (from, to) in [("/a", "/b"), ("/b", "/a")] {
+            Mock::given(path(from))
+                .respond_with(ResponseTemplate::new(301).insert_header("location", to))
+                .mount(&server)
+                .await;
+        }
+
+        let options = HttpOptions::new().block_private_ips(false);
+        let client = options.build_client().unwrap();
+        let url = format!("{}/a", server.uri());
+        let err = fetch(&client, &url, &options, &ClientState::default())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::RedirectLoop { .. }));
+        assert_eq!(err.phase(), Some(FetchPhase::Redirect));
+        let chain: Vec<String> = ["/a", "/b", "/a"]
+            .iter()
+            .map(|p| format!("{}{p}", server.uri()))
+            .collect();
+        assert_eq!(err.redirect_chain(), Some(chain.as_slice()));
+    }
+
+    #[test]
+    fn test_https_to_http_redirect_is_rejected() {
+        let secure = Url::parse("https://example.com/").unwrap();
+        let plain = Url::parse("http://example.com/").unwrap();
+        let mut chain = vec![secure.to_string()];
+
+        let err = check_redirect(
+            secure.as_str(),
+            &mut chain,
+            0,
+            &secure,
+            &plain,
+            &HttpOptions::new(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::InsecureRedirect { .. }));
+        assert_eq!(
+            err.to_string(),
+            "insecure redirect fetching https://example.com/: https://example.com/ -> http://example.com/"
+        );
+
+        let mut chain = vec![secure.to_string()];
+        let options = HttpOptions::new().allow_insecure_redirects(true);
+        assert!(check_redirect(secure.as_str(), &mut chain, 0, &secure, &plain, &options).is_ok());
+        assert_eq!(chain.len(), 2);
+    }
 }
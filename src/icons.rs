@@ -0,0 +1,457 @@
+//! Favicons and touch icons
+//!
+//! Pages declare icons with `<link rel="icon">` (or the legacy
+//! `rel="shortcut icon"`), `rel="apple-touch-icon"`, and Safari's
+//! `rel="mask-icon"`. Browsers fall back to `/favicon.ico` when none is
+//! declared, and so does [`HtmlInfo::icons`]. [`HtmlInfo::best_icon`] picks the
+//! one a link preview should show.
+
+#[cfg(feature = "dom")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "http")]
+use futures_util::future;
+#[cfg(feature = "dom")]
+use scraper::{Html, Selector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+use url::Url;
+
+#[cfg(feature = "http")]
+use crate::WebpageInfo;
+#[cfg(feature = "http")]
+use crate::base64;
+#[cfg(feature = "http")]
+use crate::client::WebpageClient;
+#[cfg(feature = "http")]
+use crate::error::{Error, Result};
+use crate::html::HtmlInfo;
+#[cfg(feature = "http")]
+use crate::http::Overrides;
+
+/// Maximum number of [`HtmlInfo::icons`].
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+const MAX_ICONS: usize = 50;
+
+/// Size assumed for touch icons that do not declare one, per Apple's default.
+const DEFAULT_TOUCH_ICON_SIZE: u32 = 180;
+
+/// Size assumed for favicons that do not declare one.
+const DEFAULT_FAVICON_SIZE: u32 = 16;
+
+#[cfg(feature = "dom")]
+fn link_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("link[rel][href]").unwrap())
+}
+
+/// How an icon was declared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum IconKind {
+    /// `rel="icon"` or `rel="shortcut icon"`
+    #[default]
+    Favicon,
+    /// `rel="apple-touch-icon"` or `rel="apple-touch-icon-precomposed"`
+    AppleTouchIcon,
+    /// `rel="mask-icon"`: a monochrome SVG Safari tints with `color`
+    MaskIcon,
+    /// Not declared: the `/favicon.ico` browsers request by default
+    Fallback,
+}
+
+/// An icon of the page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Icon {
+    /// Icon URL (resolved if base URL provided)
+    pub url: String,
+
+    /// How the icon was declared
+    pub kind: IconKind,
+
+    /// Declared `sizes` as `(width, height)` pairs
+    pub sizes: Vec<(u32, u32)>,
+
+    /// Whether `sizes="any"` marks a scalable (usually SVG) icon
+    pub scalable: bool,
+
+    /// Declared MIME type (`type`)
+    pub media_type: Option<String>,
+
+    /// Tint color of a mask icon (`color`)
+    pub color: Option<String>,
+
+    /// Shodan-style favicon hash: the signed MurmurHash3 of the icon file,
+    /// base64-encoded with a line break every 76 characters. Set by
+    /// [`Icon::fetch_hashes`].
+    pub murmur3: Option<i32>,
+
+    /// SHA-256 of the icon file as lowercase hex. Set by
+    /// [`Icon::fetch_hashes`].
+    pub sha256: Option<String>,
+}
+
+impl Icon {
+    /// The icon's largest declared width, or a typical size for its kind if it
+    /// declares none.
+    pub fn size(&self) -> u32 {
+        let declared = self.sizes.iter().map(|&(width, _)| width).max();
+        declared.unwrap_or(match self.kind {
+            IconKind::AppleTouchIcon => DEFAULT_TOUCH_ICON_SIZE,
+            IconKind::Favicon | IconKind::MaskIcon => DEFAULT_FAVICON_SIZE,
+            IconKind::Fallback => 0,
+        })
+    }
+}
+
+#[cfg(feature = "http")]
+impl Icon {
+    /// Set [`murmur3`](Self::murmur3) and [`sha256`](Self::sha256) from the
+    /// icon file.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::Icon;
+    ///
+    /// let mut icon = Icon::default();
+    /// icon.set_hashes(b"abc");
+    /// assert_eq!(
+    ///     icon.sha256.as_deref(),
+    ///     Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    /// );
+    /// ```
+    pub fn set_hashes(&mut self, data: &[u8]) {
+        self.murmur3 = Some(favicon_hash(data));
+        let digest = ring::digest::digest(&ring::digest::SHA256, data);
+        self.sha256 = Some(digest.as_ref().iter().map(|b| format!("{b:02x}")).collect());
+    }
+
+    /// Fetch the icon file with `client` and set its hashes.
+    ///
+    /// An error status fails with [`Error::ErrorStatus`] and leaves the hashes
+    /// unset.
+    pub async fn fetch_hashes(&mut self, client: &WebpageClient) -> Result<()> {
+        let overrides = Overrides::new().snapshot(true);
+        let response = client.fetch_response(&self.url, &overrides).await?;
+        let info = response.info;
+        if let Some(page) = info.error_page() {
+            return Err(Error::ErrorStatus {
+                url: info.url,
+                page: Box::new(page),
+            });
+        }
+        let data = match info.snapshot {
+            Some(snapshot) => snapshot.body,
+            None => info.body.into_bytes(),
+        };
+        self.set_hashes(&data);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "http")]
+impl WebpageInfo {
+    /// Fetch every icon of the page with `client` and set its hashes, for
+    /// fingerprinting the infrastructure behind a site.
+    ///
+    /// Icons that fail to fetch, such as a missing `/favicon.ico` fallback,
+    /// keep `None` hashes. Returns the number of icons hashed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use webpage_info::{HttpOptions, WebpageClient};
+    ///
+    /// # async fn run() -> webpage_info::Result<()> {
+    /// let client = WebpageClient::new(HttpOptions::new())?;
+    /// let mut page = client.fetch("https://example.com").await?;
+    /// page.fetch_icon_hashes(&client).await;
+    /// for icon in &page.html.icons {
+    ///     println!("{} http.favicon.hash:{:?}", icon.url, icon.murmur3);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_icon_hashes(&mut self, client: &WebpageClient) -> usize {
+        let fetches = self
+            .html
+            .icons
+            .iter_mut()
+            .map(|icon| icon.fetch_hashes(client));
+        let results = future::join_all(fetches).await;
+        results.iter().filter(|result| result.is_ok()).count()
+    }
+}
+
+impl HtmlInfo {
+    /// The best icon for a link preview: the largest, with scalable icons
+    /// counting as large; mask icons only if nothing else is declared, since
+    /// they are monochrome.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{HtmlInfo, IconKind};
+    ///
+    /// let html = r#"<head>
+    ///     <link rel="icon" href="/favicon-32.png" sizes="32x32">
+    ///     <link rel="apple-touch-icon" href="/touch.png">
+    /// </head>"#;
+    /// let info = HtmlInfo::from_string(html, Some("https://example.com/")).unwrap();
+    /// let icon = info.best_icon().unwrap();
+    /// assert_eq!(icon.kind, IconKind::AppleTouchIcon);
+    /// assert_eq!(icon.url, "https://example.com/touch.png");
+    /// ```
+    pub fn best_icon(&self) -> Option<&Icon> {
+        let rank = |icon: &Icon| {
+            let colored = icon.kind != IconKind::MaskIcon;
+            let size = if icon.scalable { u32::MAX } else { icon.size() };
+            (colored, size)
+        };
+        // Rank ties go to the first declared
+        self.icons.iter().rev().max_by_key(|icon| rank(icon))
+    }
+
+    /// Read every icon `<link>`, then add the fallback.
+    #[cfg(feature = "dom")]
+    pub(crate) fn extract_icons(&mut self, document: &Html, base_url: Option<&Url>) {
+        for element in document.select(link_selector()) {
+            let el = element.value();
+            self.add_icon_link(base_url, |name| el.attr(name));
+        }
+        self.add_fallback_icon(base_url);
+    }
+
+    /// Record a `<link>` if its `rel` declares an icon.
+    #[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+    pub(crate) fn add_icon_link<'a>(
+        &mut self,
+        base_url: Option<&Url>,
+        attr: impl Fn(&str) -> Option<&'a str>,
+    ) {
+        let Some(rel) = attr("rel") else { return };
+        let rels: Vec<String> = rel
+            .split_ascii_whitespace()
+            .map(str::to_ascii_lowercase)
+            .collect();
+        let has = |name: &str| rels.iter().any(|rel| rel == name);
+        let kind = if has("mask-icon") {
+            IconKind::MaskIcon
+        } else if has("apple-touch-icon") || has("apple-touch-icon-precomposed") {
+            IconKind::AppleTouchIcon
+        } else if has("icon") {
+            IconKind::Favicon
+        } else {
+            return;
+        };
+        let Some(href) = attr("href").map(str::trim).filter(|href| !href.is_empty()) else {
+            return;
+        };
+        if self.icons.len() >= MAX_ICONS {
+            return;
+        }
+
+        let sizes = attr("sizes").unwrap_or_default();
+        let present = |value: Option<&str>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        self.icons.push(Icon {
+            url: resolve(href, base_url),
+            kind,
+            sizes: sizes
+                .split_ascii_whitespace()
+                .filter_map(|size| {
+                    let (width, height) = size
+                        .to_ascii_lowercase()
+                        .split_once('x')
+                        .map(|(width, height)| (width.parse().ok(), height.parse().ok()))?;
+                    Some((width?, height?))
+                })
+                .collect(),
+            scalable: sizes
+                .split_ascii_whitespace()
+                .any(|size| size.eq_ignore_ascii_case("any")),
+            media_type: present(attr("type")),
+            color: present(attr("color")),
+            ..Icon::default()
+        });
+    }
+
+    /// Add the implicit `/favicon.ico` if no favicon was declared.
+    #[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+    pub(crate) fn add_fallback_icon(&mut self, base_url: Option<&Url>) {
+        if self.icons.iter().any(|icon| icon.kind == IconKind::Favicon) {
+            return;
+        }
+        self.icons.push(Icon {
+            url: resolve("/favicon.ico", base_url),
+            kind: IconKind::Fallback,
+            ..Icon::default()
+        });
+    }
+}
+
+/// Shodan's favicon hash: MurmurHash3 of the base64 encoding as Python's
+/// `base64.encodebytes` writes it.
+#[cfg(feature = "http")]
+fn favicon_hash(data: &[u8]) -> i32 {
+    let encoded = base64::encode(data);
+    let mut wrapped = Vec::with_capacity(encoded.len() + encoded.len() / 76 + 1);
+    for line in encoded.as_bytes().chunks(76) {
+        wrapped.extend_from_slice(line);
+        wrapped.push(b'\n');
+    }
+    murmur3_32(&wrapped) as i32
+}
+
+/// MurmurHash3 (x86, 32-bit) with seed 0.
+#[cfg(feature = "http")]
+fn murmur3_32(data: &[u8]) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let mix = |k: u32| k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+    let mut hash = 0u32;
+    let blocks = data.chunks_exact(4);
+    let tail = blocks.remainder();
+    for block in blocks {
+        let k = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+        hash = (hash ^ mix(k))
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xe654_6b64);
+    }
+    if !tail.is_empty() {
+        let k = tail.iter().rev().fold(0u32, |k, &b| k << 8 | u32::from(b));
+        hash ^= mix(k);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ hash >> 16
+}
+
+/// `href` resolved against the base URL, or as written without one.
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+fn resolve(href: &str, base_url: Option<&Url>) -> String {
+    match base_url.map(|base| base.join(href)) {
+        Some(Ok(url)) => url.to_string(),
+        _ => href.to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "dom"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_declarations() {
+        let html = r##"<head>
+            <link rel="shortcut icon" href="/favicon.ico" type="image/x-icon">
+            <link rel="icon" href="/icon.svg" sizes="any" type="image/svg+xml">
+            <link rel="icon" href="icons/multi.png" sizes="16x16 48X48 bogus">
+            <link rel="apple-touch-icon-precomposed" href="/touch-152.png" sizes="152x152">
+            <link rel="mask-icon" href="/pinned.svg" color="#5bbad5">
+            <link rel="stylesheet" href="/style.css">
+        </head>"##;
+        let info = HtmlInfo::from_string(html, Some("https://example.com/blog/")).unwrap();
+        let urls: Vec<_> = info.icons.iter().map(|icon| icon.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/favicon.ico",
+                "https://example.com/icon.svg",
+                "https://example.com/blog/icons/multi.png",
+                "https://example.com/touch-152.png",
+                "https://example.com/pinned.svg",
+            ]
+        );
+        assert_eq!(info.icons[0].media_type.as_deref(), Some("image/x-icon"));
+        assert!(info.icons[1].scalable);
+        assert_eq!(info.icons[2].sizes, [(16, 16), (48, 48)]);
+        assert_eq!(info.icons[3].kind, IconKind::AppleTouchIcon);
+        assert_eq!(info.icons[4].color.as_deref(), Some("#5bbad5"));
+        assert_eq!(
+            info.best_icon().unwrap().url,
+            "https://example.com/icon.svg"
+        );
+    }
+
+    #[test]
+    fn test_fallback_icon() {
+        let html = r#"<head><link rel="mask-icon" href="/pinned.svg"></head>"#;
+        let info = HtmlInfo::from_string(html, Some("https://example.com/a/b")).unwrap();
+        assert_eq!(info.icons.len(), 2);
+        assert_eq!(info.icons[1].kind, IconKind::Fallback);
+        assert_eq!(info.icons[1].url, "https://example.com/favicon.ico");
+        assert_eq!(info.best_icon().unwrap().kind, IconKind::Fallback);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_murmur3() {
+        assert_eq!(murmur3_32(b""), 0);
+        assert_eq!(murmur3_32(b"hello"), 0x248b_fa47);
+        assert_eq!(
+            murmur3_32(b"The quick brown fox jumps over the lazy dog"),
+            0x2e4f_f723
+        );
+        // 100 bytes encode to 136 characters, wrapped after the 76th
+        let data = [0xffu8; 100];
+        let encoded = base64::encode(&data);
+        let wrapped = format!("{}\n{}\n", &encoded[..76], &encoded[76..]);
+        assert_eq!(favicon_hash(&data), murmur3_32(wrapped.as_bytes()) as i32);
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_fetch_icon_hashes() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use crate::HttpOptions;
+
+        let server = MockServer::start().await;
+        let png = b"\x89PNG\r\n\x1a\n\x00\xff\xfe";
+        Mock::given(path("/"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"<head><link rel="icon" href="/icon.png"></head>"#,
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(path("/icon.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(png.to_vec(), "image/png"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/apple-touch-icon.png"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = WebpageClient::new(HttpOptions::new().block_private_ips(false)).unwrap();
+        let mut page = client.fetch(&format!("{}/", server.uri())).await.unwrap();
+        page.html.icons.push(Icon {
+            url: format!("{}/apple-touch-icon.png", server.uri()),
+            kind: IconKind::AppleTouchIcon,
+            ..Icon::default()
+        });
+        assert_eq!(page.fetch_icon_hashes(&client).await, 1);
+
+        let mut expected = Icon::default();
+        expected.set_hashes(png);
+        assert_eq!(page.html.icons[0].sha256, expected.sha256);
+        assert_eq!(page.html.icons[0].murmur3, expected.murmur3);
+        assert_eq!(page.html.icons[1].sha256, None);
+    }
+}
@@ -0,0 +1,206 @@
+//! Body image ranking for link previews
+//!
+//! When a page declares no preview image (OpenGraph, Twitter card, or
+//! Schema.org), the `<img>` elements of the body are ranked as fallbacks on
+//! [`HtmlInfo::image_candidates`](crate::HtmlInfo::image_candidates).
+
+#[cfg(feature = "dom")]
+use std::cmp::Reverse;
+#[cfg(feature = "dom")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "dom")]
+use scraper::{Html, Selector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "dom")]
+use url::Url;
+
+#[cfg(feature = "dom")]
+use crate::html::HtmlInfo;
+
+/// Number of candidates kept.
+#[cfg(feature = "dom")]
+const MAX_IMAGE_CANDIDATES: usize = 5;
+
+/// Images are only scanned this far into the document.
+#[cfg(feature = "dom")]
+const MAX_IMAGES_SCANNED: usize = 200;
+
+/// Images smaller than this in either declared dimension are not previews.
+#[cfg(feature = "dom")]
+const MIN_DIMENSION: u32 = 100;
+
+/// URL fragments of images that are never content: logos, sprites, trackers.
+#[cfg(feature = "dom")]
+const EXCLUDED_NAMES: &[&str] = &[
+    "logo", "sprite", "icon", "pixel", "spacer", "blank", "avatar", "badge", "tracking", "1x1",
+];
+
+/// A body image that could serve as a preview image.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ImageCandidate {
+    /// Image URL (resolved if base URL provided)
+    pub url: String,
+
+    /// Declared `width` attribute
+    pub width: Option<u32>,
+
+    /// Declared `height` attribute
+    pub height: Option<u32>,
+
+    /// The `alt` text, if present
+    pub alt: Option<String>,
+
+    /// Ranking score; higher is a better preview
+    pub score: i64,
+}
+
+#[cfg(feature = "dom")]
+fn image_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("body img").unwrap())
+}
+
+#[cfg(feature = "dom")]
+impl HtmlInfo {
+    /// Whether the page declares a preview image in its metadata.
+    pub(crate) fn has_preview_image(&self) -> bool {
+        #[cfg(feature = "schema-org")]
+        let schema_image = self
+            .schema_org
+            .iter()
+            .any(|item| item.value.get("image").is_some());
+        #[cfg(not(feature = "schema-org"))]
+        let schema_image = false;
+
+        !self.opengraph.images.is_empty()
+            || self.meta("twitter:image").is_some()
+            || self.meta("twitter:image:src").is_some()
+            || schema_image
+    }
+}
+
+/// Rank the body images of a document, best first.
+///
+/// Earlier and larger images score higher; logos, sprites, tracking pixels,
+/// tiny and banner-shaped images are excluded or penalized.
+#[cfg(feature = "dom")]
+pub(crate) fn rank_images(document: &Html, base_url: Option<&Url>) -> Vec<ImageCandidate> {
+    let mut candidates: Vec<ImageCandidate> = document
+        .select(image_selector())
+        .take(MAX_IMAGES_SCANNED)
+        .enumerate()
+        .filter_map(|(position, element)| {
+            let element = element.value();
+            let src = element
+                .attr("src")
+                .or_else(|| element.attr("data-src"))
+                .map(str::trim)
+                .filter(|src| !src.is_empty() && !src.starts_with("data:"))?;
+            let url = match base_url.map(|base| base.join(src)) {
+                Some(Ok(resolved)) => resolved.into(),
+                _ => src.to_string(),
+            };
+
+            let lower = url.to_ascii_lowercase();
+            if EXCLUDED_NAMES.iter().any(|name| lower.contains(name)) {
+                return None;
+            }
+
+            let width = element.attr("width").and_then(parse_dimension);
+            let height = element.attr("height").and_then(parse_dimension);
+            if [width, height]
+                .into_iter()
+                .flatten()
+                .any(|size| size < MIN_DIMENSION)
+            {
+                return None;
+            }
+
+            let mut score = 1_000 - 10 * position as i64;
+            if let (Some(w), Some(h)) = (width, height) {
+                score += (u64::from(w) * u64::from(h) / 1_000).min(500) as i64;
+                if w.max(h) > 3 * w.min(h) {
+                    score -= 300;
+                }
+            }
+            let path = lower.split(['?', '#']).next().unwrap_or_default();
+            if path.ends_with(".svg") || path.ends_with(".gif") {
+                score -= 200;
+            }
+            let alt = element
+                .attr("alt")
+                .map(str::trim)
+                .filter(|alt| !alt.is_empty())
+                .map(str::to_string);
+            if alt.is_some() {
+                score += 50;
+            }
+
+            Some(ImageCandidate {
+                url,
+                width,
+                height,
+                alt,
+                score,
+            })
+        })
+        .collect();
+
+    candidates.sort_by_key(|candidate| Reverse(candidate.score));
+    candidates.truncate(MAX_IMAGE_CANDIDATES);
+    candidates
+}
+
+/// Parse a `width`/`height` attribute such as `600` or `600px`.
+#[cfg(feature = "dom")]
+fn parse_dimension(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let digits = value
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(value, |end| &value[..end]);
+    digits.parse().ok()
+}
+
+#[cfg(all(test, feature = "dom"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranks_and_filters_candidates() {
+        let html = r#"<body>
+            <img src="/static/logo.png" width="300" height="300">
+            <img src="/t.gif" width="1" height="1">
+            <img src="/banner.jpg" width="970" height="120">
+            <img src="/thumb.jpg" width="80" height="80">
+            <img src="/hero.jpg" width="1200" height="630" alt="Launch">
+            <img src="data:image/png;base64,AAAA">
+            <img data-src="/lazy.jpg">
+        </body>"#;
+        let base = Url::parse("https://example.com/post").unwrap();
+        let candidates = rank_images(&Html::parse_document(html), Some(&base));
+
+        let urls: Vec<_> = candidates.iter().map(|c| c.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/hero.jpg",
+                "https://example.com/lazy.jpg",
+                "https://example.com/banner.jpg",
+            ]
+        );
+        assert_eq!(candidates[0].width, Some(1200));
+        assert_eq!(candidates[0].alt.as_deref(), Some("Launch"));
+    }
+
+    #[test]
+    fn test_parse_dimension() {
+        assert_eq!(parse_dimension("600px"), Some(600));
+        assert_eq!(parse_dimension(" 40 "), Some(40));
+        assert_eq!(parse_dimension("auto"), None);
+    }
+}
@@ -0,0 +1,125 @@
+//! 1xx informational responses
+//!
+//! Before the final response, a server may send interim `1xx` responses, most
+//! usefully [`103 Early Hints`](https://www.rfc-editor.org/rfc/rfc8297) with
+//! `Link` headers the browser can preload while the page is still being
+//! generated. [`HttpInfo::informational`] records them in the order received
+//! and [`HttpInfo::early_hints`] parses their links.
+//!
+//! The built-in client's HTTP stack consumes interim responses without
+//! reporting them, so the list is only filled by a custom
+//! [`Transport`](crate::Transport) that observes them.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::http::HttpInfo;
+use crate::link_header::HeaderLink;
+
+/// HTTP status code of an Early Hints response.
+const EARLY_HINTS: u16 = 103;
+
+/// An interim `1xx` response received before the final one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct InformationalResponse {
+    /// Status code, e.g. `103`
+    pub status_code: u16,
+
+    /// Response headers, in the order received
+    pub headers: Vec<(String, String)>,
+}
+
+impl InformationalResponse {
+    /// Whether this is a `103 Early Hints` response.
+    pub fn is_early_hints(&self) -> bool {
+        self.status_code == EARLY_HINTS
+    }
+}
+
+impl HttpInfo {
+    /// Links from the `Link` headers of every `103 Early Hints` response, in
+    /// order.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{HttpInfo, InformationalResponse};
+    ///
+    /// let http = HttpInfo {
+    ///     url: "https://example.com/".to_string(),
+    ///     informational: vec![InformationalResponse {
+    ///         status_code: 103,
+    ///         headers: vec![("link".to_string(), "</app.css>; rel=preload; as=style".to_string())],
+    ///     }],
+    ///     ..HttpInfo::default()
+    /// };
+    /// let hints = http.early_hints();
+    /// assert_eq!(hints[0].url, "https://example.com/app.css");
+    /// assert_eq!(hints[0].as_type.as_deref(), Some("style"));
+    /// ```
+    pub fn early_hints(&self) -> Vec<HeaderLink> {
+        let base = Url::parse(&self.url).ok();
+        self.informational
+            .iter()
+            .filter(|response| response.is_early_hints())
+            .flat_map(|response| &response.headers)
+            .filter(|(name, _)| name.eq_ignore_ascii_case("link"))
+            .flat_map(|(_, value)| HeaderLink::parse(value, base.as_ref()))
+            .collect()
+    }
+
+    /// Early-hinted links the final response's `Link` header does not repeat.
+    ///
+    /// Servers are expected to send the hints again with the final response;
+    /// a non-empty result points at hints the page no longer declares.
+    pub fn early_hints_only(&self) -> Vec<HeaderLink> {
+        let final_links = self.link_header();
+        self.early_hints()
+            .into_iter()
+            .filter(|hint| !final_links.iter().any(|link| link.url == hint.url))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status_code: u16, link: &str) -> InformationalResponse {
+        InformationalResponse {
+            status_code,
+            headers: vec![("Link".to_string(), link.to_string())],
+        }
+    }
+
+    #[test]
+    fn test_early_hints_skip_other_interim_responses() {
+        let http = HttpInfo {
+            url: "https://example.com/page".to_string(),
+            headers: vec![(
+                "link".to_string(),
+                "</app.css>; rel=preload; as=style".to_string(),
+            )],
+            informational: vec![
+                response(100, "</ignored.js>; rel=preload; as=script"),
+                response(103, "</app.css>; rel=preload; as=style"),
+                response(103, "</font.woff2>; rel=preload; as=font; type=font/woff2"),
+            ],
+            ..HttpInfo::default()
+        };
+
+        let hints: Vec<_> = http.early_hints().into_iter().map(|l| l.url).collect();
+        assert_eq!(
+            hints,
+            [
+                "https://example.com/app.css",
+                "https://example.com/font.woff2"
+            ]
+        );
+
+        let only = http.early_hints_only();
+        assert_eq!(only.len(), 1);
+        assert_eq!(only[0].media_type.as_deref(), Some("font/woff2"));
+    }
+}
@@ -58,37 +58,289 @@
 //! }
 //! ```
 //!
+//! ## Serialization
+//!
+//! With the `serde` feature (enabled by default and by `http`), all result types
+//! implement `Serialize`/`Deserialize` with a stable representation intended for
+//! long-term storage:
+//!
+//! - [`WebpageInfo::schema_version`] records the representation version
+//!   ([`SCHEMA_VERSION`]); data written before versioning deserializes as version 1.
+//! - Every field has a default, so snapshots written by older releases (missing
+//!   newer fields) still deserialize.
+//! - Unknown fields are ignored, so data written by newer releases can be read by
+//!   older ones.
+//! - Adding fields does not change the version. Renaming, removing, or changing the
+//!   type of a field bumps [`SCHEMA_VERSION`], and deserialization keeps accepting
+//!   older versions.
+//!
+//! Version history:
+//!
+//! - **2**: `html.meta` maps each name to an array of contents, preserving
+//!   repeated meta tags (version 1 stored a single string).
+//! - **1**: initial representation.
+//!
+//! With the `json-schema` feature, result types also implement
+//! `schemars::JsonSchema`, and [`WebpageInfo::json_schema`] returns the schema
+//! for publishing in OpenAPI documents.
+//!
 //! ## Without HTTP (parsing only)
 //!
 //! If you don't need HTTP fetching, disable the default `http` feature:
 //!
 //! ```toml
 //! [dependencies]
-//! webpage-info = { version = "1.0", default-features = false }
+//! webpage-info = { version = "1.0", default-features = false, features = ["dom", "extractors"] }
+//! ```
+//!
+//! `extractors` groups the `opengraph`, `schema-org`, `text`, and `links`
+//! features; enable only the ones you need for a smaller build.
+//!
+//! ## Lightweight backend
+//!
+//! The default `dom` feature parses with `scraper` (html5ever). If you only need
+//! the title, meta tags, and OpenGraph data, the `lightweight` feature provides
+//! [`HtmlInfo::from_string_lightweight`] on the much smaller `tl` parser; disable
+//! `dom` to drop html5ever entirely:
+//!
+//! ```toml
+//! [dependencies]
+//! webpage-info = { version = "1.0", default-features = false, features = ["http", "lightweight", "opengraph"] }
 //! ```
 
+mod a11y;
+#[cfg(any(feature = "http", feature = "dom"))]
+mod base64;
+mod charset;
+#[cfg(feature = "schema-org")]
+mod claim_review;
+mod consent;
+mod consistency;
+#[cfg(feature = "dom")]
+pub mod content;
+#[cfg(feature = "dom")]
+mod decompress;
 mod error;
+mod geo;
 mod html;
+mod icons;
+mod images;
+#[cfg(feature = "dom")]
+mod license;
+#[cfg(feature = "lightweight")]
+mod lightweight;
+mod link_safety;
+mod meta;
+#[cfg(feature = "dom")]
+mod mhtml;
+#[cfg(all(feature = "dom", feature = "schema-org"))]
+mod microdata;
+mod oembed;
 mod opengraph;
+mod pagination;
+mod prices;
+mod rating;
+#[cfg(all(feature = "dom", feature = "schema-org"))]
+mod rdfa;
+mod resources;
+#[cfg(feature = "schema-org")]
 mod schema_org;
+mod scholarly;
+mod security_policy;
+mod spa;
+#[cfg(all(feature = "dom", feature = "schema-org"))]
+mod speakable;
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+mod text;
+pub mod url_util;
+mod viewport;
+mod words;
 
+#[cfg(feature = "http")]
+mod analysis;
+#[cfg(feature = "http")]
+mod builder;
+#[cfg(feature = "http")]
+mod cache;
+#[cfg(feature = "http")]
+mod cdn;
+#[cfg(feature = "http")]
+mod challenge;
+#[cfg(feature = "http")]
+mod client;
+#[cfg(feature = "http")]
+mod compress;
+#[cfg(feature = "http")]
+mod cookies;
+#[cfg(feature = "http")]
+mod error_page;
+#[cfg(feature = "http")]
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 #[cfg(feature = "http")]
 mod http;
+#[cfg(feature = "http")]
+mod http_date;
+#[cfg(feature = "http")]
+mod informational;
+#[cfg(feature = "http")]
+mod link_check;
+#[cfg(feature = "http")]
+mod link_header;
+#[cfg(feature = "http")]
+mod locales;
+#[cfg(feature = "log")]
+mod logging;
+#[cfg(feature = "http")]
+mod login;
+#[cfg(feature = "http")]
+mod observer;
+#[cfg(feature = "http")]
+mod policy;
+#[cfg(feature = "http")]
+mod prerender;
+#[cfg(feature = "http")]
+mod refetch;
+#[cfg(feature = "render")]
+mod render;
+#[cfg(feature = "http")]
+mod resolve;
+#[cfg(feature = "http")]
+mod retry;
+#[cfg(feature = "http")]
+mod scheduler;
+#[cfg(feature = "http")]
+pub mod sink;
+#[cfg(feature = "http")]
+mod snapshot;
+#[cfg(feature = "http")]
+mod soft404;
+#[cfg(feature = "http")]
+mod stats;
+#[cfg(feature = "streaming")]
+mod streaming;
+#[cfg(feature = "http")]
+mod transport;
+#[cfg(feature = "http")]
+mod user_agent;
+#[cfg(feature = "warc")]
+pub mod warc;
 
-pub use error::{Error, Result};
-pub use html::{HtmlInfo, Link};
+pub use a11y::A11yStats;
+pub use charset::{CharsetInfo, CharsetSource};
+#[cfg(feature = "schema-org")]
+pub use claim_review::{ClaimRating, ClaimReview, ReviewedClaim};
+pub use consent::{ConsentInfo, ConsentPlatform};
+pub use consistency::{ConsistencyField, ConsistencyIssue, ConsistencyReport};
+pub use error::{Error, FetchPhase, Result};
+pub use geo::{Geo, GeoPoint, GeoShape};
+pub use html::{HtmlInfo, Link, ParseOptions, TextBlock, TextBlockKind, TextLayout};
+pub use icons::{Icon, IconKind};
+pub use images::ImageCandidate;
+pub use link_safety::{LinkSafetyList, LinkVerdict};
+pub use meta::MetaMap;
+#[cfg(feature = "dom")]
+pub use mhtml::{MhtmlArchive, MhtmlPart};
 pub use opengraph::{Opengraph, OpengraphMedia};
+pub use pagination::{Pagination, PaginationSource};
+pub use prices::{Price, PriceSource};
+pub use rating::ContentRating;
+pub use resources::{Resource, ResourceKind, SriStatus};
+#[cfg(feature = "schema-org")]
 pub use schema_org::SchemaOrg;
+pub use scholarly::Citation;
+#[cfg(feature = "schema-org")]
+pub use scholarly::{Course, DataDownload, Dataset, ScholarlyArticle};
+pub use security_policy::{ContentSecurityPolicy, ReferrerPolicy};
+pub use spa::{Framework, SpaSignals};
+pub use viewport::{Viewport, ViewportLength};
 
 #[cfg(feature = "http")]
-pub use http::{HttpInfo, HttpOptions};
+pub use analysis::{BatchAnalysis, DuplicateGroup};
+#[cfg(feature = "http")]
+pub use builder::FetchBuilder;
+#[cfg(feature = "http")]
+pub use cache::{Cache, CachePolicy, MemoryCache};
+#[cfg(feature = "http")]
+pub use cdn::{CacheStatus, CdnInfo, CdnProvider};
+#[cfg(feature = "http")]
+pub use challenge::{ChallengeProvider, FetchVerdict};
+#[cfg(feature = "http")]
+pub use client::WebpageClient;
+#[cfg(feature = "http")]
+pub use cookies::Cookie;
+#[cfg(feature = "http")]
+pub use error_page::ErrorPage;
+#[cfg(feature = "http")]
+pub use http::{
+    HeaderProfile, HttpInfo, HttpOptions, Overrides, ProxyAuth, RawResponse, RedirectHop,
+};
+#[cfg(feature = "http")]
+pub use informational::InformationalResponse;
+#[cfg(feature = "http")]
+pub use link_check::{LinkCheck, LinkCheckOptions, LinkStatus};
+#[cfg(feature = "http")]
+pub use link_header::HeaderLink;
+#[cfg(feature = "http")]
+pub use locales::{LocaleComparison, LocaleDifference, LocaleField, LocaleVariant};
+#[cfg(feature = "http")]
+pub use observer::FetchObserver;
+#[cfg(feature = "http")]
+pub use oembed::{Oembed, OembedType};
+#[cfg(feature = "http")]
+pub use policy::{RequestDecision, RequestPolicy, ResponseDecision};
+#[cfg(feature = "http")]
+pub use prerender::PrerenderFetcher;
+#[cfg(feature = "http")]
+pub use refetch::Refetch;
+#[cfg(feature = "render")]
+pub use render::RenderedFetcher;
+#[cfg(feature = "http")]
+pub use resolve::{ResolvedUrl, resolve_final_url};
+#[cfg(feature = "http")]
+pub use retry::RetryPolicy;
+#[cfg(feature = "http")]
+pub use sink::ResultSink;
+#[cfg(feature = "http")]
+pub use snapshot::Snapshot;
+#[cfg(feature = "http")]
+pub use stats::{ClientStats, HostStats};
+#[cfg(feature = "streaming")]
+pub use streaming::StreamingParser;
+#[cfg(feature = "http")]
+pub use transport::{Transport, TransportFuture};
+#[cfg(feature = "http")]
+pub use user_agent::{RotationPolicy, UserAgent};
+
+#[cfg(all(feature = "http", not(any(feature = "dom", feature = "lightweight"))))]
+compile_error!("the `http` feature needs an HTML backend: enable `dom` or `lightweight`");
 
+#[cfg(feature = "http")]
 use serde::{Deserialize, Serialize};
 
+/// Version of the serialized [`WebpageInfo`] representation.
+///
+/// See the [serialization](crate#serialization) section of the crate docs for the
+/// compatibility policy.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Schema version assumed for data serialized before versioning was introduced.
+#[cfg(feature = "http")]
+fn legacy_schema_version() -> u32 {
+    1
+}
+
 /// Complete webpage information including HTTP and HTML data.
 #[cfg(feature = "http")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
 pub struct WebpageInfo {
+    /// Version of the serialized representation ([`SCHEMA_VERSION`] when created)
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+
     /// HTTP transfer information
     pub http: HttpInfo,
 
@@ -96,10 +348,25 @@ pub struct WebpageInfo {
     pub html: HtmlInfo,
 }
 
+#[cfg(feature = "http")]
+impl Default for WebpageInfo {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            http: HttpInfo::default(),
+            html: HtmlInfo::default(),
+        }
+    }
+}
+
 #[cfg(feature = "http")]
 impl WebpageInfo {
     /// Fetch a webpage from a URL with default options.
     ///
+    /// Each call builds a new HTTP client. To fetch many pages, construct a
+    /// [`WebpageClient`] once and reuse it, so connections and TLS sessions are
+    /// pooled across requests.
+    ///
     /// # Example
     ///
     /// ```rust,no_run
@@ -118,6 +385,8 @@ impl WebpageInfo {
 
     /// Fetch a webpage from a URL with custom HTTP options.
     ///
+    /// Like [`fetch`](Self::fetch), this builds a new HTTP client per call.
+    ///
     /// # Example
     ///
     /// ```rust,no_run
@@ -136,21 +405,89 @@ impl WebpageInfo {
     /// }
     /// ```
     pub async fn fetch_with_options(url: &str, options: HttpOptions) -> Result<Self> {
-        let http_info = http::fetch(url, &options).await?;
-
-        // Validate content type is HTML-ish
-        if let Some(ref ct) = http_info.content_type
-            && !ct.contains("html")
-            && !ct.contains("xml")
-        {
-            return Err(Error::InvalidContentType(ct.clone()));
-        }
+        WebpageClient::new(options)?.fetch(url).await
+    }
 
-        let html = HtmlInfo::from_string(&http_info.body, Some(&http_info.url))?;
+    /// JSON Schema describing the serialized representation.
+    ///
+    /// Other result types implement [`schemars::JsonSchema`] too, so
+    /// `schemars::schema_for!(HtmlInfo)` works the same way.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use webpage_info::WebpageInfo;
+    ///
+    /// let schema = WebpageInfo::json_schema();
+    /// assert_eq!(schema.get("title").unwrap(), "WebpageInfo");
+    /// ```
+    #[cfg(feature = "json-schema")]
+    pub fn json_schema() -> schemars::Schema {
+        schemars::schema_for!(WebpageInfo)
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_empty_object() {
+        let info: WebpageInfo = serde_json::from_str("{}").unwrap();
+        assert_eq!(info.schema_version, 1);
+        assert!(info.html.title.is_none());
+        assert_eq!(info.http.status_code, 0);
+    }
+
+    #[test]
+    fn test_deserialize_tolerates_missing_and_unknown_fields() {
+        // Shape written by 1.0 (no schema_version, no warnings) plus a field from the future
+        let json = r#"{
+            "http": {"url": "https://example.com/", "status_code": 200},
+            "html": {
+                "title": "Old",
+                "meta": {"description": "Old page"},
+                "links": [{"url": "/a"}],
+                "added_later": true
+            }
+        }"#;
+        let info: WebpageInfo = serde_json::from_str(json).unwrap();
+
+        assert_eq!(info.schema_version, 1);
+        assert_eq!(info.http.status_code, 200);
+        assert_eq!(info.html.title, Some("Old".to_string()));
+        assert_eq!(info.html.meta("description"), Some("Old page"));
+        assert_eq!(&*info.html.links[0].url, "/a");
+        assert!(info.html.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let mut info = WebpageInfo::default();
+        info.html.title = Some("Round trip".to_string());
+        info.html.meta.append("article:tag", "a");
+        info.html.meta.append("article:tag", "b");
+
+        let json = serde_json::to_string(&info).unwrap();
+        assert!(json.contains(&format!("\"schema_version\":{}", SCHEMA_VERSION)));
+
+        let back: WebpageInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.html.title, info.html.title);
+        assert_eq!(back.html.meta_all("article:tag"), ["a", "b"]);
+    }
 
-        Ok(Self {
-            http: http_info,
-            html,
-        })
+    #[cfg(feature = "json-schema")]
+    #[test]
+    fn test_json_schema_matches_serialized_shape() {
+        let schema = serde_json::to_value(WebpageInfo::json_schema()).unwrap();
+        let defs = &schema["$defs"];
+        assert!(schema["properties"]["schema_version"].is_object());
+        assert!(defs["HtmlInfo"]["properties"]["opengraph"].is_object());
+        assert_eq!(defs["MetaMap"]["type"], "object");
+        assert_eq!(
+            defs["MetaMap"]["additionalProperties"]["items"]["type"],
+            "string"
+        );
+        assert!(schema["required"].is_null());
     }
 }
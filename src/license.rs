@@ -0,0 +1,124 @@
+//! License and copyright extraction
+//!
+//! Content-reuse and archiving tools need to know the usage rights a page
+//! declares. [`HtmlInfo::license`] comes from `rel="license"` links or the
+//! Schema.org `license` property; [`HtmlInfo::copyright`] from copyright meta
+//! tags or the Schema.org `copyrightNotice` property.
+
+use std::sync::OnceLock;
+
+use scraper::{Html, Selector};
+
+use crate::html::HtmlInfo;
+
+/// Meta tag names carrying a copyright statement, compared case-insensitively.
+const COPYRIGHT_META: &[&str] = &["copyright", "dcterms.rights", "dc.rights", "rights"];
+
+fn license_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse(r#"link[rel~="license"], a[rel~="license"]"#).unwrap())
+}
+
+impl HtmlInfo {
+    /// Fill `license` and `copyright`; runs after the meta and Schema.org phases.
+    pub(crate) fn extract_license(&mut self, document: &Html) {
+        self.license = document
+            .select(license_selector())
+            .filter_map(|el| el.value().attr("href"))
+            .map(str::trim)
+            .find(|href| !href.is_empty())
+            .map(str::to_string)
+            .or_else(|| self.schema_license());
+
+        self.copyright = self
+            .meta
+            .iter()
+            .find(|(name, content)| {
+                !content.is_empty()
+                    && COPYRIGHT_META
+                        .iter()
+                        .any(|meta| name.eq_ignore_ascii_case(meta))
+            })
+            .map(|(_, content)| content.to_string())
+            .or_else(|| self.schema_copyright());
+    }
+
+    /// The Schema.org `license`: a URL, or a `CreativeWork` with a `url` or `@id`.
+    #[cfg(feature = "schema-org")]
+    fn schema_license(&self) -> Option<String> {
+        self.schema_org.iter().find_map(|item| {
+            let license = item.value.get("license")?;
+            let license = license
+                .as_array()
+                .and_then(|a| a.first())
+                .unwrap_or(license);
+            license
+                .as_str()
+                .or_else(|| license.get("url").and_then(|url| url.as_str()))
+                .or_else(|| license.get("@id").and_then(|id| id.as_str()))
+                .map(str::trim)
+                .filter(|license| !license.is_empty())
+                .map(str::to_string)
+        })
+    }
+
+    #[cfg(not(feature = "schema-org"))]
+    fn schema_license(&self) -> Option<String> {
+        None
+    }
+
+    #[cfg(feature = "schema-org")]
+    fn schema_copyright(&self) -> Option<String> {
+        self.schema_org
+            .iter()
+            .find_map(|item| item.get_str("copyrightNotice"))
+            .map(str::trim)
+            .filter(|notice| !notice.is_empty())
+            .map(str::to_string)
+    }
+
+    #[cfg(not(feature = "schema-org"))]
+    fn schema_copyright(&self) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rel_license_and_copyright_meta() {
+        let html = r#"<head>
+            <link rel="license" href=" https://creativecommons.org/licenses/by/4.0/ ">
+            <meta name="Copyright" content="© 2026 Example Media">
+            </head>
+            <body><a rel="license noopener" href="/other">CC</a></body>"#;
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        assert_eq!(
+            info.license.as_deref(),
+            Some("https://creativecommons.org/licenses/by/4.0/")
+        );
+        assert_eq!(info.copyright.as_deref(), Some("© 2026 Example Media"));
+
+        let info =
+            HtmlInfo::from_string(r#"<a rel="license" href="/terms">Terms</a>"#, None).unwrap();
+        assert_eq!(info.license.as_deref(), Some("/terms"));
+        assert!(info.copyright.is_none());
+    }
+
+    #[cfg(feature = "schema-org")]
+    #[test]
+    fn test_schema_org_fallback() {
+        let html = r#"<script type="application/ld+json">{
+            "@type": "Article",
+            "license": {"@type": "CreativeWork", "url": "https://example.com/license"},
+            "copyrightNotice": "All rights reserved"
+        }</script>"#;
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        assert_eq!(info.license.as_deref(), Some("https://example.com/license"));
+        assert_eq!(info.copyright.as_deref(), Some("All rights reserved"));
+    }
+}
@@ -0,0 +1,187 @@
+//! Lightweight extraction backend
+//!
+//! [`HtmlInfo::from_string_lightweight`] uses the [`tl`] tokenizer to extract
+//! the title, language, canonical, feed, and oEmbed URLs, icons, meta tags, and
+//! OpenGraph data.
+//! Paired with `default-features = false`, it drops the html5ever dependency tree
+//! for faster builds and smaller binaries.
+//!
+//! `tl` is not a spec-compliant HTML parser: it does not repair malformed markup,
+//! and character references are only decoded when terminated by `;`. Links,
+//! JSON-LD, and body text are not extracted.
+
+use crate::error::{Error, Result};
+use crate::html::{self, HtmlInfo};
+use crate::text::decode_entities;
+
+impl HtmlInfo {
+    /// Extract head metadata with the lightweight `tl` backend.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<head><title>Tom &amp; Jerry</title>
+    ///     <meta property="og:title" content="Cartoon"></head>"#;
+    /// let info = HtmlInfo::from_string_lightweight(html).unwrap();
+    /// assert_eq!(info.title.as_deref(), Some("Tom & Jerry"));
+    /// assert_eq!(info.opengraph.title.as_deref(), Some("Cartoon"));
+    /// ```
+    pub fn from_string_lightweight(html: &str) -> Result<Self> {
+        let dom = tl::parse(html, tl::ParserOptions::default()).map_err(|_| Error::ParseError)?;
+        let parser = dom.parser();
+        let mut info = Self::default();
+
+        for tag in dom.nodes().iter().filter_map(|node| node.as_tag()) {
+            let attrs: Vec<(String, String)> = tag
+                .attributes()
+                .iter()
+                .map(|(name, value)| {
+                    let value = value.map(|v| decode_entities(&v).into_owned());
+                    (name.to_ascii_lowercase(), value.unwrap_or_default())
+                })
+                .collect();
+            let attr = |name: &str| {
+                attrs
+                    .iter()
+                    .find(|(attr, _)| attr == name)
+                    .map(|(_, value)| value.as_str())
+            };
+
+            match tag.name().as_utf8_str().to_ascii_lowercase().as_str() {
+                "html" if info.language.is_none() => {
+                    info.language = attr("lang")
+                        .map(|lang| lang.trim().to_string())
+                        .filter(|lang| !lang.is_empty());
+                }
+                "title" if info.title.is_none() => {
+                    let title = decode_entities(&tag.inner_text(parser)).trim().to_string();
+                    info.title = Some(title).filter(|title| !title.is_empty());
+                }
+                "meta" => info.add_meta_tag(attr),
+                "link" => {
+                    info.add_icon_link(None, attr);
+                    info.add_oembed_link(None, attr);
+                    let has_rel = |value: &str| {
+                        attr("rel").is_some_and(|rel| {
+                            rel.split_ascii_whitespace()
+                                .any(|token| token.eq_ignore_ascii_case(value))
+                        })
+                    };
+                    let href = || {
+                        attr("href")
+                            .map(|href| href.trim().to_string())
+                            .filter(|href| !href.is_empty())
+                    };
+                    if has_rel("canonical") && info.canonical_url.is_none() {
+                        info.canonical_url = href();
+                    }
+                    if has_rel("alternate")
+                        && info.feed_url.is_none()
+                        && attr("type").is_some_and(html::is_feed_type)
+                    {
+                        info.feed_url = attr("href").map(|href| href.trim().to_string());
+                    }
+                    if has_rel("next") && info.next_url.is_none() {
+                        info.next_url = href();
+                    }
+                    if (has_rel("prev") || has_rel("previous")) && info.prev_url.is_none() {
+                        info.prev_url = href();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        info.add_fallback_icon(None);
+        info.normalize_strings();
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_head_metadata() {
+        let html = r#"<!DOCTYPE html>
+            <HTML lang="en">
+            <head>
+                <title> Sample </title>
+                <META name="description" content="A &quot;quoted&quot; page">
+                <meta property="og:title" content="OG Title">
+                <meta charset="utf-8">
+                <link rel="canonical" href="https://example.com/page">
+                <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+                <link rel="next" href="/page/2">
+                <link rel="Shortcut Icon" href="/favicon.png" type="image/png">
+                <link rel="alternate" type="application/json+oembed" href="/oembed?format=json">
+            </head>
+            </HTML>"#;
+
+        let info = HtmlInfo::from_string_lightweight(html).unwrap();
+        assert_eq!(info.title.as_deref(), Some("Sample"));
+        assert_eq!(info.language.as_deref(), Some("en"));
+        assert_eq!(info.description.as_deref(), Some(r#"A "quoted" page"#));
+        #[cfg(feature = "opengraph")]
+        assert_eq!(info.opengraph.title.as_deref(), Some("OG Title"));
+        assert_eq!(info.meta.get("charset"), Some("utf-8"));
+        assert_eq!(
+            info.canonical_url.as_deref(),
+            Some("https://example.com/page")
+        );
+        assert_eq!(info.feed_url.as_deref(), Some("/feed.xml"));
+        assert_eq!(info.next_url.as_deref(), Some("/page/2"));
+        assert_eq!(info.icons.len(), 1);
+        assert_eq!(info.icons[0].url, "/favicon.png");
+        assert_eq!(info.icons[0].media_type.as_deref(), Some("image/png"));
+        assert_eq!(info.oembed_url.as_deref(), Some("/oembed?format=json"));
+    }
+
+    #[test]
+    fn test_rel_is_a_token_list() {
+        let html = r#"<head>
+            <link rel=" Canonical " href="/canonical">
+            <link rel="alternate nofollow" type="application/atom+xml" href="/atom.xml">
+            <link rel="NEXT prefetch" href="/page/3">
+            <link rel="Previous" href="/page/1">
+            <link rel="canonicalish" href="/wrong">
+        </head>"#;
+
+        let info = HtmlInfo::from_string_lightweight(html).unwrap();
+        assert_eq!(info.canonical_url.as_deref(), Some("/canonical"));
+        assert_eq!(info.feed_url.as_deref(), Some("/atom.xml"));
+        assert_eq!(info.next_url.as_deref(), Some("/page/3"));
+        assert_eq!(info.prev_url.as_deref(), Some("/page/1"));
+    }
+
+    #[test]
+    fn test_unclosed_tags_are_not_repaired() {
+        let html = r#"<html lang=fr><head>
+            <title>Unclosed title
+            <meta name=description content=unquoted>
+            <link rel="canonical" href="/page">
+            <div><p>Stray <b>markup</div>"#;
+
+        let info = HtmlInfo::from_string_lightweight(html).unwrap();
+        assert_eq!(info.language.as_deref(), Some("fr"));
+        assert_eq!(info.description.as_deref(), Some("unquoted"));
+        assert_eq!(info.canonical_url.as_deref(), Some("/page"));
+        // The title runs to the end of the document instead of being closed
+        let title = info.title.unwrap();
+        assert!(title.starts_with("Unclosed title"));
+        assert!(title.ends_with("Stray markup"));
+    }
+
+    #[test]
+    fn test_uncommon_entities_and_stray_brackets() {
+        let html = r#"<head>
+            <title>a < b &amp;&amp; c &gt; d</title>
+            <meta name="description" content="5 &lt; 6 &unknown; &#x41;">
+        </head>"#;
+
+        let info = HtmlInfo::from_string_lightweight(html).unwrap();
+        assert_eq!(info.description.as_deref(), Some("5 < 6 &unknown; A"));
+    }
+}
@@ -0,0 +1,458 @@
+//! Broken link checking
+//!
+//! [`WebpageClient::check_links`] requests every distinct http(s) link of a page
+//! and reports how each one responded. Requests go through the client's
+//! connection pool, scheduler, request policy, observers, statistics, and SSRF
+//! protection.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use futures_util::stream::{self, StreamExt};
+use reqwest::{Method, StatusCode, header};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::client::WebpageClient;
+use crate::error::Error;
+use crate::http::{self, HttpOptions};
+use crate::scheduler::Limits;
+use crate::{Result, WebpageInfo};
+
+const DEFAULT_CONCURRENCY: usize = 4;
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// Configuration for [`WebpageClient::check_links`].
+#[derive(Debug, Clone)]
+pub struct LinkCheckOptions {
+    /// Maximum number of links checked at once.
+    ///
+    /// Checks also wait for a slot under the client's
+    /// [`HttpOptions::max_concurrency`] and
+    /// [`HttpOptions::max_concurrent_per_host`] limits. Default: 4.
+    pub concurrency: usize,
+
+    /// Timeout for each link request.
+    ///
+    /// Default: 10 seconds.
+    pub timeout: Duration,
+
+    /// Check at most this many distinct links. Default: unlimited.
+    pub max_links: Option<usize>,
+
+    /// Retry with `GET` when a server rejects `HEAD` (405 or 501).
+    ///
+    /// Default: true.
+    pub fallback_to_get: bool,
+}
+
+impl Default for LinkCheckOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            max_links: None,
+            fallback_to_get: true,
+        }
+    }
+}
+
+impl LinkCheckOptions {
+    /// Create a new LinkCheckOptions with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of links checked at once.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Set the timeout for each link request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Check at most `max` distinct links.
+    pub fn max_links(mut self, max: usize) -> Self {
+        self.max_links = Some(max);
+        self
+    }
+
+    /// Set whether to retry with `GET` when `HEAD` is rejected.
+    pub fn fallback_to_get(mut self, fallback: bool) -> Self {
+        self.fallback_to_get = fallback;
+        self
+    }
+}
+
+/// How a checked link responded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// A 1xx or 2xx response
+    Ok {
+        /// HTTP status code
+        status: u16,
+    },
+    /// A 3xx response; redirects are reported, not followed
+    Redirect {
+        /// HTTP status code
+        status: u16,
+        /// Resolved `Location`, if the response had one
+        target: Option<String>,
+    },
+    /// A 4xx or 5xx response
+    Broken {
+        /// HTTP status code
+        status: u16,
+    },
+    /// No response within [`LinkCheckOptions::timeout`]
+    Timeout,
+    /// The request could not be made (invalid URL, DNS or connection failure,
+    /// SSRF block, or request policy denial)
+    Failed {
+        /// Description of the failure
+        reason: String,
+    },
+}
+
+/// The result of checking one link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct LinkCheck {
+    /// The URL that was checked, without its fragment
+    pub url: String,
+
+    /// How the URL responded
+    pub status: LinkStatus,
+}
+
+impl LinkCheck {
+    /// Whether the link is broken: an error status, a timeout, or a failed request.
+    pub fn is_broken(&self) -> bool {
+        matches!(
+            self.status,
+            LinkStatus::Broken { .. } | LinkStatus::Timeout | LinkStatus::Failed { .. }
+        )
+    }
+}
+
+impl WebpageClient {
+    /// Check every distinct http(s) link of a page.
+    ///
+    /// Each link is requested with `HEAD` (falling back to `GET` if the server
+    /// rejects it) and redirects are reported rather than followed. Fragments are
+    /// ignored when deduplicating. Results are in link order. Checks share the
+    /// client's concurrency limits with its batch fetches.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use webpage_info::{HttpOptions, LinkCheckOptions, WebpageClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> webpage_info::Result<()> {
+    ///     let client = WebpageClient::new(HttpOptions::new())?;
+    ///     let info = client.fetch("https://example.org").await?;
+    ///     for check in client.check_links(&info, &LinkCheckOptions::new()).await {
+    ///         if check.is_broken() {
+    ///             println!("{}: {:?}", check.url, check.status);
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn check_links(
+        &self,
+        info: &WebpageInfo,
+        options: &LinkCheckOptions,
+    ) -> Vec<LinkCheck> {
+        let mut seen = HashSet::new();
+        let urls: Vec<String> = info
+            .html
+            .links
+            .iter()
+            .filter_map(|link| {
+                let mut url = Url::parse(&link.url).ok()?;
+                url.set_fragment(None);
+                matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+            })
+            .filter(|url| seen.insert(url.clone()))
+            .take(options.max_links.unwrap_or(usize::MAX))
+            .collect();
+        // Credentials are only sent to links on the page's own origin
+        let origin = Url::parse(&info.http.url)
+            .map_or_else(|_| url::Origin::new_opaque(), |url| url.origin());
+
+        let limits = Limits::new(&self.options);
+
+        stream::iter(urls)
+            .map(|url| async {
+                let _permit = self.state.scheduler.acquire(&url, 0, limits).await;
+                let status = self.check_link(&url, &origin, options).await;
+                LinkCheck { url, status }
+            })
+            .buffered(options.concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    async fn check_link(
+        &self,
+        url: &str,
+        origin: &url::Origin,
+        options: &LinkCheckOptions,
+    ) -> LinkStatus {
+        let failed = |error: Error| LinkStatus::Failed {
+            reason: error.to_string(),
+        };
+        let url = match self.state.policies.check_request(url) {
+            Ok(url) => url,
+            Err(error) => return failed(error),
+        };
+        let url = &*url;
+        let parsed = match Url::parse(url) {
+            Ok(parsed) => parsed,
+            Err(error) => return failed(error.into()),
+        };
+
+        if self.options.block_private_ips
+            && let Err(error) = http::validate_request_for_ssrf(url, &self.options).await
+        {
+            return failed(error);
+        }
+        if let Err(error) = self.state.observers.on_request(url) {
+            return failed(error);
+        }
+
+        let mut response = self.request(Method::HEAD, &parsed, origin, options).await;
+        if options.fallback_to_get
+            && let Ok(ref head) = response
+            && matches!(
+                head.status(),
+                StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+            )
+        {
+            response = self.request(Method::GET, &parsed, origin, options).await;
+        }
+
+        match response {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_redirection() {
+                    let target = response
+                        .headers()
+                        .get(header::LOCATION)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|location| response.url().join(location).ok())
+                        .map(String::from);
+                    LinkStatus::Redirect {
+                        status: status.as_u16(),
+                        target,
+                    }
+                } else if status.is_client_error() || status.is_server_error() {
+                    LinkStatus::Broken {
+                        status: status.as_u16(),
+                    }
+                } else {
+                    LinkStatus::Ok {
+                        status: status.as_u16(),
+                    }
+                }
+            }
+            Err(error) if error.is_timeout() => LinkStatus::Timeout,
+            Err(error) => failed(Error::from_reqwest(url, error)),
+        }
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        url: &Url,
+        origin: &url::Origin,
+        options: &LinkCheckOptions,
+    ) -> std::result::Result<reqwest::Response, reqwest::Error> {
+        let _in_flight = self.state.stats.start(url.host_str().unwrap_or_default());
+        self.client
+            .request(method, url.clone())
+            .headers(self.options.headers_for(url, origin))
+            .timeout(options.timeout)
+            .send()
+            .await
+            .inspect_err(|_| self.state.stats.record_failure())
+    }
+}
+
+impl WebpageInfo {
+    /// Check the page's links with a client built from default [`HttpOptions`].
+    ///
+    /// Use [`WebpageClient::check_links`] to reuse an existing client's pool,
+    /// observers, and settings.
+    pub async fn check_links(&self, options: LinkCheckOptions) -> Result<Vec<LinkCheck>> {
+        let client = WebpageClient::new(HttpOptions::default())?;
+        Ok(client.check_links(self, &options).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html::{HtmlInfo, Link};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn page(links: &[String]) -> WebpageInfo {
+        WebpageInfo {
+            html: HtmlInfo {
+                links: links
+                    .iter()
+                    .map(|url| Link {
+                        url: url.as_str().into(),
+                        ..Link::default()
+                    })
+                    .collect(),
+                ..HtmlInfo::default()
+            },
+            ..WebpageInfo::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reports_each_status() {
+        let server = MockServer::start().await;
+        Mock::given(path("/ok"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(path("/moved"))
+            .respond_with(ResponseTemplate::new(301).insert_header("Location", "/ok"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/gone"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/no-head"))
+            .respond_with(ResponseTemplate::new(405))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/no-head"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(path("/slow"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(2)))
+            .mount(&server)
+            .await;
+
+        let base = server.uri();
+        let info = page(&[
+            format!("{base}/ok"),
+            format!("{base}/ok#section"),
+            format!("{base}/moved"),
+            format!("{base}/gone"),
+            format!("{base}/no-head"),
+            format!("{base}/slow"),
+            "mailto:someone@example.com".to_string(),
+        ]);
+        let client = WebpageClient::new(HttpOptions::new().block_private_ips(false)).unwrap();
+        let options = LinkCheckOptions::new().timeout(Duration::from_millis(300));
+        let checks = client.check_links(&info, &options).await;
+
+        let statuses: Vec<_> = checks.iter().map(|c| c.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            [
+                LinkStatus::Ok { status: 200 },
+                LinkStatus::Redirect {
+                    status: 301,
+                    target: Some(format!("{base}/ok")),
+                },
+                LinkStatus::Broken { status: 404 },
+                LinkStatus::Ok { status: 200 },
+                LinkStatus::Timeout,
+            ]
+        );
+        assert_eq!(checks.iter().filter(|c| c.is_broken()).count(), 2);
+        assert_eq!(client.stats().requests_total, 6);
+    }
+
+    #[tokio::test]
+    async fn test_checks_respect_client_limits() {
+        use std::time::Instant;
+
+        let server = MockServer::start().await;
+        Mock::given(path("/slow"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(200)))
+            .mount(&server)
+            .await;
+        let info = page(&[
+            format!("{}/slow?1", server.uri()),
+            format!("{}/slow?2", server.uri()),
+            format!("{}/slow?3", server.uri()),
+        ]);
+
+        let client = WebpageClient::new(
+            HttpOptions::new()
+                .block_private_ips(false)
+                .max_concurrent_per_host(1),
+        )
+        .unwrap();
+        let start = Instant::now();
+        let checks = client.check_links(&info, &LinkCheckOptions::new()).await;
+        assert!(checks.iter().all(|check| !check.is_broken()));
+        assert!(start.elapsed() >= Duration::from_millis(600));
+    }
+
+    #[tokio::test]
+    async fn test_request_policy_applies_to_links() {
+        use crate::RequestDecision;
+
+        let server = MockServer::start().await;
+        Mock::given(path("/private"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        Mock::given(path("/public"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let info = page(&[
+            format!("{}/private", server.uri()),
+            format!("{}/old", server.uri()),
+        ]);
+        let client = WebpageClient::new(HttpOptions::new().block_private_ips(false))
+            .unwrap()
+            .with_request_policy(|url| {
+                if url.ends_with("/private") {
+                    RequestDecision::Deny("off limits".to_string())
+                } else {
+                    RequestDecision::Rewrite(url.replace("/old", "/public"))
+                }
+            });
+        let checks = client.check_links(&info, &LinkCheckOptions::new()).await;
+
+        assert!(
+            matches!(&checks[0].status, LinkStatus::Failed { reason } if reason.contains("off limits"))
+        );
+        assert_eq!(checks[1].status, LinkStatus::Ok { status: 200 });
+        assert_eq!(client.stats().requests_total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_private_links_are_blocked() {
+        let info = page(&["http://127.0.0.1:1/admin".to_string()]);
+        let checks = info.check_links(LinkCheckOptions::new()).await.unwrap();
+        assert!(
+            matches!(&checks[0].status, LinkStatus::Failed { reason } if reason.contains("SSRF"))
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! HTTP `Link` header parsing
+//!
+//! Servers can declare the relations HTML puts in `<link>` tags in a
+//! [`Link` header](https://www.rfc-editor.org/rfc/rfc8288) instead, which is
+//! the only way for non-HTML responses and common for CDNs adding `preload`
+//! hints. [`HttpInfo::link_header`] parses it into [`HeaderLink`]s.
+//!
+//! Fetched pages also merge the header into the HTML-level fields:
+//! `canonical`, feed and oEmbed `alternate`, `next`, and `prev` fill
+//! [`HtmlInfo::canonical_url`], [`HtmlInfo::feed_url`],
+//! [`HtmlInfo::oembed_url`], [`HtmlInfo::next_url`], and
+//! [`HtmlInfo::prev_url`] when the document does not set them. The document wins otherwise, with a warning if the canonical
+//! URLs disagree.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::html::{self, HtmlInfo};
+use crate::http::HttpInfo;
+use crate::oembed;
+
+/// One link of a `Link` header.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct HeaderLink {
+    /// Target URL, resolved against the response URL
+    pub url: String,
+
+    /// Relation types (`rel`), lowercased
+    pub rel: Vec<String>,
+
+    /// Media type hint (`type`)
+    pub media_type: Option<String>,
+
+    /// Language of the target (`hreflang`)
+    pub hreflang: Option<String>,
+
+    /// `title`
+    pub title: Option<String>,
+
+    /// Destination of a `preload` (`as`), e.g. `"style"` or `"font"`
+    pub as_type: Option<String>,
+}
+
+impl HeaderLink {
+    /// Whether the link has the relation type `rel` (case-insensitive).
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.rel.iter().any(|r| r.eq_ignore_ascii_case(rel))
+    }
+
+    /// Parse a `Link` header value, resolving relative URLs against `base`.
+    ///
+    /// Malformed links are skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HeaderLink;
+    ///
+    /// let links = HeaderLink::parse(
+    ///     r#"</style.css>; rel=preload; as=style, <https://example.com/p>; rel="canonical""#,
+    ///     Some(&"https://example.com/a/".parse().unwrap()),
+    /// );
+    /// assert_eq!(links[0].url, "https://example.com/style.css");
+    /// assert_eq!(links[0].as_type.as_deref(), Some("style"));
+    /// assert!(links[1].has_rel("canonical"));
+    /// ```
+    pub fn parse(value: &str, base: Option<&Url>) -> Vec<Self> {
+        let mut links = Vec::new();
+        let mut rest = value;
+        loop {
+            rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+            if rest.is_empty() {
+                break;
+            }
+            match parse_link(rest, base) {
+                Some((link, remaining)) => {
+                    links.extend(link);
+                    rest = remaining;
+                }
+                // Skip to the next link
+                None => match rest.find(',') {
+                    Some(comma) => rest = &rest[comma + 1..],
+                    None => break,
+                },
+            }
+        }
+        links
+    }
+}
+
+impl HttpInfo {
+    /// Links from every `Link` response header, in order.
+    pub fn link_header(&self) -> Vec<HeaderLink> {
+        let base = Url::parse(&self.url).ok();
+        self.headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("link"))
+            .flat_map(|(_, value)| HeaderLink::parse(value, base.as_ref()))
+            .collect()
+    }
+}
+
+/// Fill unset HTML fields from the `Link` header, and warn if the header's
+/// canonical URL disagrees with the document's.
+pub(crate) fn merge(http: &HttpInfo, html: &mut HtmlInfo) {
+    let links = http.link_header();
+    let find = |rel: &str| links.iter().find(|link| link.has_rel(rel));
+
+    if let Some(header) = find("canonical") {
+        match &html.canonical_url {
+            None => html.canonical_url = Some(header.url.clone()),
+            Some(document) => {
+                let resolved = Url::parse(&http.url)
+                    .and_then(|base| base.join(document))
+                    .map(String::from)
+                    .unwrap_or_else(|_| document.clone());
+                if resolved != header.url {
+                    html.warnings.push(format!(
+                        "canonical conflict: Link header says {}, <link> says {document}; using {document} from <link>",
+                        header.url
+                    ));
+                }
+            }
+        }
+    }
+    if html.feed_url.is_none() {
+        html.feed_url = links
+            .iter()
+            .find(|link| {
+                link.has_rel("alternate")
+                    && link.media_type.as_deref().is_some_and(html::is_feed_type)
+            })
+            .map(|link| link.url.clone());
+    }
+    if html.oembed_url.is_none() {
+        html.oembed_url = links
+            .iter()
+            .find(|link| {
+                link.has_rel("alternate")
+                    && link
+                        .media_type
+                        .as_deref()
+                        .is_some_and(oembed::is_oembed_type)
+            })
+            .map(|link| link.url.clone());
+    }
+    if html.next_url.is_none() {
+        html.next_url = find("next").map(|link| link.url.clone());
+    }
+    if html.prev_url.is_none() {
+        html.prev_url = find("prev")
+            .or_else(|| find("previous"))
+            .map(|link| link.url.clone());
+    }
+}
+
+/// Parse one `<url>; param=value; …` link, returning it (if it has a usable
+/// URL) and the input after it; `None` if it is malformed.
+fn parse_link<'a>(input: &'a str, base: Option<&Url>) -> Option<(Option<HeaderLink>, &'a str)> {
+    let input = input.strip_prefix('<')?;
+    let end = input.find('>')?;
+    let target = input[..end].trim();
+    let mut link = HeaderLink {
+        url: match base.map(|base| base.join(target)) {
+            Some(Ok(url)) => url.to_string(),
+            _ => target.to_string(),
+        },
+        ..HeaderLink::default()
+    };
+    let mut seen_rel = false;
+
+    let mut rest = &input[end + 1..];
+    loop {
+        rest = rest.trim_start();
+        let Some(params) = rest.strip_prefix(';') else {
+            break;
+        };
+        let params = params.trim_start();
+        let name_end = params.find(['=', ';', ',']).unwrap_or(params.len());
+        let name = params[..name_end].trim().to_ascii_lowercase();
+        rest = &params[name_end..];
+
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            if let Some(quoted) = after.strip_prefix('"') {
+                let (unquoted, len) = parse_quoted(quoted)?;
+                value = unquoted;
+                rest = &quoted[len..];
+            } else {
+                let token_end = after.find([';', ',']).unwrap_or(after.len());
+                value = after[..token_end].trim().to_string();
+                rest = &after[token_end..];
+            }
+        }
+
+        let value = Some(value).filter(|value| !value.is_empty());
+        match name.as_str() {
+            // Only the first `rel` counts (RFC 8288, section 3.3)
+            "rel" if !seen_rel => {
+                seen_rel = true;
+                link.rel = value
+                    .iter()
+                    .flat_map(|rels| rels.split_ascii_whitespace())
+                    .map(str::to_ascii_lowercase)
+                    .collect();
+            }
+            "type" => link.media_type = value,
+            "hreflang" => link.hreflang = value,
+            "title" => link.title = value,
+            "as" => link.as_type = value,
+            _ => {}
+        }
+    }
+
+    let rest = rest.trim_start();
+    if !rest.is_empty() && !rest.starts_with(',') {
+        return None;
+    }
+    Some(((!target.is_empty()).then_some(link), rest))
+}
+
+/// The contents of a quoted string (after the opening quote) and the number
+/// of bytes it spans, including the closing quote.
+fn parse_quoted(input: &str) -> Option<(String, usize)> {
+    let mut value = String::new();
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, i + 1)),
+            '\\' => value.push(chars.next()?.1),
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_parameters() {
+        let links = HeaderLink::parse(
+            r#"<https://example.com/feed>; rel="alternate"; type="application/rss+xml"; title="News \"Feed\", daily", <https://example.com/de>; rel=alternate; hreflang=de; REL=ignored"#,
+            None,
+        );
+        assert_eq!(
+            links,
+            [
+                HeaderLink {
+                    url: "https://example.com/feed".to_string(),
+                    rel: vec!["alternate".to_string()],
+                    media_type: Some("application/rss+xml".to_string()),
+                    title: Some("News \"Feed\", daily".to_string()),
+                    ..HeaderLink::default()
+                },
+                HeaderLink {
+                    url: "https://example.com/de".to_string(),
+                    rel: vec!["alternate".to_string()],
+                    hreflang: Some("de".to_string()),
+                    ..HeaderLink::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_links_are_skipped() {
+        let base = Url::parse("https://example.com/blog/").unwrap();
+        let links = HeaderLink::parse(
+            r#"no-brackets; rel=next, <page/2>; rel="next prefetch", <>; rel=prev, <unterminated; rel=x"#,
+            Some(&base),
+        );
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://example.com/blog/page/2");
+        assert!(links[0].has_rel("next") && links[0].has_rel("PREFETCH"));
+    }
+
+    #[test]
+    fn test_merge_precedence() {
+        let http = HttpInfo {
+            url: "https://example.com/post".to_string(),
+            headers: vec![
+                (
+                    "Link".to_string(),
+                    "</canonical>; rel=canonical, </feed.xml>; rel=alternate; type=application/atom+xml"
+                        .to_string(),
+                ),
+                ("link".to_string(), "</post?page=2>; rel=next".to_string()),
+                (
+                    "Link".to_string(),
+                    "</oembed?format=json>; rel=alternate; type=\"application/json+oembed\""
+                        .to_string(),
+                ),
+            ],
+            ..HttpInfo::default()
+        };
+
+        let mut html = HtmlInfo::default();
+        merge(&http, &mut html);
+        assert_eq!(
+            html.canonical_url.as_deref(),
+            Some("https://example.com/canonical")
+        );
+        assert_eq!(
+            html.feed_url.as_deref(),
+            Some("https://example.com/feed.xml")
+        );
+        assert_eq!(
+            html.next_url.as_deref(),
+            Some("https://example.com/post?page=2")
+        );
+        assert_eq!(
+            html.oembed_url.as_deref(),
+            Some("https://example.com/oembed?format=json")
+        );
+        assert!(html.warnings.is_empty());
+
+        let mut html = HtmlInfo {
+            canonical_url: Some("/canonical".to_string()),
+            ..HtmlInfo::default()
+        };
+        merge(&http, &mut html);
+        assert!(html.warnings.is_empty());
+
+        let mut html = HtmlInfo {
+            canonical_url: Some("https://example.com/other".to_string()),
+            ..HtmlInfo::default()
+        };
+        merge(&http, &mut html);
+        assert_eq!(
+            html.canonical_url.as_deref(),
+            Some("https://example.com/other")
+        );
+        assert_eq!(
+            html.warnings,
+            [
+                "canonical conflict: Link header says https://example.com/canonical, <link> says https://example.com/other; using https://example.com/other from <link>"
+            ]
+        );
+    }
+}
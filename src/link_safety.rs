@@ -0,0 +1,320 @@
+//! Outbound link safety verdicts
+//!
+//! Comment moderation and forum previews need to know whether a page links
+//! somewhere known to be bad. A [`LinkSafetyList`] holds blocked and allowed
+//! domains or URL patterns, for example loaded from a Safe Browsing export,
+//! and [`HtmlInfo::annotate_links`] (or [`ParseOptions::link_safety`] while
+//! parsing) sets [`Link::verdict`] on every external link.
+//!
+//! [`ParseOptions::link_safety`]: crate::ParseOptions::link_safety
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::html::{HtmlInfo, Link};
+
+/// The safety verdict of an external link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LinkVerdict {
+    /// Matches the allowlist and not the blocklist
+    Allowed,
+    /// Matches the blocklist
+    Blocked,
+    /// Matches neither list
+    Unlisted,
+}
+
+/// A blocked or allowed entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pattern {
+    /// A host and its subdomains
+    Domain(String),
+    /// A prefix of `host/path?query`, with `*` matching any characters
+    Url(String),
+}
+
+impl Pattern {
+    /// Parse a domain (`example.com`, `*.example.com`) or a URL pattern
+    /// (`example.com/ads/`, `https://*.example.net/*/track`).
+    fn parse(pattern: &str) -> Option<Self> {
+        let pattern = pattern.trim();
+        let pattern = ["https://", "http://"]
+            .iter()
+            .find_map(|scheme| {
+                pattern
+                    .get(..scheme.len())
+                    .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+                    .map(|_| &pattern[scheme.len()..])
+            })
+            .unwrap_or(pattern);
+        let (host, path) = pattern.split_at(pattern.find('/').unwrap_or(pattern.len()));
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        if host.is_empty() {
+            return None;
+        }
+        if path.is_empty() {
+            let domain = host.trim_start_matches("*.");
+            if !domain.contains('*') {
+                return Some(Self::Domain(domain.to_string()));
+            }
+        }
+        Some(Self::Url(format!("{host}{path}")))
+    }
+
+    fn matches(&self, host: &str, subject: &str) -> bool {
+        match self {
+            Self::Domain(domain) => {
+                host == domain
+                    || host
+                        .strip_suffix(domain.as_str())
+                        .is_some_and(|sub| sub.ends_with('.'))
+            }
+            Self::Url(pattern) => glob_prefix(pattern, subject),
+        }
+    }
+}
+
+/// Blocked and allowed link targets.
+///
+/// A link matching the blocklist is [`LinkVerdict::Blocked`] even if it also
+/// matches the allowlist.
+///
+/// # Example
+/// ```
+/// use webpage_info::{LinkSafetyList, LinkVerdict};
+///
+/// let list = LinkSafetyList::new()
+///     .block("malware.example")
+///     .block("shortener.example/r/*")
+///     .allow("wikipedia.org");
+/// assert_eq!(list.verdict("https://cdn.malware.example/x"), LinkVerdict::Blocked);
+/// assert_eq!(list.verdict("https://shortener.example/r/abc"), LinkVerdict::Blocked);
+/// assert_eq!(list.verdict("https://en.wikipedia.org/wiki/Rust"), LinkVerdict::Allowed);
+/// assert_eq!(list.verdict("https://example.org/"), LinkVerdict::Unlisted);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkSafetyList {
+    blocked: Vec<Pattern>,
+    allowed: Vec<Pattern>,
+}
+
+impl LinkSafetyList {
+    /// Create empty lists.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Block a domain and its subdomains (`example.com`), or URLs starting
+    /// with a pattern (`example.com/path`, where `*` matches any characters).
+    ///
+    /// The scheme is ignored; blank patterns are skipped.
+    pub fn block(mut self, pattern: &str) -> Self {
+        self.blocked.extend(Pattern::parse(pattern));
+        self
+    }
+
+    /// Allow a domain or URL pattern, written as for [`block`](Self::block).
+    pub fn allow(mut self, pattern: &str) -> Self {
+        self.allowed.extend(Pattern::parse(pattern));
+        self
+    }
+
+    /// Block every pattern in `text`, one per line; blank lines and lines
+    /// starting with `#` are skipped.
+    pub fn block_lines(self, text: &str) -> Self {
+        lines(text).fold(self, Self::block)
+    }
+
+    /// Allow every pattern in `text`, one per line, as for
+    /// [`block_lines`](Self::block_lines).
+    pub fn allow_lines(self, text: &str) -> Self {
+        lines(text).fold(self, Self::allow)
+    }
+
+    /// Whether both lists are empty.
+    pub fn is_empty(&self) -> bool {
+        self.blocked.is_empty() && self.allowed.is_empty()
+    }
+
+    /// The verdict for an absolute `http` or `https` URL; anything else is
+    /// [`LinkVerdict::Unlisted`].
+    pub fn verdict(&self, url: &str) -> LinkVerdict {
+        let Some((host, subject)) = subject(url) else {
+            return LinkVerdict::Unlisted;
+        };
+        let matches = |patterns: &[Pattern]| patterns.iter().any(|p| p.matches(&host, &subject));
+        if matches(&self.blocked) {
+            LinkVerdict::Blocked
+        } else if matches(&self.allowed) {
+            LinkVerdict::Allowed
+        } else {
+            LinkVerdict::Unlisted
+        }
+    }
+}
+
+impl HtmlInfo {
+    /// Set [`Link::verdict`] on every link that leaves the page's host.
+    ///
+    /// Links to `page_url`'s host (ignoring a `www.` prefix) and links that
+    /// are not `http` or `https` keep no verdict. Without a page URL every web
+    /// link counts as external.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{HtmlInfo, LinkSafetyList, LinkVerdict};
+    ///
+    /// let html = r#"<a href="/about">About</a> <a href="https://spam.example/win">Win</a>"#;
+    /// let page = "https://forum.example.org/t/1";
+    /// let mut info = HtmlInfo::from_string(html, Some(page)).unwrap();
+    /// info.annotate_links(Some(page), &LinkSafetyList::new().block("spam.example"));
+    /// # #[cfg(feature = "links")] {
+    /// assert_eq!(info.links[0].verdict, None);
+    /// assert_eq!(info.links[1].verdict, Some(LinkVerdict::Blocked));
+    /// # }
+    /// ```
+    pub fn annotate_links(&mut self, page_url: Option<&str>, list: &LinkSafetyList) {
+        let page_host = page_url
+            .and_then(|url| Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(site_host));
+        for link in &mut self.links {
+            link.verdict = is_external(link, page_host.as_deref()).then(|| list.verdict(&link.url));
+        }
+    }
+}
+
+/// Whether a link is a web link that leaves the page's host.
+fn is_external(link: &Link, page_host: Option<&str>) -> bool {
+    let Ok(url) = Url::parse(&link.url) else {
+        return false;
+    };
+    matches!(url.scheme(), "http" | "https")
+        && url
+            .host_str()
+            .is_some_and(|host| page_host != Some(site_host(host).as_str()))
+}
+
+/// A host without its `www.` prefix or trailing dot.
+fn site_host(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    match host.strip_prefix("www.") {
+        Some(rest) => rest.to_string(),
+        None => host,
+    }
+}
+
+/// The lowercase host and `host/path?query` a URL is matched on.
+fn subject(url: &str) -> Option<(String, String)> {
+    let url = Url::parse(url).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.trim_end_matches('.').to_string();
+    let mut subject = format!("{host}{}", url.path());
+    if let Some(query) = url.query() {
+        subject.push('?');
+        subject.push_str(query);
+    }
+    Some((host, subject))
+}
+
+/// Whether `subject` starts with `pattern`, where `*` matches any characters.
+fn glob_prefix(pattern: &str, subject: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = subject.strip_prefix(first) else {
+        return false;
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns() {
+        let list = LinkSafetyList::new().block_lines(
+            "# exported 2026-10-01\n\
+             \n\
+             *.Tracker.example\n\
+             HTTPS://evil.example/Phish/\n\
+             files.example/*.exe\n",
+        );
+        let verdict = |url| list.verdict(url);
+        assert_eq!(verdict("http://a.b.tracker.example/"), LinkVerdict::Blocked);
+        assert_eq!(verdict("https://tracker.example"), LinkVerdict::Blocked);
+        assert_eq!(
+            verdict("https://nottracker.example/"),
+            LinkVerdict::Unlisted
+        );
+        assert_eq!(
+            verdict("https://evil.example/Phish/login?u=1"),
+            LinkVerdict::Blocked
+        );
+        assert_eq!(
+            verdict("https://evil.example/phish/"),
+            LinkVerdict::Unlisted
+        );
+        assert_eq!(
+            verdict("https://files.example/a/setup.exe"),
+            LinkVerdict::Blocked
+        );
+        assert_eq!(
+            verdict("https://files.example/setup.zip"),
+            LinkVerdict::Unlisted
+        );
+        assert_eq!(verdict("mailto:x@tracker.example"), LinkVerdict::Unlisted);
+
+        let list = list.allow("tracker.example");
+        assert_eq!(
+            list.verdict("https://tracker.example/"),
+            LinkVerdict::Blocked
+        );
+        assert!(LinkSafetyList::new().block("  ").is_empty());
+    }
+
+    #[cfg(all(feature = "dom", feature = "links"))]
+    #[test]
+    fn test_parse_options_annotate_external_links() {
+        use crate::html::ParseOptions;
+
+        let html = r#"
+            <a href="https://www.example.com/about">About</a>
+            <a href="/faq">FAQ</a>
+            <a href="https://docs.rs/url">Docs</a>
+            <a href="https://bad.example/">Bad</a>
+            <a href="mailto:team@example.com">Mail</a>"#;
+        let list = LinkSafetyList::new().block("bad.example").allow("docs.rs");
+        let options = ParseOptions::new().link_safety(list);
+        let info = HtmlInfo::from_string_with_options(html, Some("https://example.com/"), options)
+            .unwrap();
+        let verdicts: Vec<_> = info.links.iter().map(|link| link.verdict).collect();
+        assert_eq!(
+            verdicts,
+            [
+                None,
+                None,
+                Some(LinkVerdict::Allowed),
+                Some(LinkVerdict::Blocked),
+                None
+            ]
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! OpenGraph comparison between locales
+//!
+//! A localized site should show a translated share preview in every language
+//! it serves. [`WebpageClient::compare_locales`] fetches a page once per
+//! `Accept-Language` value, [`WebpageClient::compare_locale_urls`] fetches one
+//! URL per locale, and the resulting [`LocaleComparison`] lists each locale's
+//! preview next to the fields that differ between them.
+
+use futures_util::future;
+use serde::{Deserialize, Serialize};
+
+use crate::WebpageInfo;
+use crate::client::WebpageClient;
+use crate::consistency::collapse_whitespace;
+use crate::error::Result;
+use crate::http::Overrides;
+
+/// A share preview field compared between locales.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum LocaleField {
+    /// `og:title`
+    Title,
+    /// `og:description`
+    Description,
+    /// The first `og:image`
+    Image,
+}
+
+impl LocaleField {
+    const ALL: [Self; 3] = [Self::Title, Self::Description, Self::Image];
+}
+
+/// The share preview of one locale.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct LocaleVariant {
+    /// The requested locale, e.g. `"de-DE"`
+    pub locale: String,
+
+    /// Final URL of the fetched page
+    pub url: String,
+
+    /// `og:locale` the page declared
+    pub og_locale: Option<String>,
+
+    /// `og:title`
+    pub title: Option<String>,
+
+    /// `og:description`
+    pub description: Option<String>,
+
+    /// URL of the first `og:image`
+    pub image: Option<String>,
+}
+
+impl LocaleVariant {
+    /// Take the preview of a page fetched for `locale`.
+    pub fn new(locale: impl Into<String>, page: &WebpageInfo) -> Self {
+        let og = &page.html.opengraph;
+        Self {
+            locale: locale.into(),
+            url: page.http.url.clone(),
+            og_locale: og.locale.clone(),
+            title: og.title.clone(),
+            description: og.description.clone(),
+            image: og.images.first().map(|image| image.url.clone()),
+        }
+    }
+
+    /// The value of `field`.
+    pub fn get(&self, field: LocaleField) -> Option<&str> {
+        match field {
+            LocaleField::Title => self.title.as_deref(),
+            LocaleField::Description => self.description.as_deref(),
+            LocaleField::Image => self.image.as_deref(),
+        }
+    }
+}
+
+/// One field whose value differs between locales.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct LocaleDifference {
+    /// Which field differs
+    pub field: LocaleField,
+
+    /// `(locale, value)` for every locale, `None` where the page lacks the field
+    pub values: Vec<(String, Option<String>)>,
+}
+
+/// Share previews of a page in several locales.
+///
+/// # Example
+/// ```
+/// use webpage_info::{LocaleComparison, LocaleField, WebpageInfo};
+///
+/// let page = |title: &str| {
+///     let mut info = WebpageInfo::default();
+///     info.html.opengraph.title = Some(title.to_string());
+///     info.html.opengraph.description = Some("Shop online".to_string());
+///     info
+/// };
+/// let comparison = LocaleComparison::new([("en", &page("Shoes")), ("de", &page("Schuhe"))]);
+/// assert_eq!(comparison.differences[0].field, LocaleField::Title);
+/// // The description was not translated
+/// assert_eq!(comparison.uniform_fields(), [LocaleField::Description]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct LocaleComparison {
+    /// Each locale's preview, in input order
+    pub variants: Vec<LocaleVariant>,
+
+    /// Fields whose value is not the same in every locale
+    pub differences: Vec<LocaleDifference>,
+}
+
+impl LocaleComparison {
+    /// Compare pages already fetched for each locale.
+    ///
+    /// Text is compared after collapsing whitespace.
+    pub fn new<'a, I, S>(pages: I) -> Self
+    where
+        I: IntoIterator<Item = (S, &'a WebpageInfo)>,
+        S: Into<String>,
+    {
+        let variants: Vec<_> = pages
+            .into_iter()
+            .map(|(locale, page)| LocaleVariant::new(locale, page))
+            .collect();
+        let differences = LocaleField::ALL
+            .into_iter()
+            .filter(|&field| !is_uniform(&variants, field))
+            .map(|field| LocaleDifference {
+                field,
+                values: variants
+                    .iter()
+                    .map(|v| (v.locale.clone(), v.get(field).map(str::to_string)))
+                    .collect(),
+            })
+            .collect();
+        Self {
+            variants,
+            differences,
+        }
+    }
+
+    /// Whether `field` differs between locales.
+    pub fn differs(&self, field: LocaleField) -> bool {
+        self.differences.iter().any(|d| d.field == field)
+    }
+
+    /// Fields every locale sets to the same value, which usually means they
+    /// were not translated.
+    pub fn uniform_fields(&self) -> Vec<LocaleField> {
+        LocaleField::ALL
+            .into_iter()
+            .filter(|&field| {
+                !self.differs(field) && self.variants.iter().any(|v| v.get(field).is_some())
+            })
+            .collect()
+    }
+}
+
+fn is_uniform(variants: &[LocaleVariant], field: LocaleField) -> bool {
+    let mut values = variants
+        .iter()
+        .map(|v| v.get(field).map(collapse_whitespace));
+    let first = values.next();
+    values.all(|value| Some(value) == first)
+}
+
+impl WebpageClient {
+    /// Fetch `url` once per locale, sending each as `Accept-Language`, and
+    /// compare the OpenGraph previews.
+    ///
+    /// Fails with the first fetch error.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use webpage_info::{HttpOptions, WebpageClient};
+    ///
+    /// # async fn run() -> webpage_info::Result<()> {
+    /// let client = WebpageClient::new(HttpOptions::new())?;
+    /// let comparison = client
+    ///     .compare_locales("https://example.com/product", ["en-US", "de-DE", "ja-JP"])
+    ///     .await?;
+    /// for field in comparison.uniform_fields() {
+    ///     println!("{field:?} is the same in every locale");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn compare_locales<I, S>(&self, url: &str, locales: I) -> Result<LocaleComparison>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let fetches = locales
+            .into_iter()
+            .map(Into::into)
+            .map(|locale| async move {
+                let overrides = Overrides::new().header("Accept-Language", locale.as_str());
+                let page = self.fetch_with(url, &overrides).await?;
+                Ok((locale, page))
+            });
+        compare(future::join_all(fetches).await)
+    }
+
+    /// Fetch one URL per locale, such as the pages of a site with a path per
+    /// language, and compare the OpenGraph previews.
+    ///
+    /// Fails with the first fetch error.
+    pub async fn compare_locale_urls<I, L, U>(&self, pages: I) -> Result<LocaleComparison>
+    where
+        I: IntoIterator<Item = (L, U)>,
+        L: Into<String>,
+        U: AsRef<str>,
+    {
+        let fetches = pages.into_iter().map(|(locale, url)| async move {
+            let page = self.fetch(url.as_ref()).await?;
+            Ok((locale.into(), page))
+        });
+        compare(future::join_all(fetches).await)
+    }
+}
+
+fn compare(pages: Vec<Result<(String, WebpageInfo)>>) -> Result<LocaleComparison> {
+    let pages = pages.into_iter().collect::<Result<Vec<_>>>()?;
+    Ok(LocaleComparison::new(
+        pages.iter().map(|(locale, page)| (locale.as_str(), page)),
+    ))
+}
+
+#[cfg(all(test, feature = "opengraph"))]
+mod tests {
+    use wiremock::matchers::{header, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::HttpOptions;
+
+    fn page(title: &str, image: &str) -> String {
+        format!(
+            r#"<head><meta property="og:title" content="{title}">
+            <meta property="og:description" content="Handmade  shoes">
+            <meta property="og:image" content="{image}"></head>"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_compare_locales() {
+        let server = MockServer::start().await;
+        Mock::given(path("/product"))
+            .and(header("accept-language", "de-DE"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(page("Schuhe", "/en.png"), "text/html"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/product"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(page("Shoes", "/en.png"), "text/html"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/fr/product"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                page("Chaussures", "/fr.png").replace("  ", " "),
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+
+        let client = WebpageClient::new(HttpOptions::new().block_private_ips(false)).unwrap();
+        let url = format!("{}/product", server.uri());
+        let comparison = client
+            .compare_locales(&url, ["en-US", "de-DE"])
+            .await
+            .unwrap();
+        assert_eq!(comparison.variants[1].title.as_deref(), Some("Schuhe"));
+        assert_eq!(comparison.differences.len(), 1);
+        assert_eq!(
+            comparison.differences[0].values,
+            [
+                ("en-US".to_string(), Some("Shoes".to_string())),
+                ("de-DE".to_string(), Some("Schuhe".to_string())),
+            ]
+        );
+        assert_eq!(
+            comparison.uniform_fields(),
+            [LocaleField::Description, LocaleField::Image]
+        );
+
+        let comparison = client
+            .compare_locale_urls([("en", url), ("fr", format!("{}/fr/product", server.uri()))])
+            .await
+            .unwrap();
+        assert!(comparison.differs(LocaleField::Title));
+        assert!(comparison.differs(LocaleField::Image));
+        assert!(!comparison.differs(LocaleField::Description));
+    }
+}
@@ -0,0 +1,162 @@
+//! Structured fetch logging through the `log` facade
+//!
+//! A client configured with [`WebpageClient::with_logging`] emits one record
+//! per fetch with target `webpage_info` and the key-value fields `url`, `host`,
+//! `status`, `bytes`, `duration_ms`, and `outcome` (`"ok"` or `"error"`).
+
+use std::time::Duration;
+
+use log::Level;
+
+use crate::WebpageInfo;
+use crate::client::WebpageClient;
+use crate::error::Result;
+
+/// Target of every record.
+const TARGET: &str = "webpage_info";
+
+impl WebpageClient {
+    /// Log every fetch made by this client as a structured `log` record.
+    ///
+    /// Successful fetches are logged at `level`, failed ones at `level` or
+    /// [`Level::Warn`], whichever is more severe.
+    pub fn with_logging(mut self, level: Level) -> Self {
+        self.state.log_level = Some(level);
+        self
+    }
+}
+
+/// Emit the record for a finished fetch of `url`.
+pub(crate) fn log_fetch(level: Level, url: &str, result: &Result<WebpageInfo>, duration: Duration) {
+    let host = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let duration_ms = duration.as_millis() as u64;
+    match result {
+        Ok(info) => log::log!(
+            target: TARGET,
+            level,
+            url = url,
+            host = host.as_str(),
+            status = Some(info.http.status_code),
+            bytes = info.http.body.len() as u64,
+            duration_ms = duration_ms,
+            outcome = "ok";
+            "fetched {url}"
+        ),
+        Err(error) => log::log!(
+            target: TARGET,
+            level.min(Level::Warn),
+            url = url,
+            host = host.as_str(),
+            status = error.status(),
+            bytes = 0u64,
+            duration_ms = duration_ms,
+            outcome = "error";
+            "failed to fetch {url}: {error}"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::{Mutex, OnceLock};
+
+    use log::kv::{Key, Value, VisitSource};
+    use log::{Log, Metadata, Record};
+
+    use super::*;
+    use crate::HttpOptions;
+
+    /// Captured records: level, message, and fields.
+    type Captured = (Level, String, BTreeMap<String, String>);
+
+    struct Capture(Mutex<Vec<Captured>>);
+
+    impl Log for Capture {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            struct Fields(BTreeMap<String, String>);
+            impl<'kvs> VisitSource<'kvs> for Fields {
+                fn visit_pair(
+                    &mut self,
+                    key: Key<'kvs>,
+                    value: Value<'kvs>,
+                ) -> std::result::Result<(), log::kv::Error> {
+                    self.0.insert(key.to_string(), value.to_string());
+                    Ok(())
+                }
+            }
+            if record.target() != TARGET {
+                return;
+            }
+            let mut fields = Fields(BTreeMap::new());
+            record.key_values().visit(&mut fields).unwrap();
+            self.0
+                .lock()
+                .unwrap()
+                .push((record.level(), record.args().to_string(), fields.0));
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn capture() -> &'static Capture {
+        static CAPTURE: OnceLock<&'static Capture> = OnceLock::new();
+        CAPTURE.get_or_init(|| {
+            let capture = Box::leak(Box::new(Capture(Mutex::new(Vec::new()))));
+            log::set_logger(capture).unwrap();
+            log::set_max_level(log::LevelFilter::Trace);
+            capture
+        })
+    }
+
+    #[tokio::test]
+    async fn test_logs_fetch_outcomes() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let capture = capture();
+        let server = MockServer::start().await;
+        Mock::given(path("/page"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw("<title>Logged</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        let options = HttpOptions::new().block_private_ips(false);
+        let client = WebpageClient::new(options)
+            .unwrap()
+            .with_logging(Level::Info);
+        let page = format!("{}/page", server.uri());
+        client.fetch(&page).await.unwrap();
+        client.fetch("http://127.0.0.1:1/").await.unwrap_err();
+
+        let unlogged = WebpageClient::new(HttpOptions::new().block_private_ips(false)).unwrap();
+        unlogged.fetch(&page).await.unwrap();
+
+        let records = capture.0.lock().unwrap();
+        assert_eq!(records.len(), 2);
+
+        let (level, message, fields) = &records[0];
+        assert_eq!(*level, Level::Info);
+        assert_eq!(*message, format!("fetched {page}"));
+        assert_eq!(fields["url"], page);
+        assert_eq!(fields["host"], "127.0.0.1");
+        assert_eq!(fields["status"], "200");
+        assert_eq!(fields["bytes"], "21");
+        assert_eq!(fields["outcome"], "ok");
+        assert!(fields.contains_key("duration_ms"));
+
+        let (level, message, fields) = &records[1];
+        assert_eq!(*level, Level::Warn);
+        assert!(message.starts_with("failed to fetch http://127.0.0.1:1/"));
+        assert_eq!(fields["outcome"], "error");
+    }
+}
@@ -0,0 +1,121 @@
+//! Login-wall detection
+
+use url::Url;
+
+use crate::WebpageInfo;
+
+/// Path segments that identify a sign-in page.
+const LOGIN_SEGMENTS: &[&str] = &[
+    "login",
+    "log-in",
+    "signin",
+    "sign-in",
+    "sign_in",
+    "auth",
+    "sso",
+    "account-login",
+];
+
+/// Pages with fewer words than this count as thin content.
+const THIN_CONTENT_WORDS: usize = 150;
+
+impl WebpageInfo {
+    /// Whether the page is likely behind a login wall.
+    ///
+    /// A heuristic for routing crawl results; any of these signals is enough:
+    ///
+    /// - a `401 Unauthorized` status
+    /// - a redirect that ended on a `/login`, `/signin`, or similar path
+    /// - a password field on a page that is `noindex` or has thin content
+    ///   (a `403` with a password field counts as well)
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::WebpageInfo;
+    ///
+    /// let mut info = WebpageInfo::default();
+    /// info.http.status_code = 200;
+    /// info.http.url = "https://example.com/account/login?next=/inbox".to_string();
+    /// info.http.redirect_count = 1;
+    /// assert!(info.requires_login());
+    /// ```
+    pub fn requires_login(&self) -> bool {
+        if self.http.status_code == 401 {
+            return true;
+        }
+        if self.http.redirect_count > 0 && is_login_url(&self.http.url) {
+            return true;
+        }
+
+        let html = &self.html;
+        html.has_password_field
+            && (self.http.status_code == 403
+                || self.is_noindex()
+                || html.word_count() < THIN_CONTENT_WORDS)
+    }
+
+    /// Whether the page asks not to be indexed, via `<meta name="robots">` or `X-Robots-Tag`.
+    fn is_noindex(&self) -> bool {
+        let noindex = |value: &str| {
+            value
+                .split(',')
+                .any(|directive| directive.trim().eq_ignore_ascii_case("noindex"))
+        };
+        self.html.meta_all("robots").iter().any(|v| noindex(v))
+            || self
+                .http
+                .headers
+                .iter()
+                .any(|(name, value)| name.eq_ignore_ascii_case("x-robots-tag") && noindex(value))
+    }
+}
+
+/// Whether a URL's path contains a sign-in segment.
+fn is_login_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| {
+        url.path_segments().is_some_and(|mut segments| {
+            segments.any(|segment| {
+                LOGIN_SEGMENTS
+                    .iter()
+                    .any(|login| segment.eq_ignore_ascii_case(login))
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_and_redirect_signals() {
+        let mut info = WebpageInfo::default();
+        info.http.status_code = 401;
+        assert!(info.requires_login());
+
+        info.http.status_code = 200;
+        info.http.url = "https://example.com/users/sign_in".to_string();
+        assert!(!info.requires_login(), "no redirect was followed");
+        info.http.redirect_count = 2;
+        assert!(info.requires_login());
+
+        info.http.url = "https://example.com/blog/login-tips".to_string();
+        assert!(!info.requires_login());
+    }
+
+    #[test]
+    fn test_password_form_signals() {
+        let mut info = WebpageInfo::default();
+        info.http.status_code = 200;
+        info.html.has_password_field = true;
+        info.html.text_content = "word ".repeat(500);
+        assert!(!info.requires_login(), "full page with a login box");
+
+        info.html.meta.append("robots", "noindex, nofollow");
+        assert!(info.requires_login());
+
+        info.html.meta = Default::default();
+        info.html.text_content = "Sign in to continue".to_string();
+        assert!(info.requires_login());
+    }
+}
@@ -0,0 +1,331 @@
+//! Ordered storage for `<meta>` tags
+//!
+//! [`MetaMap`] keeps meta entries in document order in a single vector, with a
+//! sorted index for lookups. Names may repeat (e.g. several `article:tag`
+//! entries); all contents are kept.
+//!
+//! It serializes as a JSON object mapping each name to the array of its
+//! contents. Version 1 snapshots, which stored a single string per name, still
+//! deserialize.
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::de::{MapAccess, Visitor};
+#[cfg(feature = "serde")]
+use serde::ser::SerializeMap;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Meta tag names and contents, in document order.
+///
+/// Names are ordered by first occurrence; the contents of a repeated name are
+/// kept together in document order.
+///
+/// # Example
+/// ```
+/// use webpage_info::MetaMap;
+///
+/// let mut meta = MetaMap::new();
+/// meta.append("article:tag", "rust");
+/// meta.append("description", "A page");
+/// meta.append("article:tag", "html");
+///
+/// assert_eq!(meta.get("description"), Some("A page"));
+/// assert_eq!(meta.get_all("article:tag"), ["rust", "html"]);
+/// assert_eq!(meta.keys().collect::<Vec<_>>(), ["article:tag", "description"]);
+/// ```
+#[derive(Clone, Default)]
+pub struct MetaMap {
+    entries: Vec<(Box<str>, Vec<String>)>,
+    /// Positions into `entries`, sorted by name
+    index: Vec<u32>,
+}
+
+impl MetaMap {
+    /// Create an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct names.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Content of the last meta tag with the given name.
+    ///
+    /// The last occurrence wins, as it did before duplicates were preserved.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.get_all(name).last().map(String::as_str)
+    }
+
+    /// Contents of every meta tag with the given name, in document order.
+    pub fn get_all(&self, name: &str) -> &[String] {
+        match self.find(name) {
+            Ok(slot) => &self.entries[self.index[slot] as usize].1,
+            Err(_) => &[],
+        }
+    }
+
+    /// Whether a meta tag with the given name is present.
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.find(name).is_ok()
+    }
+
+    /// Add a content for `name`, keeping any existing ones.
+    pub fn append(&mut self, name: impl Into<Box<str>>, content: impl Into<String>) {
+        self.values_mut(name.into()).push(content.into());
+    }
+
+    /// Set the only content for `name`, returning the previous contents.
+    ///
+    /// A name that is already present keeps its original position.
+    pub fn insert(&mut self, name: impl Into<Box<str>>, content: impl Into<String>) -> Vec<String> {
+        std::mem::replace(self.values_mut(name.into()), vec![content.into()])
+    }
+
+    /// Iterate over `(name, content)` pairs, grouped by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .flat_map(|(k, values)| values.iter().map(move |v| (&**k, v.as_str())))
+    }
+
+    /// Iterate over distinct names in order of first occurrence.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(k, _)| &**k)
+    }
+
+    /// Iterate over all contents, grouped by name.
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        self.iter().map(|(_, v)| v)
+    }
+
+    /// Entries whose name starts with `prefix`, with the prefix stripped.
+    ///
+    /// Entries keep their relative order.
+    pub fn with_prefix(&self, prefix: &str) -> MetaMap {
+        let start = self
+            .index
+            .partition_point(|&pos| *self.entries[pos as usize].0 < *prefix);
+        let mut positions: Vec<usize> = self.index[start..]
+            .iter()
+            .map(|&pos| pos as usize)
+            .take_while(|&pos| self.entries[pos].0.starts_with(prefix))
+            .collect();
+        positions.sort_unstable();
+
+        let mut map = MetaMap::new();
+        for pos in positions {
+            let (name, contents) = &self.entries[pos];
+            *map.values_mut(name[prefix.len()..].into()) = contents.clone();
+        }
+        map
+    }
+
+    /// Mutable access to every name's contents.
+    #[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+    pub(crate) fn entries_mut(&mut self) -> impl Iterator<Item = (&str, &mut Vec<String>)> {
+        self.entries
+            .iter_mut()
+            .map(|(name, values)| (&**name, values))
+    }
+
+    fn values_mut(&mut self, name: Box<str>) -> &mut Vec<String> {
+        let position = match self.find(&name) {
+            Ok(slot) => self.index[slot] as usize,
+            Err(slot) => {
+                self.index.insert(slot, self.entries.len() as u32);
+                self.entries.push((name, Vec::new()));
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[position].1
+    }
+
+    fn find(&self, name: &str) -> std::result::Result<usize, usize> {
+        self.index
+            .binary_search_by(|&pos| (*self.entries[pos as usize].0).cmp(name))
+    }
+}
+
+impl PartialEq for MetaMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl Eq for MetaMap {}
+
+impl fmt::Debug for MetaMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.entries.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a MetaMap {
+    type Item = (&'a str, &'a str);
+    type IntoIter = Box<dyn Iterator<Item = (&'a str, &'a str)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl<K: Into<Box<str>>, V: Into<String>> FromIterator<(K, V)> for MetaMap {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+/// Appends each pair, keeping duplicates.
+impl<K: Into<Box<str>>, V: Into<String>> Extend<(K, V)> for MetaMap {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (name, content) in iter {
+            self.append(name, content);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for MetaMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (name, contents) in &self.entries {
+            map.serialize_entry(name, contents)?;
+        }
+        map.end()
+    }
+}
+
+/// A serialized entry: an array of contents, or a single string (schema version 1).
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Contents {
+    Many(Vec<String>),
+    One(String),
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for MetaMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct MetaVisitor;
+
+        impl<'de> Visitor<'de> for MetaVisitor {
+            type Value = MetaMap;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map of meta tag names to contents")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut access: A,
+            ) -> std::result::Result<MetaMap, A::Error> {
+                let mut map = MetaMap::new();
+                while let Some((name, contents)) = access.next_entry::<String, Contents>()? {
+                    match contents {
+                        Contents::Many(contents) => {
+                            map.extend(contents.into_iter().map(|content| (name.as_str(), content)))
+                        }
+                        Contents::One(content) => map.append(name, content),
+                    }
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(MetaVisitor)
+    }
+}
+
+/// Described as the serialized form: each name maps to an array of contents.
+#[cfg(feature = "json-schema")]
+impl schemars::JsonSchema for MetaMap {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "MetaMap".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "object",
+            "additionalProperties": generator.subschema_for::<Vec<String>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicates_are_preserved() {
+        let mut meta = MetaMap::new();
+        meta.append("b", "1");
+        meta.append("a", "2");
+        meta.append("b", "3");
+
+        assert_eq!(meta.len(), 2);
+        assert_eq!(meta.get("b"), Some("3"));
+        assert_eq!(meta.get_all("b"), ["1", "3"]);
+        assert!(meta.get_all("c").is_empty());
+        assert!(!meta.contains_key("c"));
+        assert_eq!(
+            meta.iter().collect::<Vec<_>>(),
+            [("b", "1"), ("b", "3"), ("a", "2")]
+        );
+
+        assert_eq!(meta.insert("b", "4"), ["1", "3"]);
+        assert_eq!(meta.get_all("b"), ["4"]);
+        assert_eq!(meta.keys().next(), Some("b"));
+    }
+
+    #[test]
+    fn test_with_prefix() {
+        let meta: MetaMap = [
+            ("twitter:title", "T"),
+            ("og:title", "O"),
+            ("twitter:card", "summary"),
+            ("twitter", "bare"),
+            ("twitter:title", "T2"),
+        ]
+        .into_iter()
+        .collect();
+
+        let twitter = meta.with_prefix("twitter:");
+        assert_eq!(twitter.keys().collect::<Vec<_>>(), ["title", "card"]);
+        assert_eq!(twitter.get_all("title"), ["T", "T2"]);
+        assert!(meta.with_prefix("dc:").is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serializes_as_ordered_object() {
+        let meta: MetaMap = [("z", "last"), ("a", "first"), ("z", "again")]
+            .into_iter()
+            .collect();
+        let json = serde_json::to_string(&meta).unwrap();
+        assert_eq!(json, r#"{"z":["last","again"],"a":["first"]}"#);
+
+        let back: MetaMap = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, meta);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserializes_version_1_strings() {
+        let meta: MetaMap = serde_json::from_str(r#"{"description":"Old"}"#).unwrap();
+        assert_eq!(meta.get_all("description"), ["Old"]);
+    }
+}
@@ -0,0 +1,413 @@
+//! MHTML (MIME HTML) saved-page input
+//!
+//! Browsers save complete pages as a single `multipart/related` MIME message:
+//! the HTML document plus one part per image, stylesheet, and frame. An
+//! [`MhtmlArchive`] splits the message into [`MhtmlPart`]s, picks the primary
+//! HTML part, and rewrites `cid:` references to the original URLs so the page
+//! analyzes like the live one.
+
+use std::fs;
+use std::path::Path;
+
+use url::Url;
+
+use crate::base64;
+use crate::charset::CharsetInfo;
+use crate::error::{Error, Result};
+use crate::html::HtmlInfo;
+
+/// One part of an MHTML message, with its transfer encoding removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MhtmlPart {
+    /// Full `Content-Type` header value, including parameters
+    pub content_type: Option<String>,
+
+    /// `Content-ID`, without angle brackets
+    pub content_id: Option<String>,
+
+    /// `Content-Location`: the URL the part was saved from
+    pub content_location: Option<String>,
+
+    /// Decoded part body
+    pub body: Vec<u8>,
+}
+
+impl MhtmlPart {
+    /// The MIME type of the part, without parameters, lowercased.
+    pub fn mime_type(&self) -> Option<String> {
+        let content_type = self.content_type.as_deref()?;
+        Some(
+            content_type
+                .split(';')
+                .next()
+                .unwrap_or(content_type)
+                .trim()
+                .to_ascii_lowercase(),
+        )
+    }
+
+    /// The body decoded as text, using the charset of the `Content-Type` or
+    /// a `<meta charset>` declaration.
+    pub fn text(&self) -> String {
+        CharsetInfo::detect(&self.body, self.content_type.as_deref()).decode(&self.body)
+    }
+}
+
+/// A parsed MHTML saved page.
+///
+/// # Example
+/// ```
+/// use webpage_info::MhtmlArchive;
+///
+/// let mhtml = "MIME-Version: 1.0\r\n\
+///     Content-Type: multipart/related; boundary=\"b\"\r\n\r\n\
+///     --b\r\n\
+///     Content-Type: text/html\r\n\
+///     Content-Location: https://example.com/\r\n\r\n\
+///     <title>Saved</title><img src=\"cid:logo\">\r\n\
+///     --b\r\n\
+///     Content-Type: image/png\r\n\
+///     Content-ID: <logo>\r\n\
+///     Content-Location: https://example.com/logo.png\r\n\
+///     Content-Transfer-Encoding: base64\r\n\r\n\
+///     iVBORw0KGgo=\r\n\
+///     --b--\r\n";
+///
+/// let archive = MhtmlArchive::parse(mhtml.as_bytes()).unwrap();
+/// assert_eq!(archive.url(), Some("https://example.com/"));
+/// assert_eq!(archive.resolve("cid:logo").unwrap().body, b"\x89PNG\r\n\x1a\n");
+///
+/// let info = archive.to_html_info().unwrap();
+/// assert_eq!(info.title.as_deref(), Some("Saved"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MhtmlArchive {
+    /// Every part of the message, in order
+    pub parts: Vec<MhtmlPart>,
+
+    /// Index of the primary HTML part in `parts`
+    primary: usize,
+
+    /// `Snapshot-Content-Location` of the message, as written by Chromium
+    location: Option<String>,
+}
+
+impl MhtmlArchive {
+    /// Parse an MHTML message.
+    ///
+    /// Fails with [`Error::Mhtml`] if it is not a multipart message or has no
+    /// HTML part.
+    pub fn parse(input: &[u8]) -> Result<Self> {
+        let (headers, body) = split_headers(input);
+        let content_type = header(&headers, "content-type")
+            .ok_or_else(|| invalid("missing Content-Type header"))?;
+        let boundary = param(content_type, "boundary")
+            .ok_or_else(|| invalid("Content-Type has no multipart boundary"))?;
+        let start = param(content_type, "start").map(|id| strip_brackets(&id).to_string());
+
+        let parts: Vec<MhtmlPart> = split_multipart(body, &boundary)
+            .into_iter()
+            .map(parse_part)
+            .collect();
+        let primary = start
+            .and_then(|id| {
+                parts
+                    .iter()
+                    .position(|p| p.content_id.as_deref() == Some(id.as_str()))
+            })
+            .or_else(|| {
+                parts.iter().position(|p| {
+                    p.mime_type()
+                        .is_some_and(|t| t == "text/html" || t == "application/xhtml+xml")
+                })
+            })
+            .ok_or_else(|| invalid("no HTML part"))?;
+
+        Ok(Self {
+            parts,
+            primary,
+            location: header(&headers, "snapshot-content-location").map(str::to_string),
+        })
+    }
+
+    /// Read and parse an `.mhtml` or `.mht` file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    /// The primary HTML part.
+    pub fn primary(&self) -> &MhtmlPart {
+        &self.parts[self.primary]
+    }
+
+    /// The URL the page was saved from, if recorded.
+    pub fn url(&self) -> Option<&str> {
+        self.primary()
+            .content_location
+            .as_deref()
+            .or(self.location.as_deref())
+    }
+
+    /// The part a reference in the page points to.
+    ///
+    /// Accepts `cid:` URLs and `Content-Location` URLs, relative ones being
+    /// resolved against [`url`](Self::url).
+    pub fn resolve(&self, reference: &str) -> Option<&MhtmlPart> {
+        if let Some(id) = reference.strip_prefix("cid:") {
+            return self
+                .parts
+                .iter()
+                .find(|p| p.content_id.as_deref() == Some(id));
+        }
+        let absolute = self
+            .url()
+            .and_then(|base| Url::parse(base).ok())
+            .and_then(|base| base.join(reference).ok());
+        self.parts.iter().find(|p| {
+            p.content_location.as_deref().is_some_and(|location| {
+                location == reference || absolute.as_ref().is_some_and(|a| a.as_str() == location)
+            })
+        })
+    }
+
+    /// The HTML of the primary part, with `cid:` references replaced by the
+    /// `Content-Location` of the part they point to.
+    pub fn html(&self) -> String {
+        let mut html = self.primary().text();
+        for part in &self.parts {
+            if let (Some(id), Some(location)) = (&part.content_id, &part.content_location) {
+                html = html.replace(&format!("cid:{id}"), location);
+            }
+        }
+        html
+    }
+
+    /// Parse the primary HTML part, resolving links against [`url`](Self::url).
+    pub fn to_html_info(&self) -> Result<HtmlInfo> {
+        HtmlInfo::from_string(&self.html(), self.url())
+    }
+}
+
+impl HtmlInfo {
+    /// Parse an `.mhtml` saved page from a file.
+    ///
+    /// See [`MhtmlArchive`] for access to the other parts of the page.
+    pub fn from_mhtml_file(path: impl AsRef<Path>) -> Result<Self> {
+        MhtmlArchive::from_file(path)?.to_html_info()
+    }
+}
+
+/// Parse the headers and body of one part.
+fn parse_part(raw: &[u8]) -> MhtmlPart {
+    let (headers, body) = split_headers(raw);
+    let encoding = header(&headers, "content-transfer-encoding")
+        .unwrap_or("binary")
+        .trim()
+        .to_ascii_lowercase();
+    let body = match encoding.as_str() {
+        "base64" => base64::decode(&String::from_utf8_lossy(body)).unwrap_or_default(),
+        "quoted-printable" => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    };
+    MhtmlPart {
+        content_type: header(&headers, "content-type").map(str::to_string),
+        content_id: header(&headers, "content-id").map(|id| strip_brackets(id).to_string()),
+        content_location: header(&headers, "content-location").map(str::to_string),
+        body,
+    }
+}
+
+/// Split a MIME entity into its unfolded headers and its body.
+fn split_headers(input: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut rest = input;
+    while !rest.is_empty() {
+        let end = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(rest.len(), |i| i + 1);
+        let line = String::from_utf8_lossy(&rest[..end]);
+        rest = &rest[end..];
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            // Folded continuation of the previous header
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, rest)
+}
+
+/// Split a multipart body at `--boundary` delimiter lines.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut part_start = None;
+    let mut pos = 0;
+    while pos < body.len() {
+        let end = body[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(body.len(), |i| pos + i + 1);
+        let line = body[pos..end].trim_ascii_end();
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes())
+            && (rest.is_empty() || rest == b"--")
+        {
+            if let Some(start) = part_start {
+                // The line break before a delimiter belongs to the delimiter
+                let content = &body[start..pos];
+                let content = content.strip_suffix(b"\n").unwrap_or(content);
+                parts.push(content.strip_suffix(b"\r").unwrap_or(content));
+            }
+            if rest == b"--" {
+                return parts;
+            }
+            part_start = Some(end);
+        }
+        pos = end;
+    }
+    // Tolerate a missing close delimiter
+    if let Some(start) = part_start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+/// Decode a quoted-printable body.
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] != b'=' {
+            out.push(input[i]);
+            i += 1;
+            continue;
+        }
+        let rest = &input[i + 1..];
+        if rest.starts_with(b"\r\n") {
+            i += 3; // Soft line break
+        } else if rest.starts_with(b"\n") {
+            i += 2;
+        } else if let Some(byte) = rest
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(b'=');
+            i += 1;
+        }
+    }
+    out
+}
+
+/// The first value of a header, matched case-insensitively.
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// A parameter of a header value such as `multipart/related; boundary="x"`.
+fn param(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|p| {
+        let (key, value) = p.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+fn strip_brackets(id: &str) -> &str {
+    id.trim().trim_start_matches('<').trim_end_matches('>')
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::Mhtml(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAVED_PAGE: &str = "From: <Saved by Blink>\r\n\
+        Snapshot-Content-Location: https://example.com/article\r\n\
+        Subject: Article\r\n\
+        MIME-Version: 1.0\r\n\
+        Content-Type: multipart/related;\r\n\
+        \ttype=\"text/html\";\r\n\
+        \tboundary=\"----MultipartBoundary--abc----\"\r\n\
+        \r\n\
+        ------MultipartBoundary--abc----\r\n\
+        Content-Type: text/html\r\n\
+        Content-ID: <frame-1@mhtml.blink>\r\n\
+        Content-Transfer-Encoding: quoted-printable\r\n\
+        Content-Location: https://example.com/article\r\n\
+        \r\n\
+        <html><head><meta http-equiv=3D\"Content-Type\" content=3D\"text/html; charset=3D=\r\n\
+        UTF-8\"><title>Caf=C3=A9</title></head><body>\r\n\
+        <img src=3D\"cid:img-1@mhtml.blink\" width=3D\"600\" height=3D\"400\">\r\n\
+        <a href=3D\"/next\">Next</a></body></html>\r\n\
+        ------MultipartBoundary--abc----\r\n\
+        Content-Type: image/jpeg\r\n\
+        Content-ID: <img-1@mhtml.blink>\r\n\
+        Content-Transfer-Encoding: base64\r\n\
+        Content-Location: https://example.com/photo.jpg\r\n\
+        \r\n\
+        /9j/4AAQ\r\n\
+        SkZJRg==\r\n\
+        ------MultipartBoundary--abc------\r\n";
+
+    #[test]
+    fn test_parses_saved_page() {
+        let archive = MhtmlArchive::parse(SAVED_PAGE.as_bytes()).unwrap();
+        assert_eq!(archive.parts.len(), 2);
+        assert_eq!(archive.primary().mime_type().as_deref(), Some("text/html"));
+        assert_eq!(archive.url(), Some("https://example.com/article"));
+
+        let image = archive.resolve("cid:img-1@mhtml.blink").unwrap();
+        assert_eq!(image.body, b"\xff\xd8\xff\xe0\x00\x10JFIF");
+        assert_eq!(archive.resolve("/photo.jpg"), Some(image));
+        assert_eq!(
+            archive.resolve("https://example.com/photo.jpg"),
+            Some(image)
+        );
+        assert!(archive.resolve("cid:missing").is_none());
+
+        let info = archive.to_html_info().unwrap();
+        assert_eq!(info.title.as_deref(), Some("Café"));
+        #[cfg(feature = "links")]
+        assert_eq!(&*info.links[0].url, "https://example.com/next");
+        assert_eq!(
+            info.image_candidates[0].url,
+            "https://example.com/photo.jpg"
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_multipart_input() {
+        let err = MhtmlArchive::parse(b"<html><title>Plain</title></html>").unwrap_err();
+        assert!(matches!(err, Error::Mhtml(_)));
+
+        let no_html = "Content-Type: multipart/related; boundary=b\n\n--b\n\
+                       Content-Type: image/png\n\nPNG\n--b--\n";
+        let err = MhtmlArchive::parse(no_html.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid MHTML: no HTML part");
+    }
+
+    #[test]
+    fn test_quoted_printable() {
+        assert_eq!(decode_quoted_printable(b"a=3Db=\r\nc=\nd"), b"a=bcd");
+        assert_eq!(decode_quoted_printable(b"50% =ZZ"), b"50% =ZZ");
+    }
+}
@@ -0,0 +1,241 @@
+//! HTML microdata
+//!
+//! Many sites still embed Schema.org data as
+//! [microdata](https://html.spec.whatwg.org/multipage/microdata.html)
+//! (`itemscope`, `itemtype`, `itemprop`) rather than JSON-LD. Each top-level
+//! item is converted to the JSON-LD shape, so it reads like any other
+//! [`SchemaOrg`] item, and stored in [`HtmlInfo::microdata`].
+//!
+//! ```html
+//! <div itemscope itemtype="https://schema.org/Product">
+//!   <span itemprop="name">Kettle</span>
+//! </div>
+//! ```
+//!
+//! becomes `{"@type": "Product", "name": "Kettle"}`, with `schema_type`
+//! `"Product"`.
+
+use std::sync::OnceLock;
+
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::consistency::collapse_whitespace;
+use crate::html::{HtmlInfo, MAX_SCHEMA_ORG_ITEMS};
+use crate::schema_org::SchemaOrg;
+
+/// Items nested deeper than this are dropped.
+const MAX_DEPTH: usize = 16;
+
+/// Vocabulary prefixes stripped from `itemtype` to get the Schema.org type.
+const SCHEMA_ORG_PREFIXES: &[&str] = &["https://schema.org/", "http://schema.org/"];
+
+fn top_level_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("[itemscope]:not([itemprop])").unwrap())
+}
+
+impl HtmlInfo {
+    /// Read every top-level microdata item, in document order.
+    pub(crate) fn extract_microdata(document: &Html, base_url: Option<&Url>) -> Vec<SchemaOrg> {
+        document
+            .select(top_level_selector())
+            .filter_map(|element| {
+                let value = item(document, element, base_url, 0);
+                let schema_type = value.get("@type")?.as_str()?.to_string();
+                Some(SchemaOrg { schema_type, value })
+            })
+            .take(MAX_SCHEMA_ORG_ITEMS)
+            .collect()
+    }
+
+    /// JSON-LD, microdata, and RDFa items together, in that order.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<div itemscope itemtype="https://schema.org/Person">
+    ///     <span itemprop="name">Ada Lovelace</span>
+    /// </div>"#;
+    /// let info = HtmlInfo::from_string(html, None).unwrap();
+    /// let person = info.structured_data().next().unwrap();
+    /// assert_eq!(person.schema_type, "Person");
+    /// assert_eq!(person.get_str("name"), Some("Ada Lovelace"));
+    /// ```
+    pub fn structured_data(&self) -> impl Iterator<Item = &SchemaOrg> {
+        self.schema_org
+            .iter()
+            .chain(&self.microdata)
+            .chain(&self.rdfa)
+    }
+}
+
+/// The JSON-LD form of the item rooted at `scope`.
+fn item(document: &Html, scope: ElementRef, base_url: Option<&Url>, depth: usize) -> Value {
+    let mut object = Map::new();
+    let attr = |name: &str| {
+        scope
+            .value()
+            .attr(name)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    if let Some(types) = attr("itemtype") {
+        let types: Vec<Value> = types
+            .split_ascii_whitespace()
+            .map(|t| {
+                let short = SCHEMA_ORG_PREFIXES
+                    .iter()
+                    .find_map(|prefix| t.strip_prefix(prefix));
+                Value::String(short.unwrap_or(t).to_string())
+            })
+            .collect();
+        object.insert(
+            "@type".to_string(),
+            match <[Value; 1]>::try_from(types) {
+                Ok([single]) => single,
+                Err(types) => Value::Array(types),
+            },
+        );
+    }
+    if let Some(id) = attr("itemid") {
+        object.insert("@id".to_string(), Value::String(id.to_string()));
+    }
+
+    // Properties are the `itemprop` elements under the item, and under the
+    // elements `itemref` names, without entering nested items
+    let mut pending: Vec<ElementRef> = scope.children().filter_map(ElementRef::wrap).collect();
+    for id in attr("itemref")
+        .into_iter()
+        .flat_map(str::split_ascii_whitespace)
+    {
+        let referenced = document
+            .root_element()
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .find(|element| element.value().id() == Some(id));
+        // A reference to the item itself or an ancestor would loop
+        if let Some(element) =
+            referenced.filter(|r| !scope.ancestors().chain([*scope]).any(|n| n.id() == r.id()))
+        {
+            pending.push(element);
+        }
+    }
+    pending.reverse();
+
+    while let Some(element) = pending.pop() {
+        let is_item = element.value().attr("itemscope").is_some();
+        if let Some(names) = element.value().attr("itemprop") {
+            let value = if is_item {
+                (depth < MAX_DEPTH).then(|| item(document, element, base_url, depth + 1))
+            } else {
+                Some(Value::String(property_value(element, base_url)))
+            };
+            for name in names.split_ascii_whitespace() {
+                let Some(value) = value.clone() else { break };
+                match object.get_mut(name) {
+                    Some(Value::Array(values)) => values.push(value),
+                    Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+                    None => {
+                        object.insert(name.to_string(), value);
+                    }
+                }
+            }
+        }
+        if !is_item {
+            let children: Vec<_> = element.children().filter_map(ElementRef::wrap).collect();
+            pending.extend(children.into_iter().rev());
+        }
+    }
+    Value::Object(object)
+}
+
+/// The value of a non-item property, per the element it is on.
+fn property_value(element: ElementRef, base_url: Option<&Url>) -> String {
+    let element_value = element.value();
+    let attr = |name: &str| element_value.attr(name).unwrap_or_default().trim();
+    let url = |name: &str| {
+        let value = attr(name);
+        match base_url.map(|base| base.join(value)) {
+            Some(Ok(resolved)) if !value.is_empty() => resolved.to_string(),
+            _ => value.to_string(),
+        }
+    };
+    match element_value.name() {
+        "meta" => attr("content").to_string(),
+        "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => url("src"),
+        "a" | "area" | "link" => url("href"),
+        "object" => url("data"),
+        "data" | "meter" => attr("value").to_string(),
+        "time" if element_value.attr("datetime").is_some() => attr("datetime").to_string(),
+        _ => collapse_whitespace(&element.text().collect::<String>()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn parse(html: &str) -> Vec<SchemaOrg> {
+        let base = Url::parse("https://example.com/shop/").unwrap();
+        HtmlInfo::extract_microdata(&Html::parse_document(html), Some(&base))
+    }
+
+    #[test]
+    fn test_nested_items_and_values() {
+        let items = parse(
+            r#"<div itemscope itemtype="https://schema.org/Product" itemid="urn:sku:1">
+                <h1 itemprop="name">  Steel
+                    Kettle </h1>
+                <img itemprop="image" src="kettle.jpg">
+                <a itemprop="url" href="/kettle">Kettle</a>
+                <div itemprop="offers" itemscope itemtype="http://schema.org/Offer">
+                    <meta itemprop="priceCurrency" content="EUR">
+                    <data itemprop="price" value="39.90">€39.90</data>
+                    <time itemprop="priceValidUntil" datetime="2026-12-31">New Year's Eve</time>
+                </div>
+                <span itemprop="keywords category">kitchen</span>
+                <span itemprop="keywords">steel</span>
+            </div>"#,
+        );
+        assert_eq!(items.len(), 1, "nested items are not top-level");
+        assert_eq!(items[0].schema_type, "Product");
+        assert_eq!(
+            items[0].value,
+            json!({
+                "@type": "Product",
+                "@id": "urn:sku:1",
+                "name": "Steel Kettle",
+                "image": "https://example.com/shop/kettle.jpg",
+                "url": "https://example.com/kettle",
+                "offers": {
+                    "@type": "Offer",
+                    "priceCurrency": "EUR",
+                    "price": "39.90",
+                    "priceValidUntil": "2026-12-31",
+                },
+                "keywords": ["kitchen", "steel"],
+                "category": "kitchen",
+            })
+        );
+    }
+
+    #[test]
+    fn test_itemref_and_untyped_items() {
+        let items = parse(
+            r#"<div itemscope itemtype="https://schema.org/Event" itemref="venue loop"
+                    id="loop">
+                <span itemprop="name">Jazz Night</span>
+            </div>
+            <p id="venue" itemprop="location">Blue Note</p>
+            <div itemscope><span itemprop="name">No type</span></div>"#,
+        );
+        assert_eq!(items.len(), 1, "items without itemtype are skipped");
+        assert_eq!(items[0].get_str("name"), Some("Jazz Night"));
+        assert_eq!(items[0].get_str("location"), Some("Blue Note"));
+    }
+}
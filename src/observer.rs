@@ -0,0 +1,144 @@
+//! Fetch lifecycle hooks
+//!
+//! Implement [`FetchObserver`] and register it with
+//! [`WebpageClient::with_observer`](crate::WebpageClient::with_observer) to collect
+//! metrics, write audit logs, or block requests without forking the client.
+
+use std::sync::Arc;
+
+use crate::WebpageInfo;
+use crate::error::{Error, Result};
+use crate::http::HttpInfo;
+
+/// Callbacks invoked at each stage of a fetch.
+///
+/// All methods have no-op defaults, so implementations only override the
+/// events they care about. Observers are shared between concurrent fetches and
+/// must be thread-safe.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use webpage_info::{Error, FetchObserver, Result};
+///
+/// #[derive(Default)]
+/// struct BlockAdmin {
+///     requests: AtomicUsize,
+/// }
+///
+/// impl FetchObserver for BlockAdmin {
+///     fn on_request(&self, url: &str) -> Result<()> {
+///         self.requests.fetch_add(1, Ordering::Relaxed);
+///         if url.contains("/admin") {
+///             return Err(Error::Aborted(format!("refusing to fetch {url}")));
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait FetchObserver: Send + Sync {
+    /// Called before each request is sent, including requests for redirect targets.
+    ///
+    /// Returning an error aborts the fetch with that error.
+    fn on_request(&self, _url: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when a redirect from `from` to `to` is about to be followed.
+    fn on_redirect(&self, _from: &str, _to: &str, _status: u16) {}
+
+    /// Called after each chunk of the response body is received.
+    ///
+    /// `content_length` is the size announced by the server, if any. Returning an
+    /// error stops the download and fails the fetch with that error.
+    fn on_progress(
+        &self,
+        _url: &str,
+        _downloaded: u64,
+        _content_length: Option<u64>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called once the final response has been downloaded.
+    fn on_response(&self, _info: &HttpInfo) {}
+
+    /// Called before a failed request is retried. `attempt` is the upcoming attempt number.
+    fn on_retry(&self, _url: &str, _attempt: u32, _error: &Error) {}
+
+    /// Called after the page has been fetched and parsed successfully.
+    fn on_parse_complete(&self, _info: &WebpageInfo) {}
+}
+
+/// Lets callers keep a handle on an observer (e.g. to read collected metrics).
+impl<T: FetchObserver + ?Sized> FetchObserver for Arc<T> {
+    fn on_request(&self, url: &str) -> Result<()> {
+        (**self).on_request(url)
+    }
+
+    fn on_redirect(&self, from: &str, to: &str, status: u16) {
+        (**self).on_redirect(from, to, status)
+    }
+
+    fn on_progress(&self, url: &str, downloaded: u64, content_length: Option<u64>) -> Result<()> {
+        (**self).on_progress(url, downloaded, content_length)
+    }
+
+    fn on_response(&self, info: &HttpInfo) {
+        (**self).on_response(info)
+    }
+
+    fn on_retry(&self, url: &str, attempt: u32, error: &Error) {
+        (**self).on_retry(url, attempt, error)
+    }
+
+    fn on_parse_complete(&self, info: &WebpageInfo) {
+        (**self).on_parse_complete(info)
+    }
+}
+
+/// The observers registered on a client, notified in registration order.
+#[derive(Clone, Default)]
+pub(crate) struct Observers(Vec<Arc<dyn FetchObserver>>);
+
+impl Observers {
+    pub(crate) fn push(&mut self, observer: Arc<dyn FetchObserver>) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub(crate) fn on_request(&self, url: &str) -> Result<()> {
+        self.0.iter().try_for_each(|o| o.on_request(url))
+    }
+
+    pub(crate) fn on_redirect(&self, from: &str, to: &str, status: u16) {
+        self.0.iter().for_each(|o| o.on_redirect(from, to, status));
+    }
+
+    pub(crate) fn on_progress(
+        &self,
+        url: &str,
+        downloaded: u64,
+        content_length: Option<u64>,
+    ) -> Result<()> {
+        self.0
+            .iter()
+            .try_for_each(|o| o.on_progress(url, downloaded, content_length))
+    }
+
+    pub(crate) fn on_response(&self, info: &HttpInfo) {
+        self.0.iter().for_each(|o| o.on_response(info));
+    }
+
+    pub(crate) fn on_retry(&self, url: &str, attempt: u32, error: &Error) {
+        self.0.iter().for_each(|o| o.on_retry(url, attempt, error));
+    }
+
+    pub(crate) fn on_parse_complete(&self, info: &WebpageInfo) {
+        self.0.iter().for_each(|o| o.on_parse_complete(info));
+    }
+}
@@ -0,0 +1,343 @@
+//! oEmbed discovery and fetching
+//!
+//! Video hosts, social networks, and publishing platforms describe how to
+//! embed a page through [oEmbed](https://oembed.com/): the page advertises a
+//! provider endpoint with `<link rel="alternate" type="application/json+oembed">`
+//! (or the same relation in a `Link` header), and the endpoint returns the
+//! embed HTML, dimensions, and a thumbnail. [`HtmlInfo::oembed_url`] holds the
+//! discovered endpoint, and [`WebpageInfo::fetch_oembed`] retrieves it.
+//!
+//! Only JSON endpoints are discovered; the XML format is rarely the only one
+//! offered.
+
+#[cfg(feature = "dom")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "dom")]
+use scraper::{Html, Selector};
+#[cfg(feature = "http")]
+use serde::{Deserialize, Deserializer, Serialize};
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+use url::Url;
+
+#[cfg(feature = "http")]
+use crate::WebpageInfo;
+#[cfg(feature = "http")]
+use crate::client::WebpageClient;
+#[cfg(feature = "http")]
+use crate::error::{Error, Result};
+use crate::html::HtmlInfo;
+
+/// Media type of a JSON oEmbed endpoint.
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+const OEMBED_JSON_TYPE: &str = "application/json+oembed";
+
+#[cfg(feature = "dom")]
+fn alternate_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("link[rel][href][type]").unwrap())
+}
+
+/// The kind of an oEmbed response.
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum OembedType {
+    /// A static image at [`Oembed::url`]
+    Photo,
+    /// A playable video in [`Oembed::html`]
+    Video,
+    /// No embed, only the generic fields
+    #[default]
+    Link,
+    /// Rich HTML content in [`Oembed::html`]
+    Rich,
+    /// A type this crate does not know
+    #[serde(other)]
+    Unknown,
+}
+
+/// An oEmbed response.
+///
+/// Dimensions and `cache_age` are accepted as numbers or numeric strings,
+/// since providers send both.
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct Oembed {
+    /// Response type
+    #[serde(rename = "type")]
+    pub kind: OembedType,
+
+    /// oEmbed version, `"1.0"`
+    #[serde(deserialize_with = "lenient_string")]
+    pub version: Option<String>,
+
+    /// Title of the resource
+    pub title: Option<String>,
+
+    /// Name of the author
+    pub author_name: Option<String>,
+
+    /// URL of the author
+    pub author_url: Option<String>,
+
+    /// Name of the provider, e.g. `"YouTube"`
+    pub provider_name: Option<String>,
+
+    /// URL of the provider
+    pub provider_url: Option<String>,
+
+    /// Suggested cache lifetime in seconds
+    #[serde(deserialize_with = "lenient_u64")]
+    pub cache_age: Option<u64>,
+
+    /// Thumbnail image URL
+    pub thumbnail_url: Option<String>,
+
+    /// Thumbnail width in pixels
+    #[serde(deserialize_with = "lenient_u64")]
+    pub thumbnail_width: Option<u64>,
+
+    /// Thumbnail height in pixels
+    #[serde(deserialize_with = "lenient_u64")]
+    pub thumbnail_height: Option<u64>,
+
+    /// Image URL of a `photo` response
+    pub url: Option<String>,
+
+    /// Embed HTML of a `video` or `rich` response
+    pub html: Option<String>,
+
+    /// Width of the embed in pixels
+    #[serde(deserialize_with = "lenient_u64")]
+    pub width: Option<u64>,
+
+    /// Height of the embed in pixels
+    #[serde(deserialize_with = "lenient_u64")]
+    pub height: Option<u64>,
+}
+
+#[cfg(feature = "http")]
+impl Oembed {
+    /// Parse an oEmbed JSON response.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{Oembed, OembedType};
+    ///
+    /// let oembed = Oembed::from_json(
+    ///     r#"{"type": "video", "version": "1.0", "html": "<iframe></iframe>",
+    ///         "width": 480, "height": "270"}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(oembed.kind, OembedType::Video);
+    /// assert_eq!((oembed.width, oembed.height), (Some(480), Some(270)));
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+#[cfg(feature = "http")]
+impl WebpageInfo {
+    /// Fetch and parse the page's oEmbed response with `client`.
+    ///
+    /// Returns `None` if the page has no [`HtmlInfo::oembed_url`]. An error
+    /// status from the endpoint fails with [`Error::ErrorStatus`], and a body
+    /// that is not oEmbed JSON with [`Error::Json`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use webpage_info::{HttpOptions, WebpageClient};
+    ///
+    /// # async fn run() -> webpage_info::Result<()> {
+    /// let client = WebpageClient::new(HttpOptions::new())?;
+    /// let page = client.fetch("https://vimeo.com/76979871").await?;
+    /// if let Some(oembed) = page.fetch_oembed(&client).await? {
+    ///     println!("{:?}", oembed.html);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_oembed(&self, client: &WebpageClient) -> Result<Option<Oembed>> {
+        let Some(url) = &self.html.oembed_url else {
+            return Ok(None);
+        };
+        let response = client.fetch_raw(url).await?;
+        if let Some(page) = response.info.error_page() {
+            return Err(Error::ErrorStatus {
+                url: response.info.url,
+                page: Box::new(page),
+            });
+        }
+        Oembed::from_json(&response.info.body).map(Some)
+    }
+}
+
+impl HtmlInfo {
+    /// Find the first JSON oEmbed `<link>`.
+    #[cfg(feature = "dom")]
+    pub(crate) fn extract_oembed(&mut self, document: &Html, base_url: Option<&Url>) {
+        for element in document.select(alternate_selector()) {
+            let el = element.value();
+            self.add_oembed_link(base_url, |name| el.attr(name));
+            if self.oembed_url.is_some() {
+                break;
+            }
+        }
+    }
+
+    /// Record a `<link>` as the oEmbed endpoint if it is the first JSON one.
+    #[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+    pub(crate) fn add_oembed_link<'a>(
+        &mut self,
+        base_url: Option<&Url>,
+        attr: impl Fn(&str) -> Option<&'a str>,
+    ) {
+        let alternate = attr("rel").is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("alternate"))
+        });
+        if self.oembed_url.is_some() || !alternate || !attr("type").is_some_and(is_oembed_type) {
+            return;
+        }
+        let Some(href) = attr("href").map(str::trim).filter(|href| !href.is_empty()) else {
+            return;
+        };
+        self.oembed_url = Some(match base_url.map(|base| base.join(href)) {
+            Some(Ok(url)) => url.to_string(),
+            _ => href.to_string(),
+        });
+    }
+}
+
+/// Whether a `type` attribute declares a JSON oEmbed endpoint.
+#[cfg(any(feature = "dom", feature = "lightweight", feature = "streaming"))]
+pub(crate) fn is_oembed_type(media_type: &str) -> bool {
+    media_type.trim().eq_ignore_ascii_case(OEMBED_JSON_TYPE)
+}
+
+/// A number given as a JSON number or a numeric string.
+#[cfg(feature = "http")]
+fn lenient_u64<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<u64>, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Number(number) => number
+            .as_u64()
+            .or_else(|| number.as_f64().filter(|n| *n >= 0.0).map(|n| n as u64)),
+        serde_json::Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+/// A string given as a JSON string or a number.
+#[cfg(feature = "http")]
+fn lenient_string<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<String>, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(string) => Some(string),
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "dom", feature = "http"))]
+    use super::*;
+
+    #[cfg(feature = "dom")]
+    #[test]
+    fn test_oembed_discovery() {
+        let html = r#"<head>
+            <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+            <link rel="alternate" type="text/xml+oembed" href="/oembed?format=xml">
+            <link rel="alternate" type="Application/JSON+oEmbed"
+                  href="/oembed?url=https%3A%2F%2Fexample.com%2Fv%2F1&amp;format=json">
+            <link rel="alternate" type="application/json+oembed" href="/second">
+        </head>"#;
+        let info = HtmlInfo::from_string(html, Some("https://example.com/v/1")).unwrap();
+        assert_eq!(
+            info.oembed_url.as_deref(),
+            Some("https://example.com/oembed?url=https%3A%2F%2Fexample.com%2Fv%2F1&format=json")
+        );
+
+        let info = HtmlInfo::from_string("<link rel=alternate href=/feed>", None).unwrap();
+        assert_eq!(info.oembed_url, None);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_lenient_fields() {
+        let oembed = Oembed::from_json(
+            r#"{"type": "photo", "version": 1.0, "url": "https://example.com/p.jpg",
+                "width": "640", "height": 480.0, "cache_age": "bogus",
+                "thumbnail_width": null, "extra": true}"#,
+        )
+        .unwrap();
+        assert_eq!(oembed.kind, OembedType::Photo);
+        assert_eq!(oembed.version.as_deref(), Some("1.0"));
+        assert_eq!((oembed.width, oembed.height), (Some(640), Some(480)));
+        assert_eq!(oembed.cache_age, None);
+        assert_eq!(oembed.thumbnail_width, None);
+
+        let oembed = Oembed::from_json(r#"{"type": "audio"}"#).unwrap();
+        assert_eq!(oembed.kind, OembedType::Unknown);
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_fetch_oembed() {
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use crate::HttpOptions;
+
+        let server = MockServer::start().await;
+        Mock::given(path("/watch"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"<head><link rel="alternate" type="application/json+oembed"
+                    href="/oembed?format=json"></head>"#,
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(path("/oembed"))
+            .and(query_param("format", "json"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"type": "video", "version": "1.0", "provider_name": "Example",
+                    "html": "<iframe src=\"/embed/1\"></iframe>", "width": 560, "height": 315,
+                    "thumbnail_url": "https://example.com/t.jpg"}"#,
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(path("/plain"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<html></html>", "text/html"))
+            .mount(&server)
+            .await;
+
+        let client = WebpageClient::new(HttpOptions::new().block_private_ips(false)).unwrap();
+        let page = client
+            .fetch(&format!("{}/watch", server.uri()))
+            .await
+            .unwrap();
+        let oembed = page.fetch_oembed(&client).await.unwrap().unwrap();
+        assert_eq!(oembed.kind, OembedType::Video);
+        assert_eq!(oembed.provider_name.as_deref(), Some("Example"));
+        assert_eq!((oembed.width, oembed.height), (Some(560), Some(315)));
+        assert!(oembed.html.unwrap().contains("/embed/1"));
+
+        let page = client
+            .fetch(&format!("{}/plain", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(page.fetch_oembed(&client).await.unwrap(), None);
+    }
+}
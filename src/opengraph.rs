@@ -4,6 +4,7 @@
 
 use std::collections::HashMap;
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 // Security limit for media collections
@@ -14,7 +15,10 @@ const MAX_MEDIA_ITEMS: usize = 100;
 /// OpenGraph is a protocol for structured data in web pages, originally
 /// developed by Facebook. It allows websites to control how content appears
 /// when shared on social media platforms.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Opengraph {
     /// The type of object (e.g., "website", "article", "video.movie")
     pub og_type: Option<String>,
@@ -51,7 +55,10 @@ pub struct Opengraph {
 }
 
 /// Media object (image, video, or audio) in OpenGraph.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct OpengraphMedia {
     /// URL of the media
     pub url: String,
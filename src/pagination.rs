@@ -0,0 +1,379 @@
+//! Pagination detection
+//!
+//! Crawlers continuing through archives need the next page of a listing. Pages
+//! announce it in several ways, checked in this order by
+//! [`HtmlInfo::pagination`]:
+//!
+//! 1. `<link rel="next">` in the head ([`HtmlInfo::next_url`])
+//! 2. an anchor with `rel="next"`
+//! 3. an anchor reading "Next", "Older posts", `»`, and similar
+//! 4. numbered page links (`?page=3`, `/page/3/`) one past the current page
+//!
+//! Pages that load more items in place instead are flagged as
+//! [`Pagination::infinite_scroll`].
+
+use std::sync::OnceLock;
+
+#[cfg(feature = "dom")]
+use scraper::{Html, Selector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::consistency::collapse_whitespace;
+use crate::html::{HtmlInfo, Link};
+
+/// Query parameters that hold a page number.
+const PAGE_PARAMETERS: &[&str] = &["page", "p", "pg", "paged", "pagenum"];
+
+/// Link texts of a next-page link, lowercased with punctuation removed.
+const NEXT_TEXTS: &[&str] = &["next", "next page", "older posts", "older entries", "older"];
+
+/// Link texts of a previous-page link.
+const PREV_TEXTS: &[&str] = &[
+    "prev",
+    "previous",
+    "previous page",
+    "newer posts",
+    "newer entries",
+    "newer",
+];
+
+/// Arrow-only link texts.
+const NEXT_ARROWS: &[&str] = &["»", "›", "→", ">", ">>"];
+const PREV_ARROWS: &[&str] = &["«", "‹", "←", "<", "<<"];
+
+/// Button and link texts that load more items in place.
+#[cfg(feature = "dom")]
+const LOAD_MORE_TEXTS: &[&str] = &["load more", "show more", "more posts", "see more"];
+
+/// How the next page was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PaginationSource {
+    /// `<link rel="next">`
+    LinkRel,
+    /// `<a rel="next">`
+    AnchorRel,
+    /// Link text such as "Next" or `»`
+    LinkText,
+    /// A numbered page link one past the current page
+    PageNumber,
+}
+
+/// Pagination of an archive or listing page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Pagination {
+    /// URL of the next page
+    pub next: Option<String>,
+
+    /// How `next` was found
+    pub next_source: Option<PaginationSource>,
+
+    /// URL of the previous page
+    pub prev: Option<String>,
+
+    /// The current page number, when it can be inferred
+    pub current_page: Option<u32>,
+
+    /// Numbered page links among the internal links, by page number
+    pub pages: Vec<(u32, String)>,
+
+    /// Whether the page loads more items in place (infinite scroll or a
+    /// "Load more" button)
+    pub infinite_scroll: bool,
+}
+
+impl HtmlInfo {
+    /// The page's pagination, or `None` if it shows no signs of any.
+    ///
+    /// Internal links are relative ones and those on the canonical URL's host.
+    /// The current page number comes from the canonical URL, or from the
+    /// `rel` links' page numbers, and is 1 otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{HtmlInfo, PaginationSource};
+    ///
+    /// let html = r#"<a href="/blog?page=1">1</a> <a href="/blog?page=2">2</a>
+    ///     <a href="/blog?page=3">3</a>"#;
+    /// let info = HtmlInfo::from_string(html, Some("https://example.com/blog")).unwrap();
+    /// # #[cfg(feature = "links")]
+    /// # {
+    /// let pagination = info.pagination().unwrap();
+    /// assert_eq!(pagination.next.as_deref(), Some("https://example.com/blog?page=2"));
+    /// assert_eq!(pagination.next_source, Some(PaginationSource::PageNumber));
+    /// assert_eq!(pagination.pages.len(), 3);
+    /// # }
+    /// ```
+    pub fn pagination(&self) -> Option<Pagination> {
+        let host = self
+            .canonical_url
+            .as_deref()
+            .and_then(|url| Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_string));
+        let internal: Vec<&Link> = self
+            .links
+            .iter()
+            .filter(|link| match Url::parse(&link.url) {
+                Ok(url) => host.is_none() || url.host_str() == host.as_deref(),
+                Err(_) => true,
+            })
+            .collect();
+
+        let mut pages: Vec<(u32, String)> = Vec::new();
+        for link in &internal {
+            if let Some(number) = page_number(&link.url)
+                && !pages.iter().any(|(n, _)| *n == number)
+            {
+                pages.push((number, link.url.to_string()));
+            }
+        }
+        pages.sort_by_key(|(number, _)| *number);
+
+        let current_page = self
+            .canonical_url
+            .as_deref()
+            .and_then(page_number)
+            .or_else(|| {
+                let prev = self.prev_url.as_deref().and_then(page_number)?;
+                prev.checked_add(1)
+            })
+            .or_else(|| {
+                let next = self.next_url.as_deref().and_then(page_number)?;
+                next.checked_sub(1).filter(|&page| page > 0)
+            })
+            .or_else(|| (!pages.is_empty()).then_some(1));
+        let numbered = |offset: i64| {
+            let number = i64::from(current_page?) + offset;
+            pages
+                .iter()
+                .find(|(n, _)| i64::from(*n) == number)
+                .map(|(_, url)| url.clone())
+        };
+        let by_rel = |rel: &[&str]| {
+            internal
+                .iter()
+                .find(|link| {
+                    link.rel.as_deref().is_some_and(|rels| {
+                        rels.split_ascii_whitespace()
+                            .any(|r| rel.iter().any(|rel| r.eq_ignore_ascii_case(rel)))
+                    })
+                })
+                .map(|link| link.url.to_string())
+        };
+        let by_text = |texts: &[&str], arrows: &[&str]| {
+            internal
+                .iter()
+                .find(|link| is_pagination_text(&link.text, texts, arrows))
+                .map(|link| link.url.to_string())
+        };
+
+        let (next, next_source) = if let Some(url) = self.next_url.clone() {
+            (Some(url), Some(PaginationSource::LinkRel))
+        } else if let Some(url) = by_rel(&["next"]) {
+            (Some(url), Some(PaginationSource::AnchorRel))
+        } else if let Some(url) = by_text(NEXT_TEXTS, NEXT_ARROWS) {
+            (Some(url), Some(PaginationSource::LinkText))
+        } else if let Some(url) = numbered(1) {
+            (Some(url), Some(PaginationSource::PageNumber))
+        } else {
+            (None, None)
+        };
+        let prev = self
+            .prev_url
+            .clone()
+            .or_else(|| by_rel(&["prev", "previous"]))
+            .or_else(|| by_text(PREV_TEXTS, PREV_ARROWS))
+            .or_else(|| numbered(-1));
+
+        if next.is_none() && prev.is_none() && pages.is_empty() && !self.infinite_scroll {
+            return None;
+        }
+        Some(Pagination {
+            next,
+            next_source,
+            prev,
+            current_page,
+            pages,
+            infinite_scroll: self.infinite_scroll,
+        })
+    }
+
+    /// Fill `next_url`, `prev_url`, and `infinite_scroll`.
+    #[cfg(feature = "dom")]
+    pub(crate) fn extract_pagination(&mut self, document: &Html, base_url: Option<&Url>) {
+        let href = |selector: &Selector| {
+            let href = document
+                .select(selector)
+                .find_map(|element| element.value().attr("href"))?
+                .trim();
+            if href.is_empty() {
+                return None;
+            }
+            Some(match base_url.map(|base| base.join(href)) {
+                Some(Ok(url)) => url.to_string(),
+                _ => href.to_string(),
+            })
+        };
+        self.next_url = href(next_selector());
+        self.prev_url = href(prev_selector());
+        self.infinite_scroll = document.select(infinite_scroll_selector()).next().is_some()
+            || document.select(load_more_selector()).any(|element| {
+                let text = collapse_whitespace(&element.text().collect::<String>());
+                LOAD_MORE_TEXTS.contains(&text.to_lowercase().as_str())
+            });
+    }
+}
+
+#[cfg(feature = "dom")]
+fn next_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse(r#"link[rel~="next"]"#).unwrap())
+}
+
+#[cfg(feature = "dom")]
+fn prev_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse(r#"link[rel~="prev"], link[rel~="previous"]"#).unwrap())
+}
+
+#[cfg(feature = "dom")]
+fn infinite_scroll_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| {
+        Selector::parse(
+            "[data-infinite-scroll], [data-infinite], .infinite-scroll, .load-more, [data-load-more]",
+        )
+        .unwrap()
+    })
+}
+
+#[cfg(feature = "dom")]
+fn load_more_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("button, a[href]").unwrap())
+}
+
+/// The page number of a URL: a page query parameter, or `/page/N` in the path.
+fn page_number(url: &str) -> Option<u32> {
+    static BASE: OnceLock<Url> = OnceLock::new();
+    let base = BASE.get_or_init(|| Url::parse("http://localhost/").unwrap());
+    let url = base.join(url).ok()?;
+    url.query_pairs()
+        .find(|(name, _)| PAGE_PARAMETERS.contains(&name.as_ref()))
+        .and_then(|(_, value)| value.parse().ok())
+        .or_else(|| {
+            let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+            segments
+                .windows(2)
+                .rev()
+                .find(|pair| pair[0].eq_ignore_ascii_case("page"))
+                .and_then(|pair| pair[1].parse().ok())
+        })
+        .filter(|&number| number > 0)
+}
+
+/// Whether link text names a pagination direction.
+fn is_pagination_text(text: &str, texts: &[&str], arrows: &[&str]) -> bool {
+    let text = text.trim();
+    if arrows.contains(&text) {
+        return true;
+    }
+    let words: String = text
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect();
+    texts.contains(&collapse_whitespace(&words).as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_number() {
+        assert_eq!(page_number("https://example.com/blog?page=3"), Some(3));
+        assert_eq!(page_number("/archive/page/12/"), Some(12));
+        assert_eq!(page_number("?paged=2&sort=new"), Some(2));
+        assert_eq!(page_number("/blog?page=0"), None);
+        assert_eq!(page_number("/pages/about"), None);
+    }
+
+    #[test]
+    fn test_pagination_text() {
+        assert!(is_pagination_text(
+            " Next \u{203a}",
+            NEXT_TEXTS,
+            NEXT_ARROWS
+        ));
+        assert!(is_pagination_text("\u{ab}", PREV_TEXTS, PREV_ARROWS));
+        assert!(is_pagination_text("Older Posts", NEXT_TEXTS, NEXT_ARROWS));
+        assert!(!is_pagination_text(
+            "Next steps for your team",
+            NEXT_TEXTS,
+            NEXT_ARROWS
+        ));
+    }
+
+    #[cfg(feature = "dom")]
+    #[test]
+    fn test_rel_links_and_infinite_scroll() {
+        let html = r#"<link rel="next" href="/news/page/4/">
+            <link rel="prev" href="/news/page/2/">
+            <div class="feed"><button> Load   more </button></div>"#;
+
+        let info = HtmlInfo::from_string(html, Some("https://example.com/news/page/3/")).unwrap();
+        let pagination = info.pagination().unwrap();
+        assert_eq!(
+            pagination.next.as_deref(),
+            Some("https://example.com/news/page/4/")
+        );
+        assert_eq!(pagination.next_source, Some(PaginationSource::LinkRel));
+        assert_eq!(
+            pagination.prev.as_deref(),
+            Some("https://example.com/news/page/2/")
+        );
+        assert_eq!(pagination.current_page, Some(3));
+        assert!(pagination.infinite_scroll);
+
+        let info = HtmlInfo::from_string("<p>No pages</p>", None).unwrap();
+        assert!(info.pagination().is_none());
+    }
+
+    #[cfg(all(feature = "dom", feature = "links"))]
+    #[test]
+    fn test_anchor_signals() {
+        let html = r#"<link rel="canonical" href="https://example.com/blog?page=2">
+            <a href="https://other.example/blog?page=3">Partner</a>
+            <a href="/blog?page=1">Newer posts</a>
+            <a href="/blog?page=3">Older posts</a>"#;
+
+        let info = HtmlInfo::from_string(html, Some("https://example.com/blog?page=2")).unwrap();
+        let pagination = info.pagination().unwrap();
+        assert_eq!(
+            pagination.next.as_deref(),
+            Some("https://example.com/blog?page=3")
+        );
+        assert_eq!(pagination.next_source, Some(PaginationSource::LinkText));
+        assert_eq!(
+            pagination.prev.as_deref(),
+            Some("https://example.com/blog?page=1")
+        );
+        assert_eq!(pagination.current_page, Some(2));
+        assert_eq!(pagination.pages.len(), 2);
+
+        let html = r#"<a href="/list?page=2" rel="next">Continue</a>"#;
+        let info = HtmlInfo::from_string(html, Some("https://example.com/list")).unwrap();
+        assert_eq!(
+            info.pagination().unwrap().next_source,
+            Some(PaginationSource::AnchorRel)
+        );
+    }
+}
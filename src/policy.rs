@@ -0,0 +1,255 @@
+//! Request and response policy hooks
+//!
+//! A request policy registered with [`WebpageClient::with_request_policy`] sees
+//! every URL before it is requested, including redirect targets, checked links,
+//! and the requests a rendering transport makes, and can allow, rewrite, or
+//! deny it. A response policy registered with
+//! [`WebpageClient::with_response_policy`] sees the final response before it is
+//! parsed and can reject it. Denials fail the fetch with
+//! [`Error::RequestDenied`] or [`Error::ResponseRejected`].
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::client::WebpageClient;
+use crate::error::{Error, Result};
+use crate::http::HttpInfo;
+
+/// The verdict of a request policy on a URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestDecision {
+    /// Request the URL as is
+    Allow,
+    /// Request this URL instead
+    Rewrite(String),
+    /// Fail the fetch with [`Error::RequestDenied`] and this reason
+    Deny(String),
+}
+
+/// The verdict of a response policy on a downloaded response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseDecision {
+    /// Parse the response
+    Accept,
+    /// Fail the fetch with [`Error::ResponseRejected`] and this reason
+    Reject(String),
+}
+
+type RequestPolicyFn = dyn Fn(&str) -> RequestDecision + Send + Sync;
+type ResponsePolicy = dyn Fn(&HttpInfo) -> ResponseDecision + Send + Sync;
+
+/// A client's request policy, handed to
+/// [`Transport::fetch_with_policy`](crate::Transport::fetch_with_policy) so
+/// transports that make requests of their own can apply it.
+#[derive(Clone, Default)]
+pub struct RequestPolicy(Option<Arc<RequestPolicyFn>>);
+
+impl RequestPolicy {
+    /// The policy's decision on `url`; [`RequestDecision::Allow`] if the client
+    /// has no request policy.
+    pub fn decide(&self, url: &str) -> RequestDecision {
+        self.0
+            .as_ref()
+            .map_or(RequestDecision::Allow, |policy| policy(url))
+    }
+}
+
+impl fmt::Debug for RequestPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RequestPolicy")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+/// The policies registered on a client.
+#[derive(Clone, Default)]
+pub(crate) struct Policies {
+    request: RequestPolicy,
+    response: Option<Arc<ResponsePolicy>>,
+}
+
+impl fmt::Debug for Policies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Policies")
+            .field("request", &self.request.0.is_some())
+            .field("response", &self.response.is_some())
+            .finish()
+    }
+}
+
+impl Policies {
+    /// Apply the request policy to `url`, returning the URL to request.
+    pub(crate) fn check_request<'a>(&self, url: &'a str) -> Result<Cow<'a, str>> {
+        match self.request.decide(url) {
+            RequestDecision::Allow => Ok(Cow::Borrowed(url)),
+            RequestDecision::Rewrite(rewritten) => Ok(Cow::Owned(rewritten)),
+            RequestDecision::Deny(reason) => Err(Error::RequestDenied {
+                url: url.to_string(),
+                reason,
+            }),
+        }
+    }
+
+    /// The request policy, for transports that make requests of their own.
+    pub(crate) fn request(&self) -> RequestPolicy {
+        self.request.clone()
+    }
+
+    /// Apply the response policy to a downloaded response.
+    pub(crate) fn check_response(&self, info: &HttpInfo) -> Result<()> {
+        match self.response.as_ref().map(|policy| policy(info)) {
+            Some(ResponseDecision::Reject(reason)) => Err(Error::ResponseRejected {
+                url: info.url.clone(),
+                reason,
+            }),
+            Some(ResponseDecision::Accept) | None => Ok(()),
+        }
+    }
+}
+
+impl WebpageClient {
+    /// Decide on every URL before it is requested, including redirect targets.
+    ///
+    /// Rewritten URLs are still subject to SSRF protection. Replaces any
+    /// previously registered request policy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use webpage_info::{HttpOptions, RequestDecision, WebpageClient};
+    ///
+    /// let client = WebpageClient::new(HttpOptions::new())
+    ///     .unwrap()
+    ///     .with_request_policy(|url| {
+    ///         if url.starts_with("https://example.com/") {
+    ///             RequestDecision::Allow
+    ///         } else {
+    ///             RequestDecision::Deny("not on the allowlist".to_string())
+    ///         }
+    ///     });
+    /// ```
+    pub fn with_request_policy(
+        mut self,
+        policy: impl Fn(&str) -> RequestDecision + Send + Sync + 'static,
+    ) -> Self {
+        self.state.policies.request = RequestPolicy(Some(Arc::new(policy)));
+        self
+    }
+
+    /// Decide whether to parse a downloaded response, for example based on its
+    /// headers. Replaces any previously registered response policy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use webpage_info::{HttpOptions, ResponseDecision, WebpageClient};
+    ///
+    /// let client = WebpageClient::new(HttpOptions::new())
+    ///     .unwrap()
+    ///     .with_response_policy(|info| {
+    ///         let noindex = info.headers.iter().any(|(name, value)| {
+    ///             name == "x-robots-tag" && value.contains("noindex")
+    ///         });
+    ///         if noindex {
+    ///             ResponseDecision::Reject("X-Robots-Tag: noindex".to_string())
+    ///         } else {
+    ///             ResponseDecision::Accept
+    ///         }
+    ///     });
+    /// ```
+    pub fn with_response_policy(
+        mut self,
+        policy: impl Fn(&HttpInfo) -> ResponseDecision + Send + Sync + 'static,
+    ) -> Self {
+        self.state.policies.response = Some(Arc::new(policy));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::HttpOptions;
+
+    async fn server() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(path("/old"))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", "/private"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/page"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-robots-tag", "noindex")
+                    .set_body_raw("<title>Page</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn client() -> WebpageClient {
+        WebpageClient::new(HttpOptions::new().block_private_ips(false)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_policy_rewrites_and_denies() {
+        let server = server().await;
+        let client = client().with_request_policy(|url| {
+            if url.ends_with("/private") {
+                RequestDecision::Deny("private area".to_string())
+            } else if let Some(base) = url.strip_suffix("/moved") {
+                RequestDecision::Rewrite(format!("{base}/page"))
+            } else {
+                RequestDecision::Allow
+            }
+        });
+
+        let info = client
+            .fetch(&format!("{}/moved", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(info.html.title.as_deref(), Some("Page"));
+
+        // Redirect targets are checked too
+        let err = client
+            .fetch(&format!("{}/old", server.uri()))
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, Error::RequestDenied { reason, .. } if reason == "private area"));
+        assert_eq!(
+            err.url(),
+            Some(format!("{}/private", server.uri()).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_response_policy_rejects_before_parsing() {
+        let server = server().await;
+        let client = client().with_response_policy(|info| {
+            if info.headers.iter().any(|(name, _)| name == "x-robots-tag") {
+                ResponseDecision::Reject("noindex".to_string())
+            } else {
+                ResponseDecision::Accept
+            }
+        });
+
+        let err = client
+            .fetch(&format!("{}/page", server.uri()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ResponseRejected { .. }));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "response from {}/page rejected by policy: noindex",
+                server.uri()
+            )
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Prerender service transport
+//!
+//! [`PrerenderFetcher`] routes page fetches through a hosted prerender service
+//! (prerender.io, Rendertron, or a self-hosted equivalent) that runs the page's
+//! JavaScript and returns the rendered HTML, for environments that cannot run a
+//! local headless browser.
+
+use futures_util::StreamExt;
+use reqwest::Client;
+use reqwest::header::{self, HeaderName, HeaderValue};
+use url::Url;
+
+use crate::charset::CharsetInfo;
+use crate::error::{Error, Result};
+use crate::http::{HttpInfo, HttpOptions};
+use crate::snapshot::Snapshot;
+use crate::transport::{Transport, TransportFuture};
+
+/// A [`Transport`] that fetches rendered HTML from a prerender service.
+///
+/// The endpoint is a URL template: `{url}` is replaced with the page URL as is,
+/// `{encoded_url}` with the percent-encoded page URL, and a template without a
+/// placeholder gets the page URL appended, the prerender.io convention.
+///
+/// The service's status code and headers are reported as the page's own.
+/// Requests to the service itself are not SSRF-checked, since its address is
+/// configured by the caller; the page URL is still validated by the client.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use webpage_info::{HttpOptions, PrerenderFetcher, WebpageClient};
+///
+/// # #[tokio::main]
+/// # async fn main() -> webpage_info::Result<()> {
+/// let prerender = PrerenderFetcher::new("https://service.prerender.io/")?
+///     .header("X-Prerender-Token", "my-token");
+/// let client = WebpageClient::new(HttpOptions::new())?.with_transport(prerender);
+/// let info = client.fetch("https://example.org/app").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrerenderFetcher {
+    endpoint: String,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    client: Client,
+}
+
+impl PrerenderFetcher {
+    /// Create a fetcher for the given endpoint template.
+    ///
+    /// Returns [`Error::InvalidUrl`] if the template does not expand to a valid
+    /// URL.
+    pub fn new(endpoint: impl Into<String>) -> Result<Self> {
+        let endpoint = endpoint.into();
+        Url::parse(&expand(&endpoint, "https://example.com/"))
+            .map_err(|e| Error::InvalidUrl(format!("prerender endpoint '{}': {}", endpoint, e)))?;
+        let client = Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(Error::ClientBuild)?;
+        Ok(Self {
+            endpoint,
+            headers: Vec::new(),
+            client,
+        })
+    }
+
+    /// Send a header with every request to the service, typically its
+    /// authentication token (`X-Prerender-Token`, `Authorization`).
+    ///
+    /// Invalid header names or values are ignored.
+    pub fn header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        if let (Ok(name), Ok(value)) = (
+            name.as_ref().parse::<HeaderName>(),
+            value.as_ref().parse::<HeaderValue>(),
+        ) {
+            self.headers.push((name, value));
+        }
+        self
+    }
+
+    /// The service URL that renders `url`.
+    pub fn render_url(&self, url: &str) -> String {
+        expand(&self.endpoint, url)
+    }
+
+    async fn render(&self, url: &str, options: &HttpOptions) -> Result<HttpInfo> {
+        let failed = |e: reqwest::Error| Error::Transport {
+            url: url.to_string(),
+            reason: e.to_string(),
+        };
+        let mut request = self
+            .client
+            .get(self.render_url(url))
+            .timeout(options.timeout)
+            .header(header::USER_AGENT, &options.user_agent);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let response = request.send().await.map_err(failed)?;
+
+        let status_code = response.status().as_u16();
+        let raw_content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|v| (name.to_string(), v.to_string()))
+            })
+            .collect();
+
+        let mut bytes = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(failed)?;
+            let to_take = chunk.len().min(options.max_body_size - bytes.len());
+            bytes.extend_from_slice(&chunk[..to_take]);
+            if bytes.len() == options.max_body_size {
+                break;
+            }
+        }
+
+        let charset = CharsetInfo::detect(&bytes, raw_content_type.as_deref());
+        let body = charset.decode(&bytes);
+        let snapshot =
+            (options.snapshot).then(|| Snapshot::capture(url, status_code, &headers, bytes));
+
+        Ok(HttpInfo {
+            url: url.to_string(),
+            status_code,
+            headers,
+            informational: Vec::new(),
+            content_type: raw_content_type
+                .as_deref()
+                .map(|s| s.split(';').next().unwrap_or(s).trim().to_string()),
+            charset: Some(charset),
+            redirect_count: 0,
+            redirects: Vec::new(),
+            cookies: Vec::new(),
+            user_agent: options.user_agent.clone(),
+            body,
+            snapshot,
+        })
+    }
+}
+
+impl Transport for PrerenderFetcher {
+    fn fetch<'a>(&'a self, url: &'a str, options: &'a HttpOptions) -> TransportFuture<'a> {
+        Box::pin(self.render(url, options))
+    }
+}
+
+/// Substitute `url` into an endpoint template.
+fn expand(template: &str, url: &str) -> String {
+    if template.contains("{encoded_url}") {
+        let encoded: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
+        template.replace("{encoded_url}", &encoded)
+    } else if template.contains("{url}") {
+        template.replace("{url}", url)
+    } else {
+        format!("{}{}", template, url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::WebpageClient;
+    use wiremock::matchers::{header, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_endpoint_templates() {
+        let page = "https://example.com/a?b=1";
+        let fetcher = PrerenderFetcher::new("https://service.prerender.io/").unwrap();
+        assert_eq!(
+            fetcher.render_url(page),
+            "https://service.prerender.io/https://example.com/a?b=1"
+        );
+        let fetcher = PrerenderFetcher::new("http://render:3000/render?url={encoded_url}").unwrap();
+        assert_eq!(
+            fetcher.render_url(page),
+            "http://render:3000/render?url=https%3A%2F%2Fexample.com%2Fa%3Fb%3D1"
+        );
+        assert!(PrerenderFetcher::new("not a url/").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_through_service() {
+        let server = MockServer::start().await;
+        Mock::given(path("/render"))
+            .and(query_param("url", "https://example.com/app"))
+            .and(header("x-prerender-token", "secret"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<title>Rendered</title>", "text/html; charset=utf-8"),
+            )
+            .mount(&server)
+            .await;
+
+        let prerender =
+            PrerenderFetcher::new(format!("{}/render?url={{encoded_url}}", server.uri()))
+                .unwrap()
+                .header("X-Prerender-Token", "secret");
+        let client = WebpageClient::new(HttpOptions::new())
+            .unwrap()
+            .with_transport(prerender);
+
+        let info = client.fetch("https://example.com/app").await.unwrap();
+        assert_eq!(info.http.url, "https://example.com/app");
+        assert_eq!(info.http.content_type.as_deref(), Some("text/html"));
+        assert_eq!(info.html.title.as_deref(), Some("Rendered"));
+    }
+}
@@ -0,0 +1,368 @@
+//! Price signals
+//!
+//! Product pages state their price in up to four places: Schema.org `offers`,
+//! OpenGraph `product:price:*` properties, Twitter `twitter:label`/`twitter:data`
+//! pairs, and microdata `itemprop="price"` elements. [`HtmlInfo::prices`]
+//! merges them into one list of [`Price`]s so price trackers can compare the
+//! sources; [`HtmlInfo::microdata_prices`] holds the microdata ones, which are
+//! read during parsing.
+
+#[cfg(feature = "dom")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "dom")]
+use scraper::{ElementRef, Html, Selector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "schema-org")]
+use serde_json::Value;
+
+#[cfg(feature = "http")]
+use crate::WebpageInfo;
+use crate::html::HtmlInfo;
+#[cfg(feature = "schema-org")]
+use crate::schema_org::{one_or_many, text};
+
+/// Maximum number of [`HtmlInfo::microdata_prices`].
+#[cfg(feature = "dom")]
+const MAX_MICRODATA_PRICES: usize = 100;
+
+/// Currency symbols and the ISO 4217 codes they are read as.
+///
+/// `$` is read as US dollars; other dollar currencies need a code.
+const CURRENCY_SYMBOLS: &[(&str, &str)] = &[
+    ("US$", "USD"),
+    ("C$", "CAD"),
+    ("A$", "AUD"),
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₹", "INR"),
+    ("₩", "KRW"),
+    ("₽", "RUB"),
+    ("₺", "TRY"),
+];
+
+/// Where a [`Price`] was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PriceSource {
+    /// A Schema.org `Offer` or `AggregateOffer`
+    SchemaOrg,
+    /// OpenGraph `product:price:amount` or `og:price:amount`
+    OpenGraph,
+    /// A `twitter:data` value whose `twitter:label` mentions a price
+    Twitter,
+    /// A microdata `itemprop="price"` element
+    Microdata,
+}
+
+/// A price found on a page.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct Price {
+    /// The amount, with thousands separators and currency symbols removed
+    pub amount: f64,
+
+    /// ISO 4217 currency code (uppercase), if declared or given by a symbol
+    pub currency: Option<String>,
+
+    /// Where the price was found
+    pub source: PriceSource,
+}
+
+impl Price {
+    /// Parse a price such as `"19.99"`, `"$1,299.00"`, or `"1.299,00 €"`.
+    ///
+    /// The last `.` or `,` is the decimal separator when followed by one or
+    /// two digits; other separators group thousands. A currency symbol is
+    /// read as its ISO code.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{Price, PriceSource};
+    ///
+    /// let price = Price::parse("1.299,00 €", PriceSource::Twitter).unwrap();
+    /// assert_eq!(price.amount, 1299.0);
+    /// assert_eq!(price.currency.as_deref(), Some("EUR"));
+    /// assert!(Price::parse("Free", PriceSource::Twitter).is_none());
+    /// ```
+    pub fn parse(value: &str, source: PriceSource) -> Option<Self> {
+        let value = value.trim();
+        let currency = CURRENCY_SYMBOLS
+            .iter()
+            .find(|(symbol, _)| value.contains(symbol))
+            .map(|(_, code)| code.to_string())
+            .or_else(|| {
+                value
+                    .split(|c: char| !c.is_ascii_alphabetic())
+                    .find(|word| word.len() == 3 && word.bytes().all(|b| b.is_ascii_uppercase()))
+                    .map(str::to_string)
+            });
+        Some(Self {
+            amount: parse_amount(value)?,
+            currency,
+            source,
+        })
+    }
+
+    /// A price with a separately declared currency, which takes precedence.
+    fn with_currency(mut self, currency: Option<&str>) -> Self {
+        if let Some(currency) = currency.and_then(normalize_currency) {
+            self.currency = Some(currency);
+        }
+        self
+    }
+}
+
+impl HtmlInfo {
+    /// Prices from every source, in the order Schema.org, OpenGraph, Twitter,
+    /// microdata.
+    ///
+    /// Exact duplicates within a source are dropped; the same price from
+    /// different sources is kept once per source.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{HtmlInfo, PriceSource};
+    ///
+    /// let html = r#"<meta property="product:price:amount" content="24.50">
+    ///     <meta property="product:price:currency" content="usd">
+    ///     <meta name="twitter:label1" content="Price">
+    ///     <meta name="twitter:data1" content="$24.50">"#;
+    /// let prices = HtmlInfo::from_string(html, None).unwrap().prices();
+    /// assert_eq!(prices.len(), 2);
+    /// assert_eq!(prices[0].source, PriceSource::OpenGraph);
+    /// assert!(prices.iter().all(|p| p.amount == 24.5 && p.currency.as_deref() == Some("USD")));
+    /// ```
+    pub fn prices(&self) -> Vec<Price> {
+        let mut prices = Vec::new();
+        let mut push = |price: Price| {
+            if !prices.contains(&price) {
+                prices.push(price);
+            }
+        };
+        self.schema_org_prices().into_iter().for_each(&mut push);
+
+        for (amount, currency) in [
+            ("product:price:amount", "product:price:currency"),
+            ("og:price:amount", "og:price:currency"),
+        ] {
+            let currencies = self.meta_all(currency);
+            for (i, amount) in self.meta_all(amount).iter().enumerate() {
+                if let Some(price) = Price::parse(amount, PriceSource::OpenGraph) {
+                    push(price.with_currency(currencies.get(i).map(String::as_str)));
+                }
+            }
+        }
+
+        for (name, label) in self.meta.iter() {
+            let Some(index) = name.strip_prefix("twitter:label") else {
+                continue;
+            };
+            if !label.to_lowercase().contains("price") {
+                continue;
+            }
+            let data = self.meta(&format!("twitter:data{index}"));
+            if let Some(price) = data.and_then(|data| Price::parse(data, PriceSource::Twitter)) {
+                push(price);
+            }
+        }
+
+        self.microdata_prices.iter().cloned().for_each(push);
+        prices
+    }
+
+    /// Prices of Schema.org offers: `offers` of any item, and top-level `Offer`s.
+    #[cfg(feature = "schema-org")]
+    fn schema_org_prices(&self) -> Vec<Price> {
+        let offers = self.schema_org.iter().flat_map(|item| {
+            let own = matches!(item.schema_type.as_str(), "Offer" | "AggregateOffer")
+                .then_some(&item.value);
+            own.into_iter().chain(one_or_many(item.value.get("offers")))
+        });
+        offers
+            .filter_map(|offer| {
+                let specification = offer.get("priceSpecification");
+                let amount = ["price", "lowPrice"]
+                    .iter()
+                    .find_map(|key| offer.get(key))
+                    .or_else(|| specification?.get("price"))?;
+                let price = Price::parse(&value_string(amount)?, PriceSource::SchemaOrg)?;
+                let currency = text(offer.get("priceCurrency"))
+                    .or_else(|| text(specification?.get("priceCurrency")));
+                Some(price.with_currency(currency.as_deref()))
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "schema-org"))]
+    fn schema_org_prices(&self) -> Vec<Price> {
+        Vec::new()
+    }
+}
+
+#[cfg(feature = "http")]
+impl WebpageInfo {
+    /// Prices from every source; see [`HtmlInfo::prices`].
+    pub fn prices(&self) -> Vec<Price> {
+        self.html.prices()
+    }
+}
+
+#[cfg(feature = "dom")]
+fn price_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse(r#"[itemprop~="price"]"#).unwrap())
+}
+
+#[cfg(feature = "dom")]
+fn currency_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse(r#"[itemprop~="priceCurrency"]"#).unwrap())
+}
+
+/// Read microdata prices, with the currency from the same `itemscope`.
+#[cfg(feature = "dom")]
+pub(crate) fn extract_microdata_prices(document: &Html) -> Vec<Price> {
+    document
+        .select(price_selector())
+        .filter_map(|element| {
+            let price = Price::parse(&microdata_value(element), PriceSource::Microdata)?;
+            let scope = element
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|ancestor| ancestor.value().attr("itemscope").is_some());
+            let currency = scope
+                .and_then(|scope| scope.select(currency_selector()).next())
+                .map(microdata_value);
+            Some(price.with_currency(currency.as_deref()))
+        })
+        .take(MAX_MICRODATA_PRICES)
+        .collect()
+}
+
+/// A microdata property value: the `content` attribute, or the element's text.
+#[cfg(feature = "dom")]
+fn microdata_value(element: ElementRef) -> String {
+    match element.value().attr("content") {
+        Some(content) => content.to_string(),
+        None => element.text().collect(),
+    }
+}
+
+/// A JSON-LD price, which may be a number or a string.
+#[cfg(feature = "schema-org")]
+fn value_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+/// A three-letter currency code, uppercased, or the code of a symbol.
+fn normalize_currency(currency: &str) -> Option<String> {
+    let currency = currency.trim();
+    if currency.len() == 3 && currency.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return Some(currency.to_ascii_uppercase());
+    }
+    CURRENCY_SYMBOLS
+        .iter()
+        .find(|(symbol, _)| *symbol == currency)
+        .map(|(_, code)| code.to_string())
+}
+
+/// The number in a price string; see [`Price::parse`] for separator handling.
+fn parse_amount(value: &str) -> Option<f64> {
+    let number: String = value
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | ' ' | '\u{a0}'))
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let number = number.trim_end_matches(['.', ',']);
+    let decimal = number
+        .rfind(['.', ','])
+        .filter(|&i| (1..=2).contains(&(number.len() - i - 1)));
+    let (integer, fraction) = match decimal {
+        Some(i) => (&number[..i], &number[i + 1..]),
+        None => (number, ""),
+    };
+    let integer: String = integer.chars().filter(char::is_ascii_digit).collect();
+    let amount: f64 = format!("{integer}.{fraction}").parse().ok()?;
+    (amount.is_finite() && amount >= 0.0).then_some(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("19.99"), Some(19.99));
+        assert_eq!(parse_amount("$1,299.00"), Some(1299.0));
+        assert_eq!(parse_amount("1.299,5 €"), Some(1299.5));
+        assert_eq!(parse_amount("1 299 000 ₽"), Some(1_299_000.0));
+        assert_eq!(parse_amount("1,299"), Some(1299.0));
+        assert_eq!(parse_amount("USD 5"), Some(5.0));
+        assert_eq!(parse_amount("Sold out"), None);
+    }
+
+    #[test]
+    fn test_currency_detection() {
+        let currency = |value| Price::parse(value, PriceSource::Twitter).unwrap().currency;
+        assert_eq!(currency("£12").as_deref(), Some("GBP"));
+        assert_eq!(currency("C$12").as_deref(), Some("CAD"));
+        assert_eq!(currency("12.00 CHF").as_deref(), Some("CHF"));
+        assert_eq!(currency("12.00"), None);
+        assert_eq!(normalize_currency(" eur ").as_deref(), Some("EUR"));
+        assert_eq!(normalize_currency("dollars"), None);
+    }
+
+    #[cfg(all(feature = "dom", feature = "schema-org"))]
+    #[test]
+    fn test_merged_sources() {
+        let html = r#"<script type="application/ld+json">{
+                "@type": "Product",
+                "offers": [
+                    {"@type": "Offer", "price": 49.99, "priceCurrency": "EUR"},
+                    {"@type": "AggregateOffer", "lowPrice": "39", "priceCurrency": "EUR"}
+                ]
+            }</script>
+            <meta property="og:price:amount" content="49,99">
+            <meta property="og:price:currency" content="EUR">
+            <meta name="twitter:label2" content="Availability">
+            <meta name="twitter:data2" content="In stock">
+            <div itemscope itemtype="https://schema.org/Offer">
+                <span itemprop="price" content="49.99">49,99 €</span>
+                <meta itemprop="priceCurrency" content="EUR">
+            </div>
+            <p itemprop="price">about 50</p>"#;
+
+        let prices = HtmlInfo::from_string(html, None).unwrap().prices();
+        let eur = |amount, source| Price {
+            amount,
+            currency: Some("EUR".to_string()),
+            source,
+        };
+        assert_eq!(
+            prices,
+            [
+                eur(49.99, PriceSource::SchemaOrg),
+                eur(39.0, PriceSource::SchemaOrg),
+                eur(49.99, PriceSource::OpenGraph),
+                eur(49.99, PriceSource::Microdata),
+                Price {
+                    amount: 50.0,
+                    currency: None,
+                    source: PriceSource::Microdata,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Content rating and age-restriction metadata
+//!
+//! Family filters and ad-safety checks classify pages by the ratings they
+//! declare about themselves: `<meta name="rating">` labels such as `adult` or
+//! the RTA label, the Schema.org `contentRating` property, and OpenGraph
+//! `og:restrictions:*` properties.
+//! [`HtmlInfo::content_rating`](crate::HtmlInfo::content_rating) collects them.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "dom")]
+use crate::html::HtmlInfo;
+
+/// The "Restricted To Adults" label, <https://www.rtalabel.org/>.
+#[cfg(feature = "dom")]
+const RTA_LABEL: &str = "RTA-5042-1996-1400-1577-RTA";
+
+/// `<meta name="rating">` values that mark adult content, besides the RTA label.
+#[cfg(feature = "dom")]
+const ADULT_RATINGS: &[&str] = &["adult", "mature"];
+
+/// Viewers must be at least this old for a page to count as adult.
+#[cfg(feature = "dom")]
+const ADULT_AGE: u32 = 18;
+
+/// Self-declared content ratings of a page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ContentRating {
+    /// Whether any source marks the page as adult content: an `adult`, `mature`,
+    /// or RTA rating, or a minimum age of 18 or more
+    pub adult: bool,
+
+    /// `<meta name="rating">` values, in document order
+    pub meta_ratings: Vec<String>,
+
+    /// Schema.org `contentRating`, e.g. `"MPAA PG-13"`
+    pub schema_rating: Option<String>,
+
+    /// Minimum viewer age from `og:restrictions:age` (e.g. `18+`)
+    pub min_age: Option<u32>,
+
+    /// Restricted content categories from `og:restrictions:content` (e.g. `alcohol`)
+    pub restricted_content: Vec<String>,
+}
+
+#[cfg(feature = "dom")]
+impl ContentRating {
+    /// Collect the ratings from parsed meta tags and Schema.org data, or `None`
+    /// if the page declares none.
+    pub(crate) fn detect(info: &HtmlInfo) -> Option<Self> {
+        let meta_ratings: Vec<String> = info.meta_all("rating").to_vec();
+        let min_age = info
+            .meta("og:restrictions:age")
+            .and_then(|age| age.trim().trim_end_matches('+').parse().ok());
+        let restricted_content = info.meta_all("og:restrictions:content").to_vec();
+        let schema_rating = schema_rating(info);
+
+        if meta_ratings.is_empty()
+            && min_age.is_none()
+            && restricted_content.is_empty()
+            && schema_rating.is_none()
+        {
+            return None;
+        }
+
+        let adult = meta_ratings.iter().any(|rating| {
+            let rating = rating.trim();
+            rating.eq_ignore_ascii_case(RTA_LABEL)
+                || ADULT_RATINGS
+                    .iter()
+                    .any(|adult| rating.eq_ignore_ascii_case(adult))
+        }) || min_age.is_some_and(|age| age >= ADULT_AGE);
+
+        Some(Self {
+            adult,
+            meta_ratings,
+            schema_rating,
+            min_age,
+            restricted_content,
+        })
+    }
+}
+
+/// The Schema.org `contentRating`: text, or a `Rating` with a name or value.
+#[cfg(all(feature = "dom", feature = "schema-org"))]
+fn schema_rating(info: &HtmlInfo) -> Option<String> {
+    info.schema_org.iter().find_map(|item| {
+        let rating = item.value.get("contentRating")?;
+        rating
+            .as_str()
+            .or_else(|| {
+                ["name", "alternateName", "ratingValue"]
+                    .iter()
+                    .find_map(|key| rating.get(key)?.as_str())
+            })
+            .map(str::trim)
+            .filter(|rating| !rating.is_empty())
+            .map(str::to_string)
+    })
+}
+
+#[cfg(all(feature = "dom", not(feature = "schema-org")))]
+fn schema_rating(_: &HtmlInfo) -> Option<String> {
+    None
+}
+
+#[cfg(all(test, feature = "dom"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meta_and_opengraph_ratings() {
+        let html = r#"<meta name="rating" content="RTA-5042-1996-1400-1577-RTA">
+            <meta property="og:restrictions:age" content="21+">
+            <meta property="og:restrictions:content" content="alcohol">"#;
+
+        let rating = HtmlInfo::from_string(html, None)
+            .unwrap()
+            .content_rating
+            .unwrap();
+        assert!(rating.adult);
+        assert_eq!(rating.meta_ratings, ["RTA-5042-1996-1400-1577-RTA"]);
+        assert_eq!(rating.min_age, Some(21));
+        assert_eq!(rating.restricted_content, ["alcohol"]);
+
+        let html = r#"<meta name="rating" content="general">
+            <meta property="og:restrictions:age" content="13+">"#;
+        let rating = HtmlInfo::from_string(html, None)
+            .unwrap()
+            .content_rating
+            .unwrap();
+        assert!(!rating.adult);
+        assert_eq!(rating.min_age, Some(13));
+
+        let info = HtmlInfo::from_string("<title>Plain</title>", None).unwrap();
+        assert!(info.content_rating.is_none());
+    }
+
+    #[cfg(feature = "schema-org")]
+    #[test]
+    fn test_schema_org_content_rating() {
+        let html = r#"<script type="application/ld+json">{
+            "@type": "Movie",
+            "contentRating": {"@type": "Rating", "name": "MPAA PG-13"}
+        }</script>"#;
+
+        let rating = HtmlInfo::from_string(html, None)
+            .unwrap()
+            .content_rating
+            .unwrap();
+        assert_eq!(rating.schema_rating.as_deref(), Some("MPAA PG-13"));
+        assert!(!rating.adult);
+    }
+}
@@ -0,0 +1,282 @@
+//! RDFa Lite
+//!
+//! News publishers and government sites often describe pages with
+//! [RDFa Lite](https://www.w3.org/TR/rdfa-lite/) attributes (`vocab`,
+//! `typeof`, `property`, `resource`, `prefix`). Like microdata, each top-level
+//! resource is converted to the JSON-LD shape and stored in [`HtmlInfo::rdfa`].
+//!
+//! Schema.org terms lose their vocabulary, so `typeof="schema:NewsArticle"`
+//! under the default `schema` prefix, or `typeof="NewsArticle"` under
+//! `vocab="https://schema.org/"`, both become `"@type": "NewsArticle"`. Terms
+//! of other vocabularies keep their full IRI.
+
+use std::sync::OnceLock;
+
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::consistency::collapse_whitespace;
+use crate::html::{HtmlInfo, MAX_SCHEMA_ORG_ITEMS};
+use crate::schema_org::SchemaOrg;
+
+/// Resources nested deeper than this are dropped.
+const MAX_DEPTH: usize = 16;
+
+/// Schema.org vocabulary IRIs, stripped from terms.
+const SCHEMA_ORG_VOCABS: &[&str] = &["https://schema.org/", "http://schema.org/"];
+
+/// Prefixes predefined by the RDFa initial context that pages use without
+/// declaring.
+const DEFAULT_PREFIXES: &[(&str, &str)] = &[
+    ("schema", "http://schema.org/"),
+    ("dc", "http://purl.org/dc/terms/"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("og", "http://ogp.me/ns#"),
+];
+
+fn top_level_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("[typeof]:not([property])").unwrap())
+}
+
+/// The vocabulary and prefixes in effect at an element.
+#[derive(Clone, Default)]
+struct Context {
+    vocab: Option<String>,
+    prefixes: Vec<(String, String)>,
+}
+
+impl Context {
+    /// The context at `element`, from its own and its ancestors' attributes.
+    fn at(element: ElementRef) -> Self {
+        let mut context = Self::default();
+        let mut chain: Vec<ElementRef> = element.ancestors().filter_map(ElementRef::wrap).collect();
+        chain.reverse();
+        chain.push(element);
+        for element in chain {
+            context = context.enter(element);
+        }
+        context
+    }
+
+    /// The context inside `element`.
+    fn enter(&self, element: ElementRef) -> Self {
+        let mut context = self.clone();
+        if let Some(vocab) = element.value().attr("vocab") {
+            context.vocab = Some(vocab.trim().to_string()).filter(|v| !v.is_empty());
+        }
+        if let Some(prefix) = element.value().attr("prefix") {
+            // `name: iri name: iri ...`
+            let mut tokens = prefix.split_ascii_whitespace();
+            while let (Some(name), Some(iri)) = (tokens.next(), tokens.next()) {
+                if let Some(name) = name.strip_suffix(':') {
+                    context.prefixes.push((name.to_string(), iri.to_string()));
+                }
+            }
+        }
+        context
+    }
+
+    /// A term as a JSON-LD key or type: the bare name for Schema.org terms,
+    /// the full IRI otherwise.
+    fn term(&self, term: &str) -> String {
+        let iri = match term.split_once(':') {
+            Some((prefix, rest)) if !rest.starts_with("//") => self
+                .prefixes
+                .iter()
+                .rev()
+                .map(|(name, iri)| (name.as_str(), iri.as_str()))
+                .chain(DEFAULT_PREFIXES.iter().copied())
+                .find(|(name, _)| *name == prefix)
+                .map(|(_, iri)| format!("{iri}{rest}")),
+            Some(_) => Some(term.to_string()),
+            None => self.vocab.as_ref().map(|vocab| format!("{vocab}{term}")),
+        };
+        let iri = iri.unwrap_or_else(|| term.to_string());
+        SCHEMA_ORG_VOCABS
+            .iter()
+            .find_map(|vocab| iri.strip_prefix(vocab))
+            .map_or_else(|| iri.clone(), str::to_string)
+    }
+}
+
+impl HtmlInfo {
+    /// Read every top-level RDFa resource, in document order.
+    pub(crate) fn extract_rdfa(document: &Html, base_url: Option<&Url>) -> Vec<SchemaOrg> {
+        document
+            .select(top_level_selector())
+            // Resources nested under a typed ancestor belong to it
+            .filter(|element| {
+                !element
+                    .ancestors()
+                    .filter_map(ElementRef::wrap)
+                    .any(|a| a.value().attr("typeof").is_some())
+            })
+            .filter_map(|element| {
+                let value = resource(element, &Context::at(element), base_url, 0);
+                let schema_type = value.get("@type")?.as_str()?.to_string();
+                Some(SchemaOrg { schema_type, value })
+            })
+            .take(MAX_SCHEMA_ORG_ITEMS)
+            .collect()
+    }
+}
+
+/// The JSON-LD form of the resource typed at `scope`.
+fn resource(scope: ElementRef, context: &Context, base_url: Option<&Url>, depth: usize) -> Value {
+    let mut object = Map::new();
+    let types: Vec<Value> = scope
+        .value()
+        .attr("typeof")
+        .unwrap_or_default()
+        .split_ascii_whitespace()
+        .map(|t| Value::String(context.term(t)))
+        .collect();
+    match <[Value; 1]>::try_from(types) {
+        Ok([single]) => {
+            object.insert("@type".to_string(), single);
+        }
+        Err(types) if !types.is_empty() => {
+            object.insert("@type".to_string(), Value::Array(types));
+        }
+        Err(_) => {}
+    }
+    if let Some(id) = scope.value().attr("resource").map(str::trim) {
+        object.insert("@id".to_string(), Value::String(resolve(id, base_url)));
+    }
+
+    // Properties are the `property` elements under the resource, without
+    // entering nested resources
+    let mut pending: Vec<(ElementRef, Context)> = scope
+        .children()
+        .filter_map(ElementRef::wrap)
+        .map(|child| (child, context.enter(child)))
+        .collect();
+    pending.reverse();
+
+    while let Some((element, context)) = pending.pop() {
+        let is_resource = element.value().attr("typeof").is_some();
+        if let Some(names) = element.value().attr("property") {
+            let value = if is_resource {
+                (depth < MAX_DEPTH).then(|| resource(element, &context, base_url, depth + 1))
+            } else {
+                Some(Value::String(property_value(element, base_url)))
+            };
+            for name in names.split_ascii_whitespace() {
+                let Some(value) = value.clone() else { break };
+                let name = context.term(name);
+                match object.get_mut(&name) {
+                    Some(Value::Array(values)) => values.push(value),
+                    Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+                    None => {
+                        object.insert(name, value);
+                    }
+                }
+            }
+        }
+        if !is_resource {
+            let children: Vec<_> = element
+                .children()
+                .filter_map(ElementRef::wrap)
+                .map(|child| (child, context.enter(child)))
+                .collect();
+            pending.extend(children.into_iter().rev());
+        }
+    }
+    Value::Object(object)
+}
+
+/// The value of a literal or link property.
+fn property_value(element: ElementRef, base_url: Option<&Url>) -> String {
+    let attr = |name: &str| element.value().attr(name).map(str::trim);
+    if let Some(content) = attr("content") {
+        return content.to_string();
+    }
+    if let Some(target) = attr("resource").or(attr("href")).or(attr("src")) {
+        return resolve(target, base_url);
+    }
+    if element.value().name() == "time"
+        && let Some(datetime) = attr("datetime")
+    {
+        return datetime.to_string();
+    }
+    collapse_whitespace(&element.text().collect::<String>())
+}
+
+/// `target` resolved against the base URL, or as written if it cannot be.
+fn resolve(target: &str, base_url: Option<&Url>) -> String {
+    match base_url.map(|base| base.join(target)) {
+        Some(Ok(resolved)) if !target.is_empty() => resolved.to_string(),
+        _ => target.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn parse(html: &str) -> Vec<SchemaOrg> {
+        let base = Url::parse("https://news.example/2026/10/").unwrap();
+        HtmlInfo::extract_rdfa(&Html::parse_document(html), Some(&base))
+    }
+
+    #[test]
+    fn test_vocab_and_nested_resources() {
+        let items = parse(
+            r#"<body vocab="https://schema.org/">
+              <article typeof="NewsArticle" resource="story">
+                <h1 property="headline">Council   approves budget</h1>
+                <time property="datePublished" datetime="2026-10-01">1 October</time>
+                <a property="url" href="/2026/10/story">Permalink</a>
+                <div property="author" typeof="Person">
+                  <span property="name">Jo Reporter</span>
+                </div>
+                <meta property="keywords" content="budget">
+                <span property="keywords">council</span>
+              </article>
+            </body>"#,
+        );
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].schema_type, "NewsArticle");
+        assert_eq!(
+            items[0].value,
+            json!({
+                "@type": "NewsArticle",
+                "@id": "https://news.example/2026/10/story",
+                "headline": "Council approves budget",
+                "datePublished": "2026-10-01",
+                "url": "https://news.example/2026/10/story",
+                "author": {"@type": "Person", "name": "Jo Reporter"},
+                "keywords": ["budget", "council"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let items = parse(
+            r#"<div prefix="gov: https://data.example/ns#">
+              <div typeof="schema:GovernmentService">
+                <span property="schema:name">Passport renewal</span>
+                <span property="gov:office dc:publisher">Home Office</span>
+              </div>
+            </div>
+            <p property="name">Not in a resource</p>"#,
+        );
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].schema_type, "GovernmentService");
+        assert_eq!(items[0].get_str("name"), Some("Passport renewal"));
+        assert_eq!(
+            items[0].get_str("https://data.example/ns#office"),
+            Some("Home Office")
+        );
+        assert_eq!(
+            items[0].get_str("http://purl.org/dc/terms/publisher"),
+            Some("Home Office")
+        );
+    }
+}
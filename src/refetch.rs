@@ -0,0 +1,124 @@
+//! Conditional refetching of previously fetched pages
+//!
+//! [`WebpageInfo::refetch`] revalidates a page with the `ETag` and
+//! `Last-Modified` validators of its previous response, so polling an
+//! unchanged page costs a `304 Not Modified` instead of a full download.
+
+use crate::WebpageInfo;
+use crate::client::WebpageClient;
+use crate::error::Result;
+use crate::http::Overrides;
+
+/// The outcome of [`WebpageInfo::refetch`].
+#[derive(Debug, Clone)]
+pub enum Refetch {
+    /// The server answered `304 Not Modified`; the previous result is current
+    Unchanged,
+    /// The page was fetched again
+    Changed(Box<WebpageInfo>),
+}
+
+impl Refetch {
+    /// Whether the page was unchanged.
+    pub fn is_unchanged(&self) -> bool {
+        matches!(self, Self::Unchanged)
+    }
+
+    /// The fresh result, if the page changed.
+    pub fn changed(self) -> Option<WebpageInfo> {
+        match self {
+            Self::Unchanged => None,
+            Self::Changed(info) => Some(*info),
+        }
+    }
+}
+
+impl WebpageInfo {
+    /// Fetch the page again with `client`, sending `If-None-Match` and
+    /// `If-Modified-Since` from this response's `ETag` and `Last-Modified`
+    /// headers.
+    ///
+    /// Without validators the page is fetched unconditionally and always
+    /// reported as [`Refetch::Changed`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use webpage_info::{HttpOptions, Refetch, WebpageClient};
+    ///
+    /// # async fn run() -> webpage_info::Result<()> {
+    /// let client = WebpageClient::new(HttpOptions::new())?;
+    /// let mut page = client.fetch("https://example.com/feed").await?;
+    /// if let Refetch::Changed(fresh) = page.refetch(&client).await? {
+    ///     page = *fresh;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn refetch(&self, client: &WebpageClient) -> Result<Refetch> {
+        let mut overrides = Overrides::new();
+        if let Some(etag) = self.http.header("etag") {
+            overrides = overrides.header("If-None-Match", etag);
+        }
+        if let Some(modified) = self.http.header("last-modified") {
+            overrides = overrides.header("If-Modified-Since", modified);
+        }
+        let conditional = !overrides.is_empty();
+
+        let info = client.fetch_with(&self.http.url, &overrides).await?;
+        if conditional && info.http.status_code == 304 {
+            Ok(Refetch::Unchanged)
+        } else {
+            Ok(Refetch::Changed(Box::new(info)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{header, header_exists, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::HttpOptions;
+
+    #[tokio::test]
+    async fn test_refetch_revalidates() {
+        let server = MockServer::start().await;
+        Mock::given(path("/feed"))
+            .and(header("if-none-match", "\"v1\""))
+            .and(header_exists("if-modified-since"))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&server)
+            .await;
+        Mock::given(path("/feed"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"v1\"")
+                    .insert_header("last-modified", "Tue, 13 Oct 2026 08:00:00 GMT")
+                    .set_body_raw("<title>Feed</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/plain"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw("<title>Plain</title>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = WebpageClient::new(HttpOptions::new().block_private_ips(false)).unwrap();
+        let page = client
+            .fetch(&format!("{}/feed", server.uri()))
+            .await
+            .unwrap();
+        assert!(page.refetch(&client).await.unwrap().is_unchanged());
+
+        let plain = client
+            .fetch(&format!("{}/plain", server.uri()))
+            .await
+            .unwrap();
+        let fresh = plain.refetch(&client).await.unwrap().changed().unwrap();
+        assert_eq!(fresh.html.title.as_deref(), Some("Plain"));
+    }
+}
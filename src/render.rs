@@ -0,0 +1,282 @@
+//! Headless browser rendering
+//!
+//! [`RenderedFetcher`] is a [`Transport`] that loads pages in headless Chromium
+//! over the DevTools protocol and returns the DOM after JavaScript has run, so
+//! single-page applications yield their real metadata. Behind the `render`
+//! feature; a Chromium or Chrome executable must be installed.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chromiumoxide::browser::{Browser, BrowserConfig, BrowserConfigBuilder};
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    ContinueRequestParams, EventRequestPaused, FailRequestParams,
+};
+use chromiumoxide::cdp::browser_protocol::network::ErrorReason;
+use chromiumoxide::cdp::browser_protocol::page::NavigateParams;
+use chromiumoxide::error::CdpError;
+use chromiumoxide::page::Page;
+use futures_util::StreamExt;
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::error::{Error, Result};
+use crate::http::{self, HttpInfo, HttpOptions, RedirectHop};
+use crate::policy::{RequestDecision, RequestPolicy};
+use crate::transport::{Transport, TransportFuture};
+
+const DEFAULT_SETTLE_MILLIS: u64 = 500;
+
+/// A [`Transport`] that renders pages in headless Chromium.
+///
+/// Every request the page makes, including scripts, XHR, and redirects, goes
+/// through the client's request policy and is checked against the SSRF rules
+/// when [`HttpOptions::block_private_ips`] is enabled; blocked requests fail
+/// inside the browser. The requested URL itself is validated by the client
+/// before the page is opened.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use webpage_info::{HttpOptions, RenderedFetcher, WebpageClient};
+///
+/// #[tokio::main]
+/// async fn main() -> webpage_info::Result<()> {
+///     let renderer = RenderedFetcher::launch().await?;
+///     let client = WebpageClient::new(HttpOptions::new())?.with_transport(renderer);
+///     let info = client.fetch("https://example.org").await?;
+///     println!("{:?}", info.html.title);
+///     Ok(())
+/// }
+/// ```
+pub struct RenderedFetcher {
+    browser: Arc<Browser>,
+    handler: JoinHandle<()>,
+    settle: Duration,
+}
+
+impl std::fmt::Debug for RenderedFetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RenderedFetcher")
+            .field("settle", &self.settle)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RenderedFetcher {
+    /// Launch headless Chromium with default settings.
+    pub async fn launch() -> Result<Self> {
+        Self::launch_with(BrowserConfig::builder()).await
+    }
+
+    /// Launch Chromium from a custom configuration (executable path, flags, window size).
+    ///
+    /// Request interception is always enabled, since SSRF checks depend on it.
+    pub async fn launch_with(config: BrowserConfigBuilder) -> Result<Self> {
+        let config = config
+            .enable_request_intercept()
+            .build()
+            .map_err(Error::Browser)?;
+        let (browser, mut handler) = Browser::launch(config)
+            .await
+            .map_err(|e| Error::Browser(e.to_string()))?;
+        // The handler drives the DevTools connection and must be polled for the
+        // browser to make progress
+        let handler = tokio::spawn(async move { while handler.next().await.is_some() {} });
+
+        Ok(Self {
+            browser: Arc::new(browser),
+            handler,
+            settle: Duration::from_millis(DEFAULT_SETTLE_MILLIS),
+        })
+    }
+
+    /// Wait this long after the page has loaded for scripts to finish rendering.
+    ///
+    /// Default: 500 ms.
+    pub fn settle_time(mut self, settle: Duration) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    async fn render(
+        &self,
+        url: &str,
+        options: &HttpOptions,
+        policy: RequestPolicy,
+    ) -> Result<HttpInfo> {
+        let failed = |e: CdpError| Error::Transport {
+            url: url.to_string(),
+            reason: e.to_string(),
+        };
+        let page = self.browser.new_page("about:blank").await.map_err(failed)?;
+        let result = match tokio::time::timeout(
+            options.timeout,
+            render_page(&page, url, options, policy, self.settle),
+        )
+        .await
+        {
+            Ok(result) => result.map_err(failed),
+            Err(_) => Err(Error::Transport {
+                url: url.to_string(),
+                reason: format!("rendering timed out after {:?}", options.timeout),
+            }),
+        };
+        // A page that fails to close is reclaimed with the browser
+        let _ = page.close().await;
+        result
+    }
+}
+
+impl Drop for RenderedFetcher {
+    fn drop(&mut self) {
+        self.handler.abort();
+    }
+}
+
+impl Transport for RenderedFetcher {
+    fn fetch<'a>(&'a self, url: &'a str, options: &'a HttpOptions) -> TransportFuture<'a> {
+        self.fetch_with_policy(url, options, RequestPolicy::default())
+    }
+
+    fn fetch_with_policy<'a>(
+        &'a self,
+        url: &'a str,
+        options: &'a HttpOptions,
+        policy: RequestPolicy,
+    ) -> TransportFuture<'a> {
+        Box::pin(self.render(url, options, policy))
+    }
+}
+
+async fn render_page(
+    page: &Page,
+    url: &str,
+    options: &HttpOptions,
+    policy: RequestPolicy,
+    settle: Duration,
+) -> std::result::Result<HttpInfo, CdpError> {
+    page.set_user_agent(options.user_agent.as_str()).await?;
+
+    let mut paused = page.event_listener::<EventRequestPaused>().await?;
+    let interceptor = page.clone();
+    let page_url = url.to_string();
+    let block_private_ips = options.block_private_ips;
+    let intercept = tokio::spawn(async move {
+        while let Some(event) = paused.next().await {
+            let id = event.request_id.clone();
+            let request = &event.request.url;
+            let target = route(request, &page_url, &policy, block_private_ips).await;
+            let result = match target {
+                Some(target) => {
+                    let mut params = ContinueRequestParams::new(id);
+                    params.url = (target != *request).then_some(target);
+                    interceptor.execute(params).await.map(drop)
+                }
+                None => interceptor
+                    .execute(FailRequestParams::new(id, ErrorReason::BlockedByClient))
+                    .await
+                    .map(drop),
+            };
+            if result.is_err() {
+                break;
+            }
+        }
+    });
+
+    let navigation = page.http_future(NavigateParams::new(url))?.await;
+    let result = async {
+        let request = navigation?;
+        tokio::time::sleep(settle).await;
+        let body = page.content().await?;
+        let final_url = page.url().await?.unwrap_or_else(|| url.to_string());
+
+        let redirects: Vec<RedirectHop> = request
+            .iter()
+            .flat_map(|r| &r.redirect_chain)
+            .map(|hop| RedirectHop {
+                url: hop.url.clone().unwrap_or_default(),
+                status: hop.response.as_ref().map_or(0, |r| r.status as u16),
+            })
+            .collect();
+        let response = request.as_ref().and_then(|r| r.response.as_ref());
+        let headers: Vec<(String, String)> = response
+            .and_then(|r| r.headers.inner().as_object())
+            .map(|headers| {
+                headers
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        Some((name.to_ascii_lowercase(), value.as_str()?.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(HttpInfo {
+            url: final_url,
+            status_code: response.map_or(0, |r| r.status as u16),
+            headers,
+            informational: Vec::new(),
+            content_type: response.map(|r| r.mime_type.clone()),
+            // The browser has already decoded the document
+            charset: None,
+            redirect_count: redirects.len() as u32,
+            redirects,
+            cookies: Vec::new(),
+            user_agent: options.user_agent.clone(),
+            body,
+            // Filled in from the rendered DOM by the client
+            snapshot: None,
+        })
+    }
+    .await;
+
+    intercept.abort();
+    result
+}
+
+/// Where a request made by the page should go, or `None` to block it.
+///
+/// The page URL itself was already checked by the client, so the request
+/// policy is not applied to it a second time.
+async fn route(
+    request: &str,
+    page_url: &str,
+    policy: &RequestPolicy,
+    block_private_ips: bool,
+) -> Option<String> {
+    let target = if request == page_url || !is_network_url(request) {
+        request.to_string()
+    } else {
+        match policy.decide(request) {
+            RequestDecision::Allow => request.to_string(),
+            RequestDecision::Rewrite(target) => target,
+            RequestDecision::Deny(_) => return None,
+        }
+    };
+    (!block_private_ips || is_allowed(&target).await).then_some(target)
+}
+
+/// Whether `url` leaves the browser, unlike `data:`, `blob:`, and similar URLs.
+fn is_network_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|parsed| matches!(parsed.scheme(), "http" | "https" | "ws" | "wss"))
+}
+
+/// Whether a request made by the page passes the SSRF rules.
+///
+/// Only network schemes are checked; `data:`, `blob:`, and similar URLs never
+/// leave the browser.
+async fn is_allowed(url: &str) -> bool {
+    match Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https" | "ws" | "wss") => {
+            let url = match parsed.scheme() {
+                "ws" => url.replacen("ws", "http", 1),
+                "wss" => url.replacen("wss", "https", 1),
+                _ => url.to_string(),
+            };
+            http::validate_url_for_ssrf(&url).await.is_ok()
+        }
+        Ok(_) => true,
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,218 @@
+//! Redirect resolution without fetching the page
+//!
+//! Expanding a shortened or tracking link only needs the `Location` headers
+//! along the way. [`WebpageClient::resolve_final_url`] follows them with
+//! `HEAD` requests, falling back to `GET` for servers that reject `HEAD`, and
+//! never reads a response body.
+
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::client::WebpageClient;
+use crate::error::Result;
+use crate::http::{HttpOptions, RedirectChain, RedirectHop};
+
+/// Where a URL's redirects lead.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ResolvedUrl {
+    /// The URL the redirects end at
+    pub url: String,
+
+    /// Status code of the final response
+    pub status_code: u16,
+
+    /// Each redirect followed, in order
+    pub redirects: Vec<RedirectHop>,
+}
+
+impl WebpageClient {
+    /// Follow the redirects of `url` without downloading or parsing the page.
+    ///
+    /// Each hop is requested with `HEAD`; once a server answers `405` or `501`
+    /// the rest of the chain is requested with `GET`, whose body is dropped
+    /// unread. Redirects are followed even if
+    /// [`HttpOptions::follow_redirects`] is off, with the same
+    /// [`max_redirects`](HttpOptions::max_redirects), loop, downgrade, and SSRF
+    /// checks as a fetch. Redirects made by `<meta http-equiv="refresh">` or
+    /// scripts are not seen. An error status ends the chain and is returned in
+    /// [`ResolvedUrl::status_code`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use webpage_info::{HttpOptions, WebpageClient};
+    ///
+    /// # async fn run() -> webpage_info::Result<()> {
+    /// let client = WebpageClient::new(HttpOptions::new())?;
+    /// let resolved = client.resolve_final_url("https://bit.ly/example").await?;
+    /// println!("{} ({} redirects)", resolved.url, resolved.redirects.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_final_url(&self, url: &str) -> Result<ResolvedUrl> {
+        let url = &*self.state.policies.check_request(url)?;
+        let options = self.options_for(url);
+        let mut chain = RedirectChain::start(url, &options, &self.state).await?;
+        let mut method = Method::HEAD;
+
+        loop {
+            self.state.observers.on_request(chain.current.as_str())?;
+            let _in_flight = self
+                .state
+                .stats
+                .start(chain.current.host_str().unwrap_or_default());
+            let mut response = chain
+                .send(&self.client, method.clone(), chain.headers())
+                .await?;
+            if method == Method::HEAD
+                && matches!(
+                    response.status(),
+                    StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+                )
+            {
+                method = Method::GET;
+                response = chain
+                    .send(&self.client, Method::GET, chain.headers())
+                    .await?;
+            }
+            chain.receive(&response);
+            if !chain.follow(&response).await? {
+                return Ok(ResolvedUrl {
+                    url: chain.current.to_string(),
+                    status_code: response.status().as_u16(),
+                    redirects: chain.hops,
+                });
+            }
+        }
+    }
+}
+
+/// Follow the redirects of `url` with a client built from `options`, without
+/// downloading the page.
+///
+/// Builds a new HTTP client per call; see
+/// [`WebpageClient::resolve_final_url`] to reuse one.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use webpage_info::{HttpOptions, resolve_final_url};
+///
+/// # async fn run() -> webpage_info::Result<()> {
+/// let resolved = resolve_final_url("https://t.co/example", HttpOptions::new()).await?;
+/// for hop in &resolved.redirects {
+///     println!("{} {}", hop.status, hop.url);
+/// }
+/// println!("-> {}", resolved.url);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn resolve_final_url(url: &str, options: HttpOptions) -> Result<ResolvedUrl> {
+    WebpageClient::new(options)?.resolve_final_url(url).await
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::error::Error;
+
+    #[tokio::test]
+    async fn test_resolves_with_head_and_falls_back_to_get() {
+        use std::sync::{Arc, Mutex};
+
+        use crate::FetchObserver;
+
+        #[derive(Default)]
+        struct Requests(Mutex<Vec<String>>);
+        impl FetchObserver for Requests {
+            fn on_request(&self, url: &str) -> Result<()> {
+                self.0.lock().unwrap().push(url.to_string());
+                Ok(())
+            }
+        }
+
+        let short = MockServer::start().await;
+        let target = MockServer::start().await;
+        Mock::given(path("/abc"))
+            .and(method("HEAD"))
+            .respond_with(ResponseTemplate::new(301).insert_header("location", "/track?id=1"))
+            .mount(&short)
+            .await;
+        Mock::given(path("/track"))
+            .and(method("HEAD"))
+            .respond_with(ResponseTemplate::new(405))
+            .expect(1)
+            .mount(&short)
+            .await;
+        Mock::given(path("/track"))
+            .and(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("location", format!("{}/article", target.uri())),
+            )
+            .mount(&short)
+            .await;
+        Mock::given(path("/article"))
+            .and(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("article"))
+            .expect(1)
+            .mount(&target)
+            .await;
+
+        let options = HttpOptions::new()
+            .block_private_ips(false)
+            .follow_redirects(false);
+        let requests = Arc::new(Requests::default());
+        let client = WebpageClient::new(options)
+            .unwrap()
+            .with_observer(requests.clone());
+        let resolved = client
+            .resolve_final_url(&format!("{}/abc", short.uri()))
+            .await
+            .unwrap();
+        assert_eq!(resolved.url, format!("{}/article", target.uri()));
+        assert_eq!(resolved.status_code, 200);
+        assert_eq!(
+            resolved.redirects,
+            [
+                RedirectHop {
+                    url: format!("{}/abc", short.uri()),
+                    status: 301,
+                },
+                RedirectHop {
+                    url: format!("{}/track?id=1", short.uri()),
+                    status: 302,
+                },
+            ]
+        );
+        // The GET retry of the hop that rejected HEAD is not a new request
+        assert_eq!(requests.0.lock().unwrap().len(), 3);
+        assert_eq!(client.stats().requests_total, 3);
+    }
+
+    #[tokio::test]
+    async fn test_redirect_limits_apply() {
+        let server = MockServer::start().await;
+        Mock::given(path("/loop"))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", "/loop"))
+            .mount(&server)
+            .await;
+
+        let client = WebpageClient::new(HttpOptions::new().block_private_ips(false)).unwrap();
+        let err = client
+            .resolve_final_url(&format!("{}/loop", server.uri()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::RedirectLoop { .. }));
+
+        let err = resolve_final_url("http://127.0.0.1/", HttpOptions::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::SsrfBlocked { .. }));
+    }
+}
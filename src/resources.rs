@@ -0,0 +1,222 @@
+//! Script and stylesheet inventory with Subresource Integrity audit
+//!
+//! Every `<script src>` and `<link rel="stylesheet">` is recorded on
+//! [`HtmlInfo::resources`](crate::HtmlInfo::resources) with its `integrity` and
+//! `crossorigin` attributes, so third-party assets loaded without SRI can be
+//! found.
+
+#[cfg(feature = "dom")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "dom")]
+use scraper::{Html, Selector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "dom")]
+use url::Url;
+
+/// Maximum number of resources to record, to bound memory on hostile input.
+#[cfg(feature = "dom")]
+const MAX_RESOURCES: usize = 1_000;
+
+/// Hash algorithms allowed in an `integrity` attribute.
+const SRI_ALGORITHMS: &[&str] = &["sha256-", "sha384-", "sha512-"];
+
+/// The kind of subresource.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ResourceKind {
+    /// `<script src>`
+    #[default]
+    Script,
+    /// `<link rel="stylesheet">`
+    Stylesheet,
+}
+
+/// A script or stylesheet referenced by the document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Resource {
+    /// The resource URL (resolved if base URL provided)
+    pub url: String,
+
+    /// Script or stylesheet
+    pub kind: ResourceKind,
+
+    /// Whether the resource is on a different origin than the document.
+    ///
+    /// Without a base URL, any absolute or protocol-relative URL counts as external.
+    pub external: bool,
+
+    /// The `integrity` attribute, if present
+    pub integrity: Option<String>,
+
+    /// The `crossorigin` attribute, if present (empty for a bare attribute)
+    pub crossorigin: Option<String>,
+}
+
+/// Subresource Integrity status of a [`Resource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SriStatus {
+    /// Has a valid `integrity` attribute and, if external, a `crossorigin` attribute
+    Protected,
+    /// Same-origin resource without `integrity`; SRI is optional here
+    SameOrigin,
+    /// External resource without `integrity`
+    MissingIntegrity,
+    /// `integrity` on an external resource without `crossorigin`, so browsers refuse to load it
+    MissingCrossorigin,
+    /// `integrity` present but with no `sha256`/`sha384`/`sha512` hash
+    InvalidIntegrity,
+}
+
+impl Resource {
+    /// Audit the resource's `integrity` and `crossorigin` attributes.
+    pub fn sri_status(&self) -> SriStatus {
+        let Some(integrity) = &self.integrity else {
+            return if self.external {
+                SriStatus::MissingIntegrity
+            } else {
+                SriStatus::SameOrigin
+            };
+        };
+
+        let valid = integrity.split_whitespace().any(|hash| {
+            SRI_ALGORITHMS
+                .iter()
+                .any(|algorithm| hash.len() > algorithm.len() && hash.starts_with(algorithm))
+        });
+        if !valid {
+            SriStatus::InvalidIntegrity
+        } else if self.external && self.crossorigin.is_none() {
+            SriStatus::MissingCrossorigin
+        } else {
+            SriStatus::Protected
+        }
+    }
+}
+
+#[cfg(feature = "dom")]
+fn resource_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("script[src], link[rel][href]").unwrap())
+}
+
+/// Collect scripts and stylesheets from a parsed document.
+#[cfg(feature = "dom")]
+pub(crate) fn extract_resources(document: &Html, base_url: Option<&Url>) -> Vec<Resource> {
+    document
+        .select(resource_selector())
+        .filter_map(|element| {
+            let element = element.value();
+            let (kind, href) = if element.name() == "script" {
+                (ResourceKind::Script, element.attr("src")?)
+            } else {
+                let stylesheet = element
+                    .attr("rel")?
+                    .split_ascii_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("stylesheet"));
+                (
+                    ResourceKind::Stylesheet,
+                    element.attr("href").filter(|_| stylesheet)?,
+                )
+            };
+
+            let href = href.trim();
+            if href.is_empty() {
+                return None;
+            }
+            let (url, external) = match base_url.map(|base| (base, base.join(href))) {
+                Some((base, Ok(resolved))) => {
+                    let external = resolved.origin() != base.origin();
+                    (resolved.into(), external)
+                }
+                _ => (
+                    href.to_string(),
+                    href.starts_with("//") || Url::parse(href).is_ok(),
+                ),
+            };
+
+            Some(Resource {
+                url,
+                kind,
+                external,
+                integrity: element.attr("integrity").map(|v| v.trim().to_string()),
+                crossorigin: element.attr("crossorigin").map(|v| v.trim().to_string()),
+            })
+        })
+        .take(MAX_RESOURCES)
+        .collect()
+}
+
+#[cfg(all(test, feature = "dom"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inventory_and_sri_status() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/site.css">
+            <link rel="preload stylesheet" href="https://cdn.example.net/a.css"
+                  integrity="sha384-abc" crossorigin="anonymous">
+            <link rel="icon" href="/favicon.ico">
+            <script src="https://cdn.example.net/lib.js" integrity="sha256-xyz"></script>
+            <script src="https://cdn.example.net/other.js"></script>
+            <script src="/app.js" integrity="md5-nope"></script>
+            <script>inline()</script>
+        </head></html>"#;
+        let base = Url::parse("https://example.com/page").unwrap();
+        let resources = extract_resources(&Html::parse_document(html), Some(&base));
+
+        let summary: Vec<_> = resources
+            .iter()
+            .map(|r| (r.url.as_str(), r.kind, r.sri_status()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "https://example.com/site.css",
+                    ResourceKind::Stylesheet,
+                    SriStatus::SameOrigin
+                ),
+                (
+                    "https://cdn.example.net/a.css",
+                    ResourceKind::Stylesheet,
+                    SriStatus::Protected
+                ),
+                (
+                    "https://cdn.example.net/lib.js",
+                    ResourceKind::Script,
+                    SriStatus::MissingCrossorigin
+                ),
+                (
+                    "https://cdn.example.net/other.js",
+                    ResourceKind::Script,
+                    SriStatus::MissingIntegrity
+                ),
+                (
+                    "https://example.com/app.js",
+                    ResourceKind::Script,
+                    SriStatus::InvalidIntegrity
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_external_without_base_url() {
+        let html =
+            r#"<script src="//cdn.example.net/x.js"></script><script src="local.js"></script>"#;
+        let resources = extract_resources(&Html::parse_document(html), None);
+        assert!(resources[0].external);
+        assert!(!resources[1].external);
+    }
+}
@@ -0,0 +1,324 @@
+//! Retrying transient fetch failures
+//!
+//! With [`HttpOptions::retries`] set, a [`WebpageClient`] retries requests that
+//! fail to connect, time out, or are answered with a status such as `429` or
+//! `503`. Attempts are spaced by exponential backoff with jitter, or by the
+//! server's `Retry-After`, and each retry is reported to
+//! [`FetchObserver::on_retry`].
+//!
+//! [`WebpageClient`]: crate::WebpageClient
+//! [`FetchObserver::on_retry`]: crate::FetchObserver::on_retry
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::http::HttpInfo;
+use crate::http_date::{now, parse_http_date};
+
+/// When and how often a failed request is retried, set with
+/// [`HttpOptions::retries`].
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use webpage_info::{HttpOptions, RetryPolicy};
+///
+/// let policy = RetryPolicy::new()
+///     .max_attempts(5)
+///     .backoff(Duration::from_millis(200), Duration::from_secs(10))
+///     .statuses([429, 503]);
+/// let options = HttpOptions::new().retries(policy);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first. Default: 3.
+    pub max_attempts: u32,
+
+    /// Wait before the first retry, doubled for each further one.
+    /// Default: 500 ms.
+    pub initial_backoff: Duration,
+
+    /// Longest wait between attempts. A `Retry-After` asking for more ends
+    /// the retries. Default: 30 s.
+    pub max_backoff: Duration,
+
+    /// Wait a random time between half and all of the backoff, so clients
+    /// that failed together do not retry together. Default: true.
+    pub jitter: bool,
+
+    /// Response statuses that are retried. Default: `429`, `500`, `502`,
+    /// `503`, and `504`.
+    pub statuses: Vec<u16>,
+
+    /// Wait as long as a `Retry-After` response header asks instead of the
+    /// backoff. Default: true.
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            statuses: vec![429, 500, 502, 503, 504],
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the total number of attempts, including the first.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    /// Set the wait before the first retry and the longest wait.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Set whether to randomize the backoff.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the response statuses that are retried.
+    pub fn statuses(mut self, statuses: impl IntoIterator<Item = u16>) -> Self {
+        self.statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Set whether to wait as long as `Retry-After` asks.
+    pub fn respect_retry_after(mut self, respect: bool) -> Self {
+        self.respect_retry_after = respect;
+        self
+    }
+
+    /// Whether a failed request should be retried: connection failures,
+    /// timeouts, and interrupted bodies, or a status this policy retries.
+    pub(crate) fn retries(&self, error: &Error) -> bool {
+        match error {
+            Error::Http {
+                status: Some(status),
+                ..
+            } => self.statuses.contains(status),
+            Error::Http { .. } => error.is_retryable(),
+            Error::ErrorStatus { page, .. } => self.statuses.contains(&page.status_code),
+            _ => false,
+        }
+    }
+
+    /// The error a response with a retried status stands for, if it has one.
+    pub(crate) fn status_error(&self, info: &HttpInfo) -> Option<Error> {
+        if !self.statuses.contains(&info.status_code) {
+            return None;
+        }
+        Some(Error::ErrorStatus {
+            url: info.url.clone(),
+            page: Box::new(info.error_page()?),
+        })
+    }
+
+    /// How long to wait after failed attempt number `attempt` (starting at 1),
+    /// or `None` to give up.
+    pub(crate) fn delay(&self, attempt: u32, error: &Error) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        if self.respect_retry_after
+            && let Error::ErrorStatus { page, .. } = error
+            && let Some(wait) = page
+                .headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))
+                .and_then(|(_, value)| parse_retry_after(value, now()))
+        {
+            return (wait <= self.max_backoff).then_some(wait);
+        }
+        let factor = 2u32.saturating_pow(attempt - 1);
+        let backoff = (self.initial_backoff.saturating_mul(factor)).min(self.max_backoff);
+        Some(if self.jitter {
+            backoff / 2 + backoff.mul_f64(random_fraction() / 2.0)
+        } else {
+            backoff
+        })
+    }
+}
+
+/// A uniformly distributed number in `[0, 1]`.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(now());
+    hasher.finish() as f64 / u64::MAX as f64
+}
+
+/// Parse a `Retry-After` value: delay seconds, or an HTTP date that is
+/// `now` seconds since the Unix epoch or later.
+fn parse_retry_after(value: &str, now: u64) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    Some(Duration::from_secs(
+        parse_http_date(value)?.saturating_sub(now),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::{FetchObserver, HttpOptions, WebpageClient};
+
+    #[test]
+    fn test_retry_after() {
+        assert_eq!(
+            parse_retry_after(" 120 ", 0),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:50:07 GMT", 784_111_777),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT", 784_200_000),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", 0), None);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new()
+            .max_attempts(4)
+            .backoff(Duration::from_millis(100), Duration::from_millis(300))
+            .jitter(false);
+        let error = Error::InvalidUrl(String::new());
+        assert_eq!(policy.delay(1, &error), Some(Duration::from_millis(100)));
+        assert_eq!(policy.delay(2, &error), Some(Duration::from_millis(200)));
+        assert_eq!(policy.delay(3, &error), Some(Duration::from_millis(300)));
+        assert_eq!(policy.delay(4, &error), None);
+
+        let delay = policy.jitter(true).delay(2, &error).unwrap();
+        assert!((Duration::from_millis(100)..=Duration::from_millis(200)).contains(&delay));
+    }
+
+    #[tokio::test]
+    async fn test_retries_status_until_success() {
+        #[derive(Default)]
+        struct Retries(Mutex<Vec<(u32, Option<u16>)>>);
+        impl FetchObserver for Retries {
+            fn on_retry(&self, _url: &str, attempt: u32, error: &Error) {
+                self.0.lock().unwrap().push((attempt, error.status()));
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(path("/flaky"))
+            .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "0"))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(path("/flaky"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<title>Up</title>", "text/html"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_raw("gone", "text/plain"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let retries = std::sync::Arc::new(Retries::default());
+        let policy = RetryPolicy::new().backoff(Duration::from_millis(1), Duration::from_secs(1));
+        let options = HttpOptions::new().block_private_ips(false).retries(policy);
+        let client = WebpageClient::new(options)
+            .unwrap()
+            .with_observer(retries.clone());
+
+        let info = client
+            .fetch(&format!("{}/flaky", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(info.html.title.as_deref(), Some("Up"));
+        assert_eq!(*retries.0.lock().unwrap(), [(2, Some(503)), (3, Some(503))]);
+
+        let err = client
+            .fetch(&format!("{}/missing", server.uri()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(404));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let server = MockServer::start().await;
+        Mock::given(path("/down"))
+            .respond_with(ResponseTemplate::new(502).set_body_raw("down", "text/plain"))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(path("/limited"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("retry-after", "3600")
+                    .set_body_raw("slow down", "text/plain"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let policy = RetryPolicy::new()
+            .max_attempts(2)
+            .backoff(Duration::from_millis(1), Duration::from_secs(1));
+        let options = HttpOptions::new().block_private_ips(false).retries(policy);
+        let client = WebpageClient::new(options).unwrap();
+        let err = client
+            .fetch(&format!("{}/down", server.uri()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(502));
+
+        // Retry-After beyond max_backoff ends the retries
+        let err = client
+            .fetch(&format!("{}/limited", server.uri()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(429));
+    }
+
+    #[test]
+    fn test_retried_errors() {
+        let policy = RetryPolicy::new();
+        assert!(!policy.retries(&Error::InvalidUrl(String::new())));
+        let info = HttpInfo {
+            status_code: 503,
+            ..HttpInfo::default()
+        };
+        let error = policy.status_error(&info).unwrap();
+        assert_eq!(error.phase(), None);
+        assert!(policy.retries(&error));
+        let info = HttpInfo {
+            status_code: 404,
+            ..HttpInfo::default()
+        };
+        assert!(policy.status_error(&info).is_none());
+    }
+}
@@ -0,0 +1,218 @@
+//! Client-wide scheduling of batch fetches
+//!
+//! The batch fetches of a [`WebpageClient`](crate::WebpageClient) and its
+//! clones share its [`HttpOptions::max_concurrency`] and
+//! [`HttpOptions::max_concurrent_per_host`] limits. When a slot frees up, the
+//! waiting fetch with the highest priority starts, ties going to the one that
+//! waited longest; fetches whose host is at its limit are skipped, so a busy
+//! host cannot hold back the rest of the queue.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+use url::Url;
+
+use crate::http::HttpOptions;
+
+/// Concurrency limits a fetch waits for.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Limits {
+    pub(crate) total: usize,
+    pub(crate) per_host: Option<usize>,
+}
+
+impl Limits {
+    pub(crate) fn new(options: &HttpOptions) -> Self {
+        Self {
+            total: options.max_concurrency.max(1),
+            per_host: options.max_concurrent_per_host.map(|max| max.max(1)),
+        }
+    }
+}
+
+/// Queue of fetches waiting for a slot.
+#[derive(Debug, Default)]
+pub(crate) struct Scheduler {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    running: usize,
+    hosts: HashMap<String, usize>,
+    /// Waiting fetches, highest priority first, then in arrival order
+    queue: BTreeMap<(Reverse<i32>, u64), Waiter>,
+    next_seq: u64,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    host: Option<String>,
+    limits: Limits,
+    grant: oneshot::Sender<Permit>,
+}
+
+/// A running fetch's slot, released on drop.
+#[derive(Debug)]
+pub(crate) struct Permit {
+    scheduler: Arc<Scheduler>,
+    host: Option<String>,
+}
+
+impl Scheduler {
+    /// Wait until a fetch of `url` may start.
+    ///
+    /// Dropping the future gives up the place in the queue.
+    pub(crate) async fn acquire(
+        self: &Arc<Self>,
+        url: &str,
+        priority: i32,
+        limits: Limits,
+    ) -> Permit {
+        let host = limits
+            .per_host
+            .and_then(|_| Some(Url::parse(url).ok()?.host_str()?.to_ascii_lowercase()));
+        let (grant, granted) = oneshot::channel();
+        let ready = {
+            let mut state = self.lock();
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            let waiter = Waiter {
+                host,
+                limits,
+                grant,
+            };
+            state.queue.insert((Reverse(priority), seq), waiter);
+            state.dispatch(self)
+        };
+        send(ready);
+        // Waiters only leave the queue when granted or when this receiver is gone
+        granted.await.expect("queued fetches are granted a permit")
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl State {
+    /// Start every waiting fetch the limits allow, returning the permits to
+    /// hand out once the lock is released.
+    fn dispatch(&mut self, scheduler: &Arc<Scheduler>) -> Vec<(oneshot::Sender<Permit>, Permit)> {
+        let mut started = Vec::new();
+        let mut cancelled = Vec::new();
+        for (&key, waiter) in &self.queue {
+            if waiter.grant.is_closed() {
+                cancelled.push(key);
+                continue;
+            }
+            if self.running >= waiter.limits.total {
+                continue;
+            }
+            if let (Some(host), Some(max)) = (&waiter.host, waiter.limits.per_host) {
+                let running = self.hosts.entry(host.clone()).or_default();
+                if *running >= max {
+                    continue;
+                }
+                *running += 1;
+            }
+            self.running += 1;
+            started.push(key);
+        }
+        for key in cancelled {
+            self.queue.remove(&key);
+        }
+        started
+            .into_iter()
+            .filter_map(|key| self.queue.remove(&key))
+            .map(|waiter| {
+                let permit = Permit {
+                    scheduler: scheduler.clone(),
+                    host: waiter.host,
+                };
+                (waiter.grant, permit)
+            })
+            .collect()
+    }
+}
+
+/// Hand out permits; one whose fetch was dropped meanwhile is released again.
+fn send(ready: Vec<(oneshot::Sender<Permit>, Permit)>) {
+    for (grant, permit) in ready {
+        let _ = grant.send(permit);
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let ready = {
+            let mut state = self.scheduler.lock();
+            state.running -= 1;
+            if let Some(host) = &self.host
+                && let Some(running) = state.hosts.get_mut(host)
+            {
+                *running -= 1;
+                if *running == 0 {
+                    state.hosts.remove(host);
+                }
+            }
+            state.dispatch(&self.scheduler)
+        };
+        send(ready);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(total: usize, per_host: Option<usize>) -> Limits {
+        Limits { total, per_host }
+    }
+
+    #[tokio::test]
+    async fn test_priority_and_host_limits() {
+        let scheduler = Arc::new(Scheduler::default());
+        let limits = limits(2, Some(1));
+        let running = scheduler.acquire("https://a.example/1", 0, limits).await;
+
+        let queued_a = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.acquire("https://a.example/2", 10, limits).await }
+        });
+        tokio::task::yield_now().await;
+        // a.example is at its limit, so the lower-priority b.example starts
+        let b = scheduler.acquire("https://b.example/", 0, limits).await;
+        assert!(!queued_a.is_finished());
+
+        drop(running);
+        let a = queued_a.await.unwrap();
+        assert_eq!(scheduler.lock().running, 2);
+        drop((a, b));
+        let state = scheduler.lock();
+        assert_eq!(state.running, 0);
+        assert!(state.hosts.is_empty() && state.queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_waiter_leaves_queue() {
+        let scheduler = Arc::new(Scheduler::default());
+        let limits = limits(1, None);
+        let running = scheduler.acquire("https://a.example/", 0, limits).await;
+
+        let waiting = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.acquire("https://a.example/", 0, limits).await }
+        });
+        tokio::task::yield_now().await;
+        waiting.abort();
+        let _ = waiting.await;
+
+        drop(running);
+        let state = scheduler.lock();
+        assert_eq!(state.running, 0);
+        assert!(state.queue.is_empty());
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Parses [Schema.org](https://schema.org/) JSON-LD structured data from HTML documents.
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -9,7 +10,10 @@ use serde_json::Value;
 ///
 /// Schema.org provides a collection of shared vocabularies that webmasters can use
 /// to mark up their pages in ways that can be understood by major search engines.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct SchemaOrg {
     /// The @type of the schema (e.g., "Article", "Product", "Organization")
     pub schema_type: String,
@@ -90,6 +94,48 @@ impl SchemaOrg {
     }
 }
 
+// Readers for property values, which Schema.org allows in several shapes.
+
+/// Trimmed, non-empty text.
+pub(crate) fn text(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// A number, or a string holding one.
+pub(crate) fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// A name given as text or as a `Person`/`Organization` object.
+pub(crate) fn name(value: Option<&Value>) -> Option<String> {
+    let value = one_or_many(value).next()?;
+    text(Some(value)).or_else(|| text(value.get("name")))
+}
+
+/// A URL given as text or as a `CreativeWork` object.
+pub(crate) fn url(value: Option<&Value>) -> Option<String> {
+    let value = value?;
+    text(Some(value)).or_else(|| text(value.get("url")))
+}
+
+/// A single value, or each element of an array.
+pub(crate) fn one_or_many(value: Option<&Value>) -> impl Iterator<Item = &Value> {
+    let values = match value {
+        Some(Value::Array(values)) => values.as_slice(),
+        Some(value) => std::slice::from_ref(value),
+        None => &[],
+    };
+    values.iter()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
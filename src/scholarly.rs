@@ -0,0 +1,437 @@
+//! Research metadata
+//!
+//! Research indexers read two sources. Google Scholar's Highwire Press
+//! `citation_*` meta tags describe a paper: [`HtmlInfo::citation`] collects
+//! them into a [`Citation`]. Schema.org `Dataset`, `ScholarlyArticle`, and
+//! `Course` items are read into typed values by [`HtmlInfo::datasets`],
+//! [`HtmlInfo::scholarly_articles`], and [`HtmlInfo::courses`].
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "schema-org")]
+use serde_json::Value;
+
+use crate::html::HtmlInfo;
+#[cfg(feature = "schema-org")]
+use crate::schema_org::{SchemaOrg, name, one_or_many, text, url};
+
+/// Bibliographic data from Highwire Press `citation_*` meta tags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Citation {
+    /// `citation_title`
+    pub title: Option<String>,
+
+    /// `citation_author`, in document order
+    pub authors: Vec<String>,
+
+    /// `citation_publication_date`, or `citation_date`
+    pub publication_date: Option<String>,
+
+    /// `citation_journal_title`
+    pub journal_title: Option<String>,
+
+    /// `citation_publisher`
+    pub publisher: Option<String>,
+
+    /// `citation_volume`
+    pub volume: Option<String>,
+
+    /// `citation_issue`
+    pub issue: Option<String>,
+
+    /// `citation_firstpage`
+    pub first_page: Option<String>,
+
+    /// `citation_lastpage`
+    pub last_page: Option<String>,
+
+    /// `citation_doi`, without a `doi:` or resolver prefix
+    pub doi: Option<String>,
+
+    /// `citation_pdf_url`
+    pub pdf_url: Option<String>,
+}
+
+/// A Schema.org `Dataset`.
+#[cfg(feature = "schema-org")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Dataset {
+    /// `name`
+    pub name: Option<String>,
+
+    /// `description`
+    pub description: Option<String>,
+
+    /// `url`
+    pub url: Option<String>,
+
+    /// License URL or name (`license`)
+    pub license: Option<String>,
+
+    /// `keywords`, split on commas when given as one string
+    pub keywords: Vec<String>,
+
+    /// Downloadable files (`distribution`)
+    pub distributions: Vec<DataDownload>,
+}
+
+/// A downloadable file of a [`Dataset`].
+#[cfg(feature = "schema-org")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DataDownload {
+    /// `contentUrl`
+    pub url: String,
+
+    /// MIME type or format name (`encodingFormat`), e.g. `"text/csv"`
+    pub encoding_format: Option<String>,
+}
+
+/// A Schema.org `ScholarlyArticle`.
+#[cfg(feature = "schema-org")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScholarlyArticle {
+    /// `headline`, or `name`
+    pub headline: Option<String>,
+
+    /// Author names (`author`)
+    pub authors: Vec<String>,
+
+    /// `datePublished`
+    pub date_published: Option<String>,
+
+    /// Publisher name (`publisher`)
+    pub publisher: Option<String>,
+
+    /// DOI from `identifier` or `sameAs`, without a `doi:` or resolver prefix
+    pub doi: Option<String>,
+
+    /// `url`
+    pub url: Option<String>,
+}
+
+/// A Schema.org `Course`.
+#[cfg(feature = "schema-org")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Course {
+    /// `name`
+    pub name: Option<String>,
+
+    /// `description`
+    pub description: Option<String>,
+
+    /// Provider name (`provider`)
+    pub provider: Option<String>,
+
+    /// `courseCode`
+    pub course_code: Option<String>,
+
+    /// `url`
+    pub url: Option<String>,
+}
+
+#[cfg(feature = "schema-org")]
+impl Dataset {
+    /// Read a Schema.org item, or `None` if it is not a `Dataset`.
+    pub fn from_schema(item: &SchemaOrg) -> Option<Self> {
+        if item.schema_type != "Dataset" {
+            return None;
+        }
+        let value = &item.value;
+        Some(Self {
+            name: text(value.get("name")),
+            description: text(value.get("description")),
+            url: text(value.get("url")),
+            license: url(value.get("license")).or_else(|| name(value.get("license"))),
+            keywords: one_or_many(value.get("keywords"))
+                .filter_map(Value::as_str)
+                .flat_map(|keywords| keywords.split(','))
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(str::to_string)
+                .collect(),
+            distributions: one_or_many(value.get("distribution"))
+                .filter_map(|download| {
+                    Some(DataDownload {
+                        url: text(download.get("contentUrl"))?,
+                        encoding_format: text(download.get("encodingFormat")),
+                    })
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(feature = "schema-org")]
+impl ScholarlyArticle {
+    /// Read a Schema.org item, or `None` if it is not a `ScholarlyArticle`.
+    pub fn from_schema(item: &SchemaOrg) -> Option<Self> {
+        if item.schema_type != "ScholarlyArticle" {
+            return None;
+        }
+        let value = &item.value;
+        Some(Self {
+            headline: text(value.get("headline")).or_else(|| text(value.get("name"))),
+            authors: one_or_many(value.get("author"))
+                .filter_map(|author| name(Some(author)))
+                .collect(),
+            date_published: text(value.get("datePublished")),
+            publisher: name(value.get("publisher")),
+            doi: ["identifier", "sameAs"]
+                .iter()
+                .flat_map(|key| one_or_many(value.get(key)))
+                .find_map(schema_doi),
+            url: text(value.get("url")),
+        })
+    }
+}
+
+#[cfg(feature = "schema-org")]
+impl Course {
+    /// Read a Schema.org item, or `None` if it is not a `Course`.
+    pub fn from_schema(item: &SchemaOrg) -> Option<Self> {
+        if item.schema_type != "Course" {
+            return None;
+        }
+        let value = &item.value;
+        Some(Self {
+            name: text(value.get("name")),
+            description: text(value.get("description")),
+            provider: name(value.get("provider")),
+            course_code: text(value.get("courseCode")),
+            url: text(value.get("url")),
+        })
+    }
+}
+
+impl HtmlInfo {
+    /// Bibliographic data from `citation_*` meta tags, or `None` if the page
+    /// has no `citation_title` or `citation_doi`.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<meta name="citation_title" content="On Computable Numbers">
+    ///     <meta name="citation_author" content="Turing, Alan">
+    ///     <meta name="citation_doi" content="doi:10.1112/plms/s2-42.1.230">"#;
+    /// let citation = HtmlInfo::from_string(html, None).unwrap().citation().unwrap();
+    /// assert_eq!(citation.title.as_deref(), Some("On Computable Numbers"));
+    /// assert_eq!(citation.authors, ["Turing, Alan"]);
+    /// assert_eq!(citation.doi.as_deref(), Some("10.1112/plms/s2-42.1.230"));
+    /// ```
+    pub fn citation(&self) -> Option<Citation> {
+        let meta = |name| {
+            self.meta(name)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let citation = Citation {
+            title: meta("citation_title"),
+            authors: self
+                .meta_all("citation_author")
+                .iter()
+                .map(|author| author.trim())
+                .filter(|author| !author.is_empty())
+                .map(str::to_string)
+                .collect(),
+            publication_date: meta("citation_publication_date").or_else(|| meta("citation_date")),
+            journal_title: meta("citation_journal_title"),
+            publisher: meta("citation_publisher"),
+            volume: meta("citation_volume"),
+            issue: meta("citation_issue"),
+            first_page: meta("citation_firstpage"),
+            last_page: meta("citation_lastpage"),
+            doi: self.meta("citation_doi").and_then(strip_doi),
+            pdf_url: meta("citation_pdf_url"),
+        };
+        (citation.title.is_some() || citation.doi.is_some()).then_some(citation)
+    }
+
+    /// Schema.org `Dataset` items.
+    #[cfg(feature = "schema-org")]
+    pub fn datasets(&self) -> Vec<Dataset> {
+        self.schema_org
+            .iter()
+            .filter_map(Dataset::from_schema)
+            .collect()
+    }
+
+    /// Schema.org `ScholarlyArticle` items.
+    #[cfg(feature = "schema-org")]
+    pub fn scholarly_articles(&self) -> Vec<ScholarlyArticle> {
+        self.schema_org
+            .iter()
+            .filter_map(ScholarlyArticle::from_schema)
+            .collect()
+    }
+
+    /// Schema.org `Course` items.
+    #[cfg(feature = "schema-org")]
+    pub fn courses(&self) -> Vec<Course> {
+        self.schema_org
+            .iter()
+            .filter_map(Course::from_schema)
+            .collect()
+    }
+}
+
+/// A bare DOI (`10.…`) from a DOI, `doi:` name, or resolver URL.
+fn strip_doi(value: &str) -> Option<String> {
+    let value = value.trim();
+    let doi = [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "http://dx.doi.org/",
+    ]
+    .iter()
+    .find_map(|prefix| value.strip_prefix(prefix))
+    .or_else(|| value.strip_prefix("doi:"))
+    .unwrap_or(value)
+    .trim();
+    (doi.starts_with("10.") && doi.contains('/')).then(|| doi.to_string())
+}
+
+/// A DOI from an identifier: text, a URL, or a `PropertyValue`.
+#[cfg(feature = "schema-org")]
+fn schema_doi(identifier: &Value) -> Option<String> {
+    identifier
+        .as_str()
+        .or_else(|| identifier.get("value")?.as_str())
+        .and_then(strip_doi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "dom")]
+    #[test]
+    fn test_citation_meta_tags() {
+        let html = r#"<meta name="citation_title" content=" Deep Residual Learning ">
+            <meta name="citation_author" content="He, Kaiming">
+            <meta name="citation_author" content="Zhang, Xiangyu">
+            <meta name="citation_date" content="2016/06/27">
+            <meta name="citation_journal_title" content="CVPR">
+            <meta name="citation_firstpage" content="770">
+            <meta name="citation_lastpage" content="778">
+            <meta name="citation_doi" content="https://doi.org/10.1109/CVPR.2016.90">
+            <meta name="citation_pdf_url" content="https://papers.example/resnet.pdf">"#;
+
+        let citation = HtmlInfo::from_string(html, None)
+            .unwrap()
+            .citation()
+            .unwrap();
+        assert_eq!(
+            citation,
+            Citation {
+                title: Some("Deep Residual Learning".to_string()),
+                authors: vec!["He, Kaiming".to_string(), "Zhang, Xiangyu".to_string()],
+                publication_date: Some("2016/06/27".to_string()),
+                journal_title: Some("CVPR".to_string()),
+                first_page: Some("770".to_string()),
+                last_page: Some("778".to_string()),
+                doi: Some("10.1109/CVPR.2016.90".to_string()),
+                pdf_url: Some("https://papers.example/resnet.pdf".to_string()),
+                ..Citation::default()
+            }
+        );
+
+        let info = HtmlInfo::from_string(r#"<meta name="citation_author" content="A">"#, None);
+        assert!(info.unwrap().citation().is_none());
+    }
+
+    #[test]
+    fn test_strip_doi() {
+        assert_eq!(strip_doi("10.1000/182").as_deref(), Some("10.1000/182"));
+        assert_eq!(strip_doi("doi:10.1000/182").as_deref(), Some("10.1000/182"));
+        assert_eq!(
+            strip_doi("http://dx.doi.org/10.1000/182").as_deref(),
+            Some("10.1000/182")
+        );
+        assert!(strip_doi("https://example.com/paper").is_none());
+        assert!(strip_doi("10.1000").is_none());
+    }
+
+    #[cfg(feature = "schema-org")]
+    #[test]
+    fn test_dataset() {
+        let items = SchemaOrg::parse(
+            r#"{
+                "@type": "Dataset",
+                "name": "City temperatures",
+                "license": {"@type": "CreativeWork", "url": "https://creativecommons.org/licenses/by/4.0/"},
+                "keywords": "climate, weather,",
+                "distribution": [
+                    {"@type": "DataDownload", "contentUrl": "https://data.example/temps.csv", "encodingFormat": "text/csv"},
+                    {"@type": "DataDownload", "encodingFormat": "application/json"}
+                ]
+            }"#,
+        );
+
+        let dataset = Dataset::from_schema(&items[0]).unwrap();
+        assert_eq!(dataset.name.as_deref(), Some("City temperatures"));
+        assert_eq!(
+            dataset.license.as_deref(),
+            Some("https://creativecommons.org/licenses/by/4.0/")
+        );
+        assert_eq!(dataset.keywords, ["climate", "weather"]);
+        assert_eq!(
+            dataset.distributions,
+            [DataDownload {
+                url: "https://data.example/temps.csv".to_string(),
+                encoding_format: Some("text/csv".to_string()),
+            }]
+        );
+    }
+
+    #[cfg(feature = "schema-org")]
+    #[test]
+    fn test_scholarly_article_and_course() {
+        let items = SchemaOrg::parse(
+            r#"[{
+                "@type": "ScholarlyArticle",
+                "name": "Attention Is All You Need",
+                "author": [{"@type": "Person", "name": "Ashish Vaswani"}, "Noam Shazeer"],
+                "identifier": [
+                    {"@type": "PropertyValue", "propertyID": "arXiv", "value": "1706.03762"},
+                    {"@type": "PropertyValue", "propertyID": "DOI", "value": "10.48550/arXiv.1706.03762"}
+                ]
+            }, {
+                "@type": "Course",
+                "name": "Intro to Databases",
+                "courseCode": "CS145",
+                "provider": {"@type": "CollegeOrUniversity", "name": "Stanford"}
+            }]"#,
+        );
+
+        let article = ScholarlyArticle::from_schema(&items[0]).unwrap();
+        assert_eq!(
+            article.headline.as_deref(),
+            Some("Attention Is All You Need")
+        );
+        assert_eq!(article.authors, ["Ashish Vaswani", "Noam Shazeer"]);
+        assert_eq!(article.doi.as_deref(), Some("10.48550/arXiv.1706.03762"));
+        assert!(Course::from_schema(&items[0]).is_none());
+
+        let course = Course::from_schema(&items[1]).unwrap();
+        assert_eq!(course.course_code.as_deref(), Some("CS145"));
+        assert_eq!(course.provider.as_deref(), Some("Stanford"));
+    }
+}
@@ -0,0 +1,303 @@
+//! Content Security Policy and referrer policy
+//!
+//! Both policies can be set by a response header and by a `<meta>` tag. The
+//! same types parse either source: [`HttpInfo::content_security_policy`] and
+//! [`HttpInfo::referrer_policy`] read the headers,
+//! [`HtmlInfo::content_security_policy`] and [`HtmlInfo::referrer_policy`] the
+//! meta tags. Fetched pages whose header and meta values disagree get a warning.
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::html::HtmlInfo;
+#[cfg(feature = "http")]
+use crate::http::HttpInfo;
+
+/// Directives a `<meta>` policy cannot set; browsers ignore them there.
+const HEADER_ONLY_DIRECTIVES: &[&str] = &["frame-ancestors", "report-uri", "sandbox"];
+
+/// A parsed Content Security Policy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ContentSecurityPolicy {
+    /// Directive names (lowercase) and their values, in declaration order
+    pub directives: Vec<(String, Vec<String>)>,
+}
+
+impl ContentSecurityPolicy {
+    /// Parse a policy as serialized in a header or meta tag.
+    ///
+    /// Repeated directives are ignored after the first, as browsers do.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::ContentSecurityPolicy;
+    ///
+    /// let csp = ContentSecurityPolicy::parse("default-src 'self'; img-src * data:");
+    /// assert_eq!(csp.directive("img-src").unwrap(), ["*", "data:"]);
+    /// assert!(csp.directive("script-src").is_none());
+    /// ```
+    pub fn parse(policy: &str) -> Self {
+        let mut directives: Vec<(String, Vec<String>)> = Vec::new();
+        for directive in policy.split(';') {
+            let mut tokens = directive.split_ascii_whitespace();
+            let Some(name) = tokens.next() else {
+                continue;
+            };
+            let name = name.to_ascii_lowercase();
+            if directives.iter().any(|(existing, _)| *existing == name) {
+                continue;
+            }
+            directives.push((name, tokens.map(str::to_string).collect()));
+        }
+        Self { directives }
+    }
+
+    /// The policy without the directives only headers can set.
+    fn without_header_only(mut self) -> Self {
+        self.directives
+            .retain(|(name, _)| !HEADER_ONLY_DIRECTIVES.contains(&name.as_str()));
+        self
+    }
+
+    /// The values of a directive, if the policy sets it.
+    pub fn directive(&self, name: &str) -> Option<&[String]> {
+        self.directives
+            .iter()
+            .find(|(directive, _)| directive.eq_ignore_ascii_case(name))
+            .map(|(_, values)| values.as_slice())
+    }
+}
+
+impl fmt::Display for ContentSecurityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, values)) in self.directives.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            f.write_str(name)?;
+            for value in values {
+                write!(f, " {value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A referrer policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum ReferrerPolicy {
+    /// `no-referrer`
+    NoReferrer,
+    /// `no-referrer-when-downgrade`
+    NoReferrerWhenDowngrade,
+    /// `origin`
+    Origin,
+    /// `origin-when-cross-origin`
+    OriginWhenCrossOrigin,
+    /// `same-origin`
+    SameOrigin,
+    /// `strict-origin`
+    StrictOrigin,
+    /// `strict-origin-when-cross-origin`
+    StrictOriginWhenCrossOrigin,
+    /// `unsafe-url`
+    UnsafeUrl,
+}
+
+impl ReferrerPolicy {
+    /// Parse a `Referrer-Policy` header or `<meta name="referrer">` value.
+    ///
+    /// A comma-separated list uses its last recognized policy. The legacy meta
+    /// keywords `never`, `default`, `always`, and `origin-when-crossorigin` are
+    /// accepted.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::ReferrerPolicy;
+    ///
+    /// assert_eq!(
+    ///     ReferrerPolicy::parse("no-referrer, strict-origin-when-cross-origin"),
+    ///     Some(ReferrerPolicy::StrictOriginWhenCrossOrigin)
+    /// );
+    /// assert_eq!(ReferrerPolicy::parse("never"), Some(ReferrerPolicy::NoReferrer));
+    /// assert_eq!(ReferrerPolicy::parse("sometimes"), None);
+    /// ```
+    pub fn parse(value: &str) -> Option<Self> {
+        value.split(',').rev().find_map(|token| {
+            Some(match token.trim().to_ascii_lowercase().as_str() {
+                "no-referrer" | "never" => Self::NoReferrer,
+                "no-referrer-when-downgrade" => Self::NoReferrerWhenDowngrade,
+                "origin" => Self::Origin,
+                "origin-when-cross-origin" | "origin-when-crossorigin" => {
+                    Self::OriginWhenCrossOrigin
+                }
+                "same-origin" => Self::SameOrigin,
+                "strict-origin" => Self::StrictOrigin,
+                "strict-origin-when-cross-origin" | "default" => Self::StrictOriginWhenCrossOrigin,
+                "unsafe-url" | "always" => Self::UnsafeUrl,
+                _ => return None,
+            })
+        })
+    }
+
+    /// The policy's token, e.g. `"strict-origin"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NoReferrer => "no-referrer",
+            Self::NoReferrerWhenDowngrade => "no-referrer-when-downgrade",
+            Self::Origin => "origin",
+            Self::OriginWhenCrossOrigin => "origin-when-cross-origin",
+            Self::SameOrigin => "same-origin",
+            Self::StrictOrigin => "strict-origin",
+            Self::StrictOriginWhenCrossOrigin => "strict-origin-when-cross-origin",
+            Self::UnsafeUrl => "unsafe-url",
+        }
+    }
+}
+
+impl fmt::Display for ReferrerPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl HtmlInfo {
+    /// The policy of a `<meta http-equiv="Content-Security-Policy">` tag.
+    ///
+    /// `frame-ancestors`, `report-uri`, and `sandbox` are dropped, since
+    /// browsers ignore them in meta tags.
+    pub fn content_security_policy(&self) -> Option<ContentSecurityPolicy> {
+        self.meta
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-security-policy"))
+            .map(|(_, policy)| ContentSecurityPolicy::parse(policy).without_header_only())
+    }
+
+    /// The policy of a `<meta name="referrer">` tag.
+    pub fn referrer_policy(&self) -> Option<ReferrerPolicy> {
+        self.meta("referrer").and_then(ReferrerPolicy::parse)
+    }
+}
+
+#[cfg(feature = "http")]
+impl HttpInfo {
+    /// The policy of the `Content-Security-Policy` response header.
+    pub fn content_security_policy(&self) -> Option<ContentSecurityPolicy> {
+        self.header("content-security-policy")
+            .map(ContentSecurityPolicy::parse)
+    }
+
+    /// The policy of the `Referrer-Policy` response header.
+    pub fn referrer_policy(&self) -> Option<ReferrerPolicy> {
+        self.header("referrer-policy")
+            .and_then(ReferrerPolicy::parse)
+    }
+}
+
+/// Warnings for policies whose header and meta values disagree.
+///
+/// Header-only CSP directives are not compared, since a meta tag cannot set them.
+#[cfg(feature = "http")]
+pub(crate) fn conflict_warnings(http: &HttpInfo, html: &HtmlInfo) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if let (Some(header), Some(meta)) = (
+        http.content_security_policy(),
+        html.content_security_policy(),
+    ) && header.clone().without_header_only() != meta
+    {
+        warnings.push(format!(
+            "Content-Security-Policy conflict: header says \"{header}\", <meta> says \"{meta}\"; both are enforced"
+        ));
+    }
+    if let (Some(header), Some(meta)) = (http.referrer_policy(), html.referrer_policy())
+        && header != meta
+    {
+        warnings.push(format!(
+            "referrer policy conflict: header says {header}, <meta> says {meta}; using {meta} from <meta>"
+        ));
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csp_parsing() {
+        let csp = ContentSecurityPolicy::parse(
+            "  Default-Src 'self' ;; script-src 'nonce-abc' https://cdn.example; default-src *",
+        );
+        assert_eq!(
+            csp.directives,
+            [
+                ("default-src".to_string(), vec!["'self'".to_string()]),
+                (
+                    "script-src".to_string(),
+                    vec!["'nonce-abc'".to_string(), "https://cdn.example".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(
+            csp.to_string(),
+            "default-src 'self'; script-src 'nonce-abc' https://cdn.example"
+        );
+        assert_eq!(
+            ContentSecurityPolicy::parse(""),
+            ContentSecurityPolicy::default()
+        );
+    }
+
+    #[test]
+    fn test_meta_policies() {
+        let mut html = HtmlInfo::default();
+        html.meta.append(
+            "Content-Security-Policy",
+            "default-src 'self'; frame-ancestors 'none'",
+        );
+        html.meta.append("referrer", "origin");
+
+        let csp = html.content_security_policy().unwrap();
+        assert_eq!(csp.to_string(), "default-src 'self'");
+        assert_eq!(html.referrer_policy(), Some(ReferrerPolicy::Origin));
+        assert!(HtmlInfo::default().content_security_policy().is_none());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_conflict_warnings() {
+        let http = HttpInfo {
+            headers: vec![
+                (
+                    "Content-Security-Policy".to_string(),
+                    "default-src 'self'; frame-ancestors 'none'".to_string(),
+                ),
+                ("Referrer-Policy".to_string(), "no-referrer".to_string()),
+            ],
+            ..HttpInfo::default()
+        };
+        let mut html = HtmlInfo::default();
+        html.meta
+            .append("content-security-policy", "default-src 'self'");
+        html.meta.append("referrer", "never");
+        assert!(conflict_warnings(&http, &html).is_empty());
+
+        html.meta.insert("content-security-policy", "default-src *");
+        html.meta.insert("referrer", "unsafe-url");
+        assert_eq!(
+            conflict_warnings(&http, &html),
+            [
+                "Content-Security-Policy conflict: header says \"default-src 'self'; frame-ancestors 'none'\", <meta> says \"default-src *\"; both are enforced",
+                "referrer policy conflict: header says no-referrer, <meta> says unsafe-url; using unsafe-url from <meta>",
+            ]
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Storage sinks for batch fetch results
+//!
+//! A [`ResultSink`] receives each page as [`WebpageClient::fetch_many_into`]
+//! completes it. [`JsonlSink`] writes JSON Lines to any writer or file, and
+//! `SqliteSink` (behind the `sqlite` feature) stores pages in a SQLite database.
+//!
+//! [`WebpageClient::fetch_many_into`]: crate::WebpageClient::fetch_many_into
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::WebpageInfo;
+#[cfg(feature = "sqlite")]
+use crate::compress;
+use crate::error::{Error, Result};
+use crate::export;
+
+/// Destination for batch fetch results.
+pub trait ResultSink {
+    /// Store a successfully fetched page.
+    fn store(&mut self, info: WebpageInfo) -> Result<()>;
+
+    /// Record a URL that failed to fetch. Ignored by default.
+    fn store_error(&mut self, _url: &str, _error: &Error) -> Result<()> {
+        Ok(())
+    }
+
+    /// Flush buffered results. Called once the batch completes.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Collects results in memory.
+impl ResultSink for Vec<WebpageInfo> {
+    fn store(&mut self, info: WebpageInfo) -> Result<()> {
+        self.push(info);
+        Ok(())
+    }
+}
+
+/// Writes each result as one JSON Lines record.
+#[derive(Debug)]
+pub struct JsonlSink<W: Write> {
+    writer: W,
+}
+
+impl JsonlSink<BufWriter<File>> {
+    /// Create (or truncate) a JSON Lines file.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Open a JSON Lines file for appending, creating it if needed.
+    pub fn append(path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> JsonlSink<W> {
+    /// Write JSON Lines to any `Write` implementation.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Consume the sink and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> ResultSink for JsonlSink<W> {
+    fn store(&mut self, info: WebpageInfo) -> Result<()> {
+        let line = export::to_json_line(&info)?;
+        Ok(self.writer.write_all(line.as_bytes())?)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// Stores results in a SQLite database.
+///
+/// Pages go into a `pages` table with the [`ExportRecord`](crate::export::ExportRecord)
+/// columns plus the full result as JSON in `data`; failures go into `errors`.
+/// With [`compression`](Self::compression), `data` holds zstd-compressed JSON
+/// instead; [`pages`](Self::pages) reads either.
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteSink {
+    conn: rusqlite::Connection,
+    #[cfg(feature = "zstd")]
+    level: Option<i32>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteSink {
+    /// Open (or create) a database file and ensure the tables exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_connection(rusqlite::Connection::open(path)?)
+    }
+
+    /// Use an existing connection, creating the tables if needed.
+    pub fn from_connection(conn: rusqlite::Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS pages (
+                url TEXT NOT NULL,
+                status_code INTEGER NOT NULL,
+                title TEXT,
+                description TEXT,
+                canonical_url TEXT,
+                og_title TEXT,
+                og_description TEXT,
+                og_image TEXT,
+                og_type TEXT,
+                og_site_name TEXT,
+                data TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS errors (
+                url TEXT NOT NULL,
+                error TEXT NOT NULL
+            );",
+        )?;
+        Ok(Self {
+            conn,
+            #[cfg(feature = "zstd")]
+            level: None,
+        })
+    }
+
+    /// Store `data` compressed with zstd at `level` (1 to 22; 0 selects the
+    /// default, 3).
+    ///
+    /// The metadata columns stay queryable; `data` becomes a blob that
+    /// [`pages`](Self::pages) decompresses.
+    #[cfg(feature = "zstd")]
+    pub fn compression(mut self, level: i32) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Every stored page, in insertion order.
+    pub fn pages(&self) -> Result<Vec<WebpageInfo>> {
+        let mut statement = self.conn.prepare("SELECT data FROM pages ORDER BY rowid")?;
+        let rows = statement.query_map([], |row| {
+            Ok(match row.get_ref(0)? {
+                rusqlite::types::ValueRef::Blob(bytes) => bytes.to_vec(),
+                value => value.as_str()?.as_bytes().to_vec(),
+            })
+        })?;
+        rows.map(|data| {
+            let data = data?;
+            Ok(serde_json::from_slice(&compress::decompress(&data)?)?)
+        })
+        .collect()
+    }
+
+    /// The underlying connection, e.g. for querying stored results.
+    pub fn connection(&self) -> &rusqlite::Connection {
+        &self.conn
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl ResultSink for SqliteSink {
+    fn store(&mut self, info: WebpageInfo) -> Result<()> {
+        let record = export::ExportRecord::from(&info);
+        #[cfg(feature = "zstd")]
+        let data = match self.level {
+            Some(level) => rusqlite::types::Value::Blob(compress::compress(
+                &serde_json::to_vec(&info)?,
+                level,
+            )?),
+            None => rusqlite::types::Value::Text(serde_json::to_string(&info)?),
+        };
+        #[cfg(not(feature = "zstd"))]
+        let data = serde_json::to_string(&info)?;
+        self.conn.execute(
+            "INSERT INTO pages (url, status_code, title, description, canonical_url, og_title,
+                og_description, og_image, og_type, og_site_name, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                record.url,
+                record.status_code,
+                record.title,
+                record.description,
+                record.canonical_url,
+                record.og_title,
+                record.og_description,
+                record.og_image,
+                record.og_type,
+                record.og_site_name,
+                data,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn store_error(&mut self, url: &str, error: &Error) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO errors (url, error) VALUES (?1, ?2)",
+            rusqlite::params![url, error.to_string()],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(title: &str) -> WebpageInfo {
+        let mut info = WebpageInfo::default();
+        info.http.url = "https://example.com/".to_string();
+        info.html.title = Some(title.to_string());
+        info
+    }
+
+    #[test]
+    fn test_jsonl_sink() {
+        let mut sink = JsonlSink::new(Vec::new());
+        sink.store(page("One")).unwrap();
+        sink.store(page("Two")).unwrap();
+        sink.flush().unwrap();
+
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        let titles: Vec<String> = output
+            .lines()
+            .map(|line| serde_json::from_str::<WebpageInfo>(line).unwrap())
+            .map(|info| info.html.title.unwrap())
+            .collect();
+        assert_eq!(titles, ["One", "Two"]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_sink() {
+        let mut sink =
+            SqliteSink::from_connection(rusqlite::Connection::open_in_memory().unwrap()).unwrap();
+        sink.store(page("Stored")).unwrap();
+        sink.store_error("https://bad.example/", &Error::ParseError)
+            .unwrap();
+
+        let title: String = sink
+            .connection()
+            .query_row("SELECT title FROM pages", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(title, "Stored");
+        assert_eq!(
+            sink.pages().unwrap()[0].html.title.as_deref(),
+            Some("Stored")
+        );
+        let errors: i64 = sink
+            .connection()
+            .query_row("SELECT COUNT(*) FROM errors", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(errors, 1);
+    }
+
+    #[cfg(all(feature = "sqlite", feature = "zstd"))]
+    #[test]
+    fn test_sqlite_sink_compression() {
+        let mut sink = SqliteSink::from_connection(rusqlite::Connection::open_in_memory().unwrap())
+            .unwrap()
+            .compression(19);
+        let mut info = page("Compressed");
+        info.http.body = "<p>archived</p>".repeat(1000);
+        sink.store(info.clone()).unwrap();
+
+        let size: i64 = sink
+            .connection()
+            .query_row("SELECT length(data) FROM pages", [], |row| row.get(0))
+            .unwrap();
+        assert!(size < info.http.body.len() as i64 / 10);
+        let pages = sink.pages().unwrap();
+        assert_eq!(pages[0].html.title.as_deref(), Some("Compressed"));
+        assert_eq!(pages[0].http.body, info.http.body);
+    }
+}
@@ -0,0 +1,249 @@
+//! Raw response snapshots for archival and re-extraction
+//!
+//! With [`HttpOptions::snapshot`] enabled, every fetch keeps the exact response
+//! bytes in [`HttpInfo::snapshot`]. A stored [`Snapshot`] can be parsed again
+//! later with [`WebpageInfo::from_snapshot`], for example after a crate upgrade
+//! improves extraction, without refetching the page.
+//!
+//! [`HttpOptions::snapshot`]: crate::HttpOptions::snapshot
+
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::WebpageInfo;
+use crate::charset::CharsetInfo;
+use crate::client;
+use crate::compress;
+use crate::error::Result;
+use crate::html::ParseOptions;
+use crate::http::HttpInfo;
+
+/// The raw response of a fetch.
+///
+/// Serializes with the body base64-encoded, so snapshots of any encoding
+/// survive a round trip through JSON.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use webpage_info::{HttpOptions, Snapshot, WebpageInfo};
+///
+/// #[tokio::main]
+/// async fn main() -> webpage_info::Result<()> {
+///     let options = HttpOptions::new().snapshot(true);
+///     let info = WebpageInfo::fetch_with_options("https://example.org", options).await?;
+///     info.http.snapshot.unwrap().save("example.snapshot.json")?;
+///
+///     // Later, without network access
+///     let snapshot = Snapshot::load("example.snapshot.json")?;
+///     let info = WebpageInfo::from_snapshot(&snapshot)?;
+///     println!("Title: {:?}", info.html.title);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct Snapshot {
+    /// The final URL after following redirects
+    pub url: String,
+
+    /// HTTP status code
+    pub status_code: u16,
+
+    /// Response headers, in the order received
+    pub headers: Vec<(String, String)>,
+
+    /// Response body exactly as received, after transfer decompression
+    #[serde(with = "crate::base64")]
+    #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
+    pub body: Vec<u8>,
+
+    /// When the response was received, in seconds since the Unix epoch
+    pub fetched_at: u64,
+}
+
+impl Snapshot {
+    /// Capture a response received now.
+    pub(crate) fn capture(
+        url: &str,
+        status_code: u16,
+        headers: &[(String, String)],
+        body: Vec<u8>,
+    ) -> Self {
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            url: url.to_string(),
+            status_code,
+            headers: headers.to_vec(),
+            body,
+            fetched_at,
+        }
+    }
+
+    /// The first value of a response header, matched case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Write the snapshot to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Write the snapshot to a zstd-compressed JSON file.
+    ///
+    /// `level` ranges from 1 (fastest) to 22 (smallest); 0 selects the
+    /// default, 3. HTML typically shrinks to a fifth or less.
+    #[cfg(feature = "zstd")]
+    pub fn save_compressed(&self, path: impl AsRef<Path>, level: i32) -> Result<()> {
+        fs::write(path, compress::compress(&serde_json::to_vec(self)?, level)?)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`save`](Self::save), or by
+    /// `save_compressed` with the `zstd` feature.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        Ok(serde_json::from_slice(&compress::decompress(&bytes)?)?)
+    }
+
+    /// The [`HttpInfo`] of the response, as a fetch would have reported it.
+    ///
+    /// Redirect count and user agent are not part of a snapshot and are left
+    /// empty.
+    pub fn to_http_info(&self) -> HttpInfo {
+        let raw_content_type = self.header("content-type");
+        let charset = CharsetInfo::detect(&self.body, raw_content_type);
+        let body = charset.decode(&self.body);
+        HttpInfo {
+            url: self.url.clone(),
+            status_code: self.status_code,
+            headers: self.headers.clone(),
+            informational: Vec::new(),
+            content_type: raw_content_type
+                .map(|s| s.split(';').next().unwrap_or(s).trim().to_string()),
+            charset: Some(charset),
+            redirect_count: 0,
+            redirects: Vec::new(),
+            cookies: Vec::new(),
+            user_agent: String::new(),
+            body,
+            snapshot: Some(self.clone()),
+        }
+    }
+}
+
+impl WebpageInfo {
+    /// Parse a stored [`Snapshot`] as if it had just been fetched.
+    ///
+    /// Fails with [`Error::InvalidContentType`](crate::Error::InvalidContentType)
+    /// for non-HTML responses, like a fetch does.
+    pub fn from_snapshot(snapshot: &Snapshot) -> Result<Self> {
+        client::parse_response(snapshot.to_http_info(), &ParseOptions::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    fn page(body: &[u8], content_type: &str) -> Snapshot {
+        Snapshot {
+            url: "https://example.com/".to_string(),
+            status_code: 200,
+            headers: vec![("content-type".to_string(), content_type.to_string())],
+            body: body.to_vec(),
+            fetched_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_serializes_and_reparses() {
+        let snapshot = page(
+            b"<html><head><title>Archived</title></head></html>",
+            "text/html; charset=utf-8",
+        );
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(json.contains("\"body\":\"PGh0bWw+"));
+        let back: Snapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, snapshot);
+
+        let info = WebpageInfo::from_snapshot(&back).unwrap();
+        assert_eq!(info.html.title.as_deref(), Some("Archived"));
+        assert_eq!(info.http.content_type.as_deref(), Some("text/html"));
+        assert_eq!(info.http.snapshot.as_ref(), Some(&snapshot));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_compressed_snapshot_round_trip() {
+        let snapshot = page(&b"<p>archived</p>".repeat(500), "text/html");
+        let dir = std::env::temp_dir();
+        let plain = dir.join(format!("webpage-info-{}-snapshot.json", std::process::id()));
+        let compressed = dir.join(format!("webpage-info-{}-snapshot.zst", std::process::id()));
+
+        snapshot.save(&plain).unwrap();
+        snapshot.save_compressed(&compressed, 9).unwrap();
+        assert!(fs::metadata(&compressed).unwrap().len() < fs::metadata(&plain).unwrap().len() / 5);
+        assert_eq!(Snapshot::load(&plain).unwrap(), snapshot);
+        assert_eq!(Snapshot::load(&compressed).unwrap(), snapshot);
+
+        fs::remove_file(plain).unwrap();
+        fs::remove_file(compressed).unwrap();
+    }
+
+    #[test]
+    fn test_from_snapshot_rejects_non_html() {
+        let err = WebpageInfo::from_snapshot(&page(b"{}", "application/json")).unwrap_err();
+        assert!(matches!(err, Error::InvalidContentType { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_captures_snapshot() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html; charset=iso-8859-1")
+                    .set_body_bytes(b"<title>caf\xe9</title>".to_vec()),
+            )
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/", server.uri());
+        let options = crate::HttpOptions::new().block_private_ips(false);
+        let info = WebpageInfo::fetch_with_options(&url, options.clone())
+            .await
+            .unwrap();
+        assert!(info.http.snapshot.is_none());
+
+        let info = WebpageInfo::fetch_with_options(&url, options.snapshot(true))
+            .await
+            .unwrap();
+        let snapshot = info.http.snapshot.as_ref().unwrap();
+        assert_eq!(snapshot.body, b"<title>caf\xe9</title>");
+        assert_eq!(
+            snapshot.header("Content-Type"),
+            Some("text/html; charset=iso-8859-1")
+        );
+        assert!(snapshot.fetched_at > 0);
+
+        let reparsed = WebpageInfo::from_snapshot(snapshot).unwrap();
+        assert_eq!(reparsed.html.title, info.html.title);
+        assert_eq!(reparsed.http.body, info.http.body);
+    }
+}
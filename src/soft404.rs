@@ -0,0 +1,149 @@
+//! Soft-404 detection
+//!
+//! Many sites answer requests for missing pages with `200 OK` and a friendly
+//! error page. [`WebpageInfo::looks_like_soft_404`] recognizes these from the
+//! page content.
+
+use url::Url;
+
+use crate::WebpageInfo;
+
+/// Phrases that mark an error page when found in its title or description.
+const NOT_FOUND_PHRASES: &[&str] = &[
+    "not found",
+    "404",
+    "page does not exist",
+    "page doesn't exist",
+    "page cannot be found",
+    "page can't be found",
+    "no longer available",
+    "nothing was found",
+];
+
+/// Pages with fewer words than this have tiny text content.
+const TINY_CONTENT_WORDS: usize = 50;
+
+impl WebpageInfo {
+    /// Whether a successful response is likely an error page in disguise.
+    ///
+    /// Only `2xx` responses are considered. One strong signal is enough:
+    ///
+    /// - the title, description, or `og:title` says "not found" (or similar)
+    /// - a Schema.org item of type `ErrorPage`, or named like an error page
+    ///
+    /// Otherwise both weak signals are needed: tiny text content, and a
+    /// canonical URL pointing at the homepage of a page that is not the homepage.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::WebpageInfo;
+    ///
+    /// let mut info = WebpageInfo::default();
+    /// info.http.status_code = 200;
+    /// info.html.title = Some("Oops! Page Not Found | Example".to_string());
+    /// assert!(info.looks_like_soft_404());
+    /// ```
+    pub fn looks_like_soft_404(&self) -> bool {
+        if !(200..300).contains(&self.http.status_code) {
+            return false;
+        }
+
+        let html = &self.html;
+        let titled_as_error = [
+            html.title.as_deref(),
+            html.description.as_deref(),
+            html.opengraph.title.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(is_not_found_text);
+
+        #[cfg(feature = "schema-org")]
+        let error_schema = html.schema_org.iter().any(|item| {
+            item.schema_type.eq_ignore_ascii_case("ErrorPage")
+                || ["name", "headline"]
+                    .iter()
+                    .filter_map(|key| item.get_str(key))
+                    .any(is_not_found_text)
+        });
+        #[cfg(not(feature = "schema-org"))]
+        let error_schema = false;
+
+        if titled_as_error || error_schema {
+            return true;
+        }
+
+        let tiny = html.word_count() < TINY_CONTENT_WORDS;
+        tiny && self.canonical_is_homepage()
+    }
+
+    /// Whether the canonical URL is the site root while this page is not.
+    fn canonical_is_homepage(&self) -> bool {
+        let Some(canonical) = self.html.canonical_url.as_deref() else {
+            return false;
+        };
+        let page = Url::parse(&self.http.url).ok();
+        let canonical = match &page {
+            Some(page) => page.join(canonical).ok(),
+            None => Url::parse(canonical).ok(),
+        };
+        let is_root = |url: &Url| url.path() == "/" && url.query().is_none();
+
+        canonical.is_some_and(|c| is_root(&c)) && page.is_some_and(|p| !is_root(&p))
+    }
+}
+
+fn is_not_found_text(text: &str) -> bool {
+    let text = text.to_lowercase();
+    NOT_FOUND_PHRASES.iter().any(|phrase| text.contains(phrase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "schema-org")]
+    use crate::SchemaOrg;
+
+    fn page(url: &str) -> WebpageInfo {
+        let mut info = WebpageInfo::default();
+        info.http.status_code = 200;
+        info.http.url = url.to_string();
+        info
+    }
+
+    #[test]
+    fn test_strong_signals() {
+        let mut info = page("https://example.com/missing");
+        info.html.description = Some("Sorry, this page doesn't exist.".to_string());
+        assert!(info.looks_like_soft_404());
+
+        info.http.status_code = 404;
+        assert!(!info.looks_like_soft_404(), "a real 404 is not soft");
+
+        #[cfg(feature = "schema-org")]
+        {
+            let mut info = page("https://example.com/missing");
+            info.html.schema_org.extend(SchemaOrg::parse(
+                r#"{"@type": "WebPage", "name": "Page not found"}"#,
+            ));
+            assert!(info.looks_like_soft_404());
+        }
+    }
+
+    #[test]
+    fn test_weak_signals_need_each_other() {
+        let mut info = page("https://example.com/products/123");
+        info.html.text_content = "Go back home".to_string();
+        assert!(!info.looks_like_soft_404());
+
+        info.html.canonical_url = Some("/".to_string());
+        assert!(info.looks_like_soft_404());
+
+        info.html.text_content = "word ".repeat(200);
+        assert!(!info.looks_like_soft_404());
+
+        let mut home = page("https://example.com/");
+        home.html.canonical_url = Some("https://example.com/".to_string());
+        assert!(!home.looks_like_soft_404());
+    }
+}
@@ -0,0 +1,192 @@
+//! Client-rendered page detection
+//!
+//! Single-page applications often serve an empty shell that JavaScript fills in
+//! later, so the HTML seen by a crawler has little or no content.
+//! [`HtmlInfo::is_client_rendered`] flags these pages so callers know the
+//! extracted metadata is likely incomplete.
+
+#[cfg(feature = "dom")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "dom")]
+use scraper::{ElementRef, Html, Selector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::html::HtmlInfo;
+
+/// Pages with fewer words than this have essentially no server-rendered content.
+const SHELL_TEXT_WORDS: usize = 30;
+
+/// A JavaScript framework recognized by its markup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Framework {
+    /// Next.js
+    Next,
+    /// Nuxt
+    Nuxt,
+    /// Gatsby
+    Gatsby,
+    /// Angular or AngularJS
+    Angular,
+    /// React
+    React,
+    /// Vue
+    Vue,
+    /// Svelte / SvelteKit
+    Svelte,
+    /// Ember
+    Ember,
+}
+
+/// Selectors identifying each framework's root element or bootstrap data.
+#[cfg(feature = "dom")]
+const FRAMEWORK_MARKERS: &[(Framework, &str)] = &[
+    (Framework::Next, "script#__NEXT_DATA__, div#__next"),
+    (Framework::Nuxt, "div#__nuxt, script#__NUXT_DATA__"),
+    (Framework::Gatsby, "div#___gatsby"),
+    (Framework::Angular, "[ng-version], [ng-app], [data-ng-app]"),
+    (Framework::React, "[data-reactroot], div#root"),
+    (Framework::Vue, "[data-v-app], [data-server-rendered]"),
+    (
+        Framework::Svelte,
+        "[data-sveltekit-preload-data], [data-sveltekit-hydrate]",
+    ),
+    (
+        Framework::Ember,
+        ".ember-application, [data-ember-extension]",
+    ),
+];
+
+/// Elements that are not part of the visible page structure.
+#[cfg(feature = "dom")]
+const NON_CONTENT_ELEMENTS: &[&str] = &["script", "style", "noscript", "template", "link", "meta"];
+
+/// Markup signals of a client-rendered page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SpaSignals {
+    /// Frameworks whose markers appear in the document
+    pub frameworks: Vec<Framework>,
+
+    /// Whether the body holds a single element besides scripts and styles,
+    /// the typical mount point of an app shell
+    pub single_root: bool,
+}
+
+#[cfg(feature = "dom")]
+fn framework_selectors() -> &'static [(Framework, Selector)] {
+    static SELECTORS: OnceLock<Vec<(Framework, Selector)>> = OnceLock::new();
+    SELECTORS.get_or_init(|| {
+        FRAMEWORK_MARKERS
+            .iter()
+            .map(|(framework, selector)| (*framework, Selector::parse(selector).unwrap()))
+            .collect()
+    })
+}
+
+#[cfg(feature = "dom")]
+fn body_selector() -> &'static Selector {
+    static SELECTOR: OnceLock<Selector> = OnceLock::new();
+    SELECTOR.get_or_init(|| Selector::parse("body").unwrap())
+}
+
+#[cfg(feature = "dom")]
+impl SpaSignals {
+    /// Collect framework markers and the body structure of a parsed document.
+    pub(crate) fn detect(document: &Html) -> Self {
+        let frameworks = framework_selectors()
+            .iter()
+            .filter(|(_, selector)| document.select(selector).next().is_some())
+            .map(|(framework, _)| *framework)
+            .collect();
+        let single_root = document.select(body_selector()).next().is_some_and(|body| {
+            body.children()
+                .filter_map(ElementRef::wrap)
+                .filter(|child| !NON_CONTENT_ELEMENTS.contains(&child.value().name()))
+                .count()
+                == 1
+        });
+
+        Self {
+            frameworks,
+            single_root,
+        }
+    }
+}
+
+impl HtmlInfo {
+    /// Whether the page is likely an app shell rendered by JavaScript.
+    ///
+    /// A heuristic: the body has almost no text and a framework marker
+    /// (`__NEXT_DATA__`, `ng-version`, `data-reactroot`, ...), or no text at all
+    /// inside a single root element. Server-rendered framework pages with real
+    /// content are not flagged.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let shell = r#"<body><div id="app"></div><script src="/app.js"></script></body>"#;
+    /// assert!(HtmlInfo::from_string(shell, None).unwrap().is_client_rendered());
+    ///
+    /// let article = "<body><div id=\"app\"><p>Rendered on the server.</p></div></body>";
+    /// # #[cfg(feature = "text")]
+    /// assert!(!HtmlInfo::from_string(article, None).unwrap().is_client_rendered());
+    /// ```
+    pub fn is_client_rendered(&self) -> bool {
+        let signals = &self.spa_signals;
+        if signals.single_root && self.text_content.trim().is_empty() {
+            return true;
+        }
+        !signals.frameworks.is_empty() && self.word_count() < SHELL_TEXT_WORDS
+    }
+}
+
+#[cfg(all(test, feature = "dom"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framework_shells() {
+        let next = r#"<html><body><div id="__next"><div class="spinner">Loading…</div></div>
+            <script id="__NEXT_DATA__" type="application/json">{"props":{}}</script></body></html>"#;
+        let info = HtmlInfo::from_string(next, None).unwrap();
+        assert_eq!(info.spa_signals.frameworks, [Framework::Next]);
+        assert!(info.spa_signals.single_root);
+        assert!(info.is_client_rendered());
+
+        let angular = r#"<body><app-root ng-version="17.0.0"></app-root><nav>Home</nav></body>"#;
+        let info = HtmlInfo::from_string(angular, None).unwrap();
+        assert_eq!(info.spa_signals.frameworks, [Framework::Angular]);
+        assert!(!info.spa_signals.single_root);
+        assert!(info.is_client_rendered());
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_server_rendered_pages_are_not_flagged() {
+        let article = format!(
+            r#"<body><div id="__next"><article>{}</article></div>
+            <script id="__NEXT_DATA__" type="application/json">{{}}</script></body>"#,
+            "Plenty of server-rendered words. ".repeat(20)
+        );
+        assert!(
+            !HtmlInfo::from_string(&article, None)
+                .unwrap()
+                .is_client_rendered()
+        );
+
+        let tiny = "<body><h1>Hi</h1><p>Short static page.</p></body>";
+        assert!(
+            !HtmlInfo::from_string(tiny, None)
+                .unwrap()
+                .is_client_rendered()
+        );
+    }
+}
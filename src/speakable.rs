@@ -0,0 +1,150 @@
+//! Speakable sections
+//!
+//! Schema.org [`speakable`](https://schema.org/speakable) marks the parts of a
+//! page suited to text-to-speech with CSS selectors or XPath expressions.
+//! Voice-assistant pipelines need the text those point at, so the references
+//! are resolved against the document into [`HtmlInfo::speakable`].
+//!
+//! XPath support covers the absolute paths publishers use in practice, such as
+//! `/html/head/title` or `/html/body/div[2]/p`; other expressions are skipped.
+
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+
+use crate::consistency::collapse_whitespace;
+use crate::html::HtmlInfo;
+use crate::schema_org::one_or_many;
+
+/// Maximum number of [`HtmlInfo::speakable`] segments.
+const MAX_SPEAKABLE_SEGMENTS: usize = 100;
+
+impl HtmlInfo {
+    /// Fill `speakable`; runs after the Schema.org phase.
+    pub(crate) fn extract_speakable(&mut self, document: &Html) {
+        let mut segments: Vec<String> = Vec::new();
+        let specs = self
+            .schema_org
+            .iter()
+            .filter_map(|item| item.value.get("speakable"))
+            .flat_map(|speakable| one_or_many(Some(speakable)));
+
+        for spec in specs {
+            let css = one_or_many(spec.get("cssSelector"))
+                .filter_map(Value::as_str)
+                .filter_map(|selector| Selector::parse(selector).ok())
+                .flat_map(|selector| document.select(&selector).collect::<Vec<_>>());
+            let xpath = one_or_many(spec.get("xpath"))
+                .filter_map(Value::as_str)
+                .flat_map(|path| select_xpath(document, path));
+
+            for element in css.chain(xpath) {
+                if segments.len() >= MAX_SPEAKABLE_SEGMENTS {
+                    break;
+                }
+                let text = collapse_whitespace(&element.text().collect::<String>());
+                if !text.is_empty() && !segments.contains(&text) {
+                    segments.push(text);
+                }
+            }
+        }
+        self.speakable = segments;
+    }
+}
+
+/// Evaluate an absolute XPath of element steps with optional 1-based indexes.
+fn select_xpath<'a>(document: &'a Html, path: &str) -> Vec<ElementRef<'a>> {
+    let Some(steps) = path.trim().strip_prefix('/') else {
+        return Vec::new();
+    };
+    let mut current: Vec<ElementRef> = Vec::new();
+    for (i, step) in steps.split('/').enumerate() {
+        let Some((name, index)) = parse_step(step) else {
+            return Vec::new();
+        };
+        let candidates: Vec<ElementRef> = if i == 0 {
+            vec![document.root_element()]
+        } else {
+            current
+                .iter()
+                .flat_map(|parent| parent.children().filter_map(ElementRef::wrap))
+                .collect()
+        };
+        let matching = candidates
+            .into_iter()
+            .filter(|element| element.value().name().eq_ignore_ascii_case(name));
+        current = match index {
+            Some(index) => matching.skip(index - 1).take(1).collect(),
+            None => matching.collect(),
+        };
+        if current.is_empty() {
+            break;
+        }
+    }
+    current
+}
+
+/// Split `p[2]` into `("p", Some(2))`; `None` for anything but a name and index.
+fn parse_step(step: &str) -> Option<(&str, Option<usize>)> {
+    let (name, index) = match step.split_once('[') {
+        Some((name, rest)) => {
+            let index: usize = rest.strip_suffix(']')?.trim().parse().ok()?;
+            if index == 0 {
+                return None;
+            }
+            (name, Some(index))
+        }
+        None => (step, None),
+    };
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then_some((name, index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_css_and_xpath_references() {
+        let html = r#"<html><head><title>Storm  warning</title>
+            <script type="application/ld+json">{
+                "@type": "NewsArticle",
+                "speakable": {
+                    "@type": "SpeakableSpecification",
+                    "cssSelector": [".summary", "h1", "not a [selector"],
+                    "xpath": ["/html/head/title", "/html/body/div[2]/p", "//p"]
+                }
+            }</script></head>
+            <body>
+                <h1>Storm warning</h1>
+                <p class="summary">Heavy rain <b>tonight</b>.</p>
+                <div>skip</div>
+                <div><p>First</p><p>Second</p></div>
+            </body></html>"#;
+
+        let info = HtmlInfo::from_string(html, None).unwrap();
+        assert_eq!(
+            info.speakable,
+            ["Heavy rain tonight.", "Storm warning", "First", "Second"]
+        );
+    }
+
+    #[test]
+    fn test_xpath_subset() {
+        let document =
+            Html::parse_document("<html><body><p>a</p><div><p>b</p><p>c</p></div></body></html>");
+        let texts = |path| {
+            select_xpath(&document, path)
+                .iter()
+                .map(|el| el.text().collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(texts("/html/body/div/p[2]"), ["c"]);
+        assert_eq!(texts("/html/body/p"), ["a"]);
+        assert!(texts("/html/body/p[0]").is_empty());
+        assert!(texts("html/body").is_empty());
+        assert!(texts("/html/body/p[last()]").is_empty());
+    }
+}
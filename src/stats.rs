@@ -0,0 +1,158 @@
+//! Request statistics for long-running clients
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of a [`WebpageClient`](crate::WebpageClient)'s request statistics.
+///
+/// reqwest does not expose its connection pool, so these are request-level
+/// figures: every HTTP request is counted, including each redirect hop.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ClientStats {
+    /// Requests started since the client was created
+    pub requests_total: u64,
+
+    /// Requests currently in progress
+    pub requests_in_flight: u64,
+
+    /// Requests that failed before a response was received
+    pub requests_failed: u64,
+
+    /// Response body bytes downloaded
+    pub bytes_downloaded: u64,
+
+    /// Fetches answered `304 Not Modified` and served from the
+    /// [`HttpOptions::cache`](crate::HttpOptions::cache)
+    pub cache_hits: u64,
+
+    /// Fetches that revalidated a cached response with a conditional request
+    pub cache_revalidations: u64,
+
+    /// Fetches with a cache that had no response to revalidate
+    pub cache_misses: u64,
+
+    /// Statistics per host
+    pub hosts: HashMap<String, HostStats>,
+}
+
+/// Request statistics for a single host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct HostStats {
+    /// Requests started to this host
+    pub requests: u64,
+
+    /// Requests to this host currently in progress
+    pub in_flight: u64,
+}
+
+/// Thread-safe counters behind [`ClientStats`].
+#[derive(Debug, Default)]
+pub(crate) struct StatsCollector {
+    requests_total: AtomicU64,
+    requests_in_flight: AtomicU64,
+    requests_failed: AtomicU64,
+    bytes_downloaded: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_revalidations: AtomicU64,
+    cache_misses: AtomicU64,
+    hosts: Mutex<HashMap<String, HostStats>>,
+}
+
+impl StatsCollector {
+    /// Record the start of a request; the returned guard marks it finished when dropped.
+    pub(crate) fn start(&self, host: &str) -> RequestGuard<'_> {
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+        self.requests_in_flight.fetch_add(1, Ordering::Relaxed);
+        let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = hosts.entry(host.to_string()).or_default();
+        entry.requests += 1;
+        entry.in_flight += 1;
+        RequestGuard {
+            stats: self,
+            host: host.to_string(),
+        }
+    }
+
+    pub(crate) fn record_failure(&self) {
+        self.requests_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_bytes(&self, bytes: u64) {
+        self.bytes_downloaded.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Record a cache lookup that found a response to revalidate, or none.
+    pub(crate) fn record_cache_lookup(&self, found: bool) {
+        let counter = if found {
+            &self.cache_revalidations
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> ClientStats {
+        ClientStats {
+            requests_total: self.requests_total.load(Ordering::Relaxed),
+            requests_in_flight: self.requests_in_flight.load(Ordering::Relaxed),
+            requests_failed: self.requests_failed.load(Ordering::Relaxed),
+            bytes_downloaded: self.bytes_downloaded.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_revalidations: self.cache_revalidations.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+            hosts: self.hosts.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        }
+    }
+}
+
+/// Marks a request as no longer in flight when dropped, including on error or cancellation.
+pub(crate) struct RequestGuard<'a> {
+    stats: &'a StatsCollector,
+    host: String,
+}
+
+impl Drop for RequestGuard<'_> {
+    fn drop(&mut self) {
+        self.stats
+            .requests_in_flight
+            .fetch_sub(1, Ordering::Relaxed);
+        let mut hosts = self.stats.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = hosts.get_mut(&self.host) {
+            entry.in_flight = entry.in_flight.saturating_sub(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_tracks_in_flight() {
+        let stats = StatsCollector::default();
+        let first = stats.start("a.example");
+        let _second = stats.start("b.example");
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.requests_total, 2);
+        assert_eq!(snapshot.requests_in_flight, 2);
+        assert_eq!(snapshot.hosts["a.example"].in_flight, 1);
+
+        drop(first);
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.requests_in_flight, 1);
+        assert_eq!(snapshot.hosts["a.example"].in_flight, 0);
+        assert_eq!(snapshot.hosts["a.example"].requests, 1);
+    }
+}
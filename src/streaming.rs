@@ -0,0 +1,335 @@
+//! Streaming extraction backend
+//!
+//! [`StreamingParser`] extracts head metadata, JSON-LD, and links with
+//! [`lol_html`] as bytes arrive, without building a DOM. Memory use stays
+//! roughly constant regardless of document size, which suits memory-constrained
+//! edge environments.
+//!
+//! The streaming backend does not extract `text_content`; use
+//! [`HtmlInfo::from_string`] when body text is needed.
+
+use std::cell::RefCell;
+use std::io::Read;
+use std::rc::Rc;
+
+use lol_html::html_content::{Element, TextChunk};
+use lol_html::{HtmlRewriter, Settings, element, text};
+use url::Url;
+
+use crate::error::{Error, Result};
+#[cfg(feature = "schema-org")]
+use crate::html::MAX_SCHEMA_ORG_ITEMS;
+use crate::html::{self, HtmlInfo};
+#[cfg(feature = "links")]
+use crate::html::{Interner, Link, MAX_LINKS};
+#[cfg(feature = "schema-org")]
+use crate::schema_org::SchemaOrg;
+#[cfg(feature = "links")]
+use crate::security_policy::ReferrerPolicy;
+
+/// Extraction state shared by the rewriter's handlers.
+#[derive(Default)]
+struct State {
+    info: HtmlInfo,
+    title: Option<String>,
+    title_done: bool,
+    base_url: Option<Url>,
+    #[cfg(feature = "links")]
+    strings: Interner,
+    /// Anchor text of the link currently being read
+    #[cfg(feature = "links")]
+    link_text: Option<String>,
+    #[cfg(feature = "schema-org")]
+    json_ld: String,
+}
+
+#[cfg(feature = "links")]
+impl State {
+    /// Store the anchor text collected for the previous link.
+    fn finish_link(&mut self) {
+        if let Some(text) = self.link_text.take()
+            && let Some(link) = self.info.links.last_mut()
+        {
+            link.text = self.strings.intern(text.trim());
+        }
+    }
+}
+
+/// Incremental HTML metadata extractor.
+///
+/// # Example
+/// ```
+/// use webpage_info::StreamingParser;
+///
+/// let mut parser = StreamingParser::new(Some("https://example.com/"));
+/// parser.write(b"<html><head><title>Hel").unwrap();
+/// parser.write(b"lo</title></head><body><a href=\"/a\">A</a></body></html>").unwrap();
+///
+/// let info = parser.finish().unwrap();
+/// assert_eq!(info.title.as_deref(), Some("Hello"));
+/// # #[cfg(feature = "links")]
+/// assert_eq!(&*info.links[0].url, "https://example.com/a");
+/// ```
+pub struct StreamingParser {
+    rewriter: HtmlRewriter<'static, fn(&[u8])>,
+    state: Rc<RefCell<State>>,
+}
+
+impl StreamingParser {
+    /// Create a parser; `base_url` is used to resolve relative links.
+    pub fn new(base_url: Option<&str>) -> Self {
+        let state = Rc::new(RefCell::new(State {
+            base_url: base_url.and_then(|u| Url::parse(u).ok()),
+            ..State::default()
+        }));
+
+        let s = state.clone();
+        let on_html = element!("html[lang]", move |el: &mut Element| {
+            let mut state = s.borrow_mut();
+            if state.info.language.is_none() {
+                state.info.language = el
+                    .get_attribute("lang")
+                    .map(|lang| lang.trim().to_string())
+                    .filter(|lang| !lang.is_empty());
+            }
+            Ok(())
+        });
+
+        let s = state.clone();
+        let on_title = text!("title", move |chunk: &mut TextChunk| {
+            let mut state = s.borrow_mut();
+            if !state.title_done {
+                state
+                    .title
+                    .get_or_insert_with(String::new)
+                    .push_str(chunk.as_str());
+                state.title_done = chunk.last_in_text_node();
+            }
+            Ok(())
+        });
+
+        let s = state.clone();
+        let on_meta = element!("meta", move |el: &mut Element| {
+            let attrs: Vec<(String, String)> = el
+                .attributes()
+                .iter()
+                .map(|attr| (attr.name(), attr.value()))
+                .collect();
+            s.borrow_mut().info.add_meta_tag(|name| {
+                attrs
+                    .iter()
+                    .find(|(attr, _)| attr == name)
+                    .map(|(_, value)| value.as_str())
+            });
+            Ok(())
+        });
+
+        let s = state.clone();
+        let on_link = element!("link[rel][href]", move |el: &mut Element| {
+            let mut state = s.borrow_mut();
+            let state = &mut *state;
+            let attrs: Vec<(String, String)> = el
+                .attributes()
+                .iter()
+                .map(|attr| (attr.name(), attr.value()))
+                .collect();
+            let attr = |name: &str| {
+                attrs
+                    .iter()
+                    .find(|(attr, _)| attr == name)
+                    .map(|(_, value)| value.as_str())
+            };
+            state.info.add_icon_link(state.base_url.as_ref(), attr);
+            state.info.add_oembed_link(state.base_url.as_ref(), attr);
+            let rel = el.get_attribute("rel").unwrap_or_default();
+            let href = || el.get_attribute("href").map(|href| href.trim().to_string());
+            if rel == "canonical" && state.info.canonical_url.is_none() {
+                state.info.canonical_url = href().filter(|href| !href.is_empty());
+            } else if rel == "alternate"
+                && state.info.feed_url.is_none()
+                && el
+                    .get_attribute("type")
+                    .is_some_and(|t| html::is_feed_type(&t))
+            {
+                state.info.feed_url = href();
+            } else if rel == "next" && state.info.next_url.is_none() {
+                state.info.next_url = href().filter(|href| !href.is_empty());
+            } else if (rel == "prev" || rel == "previous") && state.info.prev_url.is_none() {
+                state.info.prev_url = href().filter(|href| !href.is_empty());
+            }
+            Ok(())
+        });
+
+        #[cfg(feature = "links")]
+        let s = state.clone();
+        #[cfg(feature = "links")]
+        let on_anchor = element!("a[href]", move |el: &mut Element| {
+            let mut state = s.borrow_mut();
+            let state = &mut *state;
+            state.finish_link();
+            if state.info.links.len() >= MAX_LINKS {
+                return Ok(());
+            }
+
+            let href = el.get_attribute("href").unwrap_or_default();
+            let Some(url) = html::resolve_link(&href, state.base_url.as_ref(), &mut state.strings)
+            else {
+                return Ok(());
+            };
+            let mut attr = |name| {
+                el.get_attribute(name)
+                    .map(|value| state.strings.intern(&value))
+            };
+            let rel = attr("rel");
+            let target = attr("target");
+            let download = attr("download");
+            let referrer_policy = el
+                .get_attribute("referrerpolicy")
+                .and_then(|policy| ReferrerPolicy::parse(&policy));
+            state.info.links.push(Link {
+                url,
+                text: state.strings.intern(""),
+                rel,
+                target,
+                download,
+                referrer_policy,
+                verdict: None,
+            });
+            state.link_text = Some(String::new());
+            Ok(())
+        });
+
+        #[cfg(feature = "links")]
+        let s = state.clone();
+        #[cfg(feature = "links")]
+        let on_anchor_text = text!("a[href]", move |chunk: &mut TextChunk| {
+            if let Some(text) = s.borrow_mut().link_text.as_mut() {
+                text.push_str(chunk.as_str());
+            }
+            Ok(())
+        });
+
+        #[cfg(feature = "schema-org")]
+        let s = state.clone();
+        #[cfg(feature = "schema-org")]
+        let on_json_ld = text!(
+            r#"script[type="application/ld+json"]"#,
+            move |chunk: &mut TextChunk| {
+                let mut state = s.borrow_mut();
+                state.json_ld.push_str(chunk.as_str());
+                if chunk.last_in_text_node() {
+                    let content = std::mem::take(&mut state.json_ld);
+                    let room = MAX_SCHEMA_ORG_ITEMS.saturating_sub(state.info.schema_org.len());
+                    let items = SchemaOrg::parse(&content).into_iter().take(room);
+                    state.info.schema_org.extend(items);
+                }
+                Ok(())
+            }
+        );
+
+        let settings = Settings::new()
+            .append_element_content_handler(on_html)
+            .append_element_content_handler(on_title)
+            .append_element_content_handler(on_meta)
+            .append_element_content_handler(on_link);
+        #[cfg(feature = "links")]
+        let settings = settings
+            .append_element_content_handler(on_anchor)
+            .append_element_content_handler(on_anchor_text);
+        #[cfg(feature = "schema-org")]
+        let settings = settings.append_element_content_handler(on_json_ld);
+
+        Self {
+            rewriter: HtmlRewriter::new(settings, discard as fn(&[u8])),
+            state,
+        }
+    }
+
+    /// Feed the next chunk of the document.
+    pub fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        self.rewriter.write(chunk).map_err(|_| Error::ParseError)
+    }
+
+    /// Finish parsing and return the extracted metadata.
+    pub fn finish(self) -> Result<HtmlInfo> {
+        self.rewriter.end().map_err(|_| Error::ParseError)?;
+
+        let mut state = Rc::try_unwrap(self.state)
+            .map_err(|_| Error::ParseError)?
+            .into_inner();
+        #[cfg(feature = "links")]
+        state.finish_link();
+        state.info.add_fallback_icon(state.base_url.as_ref());
+        state.info.title = state
+            .title
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
+        state.info.normalize_strings();
+        Ok(state.info)
+    }
+
+    /// Parse a whole document from a reader, one buffer at a time.
+    pub fn parse_reader(mut reader: impl Read, base_url: Option<&str>) -> Result<HtmlInfo> {
+        let mut parser = Self::new(base_url);
+        let mut buffer = [0u8; 8 * 1024];
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            parser.write(&buffer[..read])?;
+        }
+        parser.finish()
+    }
+}
+
+/// The rewriter's output is not needed; only the handlers' side effects are.
+fn discard(_: &[u8]) {}
+
+#[cfg(all(test, any(feature = "dom", feature = "links")))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "dom")]
+    #[test]
+    fn test_matches_dom_extraction() {
+        let html = include_str!("../test_data/sample.html");
+        let base = Some("https://example.com/");
+
+        let dom = HtmlInfo::from_string(html, base).unwrap();
+        let streamed = StreamingParser::parse_reader(html.as_bytes(), base).unwrap();
+
+        assert_eq!(streamed.title, dom.title);
+        assert_eq!(streamed.description, dom.description);
+        assert_eq!(streamed.canonical_url, dom.canonical_url);
+        assert_eq!(streamed.feed_url, dom.feed_url);
+        assert_eq!(streamed.icons, dom.icons);
+        assert_eq!(streamed.oembed_url, dom.oembed_url);
+        assert_eq!(streamed.language, dom.language);
+        assert_eq!(streamed.meta, dom.meta);
+        assert_eq!(streamed.opengraph.title, dom.opengraph.title);
+        #[cfg(feature = "schema-org")]
+        assert_eq!(streamed.schema_org.len(), dom.schema_org.len());
+        #[cfg(feature = "links")]
+        assert_eq!(streamed.links, dom.links);
+        assert!(streamed.text_content.is_empty());
+    }
+
+    #[cfg(feature = "links")]
+    #[test]
+    fn test_byte_at_a_time() {
+        let html = r#"<title>T</title><a href="/x" rel="next">Next <b>page</b></a><a href="javascript:void(0)">skip</a><a href="/y"></a>"#;
+        let mut parser = StreamingParser::new(None);
+        for byte in html.as_bytes() {
+            parser.write(std::slice::from_ref(byte)).unwrap();
+        }
+        let info = parser.finish().unwrap();
+
+        assert_eq!(info.title.as_deref(), Some("T"));
+        assert_eq!(info.links.len(), 2);
+        assert_eq!(&*info.links[0].text, "Next page");
+        assert_eq!(info.links[0].rel.as_deref(), Some("next"));
+        assert_eq!(&*info.links[1].url, "/y");
+        assert_eq!(&*info.links[1].text, "");
+    }
+}
@@ -0,0 +1,194 @@
+//! Cleanup of extracted strings
+//!
+//! Extracted strings can still contain character references: markup that
+//! escapes its text twice (`&amp;#8217;`), or backends that do not decode
+//! everything. Every extracted string is passed through [`normalize`], which
+//! decodes the remaining references and applies Unicode NFC normalization so
+//! equal text compares equal.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use unicode_normalization::{IsNormalized, UnicodeNormalization, is_nfc_quick};
+
+use crate::html::HtmlInfo;
+
+/// Longest reference name considered, e.g. `CounterClockwiseContourIntegral`.
+const MAX_REFERENCE_LEN: usize = 31;
+
+/// HTML5 named character references, keyed by name without `&` and `;`.
+fn named_references() -> &'static HashMap<&'static str, &'static str> {
+    static REFERENCES: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+    REFERENCES.get_or_init(|| {
+        entities::ENTITIES
+            .iter()
+            .filter_map(|entity| {
+                let name = entity.entity.strip_prefix('&')?.strip_suffix(';')?;
+                Some((name, entity.characters))
+            })
+            .collect()
+    })
+}
+
+/// Decode numeric and HTML5 named character references.
+///
+/// Unknown or malformed references are kept verbatim.
+pub(crate) fn decode_entities(input: &str) -> Cow<'_, str> {
+    if !input.contains('&') {
+        return Cow::Borrowed(input);
+    }
+
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= MAX_REFERENCE_LEN)
+            .and_then(|end| Some((decode_reference(&rest[1..=end])?, end + 2)));
+
+        match decoded {
+            Some((decoded, len)) => {
+                output.push_str(&decoded);
+                rest = &rest[len..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    Cow::Owned(output)
+}
+
+/// The characters for a reference name without `&` and `;`.
+fn decode_reference(name: &str) -> Option<Cow<'static, str>> {
+    let code = match name.strip_prefix('#') {
+        Some(hex) if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16).ok()?,
+        Some(dec) => dec.parse().ok()?,
+        None => return named_references().get(name).map(|&c| Cow::Borrowed(c)),
+    };
+    char::from_u32(code).map(|c| Cow::Owned(c.to_string()))
+}
+
+/// Decode character references and normalize to NFC.
+pub(crate) fn normalize(input: &str) -> Cow<'_, str> {
+    let decoded = decode_entities(input);
+    if is_nfc_quick(decoded.chars()) == IsNormalized::Yes {
+        return decoded;
+    }
+    Cow::Owned(decoded.nfc().collect())
+}
+
+fn normalize_in_place(value: &mut String) {
+    if let Cow::Owned(normalized) = normalize(value) {
+        *value = normalized;
+    }
+}
+
+fn normalize_option(value: &mut Option<String>) {
+    if let Some(value) = value {
+        normalize_in_place(value);
+    }
+}
+
+fn normalize_shared(value: &mut Arc<str>) {
+    if let Cow::Owned(normalized) = normalize(value) {
+        *value = normalized.into();
+    }
+}
+
+/// Whether a meta name holds a URL, e.g. `og:image`, `og:image:secure_url`, or
+/// `twitter:player`.
+fn is_url_property(name: &str) -> bool {
+    let field = name.rsplit(':').next().unwrap_or(name);
+    matches!(
+        field.to_ascii_lowercase().as_str(),
+        "url" | "secure_url" | "src" | "image" | "video" | "audio" | "player" | "stream"
+    )
+}
+
+impl HtmlInfo {
+    /// Run [`normalize`] over every extracted text field.
+    ///
+    /// URLs are left alone: their references are resolved by the parser and
+    /// NFC can change their meaning.
+    pub(crate) fn normalize_strings(&mut self) {
+        normalize_option(&mut self.title);
+        normalize_option(&mut self.description);
+        normalize_option(&mut self.lead_text);
+        normalize_in_place(&mut self.text_content);
+        for block in &mut self.text_blocks {
+            normalize_in_place(&mut block.text);
+        }
+        #[cfg(feature = "schema-org")]
+        self.speakable.iter_mut().for_each(normalize_in_place);
+        for (name, contents) in self.meta.entries_mut() {
+            if !is_url_property(name) {
+                contents.iter_mut().for_each(normalize_in_place);
+            }
+        }
+
+        let og = &mut self.opengraph;
+        normalize_option(&mut og.title);
+        normalize_option(&mut og.description);
+        normalize_option(&mut og.site_name);
+        for media in og
+            .images
+            .iter_mut()
+            .chain(&mut og.videos)
+            .chain(&mut og.audios)
+        {
+            normalize_option(&mut media.alt);
+        }
+
+        for link in &mut self.links {
+            normalize_shared(&mut link.text);
+        }
+        for image in &mut self.image_candidates {
+            normalize_option(&mut image.alt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("a &amp; b"), "a & b");
+        assert_eq!(decode_entities("&#169; &#x2014;"), "\u{a9} \u{2014}");
+        assert_eq!(decode_entities("it&rsquo;s &copy;"), "it\u{2019}s \u{a9}");
+        assert_eq!(
+            decode_entities("AT&T &bogus; &#xZZ;"),
+            "AT&T &bogus; &#xZZ;"
+        );
+        assert_eq!(decode_entities("&#1234567890;"), "&#1234567890;");
+    }
+
+    #[test]
+    fn test_decode_full_named_table() {
+        assert_eq!(
+            decode_entities("Caf&eacute; &hearts;"),
+            "Caf\u{e9} \u{2665}"
+        );
+        assert_eq!(
+            decode_entities("&CounterClockwiseContourIntegral;"),
+            "\u{2233}"
+        );
+        // Some references expand to two code points
+        assert_eq!(decode_entities("&NotEqualTilde;"), "\u{2242}\u{338}");
+    }
+
+    #[test]
+    fn test_normalize_composes() {
+        // "e" followed by a combining acute accent
+        assert_eq!(normalize("Cafe\u{301} &amp; bar"), "Caf\u{e9} & bar");
+        assert!(matches!(normalize("plain"), Cow::Borrowed(_)));
+    }
+}
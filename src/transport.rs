@@ -0,0 +1,119 @@
+//! Pluggable page transports
+//!
+//! A [`WebpageClient`] fetches pages with its reqwest connection pool by
+//! default. A [`Transport`] registered with [`WebpageClient::with_transport`]
+//! replaces that step, for example to render pages in a headless browser,
+//! while the client keeps running observers, SSRF validation of the requested
+//! URL, the body size limit, and parsing.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::client::WebpageClient;
+use crate::error::Result;
+use crate::http::{HttpInfo, HttpOptions};
+use crate::policy::RequestPolicy;
+
+/// The future returned by [`Transport::fetch`].
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<HttpInfo>> + Send + 'a>>;
+
+/// Fetches the HTML of a page on behalf of a [`WebpageClient`].
+///
+/// Implementations should honor the [`HttpOptions`] that apply to them, such as
+/// the timeout and user agent, and report transport failures as
+/// [`Error::Transport`](crate::Error::Transport).
+///
+/// # Example
+///
+/// ```rust
+/// use webpage_info::{HttpInfo, HttpOptions, Transport, TransportFuture, WebpageClient};
+///
+/// /// Serves every page from memory.
+/// struct Fixture;
+///
+/// impl Transport for Fixture {
+///     fn fetch<'a>(&'a self, url: &'a str, _options: &'a HttpOptions) -> TransportFuture<'a> {
+///         Box::pin(async move {
+///             Ok(HttpInfo {
+///                 url: url.to_string(),
+///                 status_code: 200,
+///                 body: "<title>Fixture</title>".to_string(),
+///                 ..HttpInfo::default()
+///             })
+///         })
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> webpage_info::Result<()> {
+/// let client = WebpageClient::new(HttpOptions::new())?.with_transport(Fixture);
+/// let info = client.fetch("https://example.org/").await?;
+/// assert_eq!(info.html.title.as_deref(), Some("Fixture"));
+/// # Ok(())
+/// # }
+/// ```
+pub trait Transport: Send + Sync {
+    /// Fetch `url` and return the response with its body.
+    fn fetch<'a>(&'a self, url: &'a str, options: &'a HttpOptions) -> TransportFuture<'a>;
+
+    /// Fetch `url`, applying the client's request `policy` to every further
+    /// request the transport makes, such as subresources and redirects.
+    ///
+    /// This is what the client calls. `url` itself has already been checked.
+    /// The default ignores `policy` and calls [`fetch`](Self::fetch), which
+    /// suits transports that make no requests beyond `url`.
+    fn fetch_with_policy<'a>(
+        &'a self,
+        url: &'a str,
+        options: &'a HttpOptions,
+        policy: RequestPolicy,
+    ) -> TransportFuture<'a> {
+        let _ = policy;
+        self.fetch(url, options)
+    }
+}
+
+impl WebpageClient {
+    /// Fetch pages with a custom transport instead of the built-in HTTP client.
+    ///
+    /// Link checking still uses the HTTP client.
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.state.transport = Some(std::sync::Arc::new(transport));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    struct Echo;
+
+    impl Transport for Echo {
+        fn fetch<'a>(&'a self, url: &'a str, _options: &'a HttpOptions) -> TransportFuture<'a> {
+            Box::pin(async move {
+                Ok(HttpInfo {
+                    url: url.to_string(),
+                    status_code: 200,
+                    content_type: Some("text/html".to_string()),
+                    body: format!("<title>{}</title>", "é".repeat(100)),
+                    ..HttpInfo::default()
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_applies_limits_to_custom_transport() {
+        let options = HttpOptions::new().max_body_size(12);
+        let client = WebpageClient::new(options).unwrap().with_transport(Echo);
+
+        let info = client.fetch("https://example.com/").await.unwrap();
+        assert_eq!(info.http.body, "<title>éé");
+        assert_eq!(client.stats().requests_total, 1);
+
+        let err = client.fetch("http://127.0.0.1/").await.unwrap_err();
+        assert!(matches!(err, Error::SsrfBlocked { .. }));
+    }
+}
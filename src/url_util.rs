@@ -0,0 +1,191 @@
+//! URL normalization and comparison
+//!
+//! Pages are reachable under many spellings of the same URL: different host
+//! case, explicit default ports, trailing slashes, fragments, and analytics
+//! parameters. [`same_page`] treats these as equal, for canonical URL checks
+//! and crawl deduplication.
+
+use url::Url;
+
+/// Query parameters that only carry analytics and never change the page.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_ga", "_gl",
+];
+
+/// Options controlling [`normalize`] and [`same_page_with`].
+#[derive(Debug, Clone)]
+pub struct NormalizeOptions {
+    /// Remove `utm_*` and other analytics query parameters.
+    ///
+    /// Default: true.
+    pub strip_tracking: bool,
+
+    /// Treat `/path/` and `/path` as the same page.
+    ///
+    /// Default: true.
+    pub ignore_trailing_slash: bool,
+
+    /// Treat `www.example.com` and `example.com` as the same host.
+    ///
+    /// Default: false.
+    pub fold_www: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            strip_tracking: true,
+            ignore_trailing_slash: true,
+            fold_www: false,
+        }
+    }
+}
+
+impl NormalizeOptions {
+    /// Create a new NormalizeOptions with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to remove analytics query parameters.
+    pub fn strip_tracking(mut self, strip: bool) -> Self {
+        self.strip_tracking = strip;
+        self
+    }
+
+    /// Set whether a trailing slash on the path is ignored.
+    pub fn ignore_trailing_slash(mut self, ignore: bool) -> Self {
+        self.ignore_trailing_slash = ignore;
+        self
+    }
+
+    /// Set whether a leading `www.` on the host is ignored.
+    pub fn fold_www(mut self, fold: bool) -> Self {
+        self.fold_www = fold;
+        self
+    }
+}
+
+/// Normalize an absolute URL, or return `None` if it does not parse.
+///
+/// Scheme and host are lowercased, default ports and the fragment are removed,
+/// and the remaining steps follow `options`.
+///
+/// # Example
+/// ```
+/// use webpage_info::url_util::{NormalizeOptions, normalize};
+///
+/// let url = normalize("HTTPS://WWW.Example.com:443/a/?utm_source=x&id=1#top", &NormalizeOptions::new().fold_www(true));
+/// assert_eq!(url.as_deref(), Some("https://example.com/a?id=1"));
+/// ```
+pub fn normalize(url: &str, options: &NormalizeOptions) -> Option<String> {
+    let mut url = Url::parse(url.trim()).ok()?;
+    url.set_fragment(None);
+
+    if options.fold_www
+        && let Some(host) = url.host_str().and_then(|h| h.strip_prefix("www."))
+    {
+        let host = host.to_string();
+        url.set_host(Some(&host)).ok()?;
+    }
+
+    if options.strip_tracking && url.query().is_some() {
+        let kept: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(name, _)| !is_tracking_param(name))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        if kept.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut().clear().extend_pairs(kept);
+        }
+    }
+
+    if options.ignore_trailing_slash && url.path().len() > 1 && url.path().ends_with('/') {
+        let path = url.path().trim_end_matches('/').to_string();
+        url.set_path(&path);
+    }
+
+    Some(url.into())
+}
+
+/// Whether two URLs refer to the same page under default [`NormalizeOptions`].
+///
+/// URLs that do not parse are compared as trimmed strings.
+///
+/// # Example
+/// ```
+/// use webpage_info::url_util::same_page;
+///
+/// assert!(same_page("https://example.com/post/?utm_medium=social", "https://EXAMPLE.com:443/post#comments"));
+/// assert!(!same_page("https://example.com/post", "http://example.com/post"));
+/// ```
+pub fn same_page(a: &str, b: &str) -> bool {
+    same_page_with(a, b, &NormalizeOptions::default())
+}
+
+/// Whether two URLs refer to the same page under the given options.
+pub fn same_page_with(a: &str, b: &str, options: &NormalizeOptions) -> bool {
+    match (normalize(a, options), normalize(b, options)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.trim() == b.trim(),
+    }
+}
+
+fn is_tracking_param(name: &str) -> bool {
+    name.starts_with("utm_") || TRACKING_PARAMS.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equivalent_spellings() {
+        assert!(same_page("http://Example.COM:80", "http://example.com/"));
+        assert!(same_page(
+            "https://example.com/a/b/",
+            "https://example.com/a/b"
+        ));
+        assert!(same_page(
+            "https://example.com/?gclid=1&q=rust&utm_campaign=x",
+            "https://example.com/?q=rust"
+        ));
+        assert!(!same_page(
+            "https://example.com/?q=rust",
+            "https://example.com/?q=go"
+        ));
+        assert!(!same_page(
+            "https://www.example.com/",
+            "https://example.com/"
+        ));
+        assert!(same_page_with(
+            "https://www.example.com/",
+            "https://example.com/",
+            &NormalizeOptions::new().fold_www(true)
+        ));
+    }
+
+    #[test]
+    fn test_options_can_be_disabled() {
+        let strict = NormalizeOptions::new()
+            .strip_tracking(false)
+            .ignore_trailing_slash(false);
+        assert!(!same_page_with(
+            "https://example.com/a/",
+            "https://example.com/a",
+            &strict
+        ));
+        assert_eq!(
+            normalize("https://example.com/?utm_source=x", &strict).as_deref(),
+            Some("https://example.com/?utm_source=x")
+        );
+    }
+
+    #[test]
+    fn test_unparseable_urls_compare_as_strings() {
+        assert!(same_page(" /relative ", "/relative"));
+        assert!(!same_page("/relative", "https://example.com/relative"));
+    }
+}
@@ -0,0 +1,301 @@
+//! User-agent presets
+//!
+//! Many sites serve different metadata depending on who is asking: crawlers
+//! often get server-rendered pages with complete OpenGraph tags, while browsers
+//! get an app shell. [`UserAgent`] names the common identities so they can be
+//! passed straight to [`HttpOptions::user_agent`](crate::HttpOptions::user_agent).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A well-known User-Agent string.
+///
+/// Crawler presets only claim the crawler's identity; sites that verify
+/// crawlers by reverse DNS will still treat requests as unverified. Browser
+/// presets match recent stable releases.
+///
+/// # Example
+/// ```
+/// use webpage_info::{HttpOptions, UserAgent};
+///
+/// let options = HttpOptions::new().user_agent(UserAgent::Googlebot);
+/// assert!(options.user_agent.contains("Googlebot/2.1"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum UserAgent {
+    /// Google's desktop web crawler
+    Googlebot,
+    /// Google's smartphone web crawler, used for mobile-first indexing
+    GooglebotSmartphone,
+    /// Microsoft Bing's web crawler
+    Bingbot,
+    /// X (Twitter) link preview fetcher
+    TwitterBot,
+    /// Facebook link preview fetcher
+    FacebookBot,
+    /// LinkedIn link preview fetcher
+    LinkedInBot,
+    /// Slack link unfurler
+    Slackbot,
+    /// Discord link preview fetcher
+    DiscordBot,
+    /// Chrome on Windows
+    Chrome,
+    /// Firefox on Windows
+    Firefox,
+    /// Safari on macOS
+    Safari,
+    /// Safari on iPhone
+    MobileSafari,
+}
+
+impl UserAgent {
+    /// The User-Agent header value.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Googlebot => {
+                "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)"
+            }
+            Self::GooglebotSmartphone => {
+                "Mozilla/5.0 (Linux; Android 6.0.1; Nexus 5X Build/MMB29P) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/131.0.6778.204 Mobile Safari/537.36 \
+                 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)"
+            }
+            Self::Bingbot => {
+                "Mozilla/5.0 (compatible; bingbot/2.0; +http://www.bing.com/bingbot.htm)"
+            }
+            Self::TwitterBot => "Twitterbot/1.0",
+            Self::FacebookBot => {
+                "facebookexternalhit/1.1 (+http://www.facebook.com/externalhit_uatext.php)"
+            }
+            Self::LinkedInBot => {
+                "LinkedInBot/1.0 (compatible; Mozilla/5.0; +https://www.linkedin.com)"
+            }
+            Self::Slackbot => "Slackbot-LinkExpanding 1.0 (+https://api.slack.com/robots)",
+            Self::DiscordBot => "Mozilla/5.0 (compatible; Discordbot/2.0; +https://discordapp.com)",
+            Self::Chrome => {
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36"
+            }
+            Self::Firefox => {
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:133.0) Gecko/20100101 Firefox/133.0"
+            }
+            Self::Safari => {
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/18.2 Safari/605.1.15"
+            }
+            Self::MobileSafari => {
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 18_2 like Mac OS X) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/18.2 Mobile/15E148 Safari/604.1"
+            }
+        }
+    }
+
+    /// Whether this preset identifies as a crawler or link preview bot.
+    pub fn is_bot(self) -> bool {
+        !matches!(
+            self,
+            Self::Chrome | Self::Firefox | Self::Safari | Self::MobileSafari
+        )
+    }
+}
+
+impl fmt::Display for UserAgent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<UserAgent> for String {
+    fn from(user_agent: UserAgent) -> Self {
+        user_agent.as_str().to_string()
+    }
+}
+
+/// The low-entropy `Sec-CH-UA` client hints a Chromium browser sends along
+/// with `user_agent`, or none for other browsers and crawlers.
+///
+/// Chrome, Edge, and Opera send these with every request; Firefox and Safari
+/// do not implement client hints.
+pub(crate) fn client_hints(user_agent: &str) -> Vec<(&'static str, String)> {
+    if user_agent.contains("compatible;") || user_agent.contains("Headless") {
+        return Vec::new();
+    }
+    let version = |product: &str| {
+        let start = user_agent.find(product)? + product.len();
+        let major: String = user_agent[start..]
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        (!major.is_empty()).then_some(major)
+    };
+    let Some(chromium) = version("Chrome/") else {
+        return Vec::new();
+    };
+    let brand = if let Some(edge) = version("Edg/") {
+        ("Microsoft Edge", edge)
+    } else if let Some(opera) = version("OPR/") {
+        ("Opera", opera)
+    } else {
+        ("Google Chrome", chromium.clone())
+    };
+    let platform = if user_agent.contains("Windows") {
+        "Windows"
+    } else if user_agent.contains("Android") {
+        "Android"
+    } else if user_agent.contains("CrOS") {
+        "Chrome OS"
+    } else if user_agent.contains("Macintosh") {
+        "macOS"
+    } else {
+        "Linux"
+    };
+    let mobile = if user_agent.contains("Mobile") {
+        "?1"
+    } else {
+        "?0"
+    };
+
+    vec![
+        (
+            "sec-ch-ua",
+            format!(
+                "\"{}\";v=\"{}\", \"Chromium\";v=\"{chromium}\", \"Not_A Brand\";v=\"24\"",
+                brand.0, brand.1
+            ),
+        ),
+        ("sec-ch-ua-mobile", mobile.to_string()),
+        ("sec-ch-ua-platform", format!("\"{platform}\"")),
+    ]
+}
+
+/// How a [`WebpageClient`](crate::WebpageClient) cycles through several user agents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RotationPolicy {
+    /// Use the next user agent for every request
+    #[default]
+    PerRequest,
+    /// Give each host its own user agent and keep it for every request to that host
+    PerHost,
+}
+
+/// User agents assigned round-robin under a [`RotationPolicy`].
+#[derive(Debug)]
+pub(crate) struct UserAgentRotation {
+    agents: Vec<String>,
+    policy: RotationPolicy,
+    next: AtomicUsize,
+    hosts: Mutex<HashMap<String, usize>>,
+}
+
+impl UserAgentRotation {
+    /// Returns `None` for an empty list.
+    pub(crate) fn new(agents: Vec<String>, policy: RotationPolicy) -> Option<Self> {
+        (!agents.is_empty()).then(|| Self {
+            agents,
+            policy,
+            next: AtomicUsize::new(0),
+            hosts: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The user agent for the next request to `host`.
+    pub(crate) fn pick(&self, host: &str) -> &str {
+        let take = || self.next.fetch_add(1, Ordering::Relaxed) % self.agents.len();
+        let index = match self.policy {
+            RotationPolicy::PerRequest => take(),
+            RotationPolicy::PerHost => *self
+                .hosts
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(host.to_ascii_lowercase())
+                .or_insert_with(take),
+        };
+        &self.agents[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_are_valid_header_values() {
+        let presets = [
+            UserAgent::Googlebot,
+            UserAgent::GooglebotSmartphone,
+            UserAgent::Bingbot,
+            UserAgent::TwitterBot,
+            UserAgent::FacebookBot,
+            UserAgent::LinkedInBot,
+            UserAgent::Slackbot,
+            UserAgent::DiscordBot,
+            UserAgent::Chrome,
+            UserAgent::Firefox,
+            UserAgent::Safari,
+            UserAgent::MobileSafari,
+        ];
+        for preset in presets {
+            let value = preset.as_str();
+            assert!(
+                reqwest::header::HeaderValue::from_str(value).is_ok(),
+                "{preset:?}"
+            );
+            assert!(!value.contains("  "), "{preset:?}");
+        }
+        assert!(UserAgent::GooglebotSmartphone.is_bot());
+        assert!(!UserAgent::Firefox.is_bot());
+    }
+
+    #[test]
+    fn test_client_hints() {
+        let hints = client_hints(UserAgent::Chrome.as_str());
+        assert_eq!(
+            hints,
+            [
+                (
+                    "sec-ch-ua",
+                    r#""Google Chrome";v="131", "Chromium";v="131", "Not_A Brand";v="24""#
+                        .to_string()
+                ),
+                ("sec-ch-ua-mobile", "?0".to_string()),
+                ("sec-ch-ua-platform", r#""Windows""#.to_string()),
+            ]
+        );
+
+        let edge = client_hints(
+            "Mozilla/5.0 (Linux; Android 14) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/130.0.0.0 Mobile Safari/537.36 EdgA/130.0.0.0 Edg/130.0.2849.80",
+        );
+        assert!(edge[0].1.starts_with(r#""Microsoft Edge";v="130""#));
+        assert_eq!(edge[1].1, "?1");
+        assert_eq!(edge[2].1, r#""Android""#);
+
+        for preset in [
+            UserAgent::Firefox,
+            UserAgent::Safari,
+            UserAgent::GooglebotSmartphone,
+        ] {
+            assert!(client_hints(preset.as_str()).is_empty(), "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn test_rotation_policies() {
+        let agents = || vec!["a".to_string(), "b".to_string()];
+        assert!(UserAgentRotation::new(Vec::new(), RotationPolicy::PerRequest).is_none());
+
+        let rotation = UserAgentRotation::new(agents(), RotationPolicy::PerRequest).unwrap();
+        let picked: Vec<_> = (0..3).map(|_| rotation.pick("example.com")).collect();
+        assert_eq!(picked, ["a", "b", "a"]);
+
+        let rotation = UserAgentRotation::new(agents(), RotationPolicy::PerHost).unwrap();
+        assert_eq!(rotation.pick("example.com"), "a");
+        assert_eq!(rotation.pick("example.org"), "b");
+        assert_eq!(rotation.pick("Example.COM"), "a");
+        assert_eq!(rotation.pick("example.org"), "b");
+    }
+}
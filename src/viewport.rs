@@ -0,0 +1,190 @@
+//! Viewport meta tag parsing
+//!
+//! Mobile-friendliness audits check the `<meta name="viewport">` declaration
+//! field by field: whether the layout follows the device width, and whether
+//! users can zoom. [`HtmlInfo::viewport`] parses the tag into a [`Viewport`].
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::html::HtmlInfo;
+
+/// A `maximum-scale` below this prevents useful zooming.
+const MIN_ZOOMABLE_SCALE: f32 = 5.0;
+
+/// A viewport `width` or `height`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ViewportLength {
+    /// `device-width` or `device-height`
+    Device,
+    /// A fixed size in CSS pixels
+    Pixels(u32),
+}
+
+/// A parsed `<meta name="viewport">` declaration.
+///
+/// Properties that are missing or have values that cannot be parsed are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Viewport {
+    /// `width`
+    pub width: Option<ViewportLength>,
+    /// `height`
+    pub height: Option<ViewportLength>,
+    /// `initial-scale`
+    pub initial_scale: Option<f32>,
+    /// `minimum-scale`
+    pub minimum_scale: Option<f32>,
+    /// `maximum-scale`
+    pub maximum_scale: Option<f32>,
+    /// `user-scalable`
+    pub user_scalable: Option<bool>,
+    /// `viewport-fit` (`auto`, `contain`, or `cover`)
+    pub viewport_fit: Option<String>,
+}
+
+impl Viewport {
+    /// Parse the `content` of a viewport meta tag.
+    ///
+    /// Properties may be separated by commas or semicolons; names and keyword
+    /// values are matched case-insensitively.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::{Viewport, ViewportLength};
+    ///
+    /// let viewport = Viewport::parse("width=device-width, initial-scale=1");
+    /// assert_eq!(viewport.width, Some(ViewportLength::Device));
+    /// assert_eq!(viewport.initial_scale, Some(1.0));
+    /// assert!(!viewport.blocks_zoom());
+    /// ```
+    pub fn parse(content: &str) -> Self {
+        let mut viewport = Self::default();
+        for property in content.split([',', ';']) {
+            let Some((name, value)) = property.split_once('=') else {
+                continue;
+            };
+            let name = name.trim().to_ascii_lowercase();
+            let value = value.trim();
+            match name.as_str() {
+                "width" => viewport.width = parse_length(value, "device-width"),
+                "height" => viewport.height = parse_length(value, "device-height"),
+                "initial-scale" => viewport.initial_scale = parse_scale(value),
+                "minimum-scale" => viewport.minimum_scale = parse_scale(value),
+                "maximum-scale" => viewport.maximum_scale = parse_scale(value),
+                "user-scalable" => viewport.user_scalable = parse_scalable(value),
+                "viewport-fit" if !value.is_empty() => {
+                    viewport.viewport_fit = Some(value.to_ascii_lowercase())
+                }
+                _ => {}
+            }
+        }
+        viewport
+    }
+
+    /// Whether the layout width follows the device (`width=device-width`).
+    pub fn is_device_width(&self) -> bool {
+        self.width == Some(ViewportLength::Device)
+    }
+
+    /// Whether users are prevented from zooming: `user-scalable=no`, or a
+    /// `maximum-scale` below 5.
+    pub fn blocks_zoom(&self) -> bool {
+        self.user_scalable == Some(false)
+            || self
+                .maximum_scale
+                .is_some_and(|scale| scale < MIN_ZOOMABLE_SCALE)
+    }
+}
+
+impl HtmlInfo {
+    /// The parsed viewport meta tag, or `None` if the page declares none.
+    ///
+    /// Pages without a viewport are rendered at a desktop width on phones.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let html = r#"<meta name="viewport" content="width=device-width, user-scalable=no">"#;
+    /// let viewport = HtmlInfo::from_string(html, None).unwrap().viewport().unwrap();
+    /// assert!(viewport.is_device_width());
+    /// assert!(viewport.blocks_zoom());
+    ///
+    /// assert!(HtmlInfo::from_string("<title>Desktop</title>", None).unwrap().viewport().is_none());
+    /// ```
+    pub fn viewport(&self) -> Option<Viewport> {
+        self.meta("viewport").map(Viewport::parse)
+    }
+}
+
+fn parse_length(value: &str, device_keyword: &str) -> Option<ViewportLength> {
+    if value.eq_ignore_ascii_case(device_keyword) {
+        return Some(ViewportLength::Device);
+    }
+    let pixels: f32 = value.strip_suffix("px").unwrap_or(value).parse().ok()?;
+    (pixels.is_finite() && pixels >= 1.0).then(|| ViewportLength::Pixels(pixels.round() as u32))
+}
+
+fn parse_scale(value: &str) -> Option<f32> {
+    value
+        .parse()
+        .ok()
+        .filter(|scale: &f32| scale.is_finite() && *scale >= 0.0)
+}
+
+/// `yes`/`no`, or a number where magnitudes of at least 1 allow zooming.
+fn parse_scalable(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "true" => Some(true),
+        "no" | "false" => Some(false),
+        number => number.parse::<f32>().ok().map(|n| n.abs() >= 1.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_all_properties() {
+        let viewport = Viewport::parse(
+            "Width=375px; height=device-height, initial-scale=1.5,minimum-scale=0.5, \
+             maximum-scale=3, user-scalable=0, viewport-fit=Cover, shrink-to-fit=no",
+        );
+        assert_eq!(
+            viewport,
+            Viewport {
+                width: Some(ViewportLength::Pixels(375)),
+                height: Some(ViewportLength::Device),
+                initial_scale: Some(1.5),
+                minimum_scale: Some(0.5),
+                maximum_scale: Some(3.0),
+                user_scalable: Some(false),
+                viewport_fit: Some("cover".to_string()),
+            }
+        );
+        assert!(!viewport.is_device_width());
+        assert!(viewport.blocks_zoom());
+    }
+
+    #[test]
+    fn test_invalid_values_are_ignored() {
+        let viewport =
+            Viewport::parse("width=wide, initial-scale=-1, user-scalable=maybe, =1, zoom");
+        assert_eq!(viewport, Viewport::default());
+        assert!(!viewport.blocks_zoom());
+
+        assert_eq!(Viewport::parse("maximum-scale=5").maximum_scale, Some(5.0));
+        assert!(!Viewport::parse("maximum-scale=5").blocks_zoom());
+        assert_eq!(
+            Viewport::parse("user-scalable=yes").user_scalable,
+            Some(true)
+        );
+    }
+}
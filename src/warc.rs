@@ -0,0 +1,547 @@
+//! WARC (Web ARChive) output and input
+//!
+//! [`WarcWriter`] stores fetched pages as WARC/1.1 `response` records, and
+//! [`WarcReader`] reads `response` records back as [`Snapshot`]s or parsed
+//! [`WebpageInfo`]s, so results can move through existing web-archiving
+//! pipelines. Files whose name ends in `.gz` are written as one gzip member per
+//! record; gzipped input is detected automatically.
+//!
+//! ```rust,no_run
+//! use webpage_info::warc::{WarcReader, WarcWriter};
+//! use webpage_info::{HttpOptions, WebpageClient};
+//!
+//! #[tokio::main]
+//! async fn main() -> webpage_info::Result<()> {
+//!     let client = WebpageClient::new(HttpOptions::new().snapshot(true))?;
+//!     let mut warc = WarcWriter::create("crawl.warc.gz")?;
+//!     client.fetch_many_into(["https://example.org"], &mut warc).await?;
+//!
+//!     for page in WarcReader::open("crawl.warc.gz")?.pages() {
+//!         println!("{:?}", page?.html.title);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flate2::Compression;
+use flate2::bufread::MultiGzDecoder;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::GzEncoder;
+
+use crate::WebpageInfo;
+use crate::error::{Error, Result};
+use crate::sink::ResultSink;
+use crate::snapshot::Snapshot;
+
+/// Headers describing the transfer rather than the stored body, which is
+/// always written decoded and unchunked.
+const TRANSFER_HEADERS: &[&str] = &["content-encoding", "content-length", "transfer-encoding"];
+
+/// The WARC headers and content block of a record.
+type Record = (Vec<(String, String)>, Vec<u8>);
+
+/// Writes pages as WARC `response` records.
+///
+/// Each page is written from its [`HttpInfo::snapshot`](crate::HttpInfo::snapshot)
+/// when present, so enable [`HttpOptions::snapshot`](crate::HttpOptions::snapshot)
+/// to archive the exact response bytes; otherwise the decoded body is stored.
+#[derive(Debug)]
+pub struct WarcWriter<W: Write> {
+    writer: W,
+    gzip: bool,
+}
+
+impl WarcWriter<BufWriter<File>> {
+    /// Create (or truncate) a WARC file, gzipping records if the name ends in `.gz`.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let gzip = path.extension().is_some_and(|ext| ext == "gz");
+        let writer = BufWriter::new(File::create(path)?);
+        Ok(if gzip {
+            Self::gzip(writer)
+        } else {
+            Self::new(writer)
+        })
+    }
+}
+
+impl<W: Write> WarcWriter<W> {
+    /// Write uncompressed records to any `Write` implementation.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            gzip: false,
+        }
+    }
+
+    /// Write each record as a separate gzip member, as in `.warc.gz` files.
+    pub fn gzip(writer: W) -> Self {
+        Self { writer, gzip: true }
+    }
+
+    /// Write a page as a `response` record.
+    pub fn write(&mut self, info: &WebpageInfo) -> Result<()> {
+        match &info.http.snapshot {
+            Some(snapshot) => self.write_snapshot(snapshot),
+            None => self.write_snapshot(&Snapshot {
+                url: info.http.url.clone(),
+                status_code: info.http.status_code,
+                headers: info.http.headers.clone(),
+                body: info.http.body.as_bytes().to_vec(),
+                fetched_at: now(),
+            }),
+        }
+    }
+
+    /// Write a snapshot as a `response` record.
+    pub fn write_snapshot(&mut self, snapshot: &Snapshot) -> Result<()> {
+        let mut block = Vec::with_capacity(snapshot.body.len() + 512);
+        let reason = reqwest::StatusCode::from_u16(snapshot.status_code)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("");
+        write!(block, "HTTP/1.1 {} {}\r\n", snapshot.status_code, reason)?;
+        for (name, value) in &snapshot.headers {
+            if !TRANSFER_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                write!(block, "{name}: {value}\r\n")?;
+            }
+        }
+        write!(block, "Content-Length: {}\r\n\r\n", snapshot.body.len())?;
+        block.extend_from_slice(&snapshot.body);
+
+        let mut record = Vec::with_capacity(block.len() + 512);
+        write!(
+            record,
+            "WARC/1.1\r\n\
+             WARC-Type: response\r\n\
+             WARC-Record-ID: <urn:uuid:{}>\r\n\
+             WARC-Date: {}\r\n\
+             WARC-Target-URI: {}\r\n\
+             Content-Type: application/http; msgtype=response\r\n\
+             Content-Length: {}\r\n\r\n",
+            record_id(),
+            format_date(snapshot.fetched_at),
+            snapshot.url,
+            block.len()
+        )?;
+        record.extend_from_slice(&block);
+        record.extend_from_slice(b"\r\n\r\n");
+
+        if self.gzip {
+            let mut encoder = GzEncoder::new(&mut self.writer, Compression::default());
+            encoder.write_all(&record)?;
+            encoder.finish()?;
+        } else {
+            self.writer.write_all(&record)?;
+        }
+        Ok(())
+    }
+
+    /// Consume the writer and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> ResultSink for WarcWriter<W> {
+    fn store(&mut self, info: WebpageInfo) -> Result<()> {
+        self.write(&info)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// Reads the `response` records of a WARC file.
+///
+/// Iterating yields one [`Snapshot`] per `response` record; `request`,
+/// `metadata`, `warcinfo`, and other record types are skipped. Iteration stops
+/// after the first error.
+pub struct WarcReader<R: BufRead> {
+    reader: R,
+    failed: bool,
+}
+
+impl WarcReader<Box<dyn BufRead>> {
+    /// Open a WARC file, decompressing it if it is gzipped.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let reader: Box<dyn BufRead> = if reader.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+            Box::new(BufReader::new(MultiGzDecoder::new(reader)))
+        } else {
+            Box::new(reader)
+        };
+        Ok(Self::new(reader))
+    }
+}
+
+impl<R: BufRead> WarcReader<R> {
+    /// Read uncompressed WARC records from any `BufRead` implementation.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            failed: false,
+        }
+    }
+
+    /// Parse each `response` record into a [`WebpageInfo`].
+    pub fn pages(self) -> impl Iterator<Item = Result<WebpageInfo>> {
+        self.map(|snapshot| WebpageInfo::from_snapshot(&snapshot?))
+    }
+
+    /// Read the next record, or `None` at the end of the input.
+    fn next_record(&mut self) -> Result<Option<Record>> {
+        // Records are separated by blank lines
+        let version = loop {
+            let mut line = Vec::new();
+            if self.reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok(None);
+            }
+            if !line.trim_ascii().is_empty() {
+                break line;
+            }
+        };
+        if !version.starts_with(b"WARC/") {
+            return Err(invalid("missing WARC version line"));
+        }
+
+        let headers = read_headers(&mut self.reader)?;
+        let length = header(&headers, "content-length")
+            .and_then(|v| v.parse::<u64>().ok())
+            .ok_or_else(|| invalid("missing Content-Length"))?;
+        let mut block = Vec::new();
+        (&mut self.reader).take(length).read_to_end(&mut block)?;
+        if (block.len() as u64) < length {
+            return Err(invalid("truncated record"));
+        }
+        Ok(Some((headers, block)))
+    }
+}
+
+impl<R: BufRead> Iterator for WarcReader<R> {
+    type Item = Result<Snapshot>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            let result = match self.next_record() {
+                Ok(None) => return None,
+                Ok(Some((headers, block))) => {
+                    if header(&headers, "warc-type") != Some("response") {
+                        continue;
+                    }
+                    parse_response(&headers, &block)
+                }
+                Err(e) => Err(e),
+            };
+            self.failed = result.is_err();
+            return Some(result);
+        }
+        None
+    }
+}
+
+/// Build a snapshot from the headers and HTTP message of a `response` record.
+fn parse_response(warc_headers: &[(String, String)], block: &[u8]) -> Result<Snapshot> {
+    let url = header(warc_headers, "warc-target-uri")
+        .ok_or_else(|| invalid("response record without WARC-Target-URI"))?;
+    // WARC 1.0 examples wrap the URI in angle brackets
+    let url = url
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .to_string();
+    let fetched_at = header(warc_headers, "warc-date")
+        .and_then(parse_date)
+        .unwrap_or(0);
+
+    let mut message = block;
+    let mut status_line = Vec::new();
+    message.read_until(b'\n', &mut status_line)?;
+    let status_code = std::str::from_utf8(&status_line)
+        .ok()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| invalid("malformed HTTP status line"))?;
+    let mut headers = read_headers(&mut message)?;
+
+    let mut body = message.to_vec();
+    let mut decoded_transfer = false;
+    if header(&headers, "transfer-encoding").is_some_and(|te| te.contains("chunked")) {
+        body = dechunk(&body).ok_or_else(|| invalid("malformed chunked body"))?;
+        decoded_transfer = true;
+    }
+    if let Some(encoding) = header(&headers, "content-encoding") {
+        let mut decoded = Vec::new();
+        let ok = match encoding.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => GzDecoder::new(&body[..]).read_to_end(&mut decoded).is_ok(),
+            "deflate" => ZlibDecoder::new(&body[..])
+                .read_to_end(&mut decoded)
+                .is_ok(),
+            _ => false,
+        };
+        if ok {
+            body = decoded;
+            decoded_transfer = true;
+        }
+    }
+    if decoded_transfer {
+        headers.retain(|(name, _)| !TRANSFER_HEADERS.contains(&name.to_ascii_lowercase().as_str()));
+    }
+
+    Ok(Snapshot {
+        url,
+        status_code,
+        headers,
+        body,
+        fetched_at,
+    })
+}
+
+/// Read `Name: value` lines up to and including the terminating blank line.
+fn read_headers(reader: &mut impl BufRead) -> Result<Vec<(String, String)>> {
+    let mut headers = Vec::new();
+    loop {
+        let mut line = Vec::new();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Err(invalid("unexpected end of headers"));
+        }
+        let line = String::from_utf8_lossy(&line);
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            return Ok(headers);
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+}
+
+/// Decode a `Transfer-Encoding: chunked` body.
+fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(body.len());
+    loop {
+        let line_end = body.iter().position(|&b| b == b'\n')?;
+        let size = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size = size.split(';').next()?.trim();
+        let size = usize::from_str_radix(size, 16).ok()?;
+        body = &body[line_end + 1..];
+        if size == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(body.get(..size)?);
+        body = body.get(size..)?;
+        body = body.strip_prefix(b"\r\n").unwrap_or(body);
+    }
+}
+
+/// The first value of a header, matched case-insensitively.
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::Warc(reason.to_string())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// A random version 4 UUID for `WARC-Record-ID`.
+fn record_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let random = |salt: u64| {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        hasher.write_u64(salt);
+        hasher.finish()
+    };
+    let high = random(now());
+    let low = random(high);
+    let high = (high & !0xf000) | 0x4000;
+    let low = (low & !(0b11 << 62)) | (0b10 << 62);
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+/// Format seconds since the Unix epoch as a `WARC-Date` (`2024-01-31T12:00:00Z`).
+fn format_date(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Howard Hinnant's civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+/// Parse a `WARC-Date` into seconds since the Unix epoch.
+///
+/// Fractional seconds are ignored.
+fn parse_date(date: &str) -> Option<u64> {
+    let (date, time) = date.trim().trim_end_matches('Z').split_once('T')?;
+    let mut ymd = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (ymd.next()?.ok()?, ymd.next()?.ok()?, ymd.next()?.ok()?);
+    let time = time.split('.').next()?;
+    let mut hms = time.splitn(3, ':').map(str::parse::<u64>);
+    let (hour, minute, second) = (hms.next()?.ok()?, hms.next()?.ok()?, hms.next()?.ok()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // Howard Hinnant's days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = u64::try_from(era * 146_097 + doe - 719_468).ok()?;
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            url: "https://example.com/page".to_string(),
+            status_code: 200,
+            headers: vec![
+                ("content-type".to_string(), "text/html".to_string()),
+                ("content-encoding".to_string(), "gzip".to_string()),
+            ],
+            body: b"<html><head><title>Archived</title></head></html>".to_vec(),
+            fetched_at: 1_706_702_400,
+        }
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(format_date(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_date(1_706_702_400), "2024-01-31T12:00:00Z");
+        assert_eq!(format_date(1_709_210_096), "2024-02-29T12:34:56Z");
+        assert_eq!(parse_date("2024-02-29T12:34:56Z"), Some(1_709_210_096));
+        assert_eq!(
+            parse_date("2024-01-31T12:00:00.123456Z"),
+            Some(1_706_702_400)
+        );
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_record_ids_are_unique_v4_uuids() {
+        let (a, b) = (record_id(), record_id());
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 36);
+        assert_eq!(&a[14..15], "4");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for gzip in [false, true] {
+            let mut writer = if gzip {
+                WarcWriter::gzip(Vec::new())
+            } else {
+                WarcWriter::new(Vec::new())
+            };
+            writer.write_snapshot(&snapshot()).unwrap();
+            writer.write_snapshot(&snapshot()).unwrap();
+            let bytes = writer.into_inner();
+
+            let reader: Box<dyn BufRead> = if gzip {
+                Box::new(BufReader::new(MultiGzDecoder::new(&bytes[..])))
+            } else {
+                Box::new(&bytes[..])
+            };
+            let snapshots: Vec<Snapshot> = WarcReader::new(reader).collect::<Result<_>>().unwrap();
+            assert_eq!(snapshots.len(), 2);
+            let read = &snapshots[0];
+            assert_eq!(read.url, "https://example.com/page");
+            assert_eq!(read.status_code, 200);
+            assert_eq!(read.body, snapshot().body);
+            assert_eq!(read.fetched_at, snapshot().fetched_at);
+            // The stored body is decoded, so the encoding header is dropped
+            assert_eq!(read.header("content-encoding"), None);
+            assert_eq!(read.header("content-type"), Some("text/html"));
+        }
+    }
+
+    #[test]
+    fn test_reads_foreign_records() {
+        let http = "HTTP/1.1 200 OK\r\n\
+                    Content-Type: text/html\r\n\
+                    Transfer-Encoding: chunked\r\n\r\n\
+                    7\r\n<title>\r\n\
+                    9\r\nChunked</\r\n\
+                    6\r\ntitle>\r\n\
+                    0\r\n\r\n";
+        let warc = format!(
+            "WARC/1.0\r\n\
+             WARC-Type: warcinfo\r\n\
+             Content-Length: 5\r\n\r\n\
+             hello\r\n\r\n\
+             WARC/1.0\r\n\
+             WARC-Type: response\r\n\
+             WARC-Target-URI: <https://example.org/>\r\n\
+             WARC-Date: 2024-01-31T12:00:00Z\r\n\
+             Content-Length: {}\r\n\r\n\
+             {http}\r\n\r\n",
+            http.len()
+        );
+
+        let pages: Vec<WebpageInfo> = WarcReader::new(warc.as_bytes())
+            .pages()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].http.url, "https://example.org/");
+        assert_eq!(pages[0].html.title.as_deref(), Some("Chunked"));
+        assert_eq!(
+            pages[0]
+                .http
+                .snapshot
+                .as_ref()
+                .unwrap()
+                .header("transfer-encoding"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_stops_after_malformed_record() {
+        let mut reader = WarcReader::new(&b"not a warc file\r\n"[..]);
+        assert!(matches!(reader.next(), Some(Err(Error::Warc(_)))));
+        assert!(reader.next().is_none());
+    }
+}
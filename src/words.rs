@@ -0,0 +1,161 @@
+//! Word counting and reading time
+//!
+//! Chinese and Japanese are written without spaces between words, so splitting
+//! on whitespace counts a whole paragraph as one word. For those languages each
+//! ideograph and kana is counted as a word instead, with a matching reading
+//! speed. Korean separates words with spaces and is counted like English.
+
+use std::time::Duration;
+
+use crate::html::HtmlInfo;
+
+/// Average reading speed for space-separated languages.
+const WORDS_PER_MINUTE: usize = 230;
+
+/// Average reading speed for Chinese and Japanese, in characters.
+const CJK_CHARS_PER_MINUTE: usize = 400;
+
+/// How text is split into words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segmentation {
+    /// Words are separated by whitespace
+    Whitespace,
+    /// Each ideograph or kana is a word; other text is split on whitespace
+    Characters,
+}
+
+impl HtmlInfo {
+    /// Number of words in [`text_content`](HtmlInfo::text_content).
+    ///
+    /// Pages whose `lang` is Chinese or Japanese, or without a `lang` whose text
+    /// is mostly ideographs and kana, count each of those characters as a word.
+    ///
+    /// # Example
+    /// ```
+    /// use webpage_info::HtmlInfo;
+    ///
+    /// let info = HtmlInfo::from_string("<body>Hello there, world</body>", None).unwrap();
+    /// # #[cfg(feature = "text")]
+    /// assert_eq!(info.word_count(), 3);
+    ///
+    /// let info = HtmlInfo::from_string(r#"<html lang="ja"><body>東京へ行く</body></html>"#, None).unwrap();
+    /// # #[cfg(feature = "text")]
+    /// assert_eq!(info.word_count(), 5);
+    /// ```
+    pub fn word_count(&self) -> usize {
+        count_words(&self.text_content, self.segmentation())
+    }
+
+    /// Estimated time to read [`text_content`](HtmlInfo::text_content), rounded
+    /// up to whole minutes.
+    pub fn reading_time(&self) -> Duration {
+        let segmentation = self.segmentation();
+        let per_minute = match segmentation {
+            Segmentation::Whitespace => WORDS_PER_MINUTE,
+            Segmentation::Characters => CJK_CHARS_PER_MINUTE,
+        };
+        let minutes = count_words(&self.text_content, segmentation).div_ceil(per_minute);
+        Duration::from_secs(60 * minutes as u64)
+    }
+
+    fn segmentation(&self) -> Segmentation {
+        let primary = self
+            .language
+            .as_deref()
+            .and_then(|lang| lang.split(['-', '_']).next())
+            .map(str::to_ascii_lowercase);
+        match primary.as_deref() {
+            Some("zh" | "ja") => Segmentation::Characters,
+            Some(lang) if !lang.is_empty() => Segmentation::Whitespace,
+            _ if mostly_cjk(&self.text_content) => Segmentation::Characters,
+            _ => Segmentation::Whitespace,
+        }
+    }
+}
+
+fn count_words(text: &str, segmentation: Segmentation) -> usize {
+    match segmentation {
+        Segmentation::Whitespace => text.split_whitespace().count(),
+        Segmentation::Characters => {
+            let mut count = 0;
+            let mut in_word = false;
+            for c in text.chars() {
+                if is_cjk(c) {
+                    count += 1;
+                    in_word = false;
+                } else if c.is_alphanumeric() {
+                    count += usize::from(!in_word);
+                    in_word = true;
+                } else {
+                    in_word = false;
+                }
+            }
+            count
+        }
+    }
+}
+
+/// Whether more than half of the letters are ideographs or kana.
+fn mostly_cjk(text: &str) -> bool {
+    let (cjk, letters) = text
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .fold((0, 0), |(cjk, letters), c| {
+            (cjk + usize::from(is_cjk(c)), letters + 1)
+        });
+    cjk * 2 > letters
+}
+
+/// Han ideographs, hiragana, and katakana.
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // Hiragana, Katakana
+        | '\u{31F0}'..='\u{31FF}'   // Katakana phonetic extensions
+        | '\u{3400}'..='\u{4DBF}'   // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}'   // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}'   // Halfwidth katakana
+        | '\u{20000}'..='\u{2FA1F}' // CJK extensions B-F, compatibility supplement
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(language: Option<&str>, text: &str) -> HtmlInfo {
+        HtmlInfo {
+            language: language.map(str::to_string),
+            text_content: text.to_string(),
+            ..HtmlInfo::default()
+        }
+    }
+
+    #[test]
+    fn test_segmentation_by_language() {
+        // Mixed Chinese with a Latin product name
+        let text = "我们今天发布了 Rust 新版本。";
+        assert_eq!(page(Some("zh-CN"), text).word_count(), 11);
+        assert_eq!(page(None, text).word_count(), 11);
+        assert_eq!(page(Some("en"), text).word_count(), 3);
+
+        let korean = "안녕하세요 세계 여러분";
+        assert_eq!(page(Some("ko"), korean).word_count(), 3);
+        assert_eq!(page(None, korean).word_count(), 3);
+    }
+
+    #[test]
+    fn test_reading_time() {
+        assert_eq!(page(None, "").reading_time(), Duration::ZERO);
+        let english = "word ".repeat(WORDS_PER_MINUTE + 1);
+        assert_eq!(
+            page(Some("en"), &english).reading_time(),
+            Duration::from_secs(120)
+        );
+        let japanese = "字".repeat(CJK_CHARS_PER_MINUTE);
+        assert_eq!(
+            page(Some("ja"), &japanese).reading_time(),
+            Duration::from_secs(60)
+        );
+    }
+}